@@ -31,20 +31,207 @@
 //!    (sounds like sine)              (rounded corners)               (harsh/buzzy)
 //! ```
 //!
+//! # Filtering
+//!
+//! An optional one-pole [`Filter`] runs over the blended output. Unlike
+//! band-limiting it is stateful (each output depends on the previous one),
+//! so it lives in a [`FilterState`] owned by the note being synthesized.
+//! The cutoff can sweep across the note, e.g. a low-pass closing from
+//! bright to dull mimics a plucked or struck source.
+//!
+//! ```text
+//! low-pass (cutoff ↓ = duller)        high-pass (cutoff ↑ = thinner)
+//!
+//! gain │────╮                         gain │       ╭────
+//!      │     ╲                              │      ╱
+//!      │      ╲___                          │  ___╱
+//!      └──────┼──────→ freq                 └──────┼──────→ freq
+//!          cutoff                               cutoff
+//! ```
+//!
+//! # Modulation
+//!
+//! Two optional low-frequency oscillators (LFOs) animate the note:
+//! vibrato wobbles the pitch, tremolo wobbles the loudness.
+//!
+//! ```text
+//! vibrato (pitch)                     tremolo (amplitude)
+//!
+//!  ∿∿∿∿╲╱╲╱╲╱╲╱╲╱∿∿∿∿╲╱╲╱╲╱             ╭╮╭╮      ╭╮╭╮      ╭╮╭╮
+//!  cycles bunch up and spread out      ╰╯╰╯╲╱╲╱╲╱╰╯╰╯╲╱╲╱╲╱╰╯╰╯
+//! ```
+//!
 //! # Combination
 //!
 //! Both options can be combined: band-limit first, then blend with sine.
 //! This produces warm, musical timbres without digital harshness.
 
+use std::f64::consts::PI;
+
+use super::{MS_PER_SECOND, SAMPLE_RATE};
+use super::lut;
 use super::waveform::Waveform;
 
 /// Options for blending and filtering waveforms.
-#[derive(Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Blend {
     /// Ratio of sine wave to mix in (0.0 = none, 1.0 = pure sine)
     pub sine_mix: f64,
     /// Number of harmonics for band-limiting (None = unlimited/raw)
     pub harmonics: Option<u32>,
+    /// Filter applied after blending (None = unfiltered)
+    pub filter: Option<Filter>,
+    /// Pitch modulation; `depth` is the fraction of the note frequency
+    pub vibrato: Option<Lfo>,
+    /// Amplitude modulation; `depth` is how far the gain dips (0.0–1.0)
+    pub tremolo: Option<Lfo>,
+    /// Pitch slide into the note from a previous frequency (portamento)
+    pub glide: Option<Glide>,
+    /// Fade in and out at the note's edges
+    pub envelope: Envelope,
+}
+
+/// Linear fade in over `attack_ms` and out over the last `release_ms`.
+///
+/// Starting or stopping a wave mid-cycle jumps straight to/from a non-zero
+/// level, which the ear hears as a click; the default few milliseconds of
+/// ramp are inaudible but remove it. Longer values soften or swell the note.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Envelope {
+    pub attack_ms: u32,
+    pub release_ms: u32,
+}
+
+impl Default for Envelope {
+    fn default() -> Self {
+        Self {
+            attack_ms: 3,
+            release_ms: 3,
+        }
+    }
+}
+
+impl Envelope {
+    /// Gain (0.0–1.0) for sample `idx` of a note `num_samples` long.
+    pub fn gain(&self, idx: usize, num_samples: usize) -> f64 {
+        let ramp = |from_edge: usize, ramp_ms: u32| {
+            let ramp_samples = (SAMPLE_RATE * ramp_ms / MS_PER_SECOND) as usize;
+            if from_edge >= ramp_samples { 1.0 } else { from_edge as f64 / ramp_samples as f64 }
+        };
+        let attack = ramp(idx, self.attack_ms);
+        let release = ramp(num_samples.saturating_sub(idx + 1), self.release_ms);
+        attack.min(release)
+    }
+}
+
+/// Linear pitch slide from `from_freq` to the note's own frequency over the
+/// first `duration_ms` of the note.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Glide {
+    pub from_freq: u32,
+    pub duration_ms: u32,
+}
+
+impl Glide {
+    /// Phase offset at sample `idx` relative to a steady `angular_freq`.
+    ///
+    /// With ω sliding linearly from ω₀ to ω₁ over T samples, the phase is
+    /// `ω₁·t + (ω₀ - ω₁)·(t - t²/2T)` during the slide and the offset then
+    /// freezes at `(ω₀ - ω₁)·T/2`, so the waveform continues without a jump.
+    pub fn phase_offset(&self, angular_freq: f64, idx: usize) -> f64 {
+        let from_angular = 2.0 * PI * self.from_freq as f64 / SAMPLE_RATE as f64;
+        let glide_samples = (SAMPLE_RATE * self.duration_ms / MS_PER_SECOND) as f64;
+        if glide_samples == 0.0 {
+            return 0.0;
+        }
+        let elapsed = (idx as f64).min(glide_samples);
+        (from_angular - angular_freq) * (elapsed - elapsed * elapsed / (2.0 * glide_samples))
+    }
+}
+
+/// Low-frequency oscillator driving vibrato or tremolo.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Lfo {
+    pub rate_hz: f64,
+    pub depth: f64,
+}
+
+impl Lfo {
+    fn angular_rate(&self) -> f64 {
+        2.0 * PI * self.rate_hz / SAMPLE_RATE as f64
+    }
+
+    /// Phase offset at sample `idx` for a carrier advancing `angular_freq`
+    /// radians per sample. Frequency `ω(1 + depth·sin(ωₗ·t))` integrates to
+    /// `ω·t + ω·depth/ωₗ·(1 - cos(ωₗ·t))`; this returns the second term, so
+    /// the result is exact instead of drifting like a running sum would.
+    pub fn vibrato_offset(&self, angular_freq: f64, idx: usize) -> f64 {
+        let angular_rate = self.angular_rate();
+        angular_freq * self.depth / angular_rate * (1.0 - (angular_rate * idx as f64).cos())
+    }
+
+    /// Gain at sample `idx`: starts at 1.0 and dips to `1 - depth` each cycle.
+    pub fn tremolo_gain(&self, idx: usize) -> f64 {
+        1.0 - self.depth * (0.5 - 0.5 * (self.angular_rate() * idx as f64).cos())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FilterKind {
+    LowPass,
+    HighPass,
+}
+
+/// One-pole filter whose cutoff moves from `cutoff_hz` at the start of the
+/// note to `end_cutoff_hz` at its end (equal values = static filter).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Filter {
+    pub kind: FilterKind,
+    pub cutoff_hz: f64,
+    pub end_cutoff_hz: f64,
+}
+
+impl Filter {
+    pub fn low_pass(cutoff_hz: f64) -> Self {
+        Self { kind: FilterKind::LowPass, cutoff_hz, end_cutoff_hz: cutoff_hz }
+    }
+
+    pub fn high_pass(cutoff_hz: f64) -> Self {
+        Self { kind: FilterKind::HighPass, cutoff_hz, end_cutoff_hz: cutoff_hz }
+    }
+
+    /// Sweeps the cutoff towards `end_cutoff_hz` over the note.
+    pub fn sweep_to(self, end_cutoff_hz: f64) -> Self {
+        Self { end_cutoff_hz, ..self }
+    }
+
+    /// Cutoff at `progress` (0.0 = note start, 1.0 = note end). Interpolated
+    /// geometrically because pitch perception is logarithmic.
+    fn cutoff_at(&self, progress: f64) -> f64 {
+        self.cutoff_hz * (self.end_cutoff_hz / self.cutoff_hz).powf(progress)
+    }
+}
+
+/// Running state of a [`Filter`] across the samples of one note.
+#[derive(Default)]
+pub struct FilterState {
+    low_passed: f64,
+}
+
+impl FilterState {
+    /// Filters one sample. `progress` is the position within the note (0.0–1.0).
+    ///
+    /// One-pole low-pass: `y += α × (x - y)` with `α = 1 - e^(-2π × cutoff / rate)`.
+    /// High-pass is what the low-pass removes: `x - y`.
+    pub fn process(&mut self, filter: &Filter, input: f64, progress: f64) -> f64 {
+        let cutoff = filter.cutoff_at(progress);
+        let alpha = 1.0 - (-2.0 * PI * cutoff / SAMPLE_RATE as f64).exp();
+        self.low_passed += alpha * (input - self.low_passed);
+        match filter.kind {
+            FilterKind::LowPass => self.low_passed,
+            FilterKind::HighPass => input - self.low_passed,
+        }
+    }
 }
 
 impl Blend {
@@ -53,6 +240,11 @@ impl Blend {
         Self {
             sine_mix: 0.0,
             harmonics: None,
+            filter: None,
+            vibrato: None,
+            tremolo: None,
+            glide: None,
+            envelope: Envelope::default(),
         }
     }
 
@@ -61,7 +253,7 @@ impl Blend {
     pub fn with_sine(ratio: f64) -> Self {
         Self {
             sine_mix: ratio,
-            harmonics: None,
+            ..Self::none()
         }
     }
 
@@ -70,8 +262,8 @@ impl Blend {
     #[allow(dead_code)]
     pub fn band_limited(harmonics: u32) -> Self {
         Self {
-            sine_mix: 0.0,
             harmonics: Some(harmonics),
+            ..Self::none()
         }
     }
 
@@ -80,10 +272,50 @@ impl Blend {
         Self {
             sine_mix,
             harmonics: Some(harmonics),
+            ..Self::none()
+        }
+    }
+
+    /// Adds a filter stage after blending.
+    pub fn with_filter(self, filter: Filter) -> Self {
+        Self {
+            filter: Some(filter),
+            ..self
+        }
+    }
+
+    /// Adds pitch vibrato.
+    pub fn with_vibrato(self, lfo: Lfo) -> Self {
+        Self {
+            vibrato: Some(lfo),
+            ..self
+        }
+    }
+
+    /// Adds amplitude tremolo.
+    pub fn with_tremolo(self, lfo: Lfo) -> Self {
+        Self {
+            tremolo: Some(lfo),
+            ..self
+        }
+    }
+
+    /// Replaces the default click-free envelope.
+    pub fn with_envelope(self, envelope: Envelope) -> Self {
+        Self { envelope, ..self }
+    }
+
+    /// Slides the pitch in from another frequency.
+    pub fn with_glide(self, glide: Glide) -> Self {
+        Self {
+            glide: Some(glide),
+            ..self
         }
     }
 
     /// Apply blending to a waveform sample at the given phase.
+    /// The filter stage is applied separately by the caller, which owns
+    /// the note's [`FilterState`].
     ///
     /// # Pipeline
     /// ```text
@@ -107,8 +339,125 @@ impl Blend {
         if self.sine_mix == 0.0 {
             base
         } else {
-            let sine = phase.sin();
+            let sine = lut::sin(phase);
             sine * self.sine_mix + base * (1.0 - self.sine_mix)
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::waveform::Square;
+
+    fn filtered(filter: Filter, input: impl Iterator<Item = f64>) -> Vec<f64> {
+        let mut state = FilterState::default();
+        input.map(|sample| state.process(&filter, sample, 0.0)).collect()
+    }
+
+    fn square_wave(samples: usize) -> impl Iterator<Item = f64> {
+        let angular_freq = 2.0 * PI * 440.0 / SAMPLE_RATE as f64;
+        (0..samples).map(move |idx| Square.sample(angular_freq * idx as f64))
+    }
+
+    fn largest_jump(samples: &[f64]) -> f64 {
+        samples
+            .windows(2)
+            .map(|pair| (pair[1] - pair[0]).abs())
+            .fold(0.0, f64::max)
+    }
+
+    #[test]
+    fn low_pass_softens_square_edges() {
+        let raw: Vec<f64> = square_wave(4410).collect();
+        let smoothed = filtered(Filter::low_pass(1000.0), square_wave(4410));
+        assert!(largest_jump(&smoothed) < largest_jump(&raw) / 2.0);
+    }
+
+    #[test]
+    fn high_pass_removes_constant_offset() {
+        let output = filtered(Filter::high_pass(200.0), std::iter::repeat_n(1.0, 4410));
+        assert!(output.last().unwrap().abs() < 1e-3);
+    }
+
+    #[test]
+    fn static_filter_keeps_cutoff() {
+        let filter = Filter::low_pass(800.0);
+        assert_eq!(filter.cutoff_at(0.0), 800.0);
+        assert_eq!(filter.cutoff_at(1.0), 800.0);
+    }
+
+    #[test]
+    fn sweep_moves_cutoff_geometrically() {
+        let filter = Filter::low_pass(4000.0).sweep_to(1000.0);
+        assert_eq!(filter.cutoff_at(0.0), 4000.0);
+        assert!((filter.cutoff_at(0.5) - 2000.0).abs() < 1e-9);
+        assert!((filter.cutoff_at(1.0) - 1000.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn envelope_ramps_at_both_edges() {
+        let envelope = Envelope::default();
+        assert_eq!(envelope.gain(0, 1000), 0.0);
+        assert_eq!(envelope.gain(999, 1000), 0.0);
+        assert_eq!(envelope.gain(500, 1000), 1.0);
+        assert!(envelope.gain(66, 1000) > 0.0 && envelope.gain(66, 1000) < 1.0);
+    }
+
+    #[test]
+    fn envelope_attack_and_release_are_independent() {
+        let swell = Envelope { attack_ms: 100, release_ms: 0 };
+        assert!(swell.gain(2205, 44100) < 0.6);
+        assert_eq!(swell.gain(44099, 44100), 1.0);
+    }
+
+    #[test]
+    fn vibrato_offset_starts_at_zero() {
+        assert_eq!(Lfo { rate_hz: 5.0, depth: 0.02 }.vibrato_offset(0.06, 0), 0.0);
+    }
+
+    #[test]
+    fn vibrato_offset_returns_to_zero_each_cycle() {
+        let lfo = Lfo { rate_hz: 5.0, depth: 0.02 };
+        let cycle = (SAMPLE_RATE as f64 / 5.0) as usize;
+        assert!(lfo.vibrato_offset(0.06, cycle).abs() < 1e-9);
+        assert!(lfo.vibrato_offset(0.06, cycle / 2) > 0.0);
+    }
+
+    #[test]
+    fn tremolo_gain_dips_by_depth() {
+        let lfo = Lfo { rate_hz: 5.0, depth: 0.8 };
+        let half_cycle = (SAMPLE_RATE as f64 / 10.0) as usize;
+        assert_eq!(lfo.tremolo_gain(0), 1.0);
+        assert!((lfo.tremolo_gain(half_cycle) - 0.2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn glide_offset_starts_at_zero() {
+        let glide = Glide { from_freq: 220, duration_ms: 50 };
+        assert_eq!(glide.phase_offset(0.06, 0), 0.0);
+    }
+
+    #[test]
+    fn glide_offset_freezes_after_slide() {
+        let glide = Glide { from_freq: 220, duration_ms: 50 };
+        let slide_end = (SAMPLE_RATE * 50 / MS_PER_SECOND) as usize;
+        let settled = glide.phase_offset(0.06, slide_end);
+        assert_eq!(glide.phase_offset(0.06, slide_end + 1000), settled);
+    }
+
+    #[test]
+    fn glide_from_same_frequency_is_noop() {
+        let angular_freq = 2.0 * PI * 440.0 / SAMPLE_RATE as f64;
+        let glide = Glide { from_freq: 440, duration_ms: 50 };
+        assert!(glide.phase_offset(angular_freq, 1000).abs() < 1e-9);
+    }
+
+    #[test]
+    fn with_filter_keeps_blend_settings() {
+        let blend = Blend::with_sine_and_band_limit(0.4, 7).with_filter(Filter::high_pass(100.0));
+        assert_eq!(blend.sine_mix, 0.4);
+        assert_eq!(blend.harmonics, Some(7));
+        assert_eq!(blend.filter, Some(Filter::high_pass(100.0)));
+    }
+}
@@ -0,0 +1,87 @@
+//! Velocity from move importance.
+//!
+//! Quiet maneuvering should sound soft and tactical blows loud. Each move
+//! gets an importance score in 0.0–1.0 from what the notation and board tell
+//! us, which maps linearly onto note gain:
+//!
+//! ```text
+//! importance = captured value / 9 × 0.5     (queen capture → 0.5)
+//!            + 0.25 check | 0.5 checkmate
+//!            + 0.3 promotion
+//!            (clamped to 1.0)
+//!
+//! gain = 0.5 + 0.5 × importance              (quiet move → 0.5, mate → 1.0)
+//! ```
+
+use crate::engine::chess::{NotationMove, Piece, Threat};
+
+const CAPTURE_WEIGHT: f64 = 0.5;
+const CHECK_WEIGHT: f64 = 0.25;
+const CHECKMATE_WEIGHT: f64 = 0.5;
+const PROMOTION_WEIGHT: f64 = 0.3;
+/// Gain of a move with zero importance.
+const QUIET_GAIN: f64 = 0.5;
+
+/// Scores a move from 0.0 (quiet) to 1.0 (decisive). `captured` is the piece
+/// taken on the destination square, if the board could tell us.
+pub fn importance(m: &NotationMove, captured: Option<Piece>) -> f64 {
+    let capture = captured.map_or(0.0, |piece| {
+        piece.value() as f64 / Piece::Queen.value() as f64 * CAPTURE_WEIGHT
+    });
+    let threat = match m.threat {
+        Threat::None => 0.0,
+        Threat::Check => CHECK_WEIGHT,
+        Threat::Checkmate => CHECKMATE_WEIGHT,
+    };
+    let promotion = if m.promotion.is_some() { PROMOTION_WEIGHT } else { 0.0 };
+    (capture + threat + promotion).min(1.0)
+}
+
+/// Maps importance onto linear note gain.
+pub fn velocity(importance: f64) -> f64 {
+    QUIET_GAIN + (1.0 - QUIET_GAIN) * importance.clamp(0.0, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(notation: &str) -> NotationMove {
+        NotationMove::parse(notation, 0).unwrap()
+    }
+
+    #[test]
+    fn quiet_move_has_no_importance() {
+        assert_eq!(importance(&parse("Nf3"), None), 0.0);
+    }
+
+    #[test]
+    fn bigger_captures_matter_more() {
+        let pawn = importance(&parse("Nxe5"), Some(Piece::Pawn));
+        let queen = importance(&parse("Nxe5"), Some(Piece::Queen));
+        assert!(queen > pawn && pawn > 0.0);
+        assert_eq!(queen, CAPTURE_WEIGHT);
+    }
+
+    #[test]
+    fn checkmate_outweighs_check() {
+        assert!(importance(&parse("Qh5#"), None) > importance(&parse("Qh5+"), None));
+    }
+
+    #[test]
+    fn promotion_adds_weight() {
+        assert_eq!(importance(&parse("e8=Q"), None), PROMOTION_WEIGHT);
+    }
+
+    #[test]
+    fn importance_is_capped() {
+        assert_eq!(importance(&parse("exd8=Q#"), Some(Piece::Queen)), 1.0);
+    }
+
+    #[test]
+    fn velocity_spans_quiet_to_full() {
+        assert_eq!(velocity(0.0), QUIET_GAIN);
+        assert_eq!(velocity(1.0), 1.0);
+        assert_eq!(velocity(5.0), 1.0);
+    }
+}
@@ -0,0 +1,337 @@
+//! FLAC encoder: lossless, about half the size of WAV for a game.
+//!
+//! ```text
+//! "fLaC"
+//! STREAMINFO        block sizes, sample rate, channels, bit depth, length
+//! frame × N         4096 frames of audio each (the last may be shorter)
+//! ├── header        sync, block size, rate, channels, frame number, CRC-8
+//! ├── subframe × C  one per channel, the cheapest of:
+//! │                   CONSTANT   one value (silence between moves)
+//! │                   FIXED      polynomial predictor of order 0–4 plus
+//! │                              Rice-coded residuals
+//! │                   VERBATIM   raw samples
+//! └── footer        CRC-16
+//! ```
+//!
+//! Fixed predictors guess each sample from the ones before it (order 2:
+//! `2·x[n-1] - x[n-2]`, a straight line); smooth waveforms leave small
+//! residuals, which Rice coding stores in few bits. The stream MD5 is left
+//! as zero, which the format allows for "not computed".
+
+use super::BITS_PER_SAMPLE;
+
+const BLOCK_SIZE: usize = 4096;
+const MAX_FIXED_ORDER: usize = 4;
+/// Largest Rice parameter of the 4-bit coding method (15 is the escape code).
+const MAX_RICE_PARAM: u32 = 14;
+const STREAMINFO_SIZE: u32 = 34;
+
+const SUBFRAME_CONSTANT: u64 = 0b000000;
+const SUBFRAME_VERBATIM: u64 = 0b000001;
+const SUBFRAME_FIXED: u64 = 0b001000;
+
+/// Encodes interleaved 16-bit samples with `num_channels` channels (1–8)
+/// at `sample_rate` Hz.
+pub fn encode(samples: &[i16], num_channels: u16, sample_rate: u32) -> Vec<u8> {
+    let channels = num_channels.max(1) as usize;
+    let num_frames = samples.len() / channels;
+    let mut out = Vec::with_capacity(samples.len());
+    out.extend_from_slice(b"fLaC");
+    write_streaminfo(&mut out, num_channels, sample_rate, num_frames as u64);
+    for (frame_number, block) in samples[..num_frames * channels].chunks(BLOCK_SIZE * channels).enumerate() {
+        write_frame(&mut out, block, channels, sample_rate, frame_number as u64);
+    }
+    out
+}
+
+fn write_streaminfo(out: &mut Vec<u8>, num_channels: u16, sample_rate: u32, num_frames: u64) {
+    let mut bits = BitWriter::default();
+    bits.write(1, 1); // last metadata block
+    bits.write(0, 7); // STREAMINFO
+    bits.write(STREAMINFO_SIZE as u64, 24);
+    bits.write(BLOCK_SIZE as u64, 16); // min block size
+    bits.write(BLOCK_SIZE as u64, 16); // max block size
+    bits.write(0, 24); // min frame size: unknown
+    bits.write(0, 24); // max frame size: unknown
+    bits.write(sample_rate as u64, 20);
+    bits.write(num_channels as u64 - 1, 3);
+    bits.write(BITS_PER_SAMPLE as u64 - 1, 5);
+    bits.write(num_frames, 36);
+    bits.write(0, 64); // MD5: not computed
+    bits.write(0, 64);
+    out.extend_from_slice(&bits.finish());
+}
+
+fn write_frame(out: &mut Vec<u8>, block: &[i16], channels: usize, sample_rate: u32, frame_number: u64) {
+    let block_len = block.len() / channels;
+    let mut bits = BitWriter::default();
+    bits.write(0b11_1111_1111_1110, 14); // sync
+    bits.write(0, 1); // reserved
+    bits.write(0, 1); // fixed block size
+    bits.write(0b0111, 4); // block size - 1 follows as 16 bits
+    bits.write(sample_rate_code(sample_rate), 4);
+    bits.write(channels as u64 - 1, 4); // independent channels
+    bits.write(0b100, 3); // 16 bits per sample
+    bits.write(0, 1); // reserved
+    write_utf8_number(&mut bits, frame_number);
+    bits.write(block_len as u64 - 1, 16);
+    let header_crc = crc8(bits.bytes());
+    bits.write(header_crc as u64, 8);
+
+    for channel in 0..channels {
+        let signal: Vec<i64> = block.iter().skip(channel).step_by(channels).map(|&sample| sample as i64).collect();
+        write_subframe(&mut bits, &signal);
+    }
+    let mut frame = bits.finish();
+    let footer_crc = crc16(&frame);
+    frame.extend_from_slice(&footer_crc.to_be_bytes());
+    out.extend_from_slice(&frame);
+}
+
+fn sample_rate_code(sample_rate: u32) -> u64 {
+    match sample_rate {
+        8_000 => 0b0100,
+        16_000 => 0b0101,
+        22_050 => 0b0110,
+        24_000 => 0b0111,
+        32_000 => 0b1000,
+        44_100 => 0b1001,
+        48_000 => 0b1010,
+        88_200 => 0b0011,
+        96_000 => 0b1011,
+        176_400 => 0b0001,
+        192_000 => 0b0010,
+        // Taken from STREAMINFO
+        _ => 0b0000,
+    }
+}
+
+/// Frame numbers use UTF-8's variable-length layout, extended to 36 bits.
+fn write_utf8_number(bits: &mut BitWriter, number: u64) {
+    if number < 0x80 {
+        bits.write(number, 8);
+        return;
+    }
+    // Each continuation byte carries 6 bits and takes one from the lead byte
+    let continuation_bytes = (1..=6).find(|&count| number < 1 << (6 + 5 * count)).unwrap_or(6);
+    let marker = (0xFF00u64 >> (continuation_bytes + 1)) & 0xFF;
+    bits.write(marker | (number >> (6 * continuation_bytes)), 8);
+    for idx in (0..continuation_bytes).rev() {
+        bits.write(0x80 | ((number >> (6 * idx)) & 0x3F), 8);
+    }
+}
+
+fn write_subframe(bits: &mut BitWriter, signal: &[i64]) {
+    let sample_bits = BITS_PER_SAMPLE as u32;
+    if signal.windows(2).all(|pair| pair[0] == pair[1]) {
+        write_subframe_header(bits, SUBFRAME_CONSTANT);
+        bits.write_signed(signal[0], sample_bits);
+        return;
+    }
+    let verbatim_bits = signal.len() as u64 * sample_bits as u64;
+    let best = (0..=MAX_FIXED_ORDER.min(signal.len() - 1))
+        .map(|order| {
+            let residuals = fixed_residuals(signal, order);
+            let (rice_param, rice_bits) = best_rice_param(&residuals);
+            let total = order as u64 * sample_bits as u64 + 2 + 4 + 4 + rice_bits;
+            (total, order, rice_param, residuals)
+        })
+        .min_by_key(|(total, ..)| *total);
+    match best {
+        Some((total, order, rice_param, residuals)) if total < verbatim_bits => {
+            write_subframe_header(bits, SUBFRAME_FIXED | order as u64);
+            for &warm_up in &signal[..order] {
+                bits.write_signed(warm_up, sample_bits);
+            }
+            bits.write(0b00, 2); // Rice coding, 4-bit parameters
+            bits.write(0, 4); // partition order 0: one partition
+            bits.write(rice_param as u64, 4);
+            for &residual in &residuals {
+                bits.write_rice(residual, rice_param);
+            }
+        }
+        _ => {
+            write_subframe_header(bits, SUBFRAME_VERBATIM);
+            for &sample in signal {
+                bits.write_signed(sample, sample_bits);
+            }
+        }
+    }
+}
+
+fn write_subframe_header(bits: &mut BitWriter, subframe_type: u64) {
+    bits.write(0, 1); // zero pad
+    bits.write(subframe_type, 6);
+    bits.write(0, 1); // no wasted bits
+}
+
+/// What's left after predicting each sample from the `order` before it
+/// (the order-th difference of the signal).
+fn fixed_residuals(signal: &[i64], order: usize) -> Vec<i64> {
+    let mut residuals = signal.to_vec();
+    for _ in 0..order {
+        residuals = residuals.windows(2).map(|pair| pair[1] - pair[0]).collect();
+    }
+    residuals
+}
+
+/// Rice parameter needing the fewest bits for `residuals`, and that count.
+/// The optimum sits near log2 of the mean magnitude, so only its
+/// neighbours are tried.
+fn best_rice_param(residuals: &[i64]) -> (u32, u64) {
+    let sum: u64 = residuals.iter().map(|&residual| zigzag(residual)).sum();
+    let mean = sum / residuals.len().max(1) as u64;
+    let estimate = (u64::BITS - mean.leading_zeros()).min(MAX_RICE_PARAM);
+    (estimate.saturating_sub(1)..=(estimate + 1).min(MAX_RICE_PARAM))
+        .map(|param| {
+            let bits: u64 = residuals.iter().map(|&residual| (zigzag(residual) >> param) + 1 + param as u64).sum();
+            (param, bits)
+        })
+        .min_by_key(|&(_, bits)| bits)
+        .unwrap_or((0, 0))
+}
+
+/// Folds signed residuals onto unsigned: 0, -1, 1, -2, 2 → 0, 1, 2, 3, 4.
+fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+/// CRC-8, polynomial x^8 + x^2 + x + 1, over the frame header.
+fn crc8(bytes: &[u8]) -> u8 {
+    bytes.iter().fold(0u8, |crc, &byte| {
+        (0..8).fold(crc ^ byte, |crc, _| if crc & 0x80 != 0 { (crc << 1) ^ 0x07 } else { crc << 1 })
+    })
+}
+
+/// CRC-16, polynomial x^16 + x^15 + x^2 + 1, over the whole frame.
+fn crc16(bytes: &[u8]) -> u16 {
+    bytes.iter().fold(0u16, |crc, &byte| {
+        (0..8).fold(crc ^ ((byte as u16) << 8), |crc, _| if crc & 0x8000 != 0 { (crc << 1) ^ 0x8005 } else { crc << 1 })
+    })
+}
+
+/// Packs values MSB-first.
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    pending: u64,
+    pending_bits: u32,
+}
+
+impl BitWriter {
+    /// Appends the low `count` bits of `value` (count ≤ 56).
+    fn write(&mut self, value: u64, count: u32) {
+        if count > 32 {
+            self.write(value >> 32, count - 32);
+            self.write(value & 0xFFFF_FFFF, 32);
+            return;
+        }
+        self.pending = (self.pending << count) | (value & ((1 << count) - 1));
+        self.pending_bits += count;
+        while self.pending_bits >= 8 {
+            self.pending_bits -= 8;
+            self.bytes.push((self.pending >> self.pending_bits) as u8);
+        }
+        self.pending &= (1 << self.pending_bits) - 1;
+    }
+
+    fn write_signed(&mut self, value: i64, count: u32) {
+        self.write(value as u64, count);
+    }
+
+    /// Quotient in unary (zeros closed by a one), then `param` low bits.
+    fn write_rice(&mut self, value: i64, param: u32) {
+        let folded = zigzag(value);
+        let mut quotient = folded >> param;
+        while quotient >= 32 {
+            self.write(0, 32);
+            quotient -= 32;
+        }
+        self.write(1, quotient as u32 + 1);
+        self.write(folded, param);
+    }
+
+    /// Bytes written so far, excluding a partial last byte.
+    fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Pads to a byte boundary with zeros and returns the bytes.
+    fn finish(mut self) -> Vec<u8> {
+        if self.pending_bits > 0 {
+            self.write(0, 8 - self.pending_bits);
+        }
+        self.bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::SAMPLE_RATE;
+
+    #[test]
+    fn starts_with_marker_and_streaminfo() {
+        let flac = encode(&[0; 100], 1, SAMPLE_RATE);
+        assert_eq!(&flac[0..4], b"fLaC");
+        assert_eq!(flac[4], 0x80); // last block, STREAMINFO
+        assert_eq!(&flac[5..8], &[0, 0, 34]);
+        // Sample rate, channels and bit depth packed at bytes 18–20
+        assert_eq!(u32::from_be_bytes([0, flac[18], flac[19], flac[20]]) >> 4, SAMPLE_RATE);
+    }
+
+    #[test]
+    fn other_rates_go_in_streaminfo_and_frame_headers() {
+        let flac = encode(&[0; 100], 1, 48_000);
+        assert_eq!(u32::from_be_bytes([0, flac[18], flac[19], flac[20]]) >> 4, 48_000);
+        // The first frame header follows STREAMINFO; its byte 2 holds the
+        // block size and rate codes
+        assert_eq!(flac[4 + 38 + 2] & 0x0F, 0b1010);
+        // Rates without a code are read from STREAMINFO
+        assert_eq!(sample_rate_code(44_000), 0b0000);
+    }
+
+    #[test]
+    fn silence_is_constant_subframes() {
+        let flac = encode(&vec![0; BLOCK_SIZE * 3], 1, SAMPLE_RATE);
+        // STREAMINFO, then three frames: 8-byte header, 3-byte subframe, CRC-16
+        assert!(flac.len() < 4 + 38 + 3 * 16, "{} bytes", flac.len());
+    }
+
+    #[test]
+    fn fixed_predictors_shrink_smooth_signals() {
+        let tone: Vec<i16> = (0..BLOCK_SIZE).map(|idx| ((idx as f64 * 0.05).sin() * 10_000.0) as i16).collect();
+        let raw_bytes = tone.len() * 2;
+        assert!(encode(&tone, 1, SAMPLE_RATE).len() < raw_bytes / 2);
+    }
+
+    #[test]
+    fn residuals_are_differences() {
+        assert_eq!(fixed_residuals(&[1, 4, 9, 16], 1), vec![3, 5, 7]);
+        assert_eq!(fixed_residuals(&[1, 4, 9, 16], 2), vec![2, 2]);
+    }
+
+    #[test]
+    fn zigzag_interleaves_signs() {
+        assert_eq!([0, -1, 1, -2, 2].map(zigzag), [0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn crcs_match_reference_values() {
+        // "123456789" check values of CRC-8/SMBUS and CRC-16/UMTS
+        assert_eq!(crc8(b"123456789"), 0xF4);
+        assert_eq!(crc16(b"123456789"), 0xFEE8);
+    }
+
+    #[test]
+    fn frame_numbers_use_utf8_layout() {
+        let encode_number = |number| {
+            let mut bits = BitWriter::default();
+            write_utf8_number(&mut bits, number);
+            bits.finish()
+        };
+        assert_eq!(encode_number(0x7F), vec![0x7F]);
+        assert_eq!(encode_number(0x80), vec![0xC2, 0x80]);
+        assert_eq!(encode_number(0x800), vec![0xE0, 0xA0, 0x80]);
+    }
+}
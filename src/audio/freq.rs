@@ -22,9 +22,61 @@
 //!       a   b   c   d   e   f   g   h
 //! ```
 //!
+//! # Octave Folding
+//!
+//! Eight ranks span eight octaves, from 33 Hz to 8 kHz. Folding packs them
+//! into a narrower window centered on the middle of the board, keeping
+//! files as notes and merging neighbouring ranks into shared octaves, so
+//! higher ranks still never sound lower:
+//!
+//! ```text
+//! rank:        1  2  3  4  5  6  7  8
+//! unfolded:    1  2  3  4  5  6  7  8   (octave of the a-file C)
+//! 4 octaves:   3  3  4  4  5  5  6  6   (C3–C7)
+//! 3 octaves:   3  3  3  4  4  4  5  5   (C3–C6)
+//! ```
+//!
+//! # Chromatic Layout
+//!
+//! [`Layout::Chromatic`] instead gives every square its own semitone, rank
+//! by rank from C2 at a1 up to D♯7 at h8, so a square can be named by ear:
+//!
+//! ```text
+//!   2 │ G#2 A2  A#2 B2  C3  C#3 D3  D#3
+//!   1 │ C2  C#2 D2  D#2 E2  F2  F#2 G2
+//!     └─────────────────────────────────
+//!       a   b   c   d   e   f   g   h
+//! ```
+//!
 //! # Equal Temperament
 //!
 //! Frequency formula: f = 440 × 2^(semitones_from_A4 / 12)
+//!
+//! # Temperaments
+//!
+//! Equal temperament spaces all twelve semitones evenly. [`Tuning::Just`]
+//! and [`Tuning::Pythagorean`] instead use whole-number ratios above the
+//! tonic (C, or the key's tonic), which ring purer in that key and beat
+//! audibly in others:
+//!
+//! ```text
+//! degree:       1     3      5     ...   e.g. C–E–G
+//! equal:        1   1.260  1.498
+//! just:         1    5/4    3/2
+//! pythagorean:  1   81/64   3/2
+//! ```
+//!
+//! # Scale Quantization
+//!
+//! With a [`Key`], each pitch snaps to the nearest tone of that key's scale
+//! (ties go down), so a whole game stays in one key:
+//!
+//! ```text
+//! file:           a   b   c   d   e   f   g   h
+//! C major:        C   D   E   F   G   A   B   C    (unchanged)
+//! C minor:        C   D   E♭  F   G   A♭  B♭  C
+//! C pentatonic:   C   D   E   E   G   A   C   C
+//! ```
 
 use crate::engine::chess::Square;
 
@@ -52,10 +104,247 @@ const FILE_SEMITONES: [i32; 8] = [
     12, // h → C (octave up)
 ];
 
-/// Converts a board square to its frequency in Hz using equal temperament.
-pub fn from_square(square: &Square) -> u32 {
-    let semitones = semitones_from_a4(square);
-    frequency_from_semitones(semitones)
+/// Names of the twelve pitch classes from C, with sharps.
+const PITCH_NAMES: [&str; 12] = ["C", "C#", "D", "D#", "E", "F", "F#", "G", "G#", "A", "A#", "B"];
+
+/// The octave number of A4, which names the octave from C4 up.
+const REFERENCE_OCTAVE: i32 = 4;
+
+/// Frequency ratios above the tonic for each semitone of the octave.
+const JUST_RATIOS: [f64; 12] = [
+    1.0,
+    16.0 / 15.0,
+    9.0 / 8.0,
+    6.0 / 5.0,
+    5.0 / 4.0,
+    4.0 / 3.0,
+    45.0 / 32.0,
+    3.0 / 2.0,
+    8.0 / 5.0,
+    5.0 / 3.0,
+    9.0 / 5.0,
+    15.0 / 8.0,
+];
+const PYTHAGOREAN_RATIOS: [f64; 12] = [
+    1.0,
+    256.0 / 243.0,
+    9.0 / 8.0,
+    32.0 / 27.0,
+    81.0 / 64.0,
+    4.0 / 3.0,
+    729.0 / 512.0,
+    3.0 / 2.0,
+    128.0 / 81.0,
+    27.0 / 16.0,
+    16.0 / 9.0,
+    243.0 / 128.0,
+];
+
+/// Octaves `--fold` packs the ranks into unless told otherwise (C3–C6).
+pub const DEFAULT_FOLD_OCTAVES: u32 = 3;
+
+/// Ranks on the board, i.e. octaves spanned without folding.
+const RANKS: u32 = 8;
+
+/// Semitones from A4 down to C2, the chromatic layout's a1.
+const CHROMATIC_A1_FROM_A4: i32 = -33;
+
+/// Squares per rank, i.e. semitones per rank in the chromatic layout.
+const FILES: i32 = 8;
+
+/// How squares are laid out on the pitch axis.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Layout {
+    /// Files are the C major scale, ranks are octaves (see the module docs)
+    #[default]
+    Diatonic,
+    /// Every square one semitone above the previous, a1 lowest, h8 highest
+    Chromatic,
+}
+
+impl Layout {
+    /// Parses a layout name as given in a theme, e.g. "chromatic".
+    pub fn from_name(name: &str) -> Option<Layout> {
+        match name {
+            "diatonic" => Some(Layout::Diatonic),
+            "chromatic" => Some(Layout::Chromatic),
+            _ => None,
+        }
+    }
+}
+
+impl Layout {
+    /// Semitones from A4 of `square`'s pitch.
+    fn semitones_from_a4(self, square: &Square) -> i32 {
+        match self {
+            Layout::Diatonic => semitones_from_a4(square),
+            Layout::Chromatic => CHROMATIC_A1_FROM_A4 + square.rank as i32 * FILES + square.file as i32,
+        }
+    }
+}
+
+/// Temperament used to turn semitones into frequencies.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Tuning {
+    #[default]
+    Equal,
+    /// 5-limit just intonation (pure thirds and fifths)
+    Just,
+    /// Stacked pure fifths (3:2)
+    Pythagorean,
+}
+
+impl Tuning {
+    /// Parses a tuning name as given on the command line, e.g. "just".
+    pub fn from_name(name: &str) -> Option<Tuning> {
+        match name {
+            "equal" => Some(Tuning::Equal),
+            "just" => Some(Tuning::Just),
+            "pythagorean" => Some(Tuning::Pythagorean),
+            _ => None,
+        }
+    }
+
+    /// Ratio of `degree` semitones (0-11) above the tonic.
+    fn ratio(self, degree: i32) -> f64 {
+        match self {
+            Tuning::Equal => 2.0_f64.powf(degree as f64 / SEMITONES_PER_OCTAVE as f64),
+            Tuning::Just => JUST_RATIOS[degree as usize],
+            Tuning::Pythagorean => PYTHAGOREAN_RATIOS[degree as usize],
+        }
+    }
+}
+
+/// Intervals of the scales a [`Key`] can quantize to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Scale {
+    Major,
+    NaturalMinor,
+    Pentatonic,
+    Blues,
+    WholeTone,
+}
+
+impl Scale {
+    /// Parses a scale name as given on the command line, e.g. "minor".
+    pub fn from_name(name: &str) -> Option<Scale> {
+        match name {
+            "major" => Some(Scale::Major),
+            "minor" | "natural-minor" => Some(Scale::NaturalMinor),
+            "pentatonic" => Some(Scale::Pentatonic),
+            "blues" => Some(Scale::Blues),
+            "whole-tone" => Some(Scale::WholeTone),
+            _ => None,
+        }
+    }
+
+    /// Semitones above the tonic of each scale tone.
+    fn degrees(self) -> &'static [i32] {
+        match self {
+            Scale::Major => &[0, 2, 4, 5, 7, 9, 11],
+            Scale::NaturalMinor => &[0, 2, 3, 5, 7, 8, 10],
+            Scale::Pentatonic => &[0, 2, 4, 7, 9],
+            Scale::Blues => &[0, 3, 5, 6, 7, 10],
+            Scale::WholeTone => &[0, 2, 4, 6, 8, 10],
+        }
+    }
+}
+
+/// A scale rooted on a tonic, given in semitones above C (0-11).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Key {
+    pub tonic: i32,
+    pub scale: Scale,
+}
+
+impl Key {
+    /// Builds a key from a note name such as "C", "F#" or "Bb".
+    pub fn from_tonic_name(name: &str, scale: Scale) -> Option<Key> {
+        let mut chars = name.chars();
+        let natural: i32 = match chars.next()?.to_ascii_uppercase() {
+            'C' => 0,
+            'D' => 2,
+            'E' => 4,
+            'F' => 5,
+            'G' => 7,
+            'A' => 9,
+            'B' => 11,
+            _ => return None,
+        };
+        let accidental = match chars.as_str() {
+            "" => 0,
+            "#" => 1,
+            "b" => -1,
+            _ => return None,
+        };
+        Some(Key {
+            tonic: (natural + accidental).rem_euclid(SEMITONES_PER_OCTAVE),
+            scale,
+        })
+    }
+
+    /// Moves `semitones` (counted from any C) to the nearest scale tone,
+    /// preferring the lower one on ties.
+    fn snap(&self, semitones: i32) -> i32 {
+        let above_tonic = (semitones - self.tonic).rem_euclid(SEMITONES_PER_OCTAVE);
+        // The next octave's tonic closes the gap above the last degree
+        let nearest = self
+            .scale
+            .degrees()
+            .iter()
+            .copied()
+            .chain([SEMITONES_PER_OCTAVE])
+            .min_by_key(|degree| ((degree - above_tonic).abs(), *degree))
+            .unwrap_or(above_tonic);
+        semitones - above_tonic + nearest
+    }
+}
+
+/// How squares become pitches. The default is the diatonic C mapping above.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct PitchMap {
+    /// Where each square sits on the pitch axis
+    pub layout: Layout,
+    /// Snap every pitch into this key
+    pub key: Option<Key>,
+    /// Temperament, with ratios taken from the key's tonic (C without a key)
+    pub tuning: Tuning,
+    /// Fold the ranks into this many octaves (1-8). Applies to the diatonic
+    /// layout only, as folding would give chromatic squares shared pitches.
+    pub fold_octaves: Option<u32>,
+}
+
+impl PitchMap {
+    /// Frequency in Hz of `square` under this mapping.
+    pub fn freq(&self, square: &Square) -> u32 {
+        let tonic = self.key.map(|key| key.tonic).unwrap_or(0);
+        frequency_from_semitones(self.semitones_from_a4(square), self.tuning, tonic)
+    }
+
+    /// Scientific pitch name of `square`'s note under this mapping, e.g.
+    /// "A4". The tuning bends the frequency, not the name.
+    pub fn note_name(&self, square: &Square) -> String {
+        let from_c4 = self.semitones_from_a4(square) + A_SEMITONES_FROM_C;
+        let octave = REFERENCE_OCTAVE + from_c4.div_euclid(SEMITONES_PER_OCTAVE);
+        format!("{}{octave}", PITCH_NAMES[from_c4.rem_euclid(SEMITONES_PER_OCTAVE) as usize])
+    }
+
+    /// Semitones from A4 of `square`'s pitch, folded and snapped into the key.
+    fn semitones_from_a4(&self, square: &Square) -> i32 {
+        let semitones = match (self.layout, self.fold_octaves) {
+            (Layout::Diatonic, Some(octaves)) => semitones_from_a4(&fold(square, octaves)),
+            (layout, _) => layout.semitones_from_a4(square),
+        };
+        match &self.key {
+            Some(key) => key.snap(semitones + A_SEMITONES_FROM_C) - A_SEMITONES_FROM_C,
+            None => semitones,
+        }
+    }
+}
+
+/// Scientific pitch name of `square` on the default mapping: f4 is "A4".
+pub fn note_name(square: &Square) -> String {
+    PitchMap::default().note_name(square)
 }
 
 /// Calculates the number of semitones from A4 for a given square.
@@ -71,8 +360,27 @@ fn semitones_from_a4(square: &Square) -> i32 {
     file_semitones + rank_semitones - A_SEMITONES_FROM_C
 }
 
-fn frequency_from_semitones(semitones: i32) -> u32 {
-    let freq = A4_FREQ * 2.0_f64.powf(semitones as f64 / SEMITONES_PER_OCTAVE as f64);
+/// Moves `square` to the rank whose octave it sounds in once the board's
+/// ranks are folded into `octaves` octaves around the middle ranks.
+fn fold(square: &Square, octaves: u32) -> Square {
+    let octaves = octaves.clamp(1, RANKS);
+    let lowest_rank = RANKS / 2 - octaves.div_ceil(2);
+    let rank = lowest_rank + square.rank as u32 * octaves / RANKS;
+    Square {
+        file: square.file,
+        rank: rank as u8,
+    }
+}
+
+/// Frequency of the pitch `semitones` from A4, tuned relative to `tonic`
+/// (semitones above C). The tonic itself stays at its equal-tempered pitch.
+fn frequency_from_semitones(semitones: i32, tuning: Tuning, tonic: i32) -> u32 {
+    let tonic_from_a4 = tonic - A_SEMITONES_FROM_C;
+    let tonic_freq = A4_FREQ * 2.0_f64.powf(tonic_from_a4 as f64 / SEMITONES_PER_OCTAVE as f64);
+    let above_tonic = semitones - tonic_from_a4;
+    let octaves = above_tonic.div_euclid(SEMITONES_PER_OCTAVE);
+    let degree = above_tonic.rem_euclid(SEMITONES_PER_OCTAVE);
+    let freq = tonic_freq * 2.0_f64.powi(octaves) * tuning.ratio(degree);
     freq.round() as u32
 }
 
@@ -80,6 +388,10 @@ fn frequency_from_semitones(semitones: i32) -> u32 {
 mod tests {
     use super::*;
 
+    fn from_square(square: &Square) -> u32 {
+        PitchMap::default().freq(square)
+    }
+
     #[test]
     fn a4_is_440() {
         let f4 = Square { file: 5, rank: 3 }; // f4 → A4
@@ -137,4 +449,214 @@ mod tests {
         let h8 = Square { file: 7, rank: 7 }; // h8 → C9
         assert_eq!(from_square(&h8), 8372);
     }
+
+    fn key(tonic: &str, scale: Scale) -> Key {
+        Key::from_tonic_name(tonic, scale).unwrap()
+    }
+
+    fn file_freqs(map: PitchMap) -> Vec<u32> {
+        (0..8).map(|file| map.freq(&Square { file, rank: 3 })).collect()
+    }
+
+    #[test]
+    fn note_names_follow_the_mapping() {
+        assert_eq!(note_name(&Square { file: 5, rank: 3 }), "A4");
+        assert_eq!(note_name(&Square { file: 0, rank: 0 }), "C1");
+        assert_eq!(note_name(&Square { file: 7, rank: 7 }), "C9");
+        assert_eq!(chromatic().note_name(&Square { file: 1, rank: 0 }), "C#2");
+        assert_eq!(folded(3).note_name(&Square { file: 0, rank: 0 }), "C3");
+        let in_d = PitchMap { key: Key::from_tonic_name("D", Scale::Major), ..PitchMap::default() };
+        // F snaps down to E in D major, whose third is F#
+        assert_eq!(in_d.note_name(&Square { file: 3, rank: 3 }), "E4");
+    }
+
+    #[test]
+    fn c_major_leaves_mapping_unchanged() {
+        let map = PitchMap { key: Some(key("C", Scale::Major)), ..PitchMap::default() };
+        assert_eq!(file_freqs(map), file_freqs(PitchMap::default()));
+    }
+
+    #[test]
+    fn c_minor_flattens_third_sixth_and_seventh() {
+        let map = PitchMap { key: Some(key("C", Scale::NaturalMinor)), ..PitchMap::default() };
+        // C D E♭ F G A♭ B♭ C
+        assert_eq!(file_freqs(map), vec![262, 294, 311, 349, 392, 415, 466, 523]);
+    }
+
+    #[test]
+    fn pentatonic_snaps_to_nearest_lower_on_ties() {
+        let map = PitchMap { key: Some(key("C", Scale::Pentatonic)), ..PitchMap::default() };
+        // C D E E G A C C
+        assert_eq!(file_freqs(map), vec![262, 294, 330, 330, 392, 440, 523, 523]);
+    }
+
+    #[test]
+    fn every_scale_lands_on_its_own_tones() {
+        for scale in [Scale::Major, Scale::NaturalMinor, Scale::Pentatonic, Scale::Blues, Scale::WholeTone] {
+            let key = key("F#", scale);
+            for semitones in -30..30 {
+                let above_tonic = (key.snap(semitones) - key.tonic).rem_euclid(SEMITONES_PER_OCTAVE);
+                assert!(scale.degrees().contains(&above_tonic), "{scale:?} snapped {semitones} off-scale");
+            }
+        }
+    }
+
+    #[test]
+    fn snapping_moves_at_most_a_semitone() {
+        let key = key("C", Scale::Blues);
+        assert!((-30..30).all(|semitones| (key.snap(semitones) - semitones).abs() <= 1));
+    }
+
+    #[test]
+    fn tonic_names_with_accidentals() {
+        assert_eq!(key("F#", Scale::Major).tonic, 6);
+        assert_eq!(key("Bb", Scale::Major).tonic, 10);
+        assert_eq!(key("Cb", Scale::Major).tonic, 11);
+        assert_eq!(key("d", Scale::Major).tonic, 2);
+        assert_eq!(Key::from_tonic_name("H", Scale::Major), None);
+        assert_eq!(Key::from_tonic_name("C##", Scale::Major), None);
+    }
+
+    #[test]
+    fn scale_names() {
+        assert_eq!(Scale::from_name("minor"), Some(Scale::NaturalMinor));
+        assert_eq!(Scale::from_name("whole-tone"), Some(Scale::WholeTone));
+        assert_eq!(Scale::from_name("dorian"), None);
+    }
+
+    fn tuned(tuning: Tuning, key: Option<Key>) -> PitchMap {
+        PitchMap {
+            key,
+            tuning,
+            ..PitchMap::default()
+        }
+    }
+
+    #[test]
+    fn equal_tuning_ignores_tonic() {
+        let in_d = tuned(Tuning::Equal, Some(key("D", Scale::Major)));
+        let f4 = Square { file: 5, rank: 3 };
+        assert_eq!(in_d.freq(&f4), 440);
+    }
+
+    #[test]
+    fn just_intonation_has_pure_third_and_fifth() {
+        let just = tuned(Tuning::Just, None);
+        let c4 = just.freq(&Square { file: 0, rank: 3 });
+        assert_eq!(c4, 262);
+        // E4 = C4 × 5/4 and G4 = C4 × 3/2, against 330 and 392 equal-tempered
+        assert_eq!(just.freq(&Square { file: 2, rank: 3 }), 327);
+        assert_eq!(just.freq(&Square { file: 4, rank: 3 }), 392);
+    }
+
+    #[test]
+    fn pythagorean_third_is_wide() {
+        let pythagorean = tuned(Tuning::Pythagorean, None);
+        // C4 × 81/64
+        assert_eq!(pythagorean.freq(&Square { file: 2, rank: 3 }), 331);
+    }
+
+    #[test]
+    fn just_ratios_follow_the_tonic() {
+        let in_a = tuned(Tuning::Just, Some(key("A", Scale::Major)));
+        // A4 stays put; C#5 is its major third (× 5/4), F#4 the sixth above A3 (× 5/3)
+        assert_eq!(in_a.freq(&Square { file: 5, rank: 3 }), 440);
+        assert_eq!(frequency_from_semitones(4, Tuning::Just, 9), 550);
+        assert_eq!(frequency_from_semitones(-3, Tuning::Just, 9), 367);
+    }
+
+    #[test]
+    fn tunings_agree_on_tonic_octaves() {
+        for tuning in [Tuning::Equal, Tuning::Just, Tuning::Pythagorean] {
+            assert_eq!(tuned(tuning, None).freq(&Square { file: 0, rank: 0 }), 33);
+            assert_eq!(tuned(tuning, None).freq(&Square { file: 7, rank: 7 }), 8372);
+        }
+    }
+
+    #[test]
+    fn tuning_names() {
+        assert_eq!(Tuning::from_name("just"), Some(Tuning::Just));
+        assert_eq!(Tuning::from_name("pythagorean"), Some(Tuning::Pythagorean));
+        assert_eq!(Tuning::from_name("meantone"), None);
+    }
+
+    #[test]
+    fn layout_names() {
+        assert_eq!(Layout::from_name("chromatic"), Some(Layout::Chromatic));
+        assert_eq!(Layout::from_name("diatonic"), Some(Layout::Diatonic));
+        assert_eq!(Layout::from_name("spiral"), None);
+    }
+
+    fn chromatic() -> PitchMap {
+        PitchMap {
+            layout: Layout::Chromatic,
+            ..PitchMap::default()
+        }
+    }
+
+    #[test]
+    fn chromatic_spans_c2_to_d_sharp_7() {
+        assert_eq!(chromatic().freq(&Square { file: 0, rank: 0 }), 65);
+        assert_eq!(chromatic().freq(&Square { file: 7, rank: 7 }), 2489);
+    }
+
+    #[test]
+    fn chromatic_gives_every_square_its_own_pitch() {
+        let freqs: Vec<u32> = (0..8)
+            .flat_map(|rank| (0..8).map(move |file| Square { file, rank }))
+            .map(|square| chromatic().freq(&square))
+            .collect();
+        assert!(freqs.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn chromatic_rank_continues_from_previous() {
+        let h1 = chromatic().freq(&Square { file: 7, rank: 0 });
+        let a2 = chromatic().freq(&Square { file: 0, rank: 1 });
+        // G2 → G#2
+        assert_eq!((h1, a2), (98, 104));
+    }
+
+    fn folded(octaves: u32) -> PitchMap {
+        PitchMap {
+            fold_octaves: Some(octaves),
+            ..PitchMap::default()
+        }
+    }
+
+    #[test]
+    fn fold_to_three_octaves_spans_c3_to_c6() {
+        assert_eq!(folded(3).freq(&Square { file: 0, rank: 0 }), 131);
+        assert_eq!(folded(3).freq(&Square { file: 7, rank: 7 }), 1047);
+    }
+
+    #[test]
+    fn fold_to_four_octaves_spans_c3_to_c7() {
+        assert_eq!(folded(4).freq(&Square { file: 0, rank: 0 }), 131);
+        assert_eq!(folded(4).freq(&Square { file: 7, rank: 7 }), 2093);
+    }
+
+    #[test]
+    fn fold_keeps_contour() {
+        for octaves in 1..=8 {
+            let by_rank: Vec<u32> = (0..8).map(|rank| folded(octaves).freq(&Square { file: 4, rank })).collect();
+            assert!(by_rank.windows(2).all(|pair| pair[0] <= pair[1]), "{octaves} octaves: {by_rank:?}");
+        }
+    }
+
+    #[test]
+    fn fold_to_eight_octaves_is_identity() {
+        let square = Square { file: 2, rank: 6 };
+        assert_eq!(folded(8).freq(&square), from_square(&square));
+    }
+
+    #[test]
+    fn fold_leaves_chromatic_layout_alone() {
+        let map = PitchMap {
+            layout: Layout::Chromatic,
+            fold_octaves: Some(3),
+            ..PitchMap::default()
+        };
+        assert_eq!(map.freq(&Square { file: 7, rank: 7 }), chromatic().freq(&Square { file: 7, rank: 7 }));
+    }
 }
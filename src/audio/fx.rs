@@ -0,0 +1,266 @@
+//! Effects applied to the final mixed sample stream.
+//!
+//! All effects work on the floating-point mix bus (full scale = ±1.0), so
+//! layered notes may exceed full scale here without clipping; [`normalize`]
+//! and [`soft_limit`] bring them back before quantization to 16-bit.
+//!
+//! # Reverb
+//!
+//! A Schroeder/Freeverb-style reverb: four parallel comb filters build a
+//! dense field of decaying echoes, then two series all-pass filters smear
+//! them so individual echoes are no longer audible.
+//!
+//! ```text
+//!          ┌─→ [comb 1116] ─┐
+//!          ├─→ [comb 1188] ─┤
+//! input ───┼─→ [comb 1277] ─┼─→ Σ ─→ [allpass 556] ─→ [allpass 441] ─→ wet
+//!    │     └─→ [comb 1356] ─┘
+//!    └──────────────────────────────────────────────────────────────→ dry
+//!
+//! output = dry × (1 - wet) + wet_signal × wet
+//! ```
+//!
+//! Comb feedback grows with `room_size`, making echoes last longer. A
+//! low-pass inside each comb loop (damping) dulls late reflections the way
+//! soft surfaces absorb high frequencies in a real room.
+//!
+//! # Dynamics
+//!
+//! ```text
+//! normalize: scale everything so the loudest peak hits the target
+//! amplify: scale everything by a fixed master gain
+//! soft_limit: pass quiet samples untouched, bend loud ones smoothly
+//!
+//! out │           ╭──────── 1.0      (hard clip would be a sharp corner)
+//!     │        ╭─╯
+//!     │      ╱  ← threshold
+//!     │    ╱
+//!     │  ╱
+//!     └──────────────────→ in
+//! ```
+
+use super::{MS_PER_SECOND, SAMPLE_RATE};
+
+/// Comb delay lengths in samples (Freeverb's tuning at 44.1 kHz). Mutually
+/// prime-ish so their echoes don't line up into a metallic ring.
+const COMB_DELAYS: [usize; 4] = [1116, 1188, 1277, 1356];
+const ALLPASS_DELAYS: [usize; 2] = [556, 441];
+const ALLPASS_FEEDBACK: f64 = 0.5;
+const COMB_DAMPING: f64 = 0.2;
+
+/// Extra audio appended after the last note so the reverb can ring out.
+const TAIL_MS: u32 = 1000;
+
+/// Default normalization target: -1 dBFS leaves headroom for lossy encoders.
+pub const DEFAULT_PEAK: f64 = 0.89;
+
+/// Level above which the soft limiter starts bending the signal.
+const LIMIT_THRESHOLD: f64 = 0.8;
+
+/// Reverb parameters.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Reverb {
+    /// Perceived room size (0.0 = small/dry decay, 1.0 = hall)
+    pub room_size: f64,
+    /// Wet/dry ratio (0.0 = dry only, 1.0 = reverb only)
+    pub wet: f64,
+}
+
+impl Default for Reverb {
+    fn default() -> Self {
+        Self {
+            room_size: 0.5,
+            wet: 0.25,
+        }
+    }
+}
+
+impl Reverb {
+    /// Applies the reverb to a mono sample stream, appending a tail so the
+    /// last note's reflections are not cut off.
+    pub fn apply(&self, samples: &[f64]) -> Vec<f64> {
+        let feedback = 0.7 + 0.28 * self.room_size.clamp(0.0, 1.0);
+        let mut combs: Vec<Comb> = COMB_DELAYS.iter().map(|&delay| Comb::new(delay, feedback)).collect();
+        let mut allpasses: Vec<Allpass> = ALLPASS_DELAYS.iter().map(|&delay| Allpass::new(delay)).collect();
+        let tail = (SAMPLE_RATE * TAIL_MS / MS_PER_SECOND) as usize;
+
+        samples
+            .iter()
+            .copied()
+            .chain(std::iter::repeat_n(0.0, tail))
+            .map(|dry| {
+                let comb_sum: f64 = combs.iter_mut().map(|comb| comb.process(dry)).sum();
+                let diffused = allpasses
+                    .iter_mut()
+                    .fold(comb_sum / COMB_DELAYS.len() as f64, |signal, allpass| allpass.process(signal));
+                dry * (1.0 - self.wet) + diffused * self.wet
+            })
+            .collect()
+    }
+}
+
+/// Scales the whole stream so its loudest sample reaches `target_peak`.
+/// Silence is returned unchanged.
+pub fn normalize(samples: &[f64], target_peak: f64) -> Vec<f64> {
+    let peak = samples.iter().fold(0.0, |loudest: f64, sample| loudest.max(sample.abs()));
+    if peak == 0.0 {
+        return samples.to_vec();
+    }
+    let gain = target_peak / peak;
+    samples.iter().map(|sample| sample * gain).collect()
+}
+
+/// Scales the whole stream by `gain` (1.0 = unchanged).
+pub fn amplify(samples: &[f64], gain: f64) -> Vec<f64> {
+    samples.iter().map(|sample| sample * gain).collect()
+}
+
+/// Compresses samples above the threshold with a tanh curve so they
+/// approach full scale asymptotically instead of clipping.
+pub fn soft_limit(samples: &[f64]) -> Vec<f64> {
+    let headroom = 1.0 - LIMIT_THRESHOLD;
+    samples
+        .iter()
+        .map(|&sample| {
+            let level = sample.abs();
+            if level <= LIMIT_THRESHOLD {
+                sample
+            } else {
+                let bent = LIMIT_THRESHOLD + headroom * ((level - LIMIT_THRESHOLD) / headroom).tanh();
+                bent.copysign(sample)
+            }
+        })
+        .collect()
+}
+
+/// Feedback comb filter with a damping low-pass in the loop.
+struct Comb {
+    buffer: Vec<f64>,
+    position: usize,
+    feedback: f64,
+    damped: f64,
+}
+
+impl Comb {
+    fn new(delay: usize, feedback: f64) -> Self {
+        Self {
+            buffer: vec![0.0; delay],
+            position: 0,
+            feedback,
+            damped: 0.0,
+        }
+    }
+
+    fn process(&mut self, input: f64) -> f64 {
+        let output = self.buffer[self.position];
+        self.damped = output * (1.0 - COMB_DAMPING) + self.damped * COMB_DAMPING;
+        self.buffer[self.position] = input + self.damped * self.feedback;
+        self.position = (self.position + 1) % self.buffer.len();
+        output
+    }
+}
+
+/// Schroeder all-pass: passes all frequencies at equal gain but scrambles
+/// their phase, diffusing the comb echoes.
+struct Allpass {
+    buffer: Vec<f64>,
+    position: usize,
+}
+
+impl Allpass {
+    fn new(delay: usize) -> Self {
+        Self {
+            buffer: vec![0.0; delay],
+            position: 0,
+        }
+    }
+
+    fn process(&mut self, input: f64) -> f64 {
+        let delayed = self.buffer[self.position];
+        self.buffer[self.position] = input + delayed * ALLPASS_FEEDBACK;
+        self.position = (self.position + 1) % self.buffer.len();
+        delayed - input
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TAIL_SAMPLES: usize = (SAMPLE_RATE * TAIL_MS / MS_PER_SECOND) as usize;
+
+    fn impulse(len: usize) -> Vec<f64> {
+        let mut samples = vec![0.0; len];
+        samples[0] = 1.0;
+        samples
+    }
+
+    #[test]
+    fn appends_tail() {
+        let output = Reverb::default().apply(&[0.0; 100]);
+        assert_eq!(output.len(), 100 + TAIL_SAMPLES);
+    }
+
+    #[test]
+    fn silence_stays_silent() {
+        assert!(Reverb::default().apply(&[0.0; 1000]).iter().all(|&s| s == 0.0));
+    }
+
+    #[test]
+    fn impulse_produces_echoes() {
+        let output = Reverb::default().apply(&impulse(10));
+        assert!(output[2000..].iter().any(|&s| s != 0.0), "expected reflections after the impulse");
+    }
+
+    #[test]
+    fn fully_dry_passes_input_through() {
+        let input: Vec<f64> = (0..500).map(|idx| (idx * 37 % 200) as f64 / 200.0).collect();
+        let output = Reverb { room_size: 0.9, wet: 0.0 }.apply(&input);
+        assert_eq!(&output[..input.len()], &input[..]);
+    }
+
+    #[test]
+    fn larger_room_rings_longer() {
+        let energy = |reverb: Reverb| -> f64 {
+            let output = reverb.apply(&impulse(10));
+            output[20_000..].iter().map(|s| s.abs()).sum()
+        };
+        let small = energy(Reverb { room_size: 0.1, wet: 1.0 });
+        let large = energy(Reverb { room_size: 0.9, wet: 1.0 });
+        assert!(large > small, "large room {large} should ring longer than small {small}");
+    }
+
+    #[test]
+    fn normalize_scales_peak_to_target() {
+        let output = normalize(&[0.1, -0.5, 0.25], 1.0);
+        assert_eq!(output, vec![0.2, -1.0, 0.5]);
+    }
+
+    #[test]
+    fn normalize_brings_overs_back_into_range() {
+        let output = normalize(&[1.8, -0.9], DEFAULT_PEAK);
+        assert!((output[0] - DEFAULT_PEAK).abs() < 1e-12);
+    }
+
+    #[test]
+    fn normalize_leaves_silence_alone() {
+        assert_eq!(normalize(&[0.0, 0.0], 1.0), vec![0.0, 0.0]);
+    }
+
+    #[test]
+    fn amplify_scales_every_sample() {
+        assert_eq!(amplify(&[0.25, -0.5], 2.0), vec![0.5, -1.0]);
+    }
+
+    #[test]
+    fn soft_limit_passes_quiet_samples() {
+        assert_eq!(soft_limit(&[0.5, -0.8]), vec![0.5, -0.8]);
+    }
+
+    #[test]
+    fn soft_limit_keeps_overs_below_full_scale() {
+        let output = soft_limit(&[1.5, -3.0, 0.9]);
+        assert!(output.iter().all(|s| s.abs() < 1.0));
+        assert!(output[0] > 0.9 && output[1] < -0.9, "sign and loudness order preserved");
+    }
+}
@@ -0,0 +1,122 @@
+//! Humanized timing - small random offsets so a game sounds played rather
+//! than sequenced.
+//!
+//! Every move draws two offsets, uniform in ±`max_ms`, from a seeded
+//! [`Xorshift32`] sequence:
+//!
+//! ```text
+//! slot:     |──────────── 350 ms ────────────|
+//! grid:     ▼ onset
+//! played:     ▼ onset + jitter
+//!             ████████████████ note + jitter
+//! ```
+//!
+//! The slot grid itself never moves, so jitter does not accumulate from move
+//! to move. The same seed gives the same performance.
+
+use std::cell::Cell;
+
+use super::MS_PER_SECOND;
+use crate::rng::Xorshift32;
+
+/// How much to loosen the timing, and the seed that makes it repeatable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Humanize {
+    /// Largest onset or length offset, in either direction
+    pub max_ms: u32,
+    pub seed: u32,
+}
+
+/// One move's deviation from the grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Offsets {
+    /// Milliseconds early (negative) or late (positive)
+    pub onset_ms: i32,
+    /// Milliseconds shorter (negative) or longer (positive)
+    pub length_ms: i32,
+}
+
+/// Draws [`Offsets`] move after move; all zero without humanizing.
+pub struct Jitter {
+    rng: Option<(Cell<Xorshift32>, f64)>,
+}
+
+impl Jitter {
+    pub fn new(humanize: Option<Humanize>) -> Self {
+        Self {
+            rng: humanize.map(|humanize| (Cell::new(Xorshift32::new(humanize.seed)), humanize.max_ms as f64)),
+        }
+    }
+
+    pub fn next(&self) -> Offsets {
+        match &self.rng {
+            Some((rng, max_ms)) => {
+                let mut draws = rng.get();
+                let offsets = Offsets {
+                    onset_ms: (draws.next_signed() * max_ms).round() as i32,
+                    length_ms: (draws.next_signed() * max_ms).round() as i32,
+                };
+                rng.set(draws);
+                offsets
+            }
+            None => Offsets::default(),
+        }
+    }
+}
+
+/// `position` shifted by `offset_ms`, never before the start.
+pub fn shift(position: usize, offset_ms: i32, units_per_second: u32) -> usize {
+    let offset = offset_ms as i64 * units_per_second as i64 / MS_PER_SECOND as i64;
+    (position as i64 + offset).max(0) as usize
+}
+
+/// A note length changed by `offset_ms`, but never below half its length.
+pub fn stretch(duration_ms: u32, offset_ms: i32) -> u32 {
+    (duration_ms as i64 + offset_ms as i64).max(duration_ms as i64 / 2) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_humanize_means_no_offsets() {
+        let jitter = Jitter::new(None);
+        assert_eq!(jitter.next(), Offsets::default());
+    }
+
+    #[test]
+    fn offsets_stay_within_range_and_vary() {
+        let jitter = Jitter::new(Some(Humanize { max_ms: 15, seed: 7 }));
+        let offsets: Vec<Offsets> = (0..200).map(|_| jitter.next()).collect();
+        assert!(offsets.iter().all(|offset| offset.onset_ms.abs() <= 15 && offset.length_ms.abs() <= 15));
+        assert!(offsets.iter().any(|offset| offset.onset_ms < 0));
+        assert!(offsets.iter().any(|offset| offset.onset_ms > 0));
+    }
+
+    #[test]
+    fn same_seed_same_performance() {
+        let humanize = Humanize { max_ms: 20, seed: 42 };
+        let first = Jitter::new(Some(humanize));
+        let second = Jitter::new(Some(humanize));
+        let other = Jitter::new(Some(Humanize { seed: 43, ..humanize }));
+        let draws = |jitter: &Jitter| (0..10).map(|_| jitter.next()).collect::<Vec<_>>();
+        let reference = draws(&first);
+        assert_eq!(reference, draws(&second));
+        assert_ne!(reference, draws(&other));
+    }
+
+    #[test]
+    fn shifts_clamp_at_the_start() {
+        assert_eq!(shift(44100, 10, 44100), 44541);
+        assert_eq!(shift(44100, -10, 44100), 43659);
+        assert_eq!(shift(100, -10, 44100), 0);
+    }
+
+    #[test]
+    fn stretch_keeps_at_least_half_the_note() {
+        assert_eq!(stretch(300, 20), 320);
+        assert_eq!(stretch(300, -20), 280);
+        assert_eq!(stretch(30, -25), 15);
+    }
+}
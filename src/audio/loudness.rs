@@ -0,0 +1,221 @@
+//! Integrated loudness (LUFS) per ITU-R BS.1770.
+//!
+//! Peak level says little about how loud a game *sounds*: a single capture
+//! hit can set the peak while the rest is quiet. LUFS measures perceived
+//! loudness over the whole stream, so batches of games normalized to the
+//! same target sit at the same level on a playlist.
+//!
+//! ```text
+//! samples ─→ K-weighting ─→ 400 ms blocks ─→ gating ─→ mean power ─→ LUFS
+//!            (shelf + HP)   (75% overlap)    (-70 abs,
+//!                                             -10 rel)
+//! ```
+//!
+//! K-weighting boosts the highs (the head's acoustic effect) and cuts deep
+//! lows the ear barely hears. Gating drops silent and very quiet blocks so
+//! pauses between moves don't drag the measurement down.
+//!
+//! Multichannel streams are weighted per channel and their block powers
+//! summed (all channel weights are 1.0 for mono and stereo).
+
+use super::{MS_PER_SECOND, SAMPLE_RATE};
+
+/// Common target for podcasts and streaming platforms.
+pub const DEFAULT_TARGET_LUFS: f64 = -16.0;
+
+const BLOCK_MS: u32 = 400;
+const STEP_MS: u32 = 100;
+const ABSOLUTE_GATE_LUFS: f64 = -70.0;
+const RELATIVE_GATE_LU: f64 = -10.0;
+
+/// Measures integrated loudness of an interleaved stream with
+/// `num_channels` channels on the float mix bus.
+/// Returns None when the stream is too short or entirely below the gate.
+pub fn integrated(samples: &[f64], num_channels: usize) -> Option<f64> {
+    let weighted: Vec<Vec<f64>> = (0..num_channels)
+        .map(|channel| k_weight(samples.iter().skip(channel).step_by(num_channels).copied()))
+        .collect();
+    let num_frames = samples.len() / num_channels;
+    let block_len = (SAMPLE_RATE * BLOCK_MS / MS_PER_SECOND) as usize;
+    let step = (SAMPLE_RATE * STEP_MS / MS_PER_SECOND) as usize;
+    if num_frames < block_len {
+        return None;
+    }
+
+    let block_powers: Vec<f64> = (0..=(num_frames - block_len) / step)
+        .map(|block| {
+            let start = block * step;
+            weighted
+                .iter()
+                .map(|channel| channel[start..start + block_len].iter().map(|sample| sample * sample).sum::<f64>())
+                .sum::<f64>()
+                / block_len as f64
+        })
+        .filter(|&power| to_lufs(power) > ABSOLUTE_GATE_LUFS)
+        .collect();
+    if block_powers.is_empty() {
+        return None;
+    }
+
+    let relative_gate = to_lufs(mean(&block_powers)) + RELATIVE_GATE_LU;
+    let gated: Vec<f64> = block_powers.into_iter().filter(|&power| to_lufs(power) > relative_gate).collect();
+    Some(to_lufs(mean(&gated)))
+}
+
+/// Applies the gain that brings the stream to `target_lufs`.
+/// Streams that cannot be measured are returned unchanged.
+pub fn normalize(samples: &[f64], num_channels: usize, target_lufs: f64) -> Vec<f64> {
+    match integrated(samples, num_channels) {
+        Some(measured) => {
+            let gain = 10f64.powf((target_lufs - measured) / 20.0);
+            samples.iter().map(|sample| sample * gain).collect()
+        }
+        None => samples.to_vec(),
+    }
+}
+
+fn to_lufs(mean_square: f64) -> f64 {
+    -0.691 + 10.0 * mean_square.log10()
+}
+
+fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+fn k_weight(samples: impl Iterator<Item = f64>) -> Vec<f64> {
+    let mut shelf = Biquad::high_shelf();
+    let mut high_pass = Biquad::high_pass();
+    samples.map(|sample| high_pass.process(shelf.process(sample))).collect()
+}
+
+/// Direct form I biquad. Coefficients are derived from BS.1770's analog
+/// prototypes so the filter is correct at our 44.1 kHz rate, not just the
+/// 48 kHz the spec tabulates.
+struct Biquad {
+    feedforward: [f64; 3],
+    feedback: [f64; 2],
+    inputs: [f64; 2],
+    outputs: [f64; 2],
+}
+
+impl Biquad {
+    fn new(feedforward: [f64; 3], feedback: [f64; 2]) -> Self {
+        Self {
+            feedforward,
+            feedback,
+            inputs: [0.0; 2],
+            outputs: [0.0; 2],
+        }
+    }
+
+    /// Stage 1: +4 dB shelf above ~1.7 kHz.
+    fn high_shelf() -> Self {
+        let center_hz = 1681.974450955533;
+        let gain_db = 3.999843853973347;
+        let quality = 0.7071752369554196;
+        let warped = (std::f64::consts::PI * center_hz / SAMPLE_RATE as f64).tan();
+        let high_gain = 10f64.powf(gain_db / 20.0);
+        let band_gain = high_gain.powf(0.4996667741545416);
+        let normalizer = 1.0 + warped / quality + warped * warped;
+        Self::new(
+            [
+                (high_gain + band_gain * warped / quality + warped * warped) / normalizer,
+                2.0 * (warped * warped - high_gain) / normalizer,
+                (high_gain - band_gain * warped / quality + warped * warped) / normalizer,
+            ],
+            [2.0 * (warped * warped - 1.0) / normalizer, (1.0 - warped / quality + warped * warped) / normalizer],
+        )
+    }
+
+    /// Stage 2: second-order high-pass at ~38 Hz.
+    fn high_pass() -> Self {
+        let corner_hz = 38.13547087602444;
+        let quality = 0.5003270373238773;
+        let warped = (std::f64::consts::PI * corner_hz / SAMPLE_RATE as f64).tan();
+        let normalizer = 1.0 + warped / quality + warped * warped;
+        Self::new([1.0, -2.0, 1.0], [2.0 * (warped * warped - 1.0) / normalizer, (1.0 - warped / quality + warped * warped) / normalizer])
+    }
+
+    fn process(&mut self, input: f64) -> f64 {
+        let output = self.feedforward[0] * input
+            + self.feedforward[1] * self.inputs[0]
+            + self.feedforward[2] * self.inputs[1]
+            - self.feedback[0] * self.outputs[0]
+            - self.feedback[1] * self.outputs[1];
+        self.inputs = [input, self.inputs[0]];
+        self.outputs = [output, self.outputs[0]];
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(freq: f64, amplitude: f64, seconds: f64) -> Vec<f64> {
+        let len = (SAMPLE_RATE as f64 * seconds) as usize;
+        (0..len)
+            .map(|idx| amplitude * (2.0 * std::f64::consts::PI * freq * idx as f64 / SAMPLE_RATE as f64).sin())
+            .collect()
+    }
+
+    #[test]
+    fn full_scale_1khz_sine_reads_minus_3_lufs() {
+        // BS.1770 reference: a 997 Hz sine at 0 dBFS measures -3.01 LUFS (mono)
+        let measured = integrated(&sine(997.0, 1.0, 3.0), 1).unwrap();
+        assert!((measured + 3.01).abs() < 0.1, "measured {measured}");
+    }
+
+    #[test]
+    fn halving_amplitude_drops_6_lu() {
+        let loud = integrated(&sine(997.0, 1.0, 2.0), 1).unwrap();
+        let quiet = integrated(&sine(997.0, 0.5, 2.0), 1).unwrap();
+        assert!((loud - quiet - 6.02).abs() < 0.05);
+    }
+
+    #[test]
+    fn silence_is_unmeasurable() {
+        assert_eq!(integrated(&[0.0; 44100], 1), None);
+    }
+
+    #[test]
+    fn too_short_is_unmeasurable() {
+        assert_eq!(integrated(&sine(997.0, 1.0, 0.2), 1), None);
+    }
+
+    #[test]
+    fn gating_ignores_long_silences() {
+        let tone = sine(997.0, 0.5, 2.0);
+        let padded: Vec<f64> = tone.iter().copied().chain(std::iter::repeat_n(0.0, 44100 * 4)).collect();
+        let difference = integrated(&tone, 1).unwrap() - integrated(&padded, 1).unwrap();
+        assert!(difference.abs() < 0.5, "silence shifted loudness by {difference}");
+    }
+
+    #[test]
+    fn normalize_hits_target() {
+        let output = normalize(&sine(440.0, 0.3, 2.0), 1, DEFAULT_TARGET_LUFS);
+        let measured = integrated(&output, 1).unwrap();
+        assert!((measured - DEFAULT_TARGET_LUFS).abs() < 0.01, "measured {measured}");
+    }
+
+    #[test]
+    fn normalize_leaves_silence_alone() {
+        assert_eq!(normalize(&[0.0; 100], 1, DEFAULT_TARGET_LUFS), vec![0.0; 100]);
+    }
+
+    #[test]
+    fn identical_stereo_channels_read_3_lu_louder() {
+        let mono = sine(997.0, 1.0, 2.0);
+        let stereo: Vec<f64> = mono.iter().flat_map(|&sample| [sample, sample]).collect();
+        let difference = integrated(&stereo, 2).unwrap() - integrated(&mono, 1).unwrap();
+        assert!((difference - 3.01).abs() < 0.05, "difference {difference}");
+    }
+
+    #[test]
+    fn stereo_normalize_applies_one_gain_to_both_channels() {
+        let stereo: Vec<f64> = sine(440.0, 0.3, 2.0).iter().flat_map(|&sample| [sample, sample * 0.5]).collect();
+        let output = normalize(&stereo, 2, DEFAULT_TARGET_LUFS);
+        assert!((integrated(&output, 2).unwrap() - DEFAULT_TARGET_LUFS).abs() < 0.01);
+        assert!((output[1001] - output[1000] * 0.5).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,207 @@
+//! Standard MIDI File export: the game as notes instead of audio, for DAWs
+//! and notation software.
+//!
+//! ```text
+//! MThd   format 0 (one track), 1000 ticks per quarter note
+//! MTrk   tempo 60 bpm, so one tick is one millisecond
+//!        program change per channel (one channel per piece, GM instrument)
+//!        note on / note off for every move
+//!        end of track
+//! ```
+//!
+//! Events carry delta times as variable-length quantities: 7 bits per
+//! byte, high bit set on all but the last.
+
+use super::piece_channel;
+use super::sound_map::PIECES;
+use crate::engine::chess::Piece;
+
+const TICKS_PER_QUARTER: u16 = 1000;
+/// Microseconds per quarter note: 60 bpm, one tick per millisecond.
+const MICROS_PER_QUARTER: u32 = 1_000_000;
+const A4_KEY: f64 = 69.0;
+const A4_FREQ_HZ: f64 = 440.0;
+
+const NOTE_OFF: u8 = 0x80;
+const NOTE_ON: u8 = 0x90;
+const PROGRAM_CHANGE: u8 = 0xC0;
+const META: u8 = 0xFF;
+const META_TEMPO: u8 = 0x51;
+const META_END_OF_TRACK: u8 = 0x2F;
+
+/// General MIDI program (0-based) for each piece.
+pub fn gm_program(piece: Piece) -> u8 {
+    match piece {
+        Piece::Pawn => 0,    // Acoustic Grand Piano
+        Piece::Knight => 45, // Pizzicato Strings
+        Piece::Bishop => 73, // Flute
+        Piece::Rook => 56,   // Trumpet
+        Piece::Queen => 48,  // String Ensemble 1
+        Piece::King => 60,   // French Horn
+    }
+}
+
+/// One note of the game.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Note {
+    pub piece: Piece,
+    pub start_ms: u32,
+    pub duration_ms: u32,
+    /// MIDI key number, 60 = middle C (see [`key_for_freq`])
+    pub key: u8,
+    /// 1–127
+    pub velocity: u8,
+}
+
+/// Nearest MIDI key for a frequency (A4 = 440 Hz = key 69).
+pub fn key_for_freq(freq_hz: u32) -> u8 {
+    let key = A4_KEY + 12.0 * (freq_hz.max(1) as f64 / A4_FREQ_HZ).log2();
+    key.round().clamp(0.0, 127.0) as u8
+}
+
+/// Scientific pitch name of a MIDI key: 60 → "C4", 61 → "C#4".
+pub fn key_name(key: u8) -> String {
+    const NAMES: [&str; 12] = ["C", "C#", "D", "D#", "E", "F", "F#", "G", "G#", "A", "A#", "B"];
+    let octave = key as i32 / 12 - 1;
+    format!("{}{}", NAMES[key as usize % 12], octave)
+}
+
+/// Writes `notes` as a format 0 MIDI file, each piece on its own channel.
+pub fn encode(notes: &[Note]) -> Vec<u8> {
+    // (tick, status, data): note-offs sort before note-ons at the same tick
+    let mut events: Vec<(u32, u8, [u8; 2])> = notes
+        .iter()
+        .flat_map(|note| {
+            let channel = piece_channel(note.piece) as u8;
+            [
+                (note.start_ms, NOTE_ON | channel, [note.key, note.velocity]),
+                (note.start_ms + note.duration_ms, NOTE_OFF | channel, [note.key, 0]),
+            ]
+        })
+        .collect();
+    events.sort_by_key(|&(tick, status, _)| (tick, status & 0xF0 == NOTE_ON));
+
+    let mut track = Vec::new();
+    push_var_len(&mut track, 0);
+    track.extend_from_slice(&[META, META_TEMPO, 3]);
+    track.extend_from_slice(&MICROS_PER_QUARTER.to_be_bytes()[1..]);
+    for piece in PIECES {
+        push_var_len(&mut track, 0);
+        track.extend_from_slice(&[PROGRAM_CHANGE | piece_channel(piece) as u8, gm_program(piece)]);
+    }
+    let mut previous_tick = 0;
+    for (tick, status, data) in events {
+        push_var_len(&mut track, tick - previous_tick);
+        track.push(status);
+        track.extend_from_slice(&data);
+        previous_tick = tick;
+    }
+    push_var_len(&mut track, 0);
+    track.extend_from_slice(&[META, META_END_OF_TRACK, 0]);
+
+    let mut out = Vec::with_capacity(22 + track.len());
+    out.extend_from_slice(b"MThd");
+    out.extend_from_slice(&6u32.to_be_bytes());
+    out.extend_from_slice(&0u16.to_be_bytes()); // format 0
+    out.extend_from_slice(&1u16.to_be_bytes()); // one track
+    out.extend_from_slice(&TICKS_PER_QUARTER.to_be_bytes());
+    out.extend_from_slice(b"MTrk");
+    out.extend_from_slice(&(track.len() as u32).to_be_bytes());
+    out.extend_from_slice(&track);
+    out
+}
+
+fn push_var_len(out: &mut Vec<u8>, value: u32) {
+    let mut groups = vec![(value & 0x7F) as u8];
+    let mut rest = value >> 7;
+    while rest > 0 {
+        groups.push((rest & 0x7F) as u8 | 0x80);
+        rest >>= 7;
+    }
+    out.extend(groups.iter().rev());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn var_len(value: u32) -> Vec<u8> {
+        let mut out = Vec::new();
+        push_var_len(&mut out, value);
+        out
+    }
+
+    #[test]
+    fn variable_length_quantities() {
+        assert_eq!(var_len(0), vec![0x00]);
+        assert_eq!(var_len(0x7F), vec![0x7F]);
+        assert_eq!(var_len(0x80), vec![0x81, 0x00]);
+        assert_eq!(var_len(350), vec![0x82, 0x5E]);
+        assert_eq!(var_len(0x0FFF_FFFF), vec![0xFF, 0xFF, 0xFF, 0x7F]);
+    }
+
+    #[test]
+    fn keys_from_frequencies() {
+        assert_eq!(key_for_freq(440), 69);
+        assert_eq!(key_for_freq(262), 60);
+        assert_eq!(key_for_freq(880), 81);
+    }
+
+    #[test]
+    fn key_names() {
+        assert_eq!(key_name(60), "C4");
+        assert_eq!(key_name(69), "A4");
+        assert_eq!(key_name(70), "A#4");
+        assert_eq!(key_name(0), "C-1");
+    }
+
+    #[test]
+    fn header_and_track_chunks() {
+        let midi = encode(&[]);
+        assert_eq!(&midi[0..4], b"MThd");
+        assert_eq!(&midi[8..14], &[0, 0, 0, 1, 0x03, 0xE8]);
+        assert_eq!(&midi[14..18], b"MTrk");
+        let track_len = u32::from_be_bytes([midi[18], midi[19], midi[20], midi[21]]) as usize;
+        assert_eq!(midi.len(), 22 + track_len);
+        assert_eq!(&midi[midi.len() - 3..], &[META, META_END_OF_TRACK, 0]);
+    }
+
+    #[test]
+    fn notes_become_on_off_pairs_on_their_piece_channel() {
+        let note = Note {
+            piece: Piece::Knight,
+            start_ms: 350,
+            duration_ms: 300,
+            key: 64,
+            velocity: 100,
+        };
+        let midi = encode(&[note]);
+        let events = &midi[midi.len() - 4 - 10..midi.len() - 4];
+        // delta 350, note on ch 1; delta 300, note off ch 1
+        assert_eq!(events, &[0x82, 0x5E, 0x91, 64, 100, 0x82, 0x2C, 0x81, 64, 0]);
+    }
+
+    #[test]
+    fn note_off_precedes_note_on_at_the_same_tick() {
+        let first = Note {
+            piece: Piece::Pawn,
+            start_ms: 0,
+            duration_ms: 100,
+            key: 60,
+            velocity: 100,
+        };
+        let second = Note { start_ms: 100, key: 62, ..first };
+        let midi = encode(&[first, second]);
+        let off = midi.windows(3).position(|event| event == [NOTE_OFF, 60, 0]);
+        let on = midi.windows(3).position(|event| event == [NOTE_ON, 62, 100]);
+        assert!(off < on);
+    }
+
+    #[test]
+    fn every_piece_has_a_distinct_program() {
+        let mut programs: Vec<u8> = PIECES.iter().map(|&piece| gm_program(piece)).collect();
+        programs.sort_unstable();
+        programs.dedup();
+        assert_eq!(programs.len(), PIECES.len());
+    }
+}
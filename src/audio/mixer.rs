@@ -0,0 +1,275 @@
+//! Polyphonic mixer - sums voices that may overlap in time.
+//!
+//! Each voice is a rendered buffer placed at a start offset with its own
+//! gain and pan. Nothing forces voices to follow one another, so chords,
+//! echoes and several games can share one timeline.
+//!
+//! ```text
+//! sample:  0        13230      15435      28665
+//! voice 1: [══ e4 ══]
+//! voice 2: [capture]                              (layered on voice 1)
+//! voice 3:                     [══ e5 ══]
+//! ─────────────────────────────────────────────
+//! mix:     [══ Σ ═══]   0 0 0  [══ e5 ══]  0 0    (extend_to pads the end)
+//! ```
+//!
+//! Sums may exceed full scale; the caller decides whether to normalize,
+//! limit or clip when quantizing.
+
+use std::f64::consts::{FRAC_PI_2, FRAC_PI_4};
+
+/// A rendered buffer positioned on the mixer timeline.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Voice {
+    /// Offset of the first sample, in samples from the start of the mix
+    pub start: usize,
+    pub samples: Vec<f64>,
+    /// Linear gain (1.0 = unchanged)
+    pub gain: f64,
+    /// Stereo position (-1.0 = hard left, 0.0 = center, 1.0 = hard right)
+    pub pan: f64,
+    /// Samples by which the ear facing away from `pan` hears the voice
+    /// later, as sound reaches the far side of the head last
+    pub interaural_delay: usize,
+}
+
+impl Voice {
+    pub fn new(start: usize, samples: Vec<f64>) -> Self {
+        Self {
+            start,
+            samples,
+            gain: 1.0,
+            pan: 0.0,
+            interaural_delay: 0,
+        }
+    }
+
+    pub fn with_gain(self, gain: f64) -> Self {
+        Self { gain, ..self }
+    }
+
+    pub fn with_pan(self, pan: f64) -> Self {
+        Self {
+            pan: pan.clamp(-1.0, 1.0),
+            ..self
+        }
+    }
+
+    pub fn with_interaural_delay(self, interaural_delay: usize) -> Self {
+        Self { interaural_delay, ..self }
+    }
+
+    /// Fades both ends over `fade_samples` with equal-power curves, so a
+    /// voice overlapping its neighbour by that much crossfades into it
+    /// without a dip or bump in loudness.
+    pub fn with_crossfade(mut self, fade_samples: usize) -> Self {
+        let fade_samples = fade_samples.min(self.samples.len() / 2);
+        let len = self.samples.len();
+        let curve = |step: usize| (step as f64 / fade_samples as f64 * FRAC_PI_2).sin();
+        for idx in 0..fade_samples {
+            self.samples[idx] *= curve(idx);
+            // Offset by one so fade-out gain² + overlapping fade-in gain² = 1
+            self.samples[len - 1 - idx] *= curve(idx + 1);
+        }
+        self
+    }
+
+    fn end(&self) -> usize {
+        self.start + self.samples.len() + self.interaural_delay
+    }
+
+    /// Equal-power pan law: the center sits at -3 dB per side so a voice
+    /// sweeping across the field keeps a constant perceived loudness.
+    fn stereo_gains(&self) -> (f64, f64) {
+        let angle = (self.pan + 1.0) * FRAC_PI_4;
+        (self.gain * angle.cos(), self.gain * angle.sin())
+    }
+
+    /// Start offsets of the left and right ear: the ear on the far side of
+    /// the pan is delayed, a centered voice reaches both at once.
+    fn stereo_starts(&self) -> (usize, usize) {
+        if self.pan > 0.0 {
+            (self.start + self.interaural_delay, self.start)
+        } else if self.pan < 0.0 {
+            (self.start, self.start + self.interaural_delay)
+        } else {
+            (self.start, self.start)
+        }
+    }
+}
+
+/// Collects voices and sums them into a mono or stereo buffer.
+#[derive(Debug, Clone, Default)]
+pub struct Mixer {
+    voices: Vec<Voice>,
+    length: usize,
+}
+
+impl Mixer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, voice: Voice) {
+        self.length = self.length.max(voice.end());
+        self.voices.push(voice);
+    }
+
+    /// Makes the mix at least `length` samples long, padding with silence.
+    pub fn extend_to(&mut self, length: usize) {
+        self.length = self.length.max(length);
+    }
+
+    /// Sums every voice into one channel, ignoring pan.
+    pub fn mix_mono(&self) -> Vec<f64> {
+        let mut output = vec![0.0; self.length];
+        for voice in &self.voices {
+            for (slot, sample) in output[voice.start..].iter_mut().zip(&voice.samples) {
+                *slot += sample * voice.gain;
+            }
+        }
+        output
+    }
+
+    /// Sums every voice into interleaved left/right frames, honoring pan
+    /// and interaural delay.
+    pub fn mix_stereo(&self) -> Vec<f64> {
+        let mut output = vec![0.0; self.length * 2];
+        for voice in &self.voices {
+            let (left_gain, right_gain) = voice.stereo_gains();
+            let (left_start, right_start) = voice.stereo_starts();
+            for (frame, sample) in output[left_start * 2..].chunks_exact_mut(2).zip(&voice.samples) {
+                frame[0] += sample * left_gain;
+            }
+            for (frame, sample) in output[right_start * 2..].chunks_exact_mut(2).zip(&voice.samples) {
+                frame[1] += sample * right_gain;
+            }
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_mixer_is_silent() {
+        assert!(Mixer::new().mix_mono().is_empty());
+    }
+
+    #[test]
+    fn voices_are_placed_at_their_start() {
+        let mut mixer = Mixer::new();
+        mixer.add(Voice::new(2, vec![0.5, 0.5]));
+        assert_eq!(mixer.mix_mono(), vec![0.0, 0.0, 0.5, 0.5]);
+    }
+
+    #[test]
+    fn overlapping_voices_sum() {
+        let mut mixer = Mixer::new();
+        mixer.add(Voice::new(0, vec![0.25, 0.25, 0.25]));
+        mixer.add(Voice::new(1, vec![0.5, 0.5, 0.5]));
+        assert_eq!(mixer.mix_mono(), vec![0.25, 0.75, 0.75, 0.5]);
+    }
+
+    #[test]
+    fn sums_may_exceed_full_scale() {
+        let mut mixer = Mixer::new();
+        mixer.add(Voice::new(0, vec![0.75]));
+        mixer.add(Voice::new(0, vec![0.75]));
+        assert_eq!(mixer.mix_mono(), vec![1.5]);
+    }
+
+    #[test]
+    fn gain_scales_voice() {
+        let mut mixer = Mixer::new();
+        mixer.add(Voice::new(0, vec![1.0, -1.0]).with_gain(0.5));
+        assert_eq!(mixer.mix_mono(), vec![0.5, -0.5]);
+    }
+
+    #[test]
+    fn extend_to_pads_with_silence() {
+        let mut mixer = Mixer::new();
+        mixer.add(Voice::new(0, vec![1.0]));
+        mixer.extend_to(3);
+        assert_eq!(mixer.mix_mono(), vec![1.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn extend_to_never_truncates() {
+        let mut mixer = Mixer::new();
+        mixer.add(Voice::new(0, vec![1.0, 1.0]));
+        mixer.extend_to(1);
+        assert_eq!(mixer.mix_mono().len(), 2);
+    }
+
+    #[test]
+    fn stereo_hard_left_and_right() {
+        let mut mixer = Mixer::new();
+        mixer.add(Voice::new(0, vec![1.0]).with_pan(-1.0));
+        mixer.add(Voice::new(1, vec![1.0]).with_pan(1.0));
+        let stereo = mixer.mix_stereo();
+        assert!((stereo[0] - 1.0).abs() < 1e-12 && stereo[1].abs() < 1e-12);
+        assert!(stereo[2].abs() < 1e-12 && (stereo[3] - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn stereo_center_keeps_constant_power() {
+        let mut mixer = Mixer::new();
+        mixer.add(Voice::new(0, vec![1.0]));
+        let stereo = mixer.mix_stereo();
+        assert!((stereo[0] - stereo[1]).abs() < 1e-12);
+        assert!((stereo[0].powi(2) + stereo[1].powi(2) - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn pan_is_clamped() {
+        assert_eq!(Voice::new(0, vec![]).with_pan(3.0).pan, 1.0);
+    }
+
+    #[test]
+    fn crossfade_ramps_both_ends() {
+        let voice = Voice::new(0, vec![1.0; 10]).with_crossfade(4);
+        assert_eq!(voice.samples[0], 0.0);
+        assert!(voice.samples[1] < voice.samples[2] && voice.samples[2] < voice.samples[3]);
+        assert!(voice.samples[9] < voice.samples[8] && voice.samples[8] < voice.samples[7]);
+        assert_eq!(voice.samples[4..6], [1.0, 1.0]);
+    }
+
+    #[test]
+    fn crossfade_keeps_power_constant_across_overlap() {
+        let fade = 100;
+        let outgoing = Voice::new(0, vec![1.0; 400]).with_crossfade(fade);
+        let incoming = Voice::new(300, vec![1.0; 400]).with_crossfade(fade);
+        for offset in 0..fade {
+            let power = outgoing.samples[300 + offset].powi(2) + incoming.samples[offset].powi(2);
+            assert!((power - 1.0).abs() < 1e-3, "power {power} at {offset}");
+        }
+    }
+
+    #[test]
+    fn crossfade_longer_than_voice_is_capped() {
+        let voice = Voice::new(0, vec![1.0; 4]).with_crossfade(100);
+        assert_eq!(voice.samples.len(), 4);
+        assert_eq!(voice.samples[0], 0.0);
+    }
+
+    #[test]
+    fn interaural_delay_lags_the_far_ear() {
+        let mut mixer = Mixer::new();
+        mixer.add(Voice::new(0, vec![1.0]).with_pan(0.5).with_interaural_delay(2));
+        let stereo = mixer.mix_stereo();
+        assert_eq!(stereo.len(), 6);
+        assert!(stereo[1] > 0.0 && stereo[0] == 0.0, "right ear hears it first");
+        assert!(stereo[4] > 0.0, "left ear two frames later");
+    }
+
+    #[test]
+    fn centered_voice_ignores_interaural_delay() {
+        let mut mixer = Mixer::new();
+        mixer.add(Voice::new(0, vec![1.0]).with_interaural_delay(3));
+        let stereo = mixer.mix_stereo();
+        assert!(stereo[0] > 0.0 && stereo[1] > 0.0);
+    }
+}
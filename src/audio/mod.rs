@@ -8,117 +8,982 @@
 //!     ▼ NotationMove::parse()
 //! [NotationMove, NotationMove]
 //!     │
-//!     ▼ freq::from_square()
+//!     ▼ freq::PitchMap::freq()
 //! [392 Hz, 349 Hz]
 //!     │
 //!     ▼ synth::sine()
-//! [samples...] + silence
+//! [voices...]
 //!     │
-//!     ▼ wav::header()
+//!     ▼ mixer::Mixer (each move placed on the timeline, layers overlap)
+//! [samples...]
+//!     │
+//!     ▼ fx (optional reverb, loudness, normalize, limit — see `AudioConfig`)
+//! [samples...]
+//!     │
+//!     ▼ resample::resample() (only for an output rate other than 44.1 kHz)
+//! [samples...]
+//!     │
+//!     ▼ wav::header_with_chunks() (plus game_info() metadata for PGN input)
 //! [WAV file bytes]
 //! ```
 
 mod blend;
-mod freq;
+mod dynamics;
+pub mod flac;
+pub mod freq;
+mod fx;
+mod humanize;
+mod loudness;
+mod lut;
+pub mod midi;
+pub mod mixer;
+#[cfg(feature = "opus")]
+pub mod opus;
+mod playback;
+pub mod resample;
+#[cfg(feature = "soundfont")]
+pub mod soundfont;
+pub mod sampler;
+pub mod sound_map;
+mod spatial;
+pub mod stats;
 mod synth;
-mod wav;
+pub mod theme;
+pub mod trace;
+pub mod wav;
 mod waveform;
 
-use blend::Blend;
-use crate::engine::chess::{NotationMove, Piece, Threat};
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, Write};
+use std::sync::OnceLock;
+
+use blend::Glide;
+pub use blend::{Blend, Envelope, Filter, FilterKind, Lfo};
+use mixer::{Mixer, Voice};
+pub use freq::{DEFAULT_FOLD_OCTAVES, Key, Layout, PitchMap, Scale, Tuning};
+pub use fx::{DEFAULT_PEAK, Reverb};
+pub use humanize::Humanize;
+pub use loudness::DEFAULT_TARGET_LUFS;
+pub use playback::{Device, Playback, PlaybackQueue, devices, play, play_on};
+use sound_map::SoundMap;
+pub use synth::Timbre;
+pub use waveform::Fm;
+use crate::engine::board::{Board, Color, Position};
+use crate::engine::chess::{Capture, NotationMove, Piece, Square, Threat};
+use crate::engine::pgn::Tags;
 
 // Audio format constants
 pub const SAMPLE_RATE: u32 = 44100;
 pub const BITS_PER_SAMPLE: u16 = 16;
 pub const BYTES_PER_SAMPLE: usize = (BITS_PER_SAMPLE / 8) as usize;
 pub const NUM_CHANNELS: u16 = 1;
+pub const STEREO_CHANNELS: u16 = 2;
+/// One channel per piece type in multi-channel output (see `piece_channel`).
+/// Six channels carry the 5.1 speaker mask in the WAV header, so surround
+/// players spread the pieces around the room.
+pub const PIECE_CHANNELS: u16 = 6;
 pub const MS_PER_SECOND: u32 = 1000;
 
+// Black's default voicing: an octave down and muffled
+const DARK_OCTAVE_SHIFT: i32 = -1;
+const DARK_CUTOFF_HZ: f64 = 1200.0;
+
 // Timing constants
 const NOTE_MS: u32 = 300;
 const SILENCE_MS: u32 = 50;
+const CAPTURE_BURST_MS: u32 = 40;
+const CLICK_MS: u32 = 20;
+const KICK_MS: u32 = 200;
+const REWIND_MS: u32 = 180;
+const CHIME_MS: u32 = 150;
+const BUZZ_MS: u32 = 200;
+const JINGLE_NOTE_MS: u32 = 160;
+/// A win climbs a C major arpeggio; a draw steps up a fourth and stops.
+const WIN_JINGLE_HZ: [f64; 4] = [523.25, 659.25, 783.99, 1046.5];
+const DRAW_JINGLE_HZ: [f64; 2] = [392.0, 523.25];
+/// In legato mode each note runs this far into the next one's slot.
+const CROSSFADE_MS: u32 = 80;
+
+// Percussion sits under the melody; every tenth full move gets a kick
+const PERCUSSION_GAIN: f64 = 0.35;
+const KICK_EVERY_MOVES: usize = 10;
+/// Stereo spread of the files: a-file notes sit this far left, h-file this
+/// far right. Short of hard-panning so edge files stay in both ears.
+const STEREO_WIDTH: f64 = 0.8;
+/// How far each side sits from center when panning by side.
+const SIDE_PAN: f64 = 0.6;
+/// MIDI velocity of a move at full level (dynamics scale it down).
+const MIDI_VELOCITY: f64 = 100.0;
+const SECONDS_PER_MINUTE: f64 = 60.0;
+/// Timeline slot of one move at the default [`Timing`]: its note followed by
+/// the gap before the next.
+const SAMPLES_PER_MOVE: usize = (SAMPLE_RATE * (NOTE_MS + SILENCE_MS) / MS_PER_SECOND) as usize;
+
+/// Why audio could not be played or written.
+#[derive(Debug)]
+pub enum AudioError {
+    /// None of the platform's audio players is installed
+    NoPlayer { tried: Vec<&'static str> },
+    /// The temp file, a player's pipe or the output could not be written
+    Io(io::Error),
+}
+
+impl fmt::Display for AudioError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AudioError::NoPlayer { tried } if tried.is_empty() => write!(f, "no audio player for this platform"),
+            AudioError::NoPlayer { tried } => write!(f, "no audio player found (install one of: {})", tried.join(", ")),
+            AudioError::Io(err) => write!(f, "audio output failed: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for AudioError {}
+
+impl From<io::Error> for AudioError {
+    fn from(err: io::Error) -> Self {
+        AudioError::Io(err)
+    }
+}
+
+/// Container the game is written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    #[default]
+    Wav,
+    /// Standard MIDI File: notes only, no audio (see [`generate_midi`])
+    Midi,
+    /// Ogg Opus through the system's opusenc (`opus` feature)
+    Ogg,
+    /// Lossless FLAC (see [`flac`])
+    Flac,
+    /// Headerless interleaved 16-bit little-endian PCM at [`SAMPLE_RATE`]
+    Raw,
+}
+
+impl Format {
+    /// Parses a format name or a file extension (`mid`, `opus`, `pcm`, …).
+    pub fn from_name(name: &str) -> Option<Format> {
+        match name.to_ascii_lowercase().as_str() {
+            "wav" => Some(Format::Wav),
+            "midi" | "mid" => Some(Format::Midi),
+            "ogg" | "opus" => Some(Format::Ogg),
+            "flac" => Some(Format::Flac),
+            "raw" | "pcm" => Some(Format::Raw),
+            _ => None,
+        }
+    }
+
+    /// Infers the format from `path`'s extension, e.g. `game.flac`.
+    pub fn from_path(path: &std::path::Path) -> Option<Format> {
+        path.extension()?.to_str().and_then(Format::from_name)
+    }
+
+    /// The usual file extension, e.g. "mid" for MIDI.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Format::Wav => "wav",
+            Format::Midi => "mid",
+            Format::Ogg => "opus",
+            Format::Flac => "flac",
+            Format::Raw => "raw",
+        }
+    }
+}
+
+/// Rendering options applied on top of the piece→sound mapping.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct AudioConfig {
+    /// Timbre, blend, envelope and duration of every piece and threat level
+    pub sounds: SoundMap,
+    /// Reverb over the whole game (None = dry)
+    pub reverb: Option<Reverb>,
+    /// Glide each note in from the previous move's pitch over this many ms
+    /// (None = discrete notes)
+    pub portamento_ms: Option<u32>,
+    /// Adjust gain so the game's integrated loudness hits this target (LUFS)
+    pub loudness_target: Option<f64>,
+    /// Scale the whole game so its loudest peak hits this level (0.0–1.0)
+    pub normalize_peak: Option<f64>,
+    /// Master gain on the final mix, after normalization and before the
+    /// limiter and quantization (None = unity)
+    pub gain: Option<f64>,
+    /// Round off peaks above full scale instead of clipping them
+    pub soft_limit: bool,
+    /// Voice White and Black differently (None = same sound for both sides)
+    pub color_voicing: Option<ColorVoicing>,
+    /// Scale each note's loudness by the move's importance (captures, checks,
+    /// promotions) instead of playing every move at full level
+    pub dynamics: bool,
+    /// Tick on every full move and kick on moves 10, 20, … to mark progress
+    pub percussion: bool,
+    /// Let each note overlap and crossfade into the next instead of
+    /// separating them with silence
+    pub legato: bool,
+    /// How squares map to pitches, e.g. snapped into a key
+    pub pitch: PitchMap,
+    /// Render interleaved stereo, panning each note by its file or by the
+    /// side that moved (None = mono)
+    pub stereo: Option<Panning>,
+    /// Render stereo for headphones with the board laid out around the
+    /// listener: files in azimuth, ranks in distance (see `spatial`)
+    pub spatial: bool,
+    /// Note length and gap of every move (default 300 ms + 50 ms)
+    pub timing: Timing,
+    /// Loosen note onsets and lengths by small seeded random offsets
+    /// (None = exactly on the grid)
+    pub humanize: Option<Humanize>,
+    /// Position the moves are played from (default the initial position,
+    /// White to move)
+    pub start: Position,
+}
+
+impl AudioConfig {
+    /// Channels in the rendered output (see [`to_wav_with_channels`]).
+    pub fn num_channels(&self) -> u16 {
+        if self.stereo.is_some() || self.spatial { STEREO_CHANNELS } else { NUM_CHANNELS }
+    }
+}
+
+/// Where a note sits between the speakers in stereo output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Panning {
+    /// By destination file: a = left, h = right
+    #[default]
+    File,
+    /// By the mover: White on the left, Black on the right
+    Side,
+}
+
+impl Panning {
+    pub fn from_name(name: &str) -> Option<Panning> {
+        match name {
+            "file" => Some(Panning::File),
+            "side" => Some(Panning::Side),
+            _ => None,
+        }
+    }
+
+    /// Pan position (-1.0 left … 1.0 right) of a move by `color` to `dest`.
+    fn pan(self, dest: &Square, color: Color) -> f64 {
+        match (self, color) {
+            (Panning::File, _) => file_pan(dest),
+            (Panning::Side, Color::White) => -SIDE_PAN,
+            (Panning::Side, Color::Black) => SIDE_PAN,
+        }
+    }
+}
+
+/// Per-color adjustment on top of the piece's timbre.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct SideVoicing {
+    /// Octaves to shift the note by (negative = lower)
+    pub octave_shift: i32,
+    /// Low-pass cutoff replacing the piece's own filter (None = unchanged)
+    pub cutoff_hz: Option<f64>,
+}
+
+/// Lets listeners tell who moved without counting plies.
+/// Default: White as-is (bright, high), Black an octave down and darker.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColorVoicing {
+    pub white: SideVoicing,
+    pub black: SideVoicing,
+}
+
+impl Default for ColorVoicing {
+    fn default() -> Self {
+        Self {
+            white: SideVoicing::default(),
+            black: SideVoicing {
+                octave_shift: DARK_OCTAVE_SHIFT,
+                cutoff_hz: Some(DARK_CUTOFF_HZ),
+            },
+        }
+    }
+}
+
+impl ColorVoicing {
+    pub fn for_color(&self, color: Color) -> SideVoicing {
+        match color {
+            Color::White => self.white,
+            Color::Black => self.black,
+        }
+    }
+}
+
+/// How long each move lasts: its note, then the silence before the next.
+/// Default: 300 ms + 50 ms, about 171 moves a minute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Timing {
+    /// Length of a detached note. Sound map durations are written against
+    /// the default 300 ms and scale with it.
+    pub note_ms: u32,
+    /// Silence between the end of one note and the start of the next move
+    pub gap_ms: u32,
+}
+
+impl Default for Timing {
+    fn default() -> Self {
+        Self {
+            note_ms: NOTE_MS,
+            gap_ms: SILENCE_MS,
+        }
+    }
+}
+
+impl Timing {
+    /// `moves_per_minute` moves a minute, split between note and gap in the
+    /// default proportion.
+    pub fn from_tempo(moves_per_minute: f64) -> Timing {
+        let slot_ms = (SECONDS_PER_MINUTE * MS_PER_SECOND as f64 / moves_per_minute).round() as u32;
+        let note_ms = (slot_ms as u64 * NOTE_MS as u64 / (NOTE_MS + SILENCE_MS) as u64) as u32;
+        Timing {
+            note_ms,
+            gap_ms: slot_ms - note_ms,
+        }
+    }
+
+    /// Milliseconds from one move to the next.
+    pub fn slot_ms(&self) -> u32 {
+        self.note_ms + self.gap_ms
+    }
+
+    fn slot_samples(&self) -> usize {
+        (SAMPLE_RATE as u64 * self.slot_ms() as u64 / MS_PER_SECOND as u64) as usize
+    }
+
+    /// A sound map duration rescaled from the default note length to `note_ms`.
+    fn note_duration(&self, duration_ms: u32) -> u32 {
+        (duration_ms as u64 * self.note_ms as u64 / NOTE_MS as u64) as u32
+    }
+}
 
 /// Converts chess notation to audio samples. Input is a string of chess moves,
 /// e.g. "e4 e5 Nf3 Nc6".
 pub fn generate(input: &str) -> Vec<i16> {
-    // Generates silence samples for the specified duration.
-    // E.g vec![0, 0, 0, ...] for 50 ms.
-    let silence: Vec<i16> = vec![0; (SAMPLE_RATE * SILENCE_MS / MS_PER_SECOND) as usize];
+    generate_with_config(input, &AudioConfig::default())
+}
+
+/// Like [`generate`], but each piece and threat plays the sound in `map`.
+pub fn generate_with(map: &SoundMap, input: &str) -> Vec<i16> {
+    let config = AudioConfig {
+        sounds: map.clone(),
+        ..AudioConfig::default()
+    };
+    generate_with_config(input, &config)
+}
+
+/// Like [`generate`], then applies the effects selected in `config`.
+pub fn generate_with_config(input: &str, config: &AudioConfig) -> Vec<i16> {
+    render_game(input, config, None)
+}
+
+/// Like [`generate_with_config`], but each piece plays a General MIDI
+/// instrument from `soundfont` instead of a synthesized waveform.
+#[cfg(feature = "soundfont")]
+pub fn generate_with_soundfont(input: &str, config: &AudioConfig, soundfont: &soundfont::SoundFont) -> Vec<i16> {
+    let instrument = |piece: Piece, freq: u32, duration_ms: u32| {
+        Some(soundfont.render(midi::gm_program(piece), freq, duration_ms))
+    };
+    render_game(input, config, Some(&instrument))
+}
+
+/// Like [`generate_with_config`], but pieces with a recording in `samples`
+/// play it pitch-shifted to the square; the rest keep their synth voice.
+pub fn generate_with_samples(input: &str, config: &AudioConfig, samples: &sampler::SampleSet) -> Vec<i16> {
+    let instrument = |piece: Piece, freq: u32, duration_ms: u32| samples.render(piece, freq, duration_ms);
+    render_game(input, config, Some(&instrument))
+}
+
+/// Renders a note for a piece at a frequency and duration in place of the
+/// built-in synth (e.g. a SoundFont), or None to fall back to the synth.
+/// Threat and color-filter shaping do not apply to instrument notes.
+type Instrument<'a> = &'a dyn Fn(Piece, u32, u32) -> Option<Vec<f64>>;
+
+/// Whether notes are separated by silence or run into each other.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Articulation {
+    Detached,
+    Legato,
+}
+
+/// Per-move shaping on top of the piece→sound mapping.
+#[derive(Clone, Copy)]
+struct Phrasing<'a> {
+    sounds: &'a SoundMap,
+    pitch: PitchMap,
+    side: SideVoicing,
+    glide: Option<Glide>,
+    articulation: Articulation,
+    instrument: Option<Instrument<'a>>,
+    timing: Timing,
+    /// Humanized change to a detached note's length
+    length_offset_ms: i32,
+}
+
+impl Phrasing<'_> {
+    fn plain() -> Self {
+        Self {
+            sounds: classic_sounds(),
+            pitch: PitchMap::default(),
+            side: SideVoicing::default(),
+            glide: None,
+            articulation: Articulation::Detached,
+            instrument: None,
+            timing: Timing::default(),
+            length_offset_ms: 0,
+        }
+    }
+}
+
+impl<'a> Phrasing<'a> {
+    /// [`Phrasing::plain`] with the sounds and pitch map of `theme`.
+    fn themed(theme: &'a theme::Theme) -> Self {
+        Self { sounds: &theme.sounds, pitch: theme.pitch, ..Phrasing::plain() }
+    }
+}
+
+fn render_game(input: &str, config: &AudioConfig, instrument: Option<Instrument>) -> Vec<i16> {
+    let mut mixer = Mixer::new();
+    let (voices, length) = game_voices(input, config, instrument);
+    for (_, voice) in voices {
+        mixer.add(voice);
+    }
+    mixer.extend_to(length);
+    let num_channels = config.num_channels() as usize;
+    let bus = if num_channels == STEREO_CHANNELS as usize { mixer.mix_stereo() } else { mixer.mix_mono() };
+    master(bus, num_channels, config)
+}
+
+/// Every voice of the game on its timeline, with the piece channel it
+/// belongs to (None for the percussion), and the game's length in samples,
+/// the silence after the last move included.
+fn game_voices(input: &str, config: &AudioConfig, instrument: Option<Instrument>) -> (Vec<(Option<usize>, Voice)>, usize) {
+    let articulation = if config.legato { Articulation::Legato } else { Articulation::Detached };
+    let slot_samples = config.timing.slot_samples();
+    let jitter = humanize::Jitter::new(config.humanize);
+    let mut voices = Vec::new();
+    let mut length = 0;
+    let mut notes = NoteCache::default();
+    let mut board = config.start.board.clone();
+    let mut previous_freq: Option<u32> = None;
+    let moves = game_moves(input, &config.start);
+
+    for (slot, (move_index, notation, m)) in moves.enumerate() {
+        let color = mover(move_index);
+        let captured = play_on_board(&mut board, &m, notation, color);
+        let gain = if config.dynamics { dynamics::velocity(dynamics::importance(&m, captured)) } else { 1.0 };
+        let side = config.color_voicing.map(|voicing| voicing.for_color(color)).unwrap_or_default();
+        let glide = config
+            .portamento_ms
+            .zip(previous_freq)
+            .map(|(duration_ms, from_freq)| Glide { from_freq, duration_ms });
+        let offsets = jitter.next();
+        let phrasing = Phrasing {
+            sounds: &config.sounds,
+            pitch: config.pitch,
+            side,
+            glide,
+            articulation,
+            instrument,
+            timing: config.timing,
+            length_offset_ms: offsets.length_ms,
+        };
+        previous_freq = Some(sounding_freq(&m, phrasing));
+        let pan = config.stereo.unwrap_or_default().pan(&m.dest, color);
+        let placement = spatial::Placement::of(&m.dest);
+        let onset = humanize::shift(slot * slot_samples, offsets.onset_ms, SAMPLE_RATE);
+        let channel = piece_channel(sounding_piece(&m));
+        for voice in move_voices(&m, onset, phrasing, &mut notes) {
+            let voice = voice.with_gain(gain);
+            voices.push((Some(channel), if config.spatial { placement.apply(voice) } else { voice.with_pan(pan) }));
+        }
+        if config.percussion && color == Color::White {
+            let hit = full_move_hit(move_index / 2 + 1);
+            voices.push((None, Voice::new(slot * slot_samples, hit).with_gain(PERCUSSION_GAIN)));
+        }
+        // Keeps the trailing silence after the last move
+        length = (slot + 1) * slot_samples;
+    }
+    (voices, length)
+}
+
+/// The effects `config` selects over the whole mix, `num_channels`
+/// interleaved, then quantized.
+fn master(mut bus: Vec<f64>, num_channels: usize, config: &AudioConfig) -> Vec<i16> {
+    if let Some(reverb) = &config.reverb {
+        bus = map_channels(&bus, num_channels, |channel| reverb.apply(channel));
+    }
+    if let Some(target) = config.loudness_target {
+        bus = loudness::normalize(&bus, num_channels, target);
+    }
+    if let Some(peak) = config.normalize_peak {
+        bus = fx::normalize(&bus, peak);
+    }
+    if let Some(gain) = config.gain {
+        bus = fx::amplify(&bus, gain);
+    }
+    if config.soft_limit {
+        bus = fx::soft_limit(&bus);
+    }
+    to_pcm(&bus)
+}
+
+/// Converts chess notation to interleaved 6-channel samples, one channel per
+/// piece type. Each move sounds only on its piece's channel and is silent on
+/// the others, so pieces can be remixed or muted individually in an editor.
+/// The moves are timed and voiced as [`generate_with_config`] renders them;
+/// each channel is mono, so `stereo` and `spatial` do not apply, and the
+/// percussion, on no piece's channel, is left out.
+pub fn generate_multichannel(input: &str, config: &AudioConfig) -> Vec<i16> {
+    let (voices, length) = game_voices(input, config, None);
+    let mut tracks: Vec<Mixer> = (0..PIECE_CHANNELS).map(|_| Mixer::new()).collect();
+    for (channel, voice) in voices {
+        if let Some(channel) = channel {
+            tracks[channel].add(voice);
+        }
+    }
+    let tracks: Vec<Vec<f64>> = tracks
+        .iter_mut()
+        .map(|track| {
+            track.extend_to(length);
+            track.mix_mono()
+        })
+        .collect();
+    // A note running past the end leaves the other tracks silent there
+    let frames = tracks.iter().map(Vec::len).max().unwrap_or(0);
+    let bus = (0..frames)
+        .flat_map(|frame| tracks.iter().map(move |track| track.get(frame).copied().unwrap_or(0.0)))
+        .collect();
+    master(bus, PIECE_CHANNELS as usize, config)
+}
+
+/// Converts chess notation to a Standard MIDI File: one note per move, on
+/// its piece's channel, with the pitch, side voicing, duration and dynamics
+/// from `config`. Timbre and effects have no MIDI equivalent and are dropped.
+pub fn generate_midi(input: &str, config: &AudioConfig) -> Vec<u8> {
+    let mut board = config.start.board.clone();
+    let jitter = humanize::Jitter::new(config.humanize);
+    let moves = game_moves(input, &config.start);
+
+    let notes: Vec<midi::Note> = moves
+        .enumerate()
+        .map(|(slot, (move_index, notation, m))| {
+            let color = mover(move_index);
+            let captured = play_on_board(&mut board, &m, notation, color);
+            let gain = if config.dynamics { dynamics::velocity(dynamics::importance(&m, captured)) } else { 1.0 };
+            let phrasing = Phrasing {
+                sounds: &config.sounds,
+                pitch: config.pitch,
+                side: config.color_voicing.map(|voicing| voicing.for_color(color)).unwrap_or_default(),
+                ..Phrasing::plain()
+            };
+            let offsets = jitter.next();
+            let duration_ms = if config.legato {
+                config.timing.slot_ms()
+            } else {
+                let duration_ms = config.timing.note_duration(config.sounds.get(sounding_piece(&m), m.threat).duration_ms);
+                humanize::stretch(duration_ms, offsets.length_ms)
+            };
+            let start_ms = humanize::shift(slot * config.timing.slot_ms() as usize, offsets.onset_ms, MS_PER_SECOND);
+            midi::Note {
+                piece: sounding_piece(&m),
+                start_ms: start_ms as u32,
+                duration_ms,
+                key: midi::key_for_freq(sounding_freq(&m, phrasing)),
+                velocity: (MIDI_VELOCITY * gain).round() as u8,
+            }
+        })
+        .collect();
+    midi::encode(&notes)
+}
+
+/// Channel index for a piece in multi-channel output:
+/// pawn, knight, bishop, rook, queen, king.
+pub fn piece_channel(piece: Piece) -> usize {
+    match piece {
+        Piece::Pawn => 0,
+        Piece::Knight => 1,
+        Piece::Bishop => 2,
+        Piece::Rook => 3,
+        Piece::Queen => 4,
+        Piece::King => 5,
+    }
+}
+
+/// Stereo position of a square's file, from `-STEREO_WIDTH` (a) to
+/// `STEREO_WIDTH` (h).
+fn file_pan(square: &Square) -> f64 {
+    (square.file as f64 / 7.0 * 2.0 - 1.0) * STEREO_WIDTH
+}
+
+/// Runs a mono effect over each channel of an interleaved stream and
+/// re-interleaves the results, which must all have the same length.
+fn map_channels(samples: &[f64], num_channels: usize, effect: impl Fn(&[f64]) -> Vec<f64>) -> Vec<f64> {
+    if num_channels == 1 {
+        return effect(samples);
+    }
+    let processed: Vec<Vec<f64>> = (0..num_channels)
+        .map(|channel| effect(&samples.iter().skip(channel).step_by(num_channels).copied().collect::<Vec<f64>>()))
+        .collect();
+    (0..processed[0].len())
+        .flat_map(|frame| processed.iter().map(move |channel| channel[frame]))
+        .collect()
+}
+
+/// The moves of `input` played from `start`, with their move index: the
+/// token's place in the game counted from move 1, so the first token of a
+/// game starting with Black to move has an odd index.
+fn game_moves<'a>(input: &'a str, start: &Position) -> impl Iterator<Item = (usize, &'a str, NotationMove)> {
+    let first_index = start.ply;
+    input
+        .split_whitespace()
+        .enumerate()
+        .map(move |(idx, notation)| (first_index + idx, notation))
+        .filter_map(|(move_index, notation)| NotationMove::parse(notation, move_index).map(|m| (move_index, notation, m)))
+}
 
+/// Tokens of `input` that are not moves and make no sound, such as typos.
+/// Move numbers ("12.", "12...") and results ("1-0") belong in movetext and
+/// are not listed.
+pub fn skipped_tokens(input: &str) -> Vec<&str> {
     input
         .split_whitespace()
         .enumerate()
-        .filter_map(|(idx, notation)| NotationMove::parse(notation, idx))
-        .flat_map(|m| move_to_samples(&m, &silence))
+        .filter(|(idx, token)| NotationMove::parse(token, *idx).is_none() && !is_movetext_marker(token))
+        .map(|(_, token)| token)
         .collect()
 }
 
+/// A move number or a game result.
+fn is_movetext_marker(token: &str) -> bool {
+    let after_digits = token.trim_start_matches(|ch: char| ch.is_ascii_digit());
+    let move_number = after_digits.len() < token.len() && !after_digits.is_empty() && after_digits.chars().all(|ch| ch == '.');
+    move_number || matches!(token, "1-0" | "0-1" | "1/2-1/2" | "*")
+}
+
+/// Side to move for the `move_index`-th token: White on even, Black on odd.
+fn mover(move_index: usize) -> Color {
+    if move_index.is_multiple_of(2) { Color::White } else { Color::Black }
+}
+
+/// Percussion marking the start of a full move: a kick every
+/// `KICK_EVERY_MOVES`, a click otherwise.
+fn full_move_hit(full_move: usize) -> Vec<f64> {
+    if full_move.is_multiple_of(KICK_EVERY_MOVES) {
+        to_bus(&synth::kick(KICK_MS))
+    } else {
+        to_bus(&synth::click(CLICK_MS))
+    }
+}
+
+/// Replays the move on `board` and returns the piece it captured, if any.
+/// Moves the board cannot resolve are skipped, so later captures may be missed.
+fn play_on_board(board: &mut Board, m: &NotationMove, notation: &str, color: Color) -> Option<Piece> {
+    let resolved = board.resolve_move(m, notation, color).ok()?;
+    let occupant = board.get(resolved.dest.file, resolved.dest.rank).map(|(piece, _)| piece);
+    board.apply_move(&resolved).ok()?;
+    match (m.capture, occupant) {
+        (Capture::Taken, Some(piece)) => Some(piece),
+        // En passant: the destination is empty but a pawn was taken
+        (Capture::Taken, None) => Some(Piece::Pawn),
+        (Capture::None, _) => None,
+    }
+}
+
+/// The destination square's pitch, shifted by the side's octave offset.
+fn sounding_freq(m: &NotationMove, phrasing: Phrasing) -> u32 {
+    let freq = phrasing.pitch.freq(&m.dest);
+    let octave_shift = phrasing.side.octave_shift;
+    if octave_shift >= 0 {
+        freq << octave_shift
+    } else {
+        freq >> -octave_shift
+    }
+}
+
+/// A promoted pawn sounds (and is routed) as the piece it becomes.
+fn sounding_piece(m: &NotationMove) -> Piece {
+    m.promotion.unwrap_or(m.piece)
+}
+
+/// The note `piece` plays on `square`, with no threat, capture or trailing
+/// silence — for exploring the board's pitches interactively.
+pub fn preview_square(square: Square, piece: Piece) -> Vec<i16> {
+    preview_square_with_gain(square, piece, 1.0)
+}
+
+/// [`preview_square`] scaled by `gain` before quantization.
+pub fn preview_square_with_gain(square: Square, piece: Piece, gain: f64) -> Vec<i16> {
+    preview_note(square, piece, Phrasing::plain(), gain)
+}
+
+/// [`preview_square_with_gain`] with `theme`'s sounds and pitches.
+pub fn preview_square_themed(square: Square, piece: Piece, theme: &theme::Theme, gain: f64) -> Vec<i16> {
+    preview_note(square, piece, Phrasing::themed(theme), gain)
+}
+
+fn preview_note(square: Square, piece: Piece, phrasing: Phrasing, gain: f64) -> Vec<i16> {
+    let m = NotationMove {
+        piece,
+        dest: square,
+        threat: Threat::None,
+        capture: Capture::None,
+        promotion: None,
+    };
+    to_pcm(&fx::amplify(&render_note(&m, NOTE_MS, phrasing), gain))
+}
+
+/// The soft downward sweep played when a move is taken back, scaled by
+/// `gain`.
+pub fn rewind_with_gain(gain: f64) -> Vec<i16> {
+    to_pcm(&fx::amplify(&to_bus(&synth::rewind(REWIND_MS)), gain))
+}
+
+/// The quiet chime played when a comment is added to a move, scaled by
+/// `gain`.
+pub fn chime_with_gain(gain: f64) -> Vec<i16> {
+    to_pcm(&fx::amplify(&to_bus(&synth::chime(CHIME_MS)), gain))
+}
+
+/// The low buzz played when a typed move is rejected, scaled by `gain`.
+pub fn buzz_with_gain(gain: f64) -> Vec<i16> {
+    to_pcm(&fx::amplify(&to_bus(&synth::buzz(BUZZ_MS)), gain))
+}
+
+/// The jingle played when a side wins by resignation, scaled by `gain`.
+pub fn win_jingle_with_gain(gain: f64) -> Vec<i16> {
+    to_pcm(&fx::amplify(&to_bus(&synth::jingle(&WIN_JINGLE_HZ, JINGLE_NOTE_MS)), gain))
+}
+
+/// The jingle played when a draw is agreed, scaled by `gain`.
+pub fn draw_jingle_with_gain(gain: f64) -> Vec<i16> {
+    to_pcm(&fx::amplify(&to_bus(&synth::jingle(&DRAW_JINGLE_HZ, JINGLE_NOTE_MS)), gain))
+}
+
 pub fn synthesize_move(m: &NotationMove) -> Vec<i16> {
-    let silence: Vec<i16> = vec![0; (SAMPLE_RATE * SILENCE_MS / MS_PER_SECOND) as usize];
-    move_to_samples(m, &silence)
-}
-
-pub fn play(wav: &[u8]) {
-    let path = std::env::temp_dir().join("chesswav.wav");
-    std::fs::write(&path, wav).expect("Failed to write temp file");
-
-    #[cfg(target_os = "macos")]
-    std::process::Command::new("afplay")
-        .arg(&path)
-        .status()
-        .expect("Failed to play audio");
-
-    #[cfg(target_os = "linux")]
-    std::process::Command::new("aplay")
-        .args(["-f", "S16_LE", "-r", "44100", "-c", "1"])
-        .arg(&path)
-        .status()
-        .expect("Failed to play audio");
-
-    std::fs::remove_file(&path).ok();
-}
-
-fn move_to_samples(m: &NotationMove, silence: &[i16]) -> Vec<i16> {
-    let freq: u32 = freq::from_square(&m.dest);
-    let piece = m.promotion.unwrap_or(m.piece);
-    let note: Vec<i16> = match (piece, m.threat) {
-        (Piece::Pawn, Threat::None) => synth::sine(freq, NOTE_MS),
-        (Piece::Pawn, Threat::Check) => synth::triangle(freq, NOTE_MS, Blend::with_sine(0.7)),
-        (Piece::Pawn, Threat::Checkmate) => synth::triangle(freq, NOTE_MS, Blend::with_sine(0.9)),
-        (Piece::Knight, Threat::None) => synth::triangle(freq, NOTE_MS, Blend::none()),
-        (Piece::Knight, Threat::Check) => synth::triangle(freq, NOTE_MS, Blend::with_sine(0.4)),
-        (Piece::Knight, Threat::Checkmate) => synth::triangle(freq, NOTE_MS, Blend::with_sine(0.7)),
-        (Piece::Rook, Threat::None) => synth::square(freq, NOTE_MS, Blend::with_sine_and_band_limit(0.4, 7)),
-        (Piece::Rook, Threat::Check) => synth::square(freq, NOTE_MS, Blend::with_sine_and_band_limit(0.6, 3)),
-        (Piece::Rook, Threat::Checkmate) => synth::square(freq, NOTE_MS, Blend::with_sine_and_band_limit(0.8, 2)),
-        (Piece::Bishop, Threat::None) => synth::sawtooth(freq, NOTE_MS, Blend::with_sine_and_band_limit(0.3, 8)),
-        (Piece::Bishop, Threat::Check) => synth::sawtooth(freq, NOTE_MS, Blend::with_sine_and_band_limit(0.5, 3)),
-        (Piece::Bishop, Threat::Checkmate) => synth::sawtooth(freq, NOTE_MS, Blend::with_sine_and_band_limit(0.7, 2)),
-        (Piece::Queen, Threat::None) => synth::composite(freq, NOTE_MS, Blend::none()),
-        (Piece::Queen, Threat::Check) => synth::composite(freq, NOTE_MS, Blend::with_sine_and_band_limit(0.4, 3)),
-        (Piece::Queen, Threat::Checkmate) => synth::composite(freq, NOTE_MS, Blend::with_sine_and_band_limit(0.6, 2)),
-        (Piece::King, Threat::None) => synth::harmonics(freq, NOTE_MS, Blend::none()),
-        (Piece::King, Threat::Check) => synth::harmonics(freq, NOTE_MS, Blend::none()),
-        (Piece::King, Threat::Checkmate) => synth::harmonics(freq, NOTE_MS, Blend::with_sine(0.5)),
+    synthesize_move_with_gain(m, 1.0)
+}
+
+/// [`synthesize_move`] scaled by `gain` before quantization.
+pub fn synthesize_move_with_gain(m: &NotationMove, gain: f64) -> Vec<i16> {
+    to_pcm(&fx::amplify(&move_to_samples(m, Phrasing::plain()), gain))
+}
+
+/// [`synthesize_move_with_gain`] with `theme`'s sounds and pitches.
+pub fn synthesize_move_themed(m: &NotationMove, theme: &theme::Theme, gain: f64) -> Vec<i16> {
+    to_pcm(&fx::amplify(&move_to_samples(m, Phrasing::themed(theme)), gain))
+}
+
+/// Renders one move in isolation: its voices mixed into a single slot.
+fn move_to_samples(m: &NotationMove, phrasing: Phrasing) -> Vec<f64> {
+    let mut mixer = Mixer::new();
+    for voice in move_voices(m, 0, phrasing, &mut NoteCache::default()) {
+        mixer.add(voice);
+    }
+    mixer.extend_to(SAMPLES_PER_MOVE);
+    mixer.mix_mono()
+}
+
+/// Voices for one move starting at `start`: its note, plus a noise hit on captures.
+/// A legato note fills its whole slot and overlaps the next by `CROSSFADE_MS`.
+fn move_voices(m: &NotationMove, start: usize, phrasing: Phrasing, notes: &mut NoteCache) -> Vec<Voice> {
+    let note = match phrasing.articulation {
+        Articulation::Detached => {
+            let duration_ms = phrasing.timing.note_duration(phrasing.sounds.get(sounding_piece(m), m.threat).duration_ms);
+            let duration_ms = humanize::stretch(duration_ms, phrasing.length_offset_ms);
+            Voice::new(start, notes.render(m, duration_ms, phrasing))
+        }
+        Articulation::Legato => {
+            let fade_samples = (SAMPLE_RATE * CROSSFADE_MS / MS_PER_SECOND) as usize;
+            Voice::new(start, notes.render(m, phrasing.timing.slot_ms() + CROSSFADE_MS, phrasing)).with_crossfade(fade_samples)
+        }
+    };
+    let mut voices = vec![note];
+
+    if m.capture == Capture::Taken {
+        voices.push(Voice::new(start, to_bus(&synth::noise_burst(CAPTURE_BURST_MS))));
+    }
+
+    voices
+}
+
+/// Everything that shapes a rendered note. Games revisit the same squares
+/// with the same pieces constantly, so equal keys share one buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct NoteKey {
+    piece: Piece,
+    freq: u32,
+    threat: Threat,
+    duration_ms: u32,
+    /// `f64::to_bits` of the side's cutoff, as f64 is not `Hash`
+    cutoff_bits: Option<u64>,
+    glide: Option<Glide>,
+}
+
+/// Notes already rendered during one game, copied instead of re-synthesized.
+/// Only valid for a single instrument, so it lives no longer than one render.
+#[derive(Debug, Default)]
+struct NoteCache {
+    notes: HashMap<NoteKey, Vec<f64>>,
+}
+
+impl NoteCache {
+    fn render(&mut self, m: &NotationMove, duration_ms: u32, phrasing: Phrasing) -> Vec<f64> {
+        let key = NoteKey {
+            piece: sounding_piece(m),
+            freq: sounding_freq(m, phrasing),
+            threat: m.threat,
+            duration_ms,
+            cutoff_bits: phrasing.side.cutoff_hz.map(f64::to_bits),
+            glide: phrasing.glide,
+        };
+        self.notes
+            .entry(key)
+            .or_insert_with(|| render_note(m, duration_ms, phrasing))
+            .clone()
+    }
+}
+
+/// The move's note on the mix bus, from the instrument if one is set,
+/// otherwise from the synth with the piece's sound, color and glide shaping.
+fn render_note(m: &NotationMove, duration_ms: u32, phrasing: Phrasing) -> Vec<f64> {
+    let freq: u32 = sounding_freq(m, phrasing);
+    if let Some(note) = phrasing.instrument.and_then(|instrument| instrument(sounding_piece(m), freq, duration_ms)) {
+        return note;
+    }
+
+    let sound = phrasing.sounds.get(sounding_piece(m), m.threat);
+    let colored = match phrasing.side.cutoff_hz {
+        Some(cutoff_hz) => sound.blend.with_filter(Filter::low_pass(cutoff_hz)),
+        None => sound.blend,
+    };
+    let blend = match phrasing.glide {
+        Some(glide) => colored.with_glide(glide),
+        None => colored,
     };
+    to_bus(&synth::render(&sound.timbre, freq, duration_ms, blend))
+}
+
+/// The default [`SoundMap`], shared by renders that take no config.
+fn classic_sounds() -> &'static SoundMap {
+    static CLASSIC: OnceLock<SoundMap> = OnceLock::new();
+    CLASSIC.get_or_init(SoundMap::default)
+}
 
-    note.into_iter().chain(silence.iter().copied()).collect()
+/// Lifts 16-bit samples onto the floating-point mix bus (full scale = ±1.0).
+fn to_bus(samples: &[i16]) -> Vec<f64> {
+    samples.iter().map(|&sample| sample as f64 / i16::MAX as f64).collect()
 }
 
-/// Converts samples to WAV file format.
+/// Quantizes the mix bus to 16-bit, hard-clipping anything beyond full scale
+/// (use `fx::soft_limit`/`fx::normalize` first to avoid that).
+fn to_pcm(bus: &[f64]) -> Vec<i16> {
+    bus.iter()
+        .map(|sample| (sample.clamp(-1.0, 1.0) * i16::MAX as f64).round() as i16)
+        .collect()
+}
+
+/// Converts mono samples to WAV file format.
 pub fn to_wav(samples: &[i16]) -> Vec<u8> {
-    let mut data = Vec::with_capacity(wav::HEADER_SIZE + samples.len() * BYTES_PER_SAMPLE);
-    data.extend_from_slice(&wav::header(samples.len() as u32));
+    to_wav_with_channels(samples, NUM_CHANNELS)
+}
+
+/// Converts interleaved samples with `num_channels` channels to WAV file format.
+pub fn to_wav_with_channels(samples: &[i16], num_channels: u16) -> Vec<u8> {
+    to_wav_with_chunks(samples, num_channels, SAMPLE_RATE, &[])
+}
+
+/// Like [`to_wav_with_channels`], for samples at `sample_rate` Hz and with
+/// metadata `chunks` ahead of them.
+pub fn to_wav_with_chunks(samples: &[i16], num_channels: u16, sample_rate: u32, chunks: &[wav::Chunk]) -> Vec<u8> {
+    let num_frames = samples.len() / num_channels as usize;
+    let header = wav::header_with_rate(num_frames as u32, num_channels, sample_rate, chunks);
+    let mut data = Vec::with_capacity(header.len() + samples.len() * BYTES_PER_SAMPLE);
+    data.extend_from_slice(&header);
     data.extend(samples.iter().flat_map(|s| s.to_le_bytes()));
     data
 }
 
+/// Writes interleaved samples at `sample_rate` Hz as a WAV file to `out`,
+/// without building the whole file in memory first.
+pub fn write_wav(
+    out: &mut impl Write,
+    samples: &[i16],
+    num_channels: u16,
+    sample_rate: u32,
+    chunks: &[wav::Chunk],
+) -> Result<(), AudioError> {
+    let num_frames = samples.len() / num_channels as usize;
+    out.write_all(&wav::header_with_rate(num_frames as u32, num_channels, sample_rate, chunks))?;
+    for block in samples.chunks(SAMPLES_PER_MOVE) {
+        let bytes: Vec<u8> = block.iter().flat_map(|s| s.to_le_bytes()).collect();
+        out.write_all(&bytes)?;
+    }
+    out.flush()?;
+    Ok(())
+}
+
+/// A cue at the start of each move's slot, labelled in PGN style: "12. Nxe5"
+/// for White, "12... Nc6" for Black. Matches the timeline of every
+/// `generate*` function, in frames.
+pub fn move_cues(input: &str) -> Vec<wav::Cue> {
+    move_cues_with_timing(input, Timing::default())
+}
+
+/// [`move_cues`] for a game rendered with `timing`.
+pub fn move_cues_with_timing(input: &str, timing: Timing) -> Vec<wav::Cue> {
+    move_cues_with_config(input, &AudioConfig { timing, ..AudioConfig::default() })
+}
+
+/// [`move_cues`] for a game rendered with `config`: its timing, and move
+/// numbers counted from its start position.
+pub fn move_cues_with_config(input: &str, config: &AudioConfig) -> Vec<wav::Cue> {
+    let slot_samples = config.timing.slot_samples();
+    game_moves(input, &config.start)
+        .enumerate()
+        .map(|(slot, (move_index, notation, _))| {
+            let full_move = move_index / 2 + 1;
+            let dots = match mover(move_index) {
+                Color::White => ".",
+                Color::Black => "...",
+            };
+            wav::Cue {
+                frame: (slot * slot_samples) as u32,
+                label: format!("{full_move}{dots} {notation}"),
+            }
+        })
+        .collect()
+}
+
+/// `LIST`/`INFO` metadata from a game's PGN tags, so players show it: the
+/// event as title (INAM), the players as artist (IART), date (ICRD), and
+/// site, round and result as comment (ICMT). None if no tag applies.
+pub fn game_info(tags: &Tags) -> Option<wav::Chunk> {
+    let event = match (tags.get("Event"), tags.get("Round")) {
+        (Some(event), Some(round)) => format!("{event}, round {round}"),
+        (event, _) => event.unwrap_or_default().to_string(),
+    };
+    let players = match (tags.get("White"), tags.get("Black")) {
+        (Some(white), Some(black)) => format!("{white} vs {black}"),
+        (white, black) => white.or(black).unwrap_or_default().to_string(),
+    };
+    let comment = [tags.get("Site"), tags.get("Result").map(|result| format!("Result {result}")).as_deref()]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(", ");
+    let date = tags.get("Date").unwrap_or_default();
+    let info = [(b"INAM", event.as_str()), (b"IART", &players), (b"ICRD", date), (b"ICMT", &comment)];
+    info.iter().any(|(_, text)| !text.is_empty()).then(|| wav::Chunk::info(&info))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const SAMPLES_PER_MOVE: usize = (SAMPLE_RATE * (NOTE_MS + SILENCE_MS) / MS_PER_SECOND) as usize;
-
     #[test]
     fn empty_input() {
         assert!(generate("").is_empty());
@@ -156,6 +1021,57 @@ mod tests {
         );
     }
 
+    #[test]
+    fn game_info_maps_pgn_tags() {
+        let (tags, _) = crate::engine::pgn::split_tags(
+            "[Event \"Casual\"]\n[Site \"London\"]\n[White \"Anderssen\"]\n[Black \"Kieseritzky\"]\n[Result \"1-0\"]",
+        );
+        let expected = wav::Chunk::info(&[
+            (b"INAM", "Casual"),
+            (b"IART", "Anderssen vs Kieseritzky"),
+            (b"ICMT", "London, Result 1-0"),
+        ]);
+        assert_eq!(game_info(&tags), Some(expected));
+    }
+
+    #[test]
+    fn move_cues_mark_each_slot() {
+        let cues = move_cues("e4 e5 Nf3");
+        let labels: Vec<&str> = cues.iter().map(|cue| cue.label.as_str()).collect();
+        assert_eq!(labels, vec!["1. e4", "1... e5", "2. Nf3"]);
+        assert_eq!(cues[2].frame, 2 * SAMPLES_PER_MOVE as u32);
+    }
+
+    #[test]
+    fn move_cues_count_from_the_start_position() {
+        let config = AudioConfig {
+            start: Board::from_fen("4k3/8/8/8/8/8/4P3/4K3 b - - 0 40").unwrap(),
+            ..AudioConfig::default()
+        };
+        let labels: Vec<String> = move_cues_with_config("Kd7 e4", &config).into_iter().map(|cue| cue.label).collect();
+        assert_eq!(labels, vec!["40... Kd7", "41. e4"]);
+    }
+
+    #[test]
+    fn move_cues_skip_unparsed_tokens() {
+        let cues = move_cues("e4 ?? e5");
+        assert_eq!(cues.len(), 2);
+        assert_eq!(cues[1].frame, SAMPLES_PER_MOVE as u32);
+    }
+
+    #[test]
+    fn game_info_needs_tags() {
+        assert_eq!(game_info(&Tags::default()), None);
+    }
+
+    #[test]
+    fn wav_with_chunks_grows_by_the_chunks() {
+        let samples = generate("e4");
+        let info = wav::Chunk::info(&[(b"INAM", "Game")]);
+        let wav = to_wav_with_chunks(&samples, NUM_CHANNELS, SAMPLE_RATE, &[info]);
+        assert_eq!(wav.len(), to_wav(&samples).len() + 26);
+    }
+
     #[test]
     fn check_produces_different_samples() {
         let normal = generate("Nf3");
@@ -177,10 +1093,613 @@ mod tests {
         assert_ne!(check, checkmate);
     }
 
+    #[test]
+    fn king_check_rings_differently_from_quiet_king_move() {
+        assert_ne!(generate("Kf1"), generate("Kf1+"));
+    }
+
     #[test]
     fn promotion_uses_promoted_piece_timbre() {
         let pawn = generate("e8");
         let promoted = generate("e8=Q");
         assert_ne!(pawn, promoted);
     }
+
+    #[test]
+    fn multichannel_has_six_samples_per_frame() {
+        assert_eq!(generate_multichannel("e4 Nf6", &AudioConfig::default()).len(), SAMPLES_PER_MOVE * 2 * 6);
+    }
+
+    #[test]
+    fn multichannel_routes_move_to_its_piece_channel() {
+        let samples = generate_multichannel("Nf3", &AudioConfig::default());
+        let knight = piece_channel(Piece::Knight);
+        let frames: Vec<&[i16]> = samples.chunks(PIECE_CHANNELS as usize).collect();
+        assert!(frames.iter().any(|frame| frame[knight] != 0));
+        for frame in frames {
+            for (channel, &sample) in frame.iter().enumerate() {
+                if channel != knight {
+                    assert_eq!(sample, 0, "channel {channel} should be silent");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn multichannel_channel_matches_mono_mix() {
+        let mono = generate("e4");
+        let pawn = piece_channel(Piece::Pawn);
+        let pawn_track: Vec<i16> = generate_multichannel("e4", &AudioConfig::default())
+            .chunks(PIECE_CHANNELS as usize)
+            .map(|frame| frame[pawn])
+            .collect();
+        assert_eq!(pawn_track, mono);
+    }
+
+    #[test]
+    fn multichannel_follows_the_config() {
+        let config = AudioConfig {
+            sounds: theme::Theme::from_name("8-bit").unwrap().sounds,
+            color_voicing: Some(ColorVoicing::default()),
+            timing: Timing { note_ms: 150, gap_ms: 50 },
+            start: Board::from_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1").unwrap(),
+            ..AudioConfig::default()
+        };
+        let pawn = piece_channel(Piece::Pawn);
+        let pawn_track: Vec<i16> = generate_multichannel("e5 d4", &config)
+            .chunks(PIECE_CHANNELS as usize)
+            .map(|frame| frame[pawn])
+            .collect();
+        assert_eq!(pawn_track, generate_with_config("e5 d4", &config));
+        assert_eq!(pawn_track.len(), config.timing.slot_samples() * 2);
+    }
+
+    #[test]
+    fn multichannel_wav_header_declares_six_channels() {
+        let wav = to_wav_with_channels(&generate_multichannel("e4", &AudioConfig::default()), PIECE_CHANNELS);
+        assert_eq!(u16::from_le_bytes([wav[22], wav[23]]), 6);
+        assert_eq!(wav.len(), wav::header(0, 6).len() + SAMPLES_PER_MOVE * 6 * BYTES_PER_SAMPLE);
+    }
+
+    #[test]
+    fn capture_adds_percussive_layer() {
+        assert_ne!(generate("Nf3"), generate("Nxf3"));
+    }
+
+    #[test]
+    fn capture_same_length_as_quiet_move() {
+        assert_eq!(generate("Nf3").len(), generate("Nxf3").len());
+    }
+
+    #[test]
+    fn default_config_matches_generate() {
+        assert_eq!(generate_with_config("e4 e5", &AudioConfig::default()), generate("e4 e5"));
+    }
+
+    #[test]
+    fn reverb_config_adds_tail() {
+        let config = AudioConfig {
+            reverb: Some(Reverb::default()),
+            ..AudioConfig::default()
+        };
+        assert!(generate_with_config("e4", &config).len() > generate("e4").len());
+    }
+
+    #[test]
+    fn portamento_leaves_first_note_untouched() {
+        let config = AudioConfig {
+            portamento_ms: Some(80),
+            ..AudioConfig::default()
+        };
+        let gliding = generate_with_config("e4 e5", &config);
+        let discrete = generate("e4 e5");
+        assert_eq!(gliding.len(), discrete.len());
+        assert_eq!(gliding[..SAMPLES_PER_MOVE], discrete[..SAMPLES_PER_MOVE]);
+        assert_ne!(gliding[SAMPLES_PER_MOVE..], discrete[SAMPLES_PER_MOVE..]);
+    }
+
+    #[test]
+    fn bus_round_trip_is_lossless() {
+        let samples = vec![i16::MIN + 1, -1234, 0, 42, i16::MAX];
+        assert_eq!(to_pcm(&to_bus(&samples)), samples);
+    }
+
+    #[test]
+    fn to_pcm_clips_overs_instead_of_wrapping() {
+        assert_eq!(to_pcm(&[1.7, -2.0]), vec![i16::MAX, -i16::MAX]);
+    }
+
+    #[test]
+    fn normalize_config_sets_peak() {
+        let config = AudioConfig {
+            normalize_peak: Some(0.5),
+            ..AudioConfig::default()
+        };
+        let peak = generate_with_config("Nxf3", &config).iter().map(|s| s.unsigned_abs()).max();
+        assert_eq!(peak, Some((0.5 * i16::MAX as f64).round() as u16));
+    }
+
+    #[test]
+    fn soft_limit_config_keeps_capture_below_full_scale() {
+        let config = AudioConfig {
+            soft_limit: true,
+            ..AudioConfig::default()
+        };
+        let peak = generate_with_config("Qxf7", &config).iter().map(|s| s.unsigned_abs()).max();
+        assert!(peak < Some(i16::MAX as u16));
+    }
+
+    #[test]
+    fn loudness_target_config_changes_level() {
+        let quiet = AudioConfig {
+            loudness_target: Some(-30.0),
+            ..AudioConfig::default()
+        };
+        let loud = AudioConfig {
+            loudness_target: Some(-14.0),
+            ..AudioConfig::default()
+        };
+        let peak = |config: &AudioConfig| generate_with_config("e4 e5 Nf3", config).iter().map(|s| s.unsigned_abs()).max();
+        assert!(peak(&quiet) < peak(&loud));
+    }
+
+    #[test]
+    fn color_voicing_off_by_default() {
+        assert_eq!(AudioConfig::default().color_voicing, None);
+    }
+
+    #[test]
+    fn skipped_tokens_are_typos_not_movetext() {
+        assert_eq!(skipped_tokens("1. e4 e5 2. Nf9 Nc6 3... Bx 1-0"), vec!["Nf9", "Bx"]);
+        assert!(skipped_tokens("e4 e5 O-O 1/2-1/2").is_empty());
+        assert_eq!(skipped_tokens("12x. e4"), vec!["12x."]);
+    }
+
+    #[test]
+    fn mover_alternates_white_and_black() {
+        assert_eq!(mover(0), Color::White);
+        assert_eq!(mover(1), Color::Black);
+        assert_eq!(mover(2), Color::White);
+    }
+
+    #[test]
+    fn sounding_freq_shifts_by_octaves() {
+        let m = NotationMove::parse("e4", 0).unwrap();
+        let base = PitchMap::default().freq(&m.dest);
+        let up = Phrasing {
+            side: SideVoicing { octave_shift: 1, cutoff_hz: None },
+            ..Phrasing::plain()
+        };
+        let down = Phrasing {
+            side: SideVoicing { octave_shift: -1, cutoff_hz: None },
+            ..Phrasing::plain()
+        };
+        assert_eq!(sounding_freq(&m, up), base * 2);
+        assert_eq!(sounding_freq(&m, down), base / 2);
+    }
+
+    #[test]
+    fn key_config_snaps_pitches() {
+        let config = AudioConfig {
+            pitch: PitchMap {
+                key: Key::from_tonic_name("C", Scale::NaturalMinor),
+                ..PitchMap::default()
+            },
+            ..AudioConfig::default()
+        };
+        // c4 is E in C major but E♭ in C minor; d4 (F) is in both
+        assert_ne!(generate_with_config("c4", &config), generate("c4"));
+        assert_eq!(generate_with_config("d4", &config), generate("d4"));
+    }
+
+    #[test]
+    fn default_voicing_keeps_white_and_changes_black() {
+        let config = AudioConfig {
+            color_voicing: Some(ColorVoicing::default()),
+            ..AudioConfig::default()
+        };
+        let plain = generate("e4 e5");
+        let voiced = generate_with_config("e4 e5", &config);
+        assert_eq!(voiced[..SAMPLES_PER_MOVE], plain[..SAMPLES_PER_MOVE]);
+        assert_ne!(voiced[SAMPLES_PER_MOVE..], plain[SAMPLES_PER_MOVE..]);
+    }
+
+    #[test]
+    fn same_move_sounds_different_per_color() {
+        let config = AudioConfig {
+            color_voicing: Some(ColorVoicing::default()),
+            ..AudioConfig::default()
+        };
+        // Same notation for both plies, so only the mover differs
+        let samples = generate_with_config("Nf3 Nf3", &config);
+        assert_ne!(samples[..SAMPLES_PER_MOVE], samples[SAMPLES_PER_MOVE..]);
+    }
+
+    #[test]
+    fn play_on_board_reports_captured_piece() {
+        let mut board = Board::new();
+        let moves = ["e4", "d5", "exd5", "Qxd5"];
+        let captured: Vec<Option<Piece>> = moves
+            .iter()
+            .enumerate()
+            .map(|(idx, notation)| {
+                let m = NotationMove::parse(notation, idx).unwrap();
+                play_on_board(&mut board, &m, notation, mover(idx))
+            })
+            .collect();
+        assert_eq!(captured, vec![None, None, Some(Piece::Pawn), Some(Piece::Pawn)]);
+    }
+
+    #[test]
+    fn dynamics_make_quiet_moves_softer_than_captures() {
+        let config = AudioConfig {
+            dynamics: true,
+            ..AudioConfig::default()
+        };
+        let samples = generate_with_config("e4 d5 Nf3 Qxd5 Nc3 Qxa2", &config);
+        let peak = |slot: usize| {
+            samples[slot * SAMPLES_PER_MOVE..(slot + 1) * SAMPLES_PER_MOVE]
+                .iter()
+                .map(|s| s.unsigned_abs())
+                .max()
+                .unwrap()
+        };
+        let quiet = peak(2);
+        let capture = peak(3);
+        assert!(capture > quiet, "capture {capture} should be louder than quiet move {quiet}");
+    }
+
+    #[test]
+    fn dynamics_off_by_default() {
+        assert!(!AudioConfig::default().dynamics);
+    }
+
+    #[test]
+    fn midi_plays_each_move_on_its_piece_channel() {
+        let midi = generate_midi("e4 Nf6", &AudioConfig::default());
+        let key = |notation: &str| {
+            let m = NotationMove::parse(notation, 0).unwrap();
+            midi::key_for_freq(PitchMap::default().freq(&m.dest))
+        };
+        let pawn_on = [0x90, key("e4"), 100];
+        let knight_on = [0x91, key("Nf6"), 100];
+        assert!(midi.windows(3).any(|event| event == pawn_on));
+        assert!(midi.windows(3).any(|event| event == knight_on));
+    }
+
+    #[test]
+    fn midi_dynamics_lower_quiet_velocities() {
+        let config = AudioConfig {
+            dynamics: true,
+            ..AudioConfig::default()
+        };
+        let midi = generate_midi("e4", &config);
+        let velocity = (MIDI_VELOCITY * dynamics::velocity(0.0)).round() as u8;
+        assert!(midi.windows(3).any(|event| event[0] == 0x90 && event[2] == velocity));
+    }
+
+    #[test]
+    fn default_timing_matches_the_fixed_slot() {
+        assert_eq!(Timing::default().slot_samples(), SAMPLES_PER_MOVE);
+        assert_eq!(Timing::default().note_duration(NOTE_MS), NOTE_MS);
+    }
+
+    #[test]
+    fn tempo_sets_the_slot_in_the_default_proportion() {
+        let timing = Timing::from_tempo(60.0);
+        assert_eq!(timing.slot_ms(), 1000);
+        assert_eq!(timing.note_ms, 857);
+        assert_eq!(timing.gap_ms, 143);
+    }
+
+    #[test]
+    fn timing_sets_slot_and_note_length() {
+        let config = AudioConfig {
+            timing: Timing { note_ms: 100, gap_ms: 20 },
+            ..AudioConfig::default()
+        };
+        let samples = generate_with_config("e4 e5", &config);
+        let slot_samples = (SAMPLE_RATE * 120 / MS_PER_SECOND) as usize;
+        let note_samples = (SAMPLE_RATE * 100 / MS_PER_SECOND) as usize;
+        assert_eq!(samples.len(), slot_samples * 2);
+        assert!(samples[note_samples..slot_samples].iter().all(|&s| s == 0));
+        assert!(samples[slot_samples..slot_samples + note_samples].iter().any(|&s| s != 0));
+    }
+
+    #[test]
+    fn theme_durations_scale_with_note_length() {
+        let timing = Timing { note_ms: 600, gap_ms: 0 };
+        assert_eq!(timing.note_duration(150), 300);
+    }
+
+    #[test]
+    fn cues_follow_the_timing() {
+        let cues = move_cues_with_timing("e4 e5", Timing { note_ms: 900, gap_ms: 100 });
+        assert_eq!(cues[1].frame, SAMPLE_RATE);
+    }
+
+    #[test]
+    fn humanize_is_repeatable_per_seed() {
+        let humanized = |max_ms: u32, seed: u32| {
+            let config = AudioConfig {
+                humanize: Some(Humanize { max_ms, seed }),
+                ..AudioConfig::default()
+            };
+            generate_with_config("e4 e5 Nf3 Nc6", &config)
+        };
+        assert_eq!(humanized(15, 1), humanized(15, 1));
+        assert_ne!(humanized(15, 1), humanized(15, 2));
+        assert_ne!(humanized(15, 1), generate("e4 e5 Nf3 Nc6"));
+        assert_eq!(humanized(0, 1), generate("e4 e5 Nf3 Nc6"));
+    }
+
+    #[test]
+    fn formats_from_names_and_extensions() {
+        assert_eq!(Format::from_name("flac"), Some(Format::Flac));
+        assert_eq!(Format::from_name("opus"), Some(Format::Ogg));
+        assert_eq!(Format::from_name("mp3"), None);
+        assert_eq!(Format::from_path(std::path::Path::new("game.MID")), Some(Format::Midi));
+        assert_eq!(Format::from_path(std::path::Path::new("game.pcm")), Some(Format::Raw));
+        assert_eq!(Format::from_path(std::path::Path::new("game")), None);
+    }
+
+    #[test]
+    fn extensions_read_back_as_their_format() {
+        for format in [Format::Wav, Format::Midi, Format::Ogg, Format::Flac, Format::Raw] {
+            assert_eq!(Format::from_name(format.extension()), Some(format));
+        }
+    }
+
+    #[test]
+    fn tenth_full_move_gets_a_kick() {
+        assert_eq!(full_move_hit(1), to_bus(&synth::click(CLICK_MS)));
+        assert_eq!(full_move_hit(10), to_bus(&synth::kick(KICK_MS)));
+        assert_eq!(full_move_hit(20), to_bus(&synth::kick(KICK_MS)));
+    }
+
+    #[test]
+    fn percussion_marks_only_white_moves() {
+        let config = AudioConfig {
+            percussion: true,
+            ..AudioConfig::default()
+        };
+        let plain = generate("e4 e5 Nf3");
+        let marked = generate_with_config("e4 e5 Nf3", &config);
+        let slot = |samples: &[i16], index: usize| samples[index * SAMPLES_PER_MOVE..(index + 1) * SAMPLES_PER_MOVE].to_vec();
+        assert_ne!(slot(&marked, 0), slot(&plain, 0));
+        assert_eq!(slot(&marked, 1), slot(&plain, 1));
+        assert_ne!(slot(&marked, 2), slot(&plain, 2));
+    }
+
+    #[test]
+    fn legato_fills_the_gap_between_notes() {
+        let config = AudioConfig {
+            legato: true,
+            ..AudioConfig::default()
+        };
+        let samples = generate_with_config("e4 e5", &config);
+        let note_samples = (SAMPLE_RATE * NOTE_MS / MS_PER_SECOND) as usize;
+        let gap = &samples[note_samples..SAMPLES_PER_MOVE];
+        assert!(gap.iter().any(|&s| s != 0), "legato should leave no silence between moves");
+        assert!(generate("e4 e5")[note_samples..SAMPLES_PER_MOVE].iter().all(|&s| s == 0));
+    }
+
+    #[test]
+    fn legato_last_note_rings_past_its_slot() {
+        let config = AudioConfig {
+            legato: true,
+            ..AudioConfig::default()
+        };
+        let crossfade_samples = (SAMPLE_RATE * CROSSFADE_MS / MS_PER_SECOND) as usize;
+        assert_eq!(generate_with_config("e4 e5", &config).len(), SAMPLES_PER_MOVE * 2 + crossfade_samples);
+    }
+
+    #[test]
+    fn pieces_without_samples_keep_synth_voice() {
+        let samples = sampler::SampleSet::default();
+        assert_eq!(generate_with_samples("e4 Nf6", &AudioConfig::default(), &samples), generate("e4 Nf6"));
+    }
+
+    #[test]
+    fn note_cache_reuses_repeated_notes() {
+        let mut notes = NoteCache::default();
+        let m = NotationMove::parse("Nf3", 0).unwrap();
+        let first = notes.render(&m, NOTE_MS, Phrasing::plain());
+        let second = notes.render(&m, NOTE_MS, Phrasing::plain());
+        assert_eq!(first, second);
+        assert_eq!(notes.notes.len(), 1);
+        assert_eq!(first, render_note(&m, NOTE_MS, Phrasing::plain()));
+    }
+
+    #[test]
+    fn note_cache_keeps_threats_apart() {
+        let mut notes = NoteCache::default();
+        let quiet = notes.render(&NotationMove::parse("Qd5", 0).unwrap(), NOTE_MS, Phrasing::plain());
+        let check = notes.render(&NotationMove::parse("Qd5+", 0).unwrap(), NOTE_MS, Phrasing::plain());
+        assert_ne!(quiet, check);
+        assert_eq!(notes.notes.len(), 2);
+    }
+
+    #[test]
+    fn preview_square_is_the_bare_note() {
+        let square = Square::from_name("f3").unwrap();
+        let preview = preview_square(square, Piece::Knight);
+        assert_eq!(preview.len(), (SAMPLE_RATE * NOTE_MS / MS_PER_SECOND) as usize);
+        assert_eq!(preview[..], generate("Nf3")[..preview.len()]);
+    }
+
+    #[test]
+    fn write_wav_matches_to_wav() {
+        let samples = generate("e4 e5");
+        let mut written = Vec::new();
+        assert!(write_wav(&mut written, &samples, NUM_CHANNELS, SAMPLE_RATE, &[]).is_ok());
+        assert_eq!(written, to_wav(&samples));
+    }
+
+    #[test]
+    fn no_player_error_names_players() {
+        let err = AudioError::NoPlayer { tried: vec!["aplay", "paplay"] };
+        assert_eq!(err.to_string(), "no audio player found (install one of: aplay, paplay)");
+    }
+
+    #[test]
+    fn gain_scales_before_quantization() {
+        let square = Square::from_name("e4").unwrap();
+        let full = preview_square(square, Piece::Queen);
+        let half = preview_square_with_gain(square, Piece::Queen, 0.5);
+        let peak = |samples: &[i16]| samples.iter().map(|s| s.unsigned_abs()).max().unwrap_or(0);
+        assert!(peak(&half).abs_diff(peak(&full) / 2) <= 1);
+    }
+
+    #[test]
+    fn master_gain_config_scales_game() {
+        let quiet = AudioConfig {
+            gain: Some(0.0),
+            ..AudioConfig::default()
+        };
+        assert!(generate_with_config("e4 e5", &quiet).iter().all(|&sample| sample == 0));
+    }
+
+    #[test]
+    fn preview_square_pitch_follows_square() {
+        let low = preview_square(Square::from_name("a1").unwrap(), Piece::Pawn);
+        let high = preview_square(Square::from_name("h8").unwrap(), Piece::Pawn);
+        assert_ne!(low, high);
+    }
+
+    #[test]
+    fn themed_moves_play_the_theme() {
+        let knight = NotationMove::parse("Nf3", 0).unwrap();
+        let classic = theme::Theme::default();
+        assert_eq!(synthesize_move_themed(&knight, &classic, 1.0), synthesize_move(&knight));
+        let chiptune = theme::Theme::from_name("8-bit").unwrap();
+        assert_ne!(synthesize_move_themed(&knight, &chiptune, 1.0), synthesize_move(&knight));
+        let square = Square::from_name("f3").unwrap();
+        assert_eq!(preview_square_themed(square, Piece::Knight, &classic, 1.0), preview_square(square, Piece::Knight));
+    }
+
+    #[test]
+    fn generate_with_default_map_matches_generate() {
+        assert_eq!(generate_with(&SoundMap::default(), "e4 Nf6 Qh5+"), generate("e4 Nf6 Qh5+"));
+    }
+
+    #[test]
+    fn generate_with_plays_the_map_sounds() {
+        let square_pawn = sound_map::Sound::new(Timbre::Square, Blend::none());
+        let map = SoundMap::default().with(Piece::Pawn, Threat::None, square_pawn);
+        assert_ne!(generate_with(&map, "e4"), generate("e4"));
+        assert_eq!(generate_with(&map, "Nf3"), generate("Nf3"));
+    }
+
+    #[test]
+    fn sound_duration_shortens_detached_notes() {
+        let short = SoundMap::default().get(Piece::Pawn, Threat::None).clone().with_duration(100);
+        let map = SoundMap::default().with(Piece::Pawn, Threat::None, short);
+        let samples = generate_with(&map, "e4");
+        let note_end = (SAMPLE_RATE * 100 / MS_PER_SECOND) as usize;
+        assert_eq!(samples.len(), SAMPLES_PER_MOVE);
+        assert!(samples[note_end..].iter().all(|&sample| sample == 0));
+    }
+
+    #[test]
+    fn sound_envelope_shapes_the_attack() {
+        let slow = SoundMap::default().get(Piece::Pawn, Threat::None).clone();
+        let slow = sound_map::Sound {
+            blend: slow.blend.with_envelope(Envelope { attack_ms: 100, release_ms: 3 }),
+            ..slow
+        };
+        let map = SoundMap::default().with(Piece::Pawn, Threat::None, slow);
+        let peak = |samples: &[i16]| samples.iter().map(|&sample| sample.unsigned_abs()).max().unwrap_or(0);
+        let onset = 441;
+        assert!(peak(&generate_with(&map, "e4")[..onset]) < peak(&generate("e4")[..onset]) / 4);
+    }
+
+    #[test]
+    fn stereo_doubles_the_samples() {
+        let config = AudioConfig {
+            stereo: Some(Panning::File),
+            ..AudioConfig::default()
+        };
+        assert_eq!(config.num_channels(), 2);
+        assert_eq!(generate_with_config("e4 e5", &config).len(), generate("e4 e5").len() * 2);
+    }
+
+    #[test]
+    fn stereo_pans_by_file() {
+        let config = AudioConfig {
+            stereo: Some(Panning::File),
+            ..AudioConfig::default()
+        };
+        let energy = |samples: &[i16], channel: usize| -> i64 {
+            samples.iter().skip(channel).step_by(2).map(|&sample| (sample as i64).abs()).sum()
+        };
+        let queenside = generate_with_config("a4", &config);
+        let kingside = generate_with_config("h4", &config);
+        assert!(energy(&queenside, 0) > energy(&queenside, 1) * 4);
+        assert!(energy(&kingside, 1) > energy(&kingside, 0) * 4);
+    }
+
+    #[test]
+    fn stereo_pans_by_side() {
+        let config = AudioConfig {
+            stereo: Some(Panning::Side),
+            ..AudioConfig::default()
+        };
+        let samples = generate_with_config("h4 a5", &config);
+        let energy = |slot: usize, channel: usize| -> i64 {
+            let frames = &samples[slot * SAMPLES_PER_MOVE * 2..(slot + 1) * SAMPLES_PER_MOVE * 2];
+            frames.iter().skip(channel).step_by(2).map(|&sample| (sample as i64).abs()).sum()
+        };
+        assert!(energy(0, 0) > energy(0, 1) * 2, "White's kingside move still sits left");
+        assert!(energy(1, 1) > energy(1, 0) * 2, "Black's queenside move still sits right");
+    }
+
+    #[test]
+    fn panning_names() {
+        assert_eq!(Panning::from_name("file"), Some(Panning::File));
+        assert_eq!(Panning::from_name("side"), Some(Panning::Side));
+        assert_eq!(Panning::from_name("color"), None);
+    }
+
+    #[test]
+    fn stereo_reverb_keeps_channels_interleaved() {
+        let config = AudioConfig {
+            stereo: Some(Panning::File),
+            reverb: Some(Reverb::default()),
+            ..AudioConfig::default()
+        };
+        let mono_config = AudioConfig {
+            reverb: Some(Reverb::default()),
+            ..AudioConfig::default()
+        };
+        assert_eq!(generate_with_config("e4", &config).len(), generate_with_config("e4", &mono_config).len() * 2);
+    }
+
+    #[test]
+    fn map_channels_round_trips() {
+        let interleaved = vec![1.0, -1.0, 2.0, -2.0];
+        assert_eq!(map_channels(&interleaved, 2, |channel| channel.to_vec()), interleaved);
+        let left_only = map_channels(&interleaved, 2, |channel| channel.iter().map(|sample| sample.max(0.0)).collect());
+        assert_eq!(left_only, vec![1.0, 0.0, 2.0, 0.0]);
+    }
+
+    #[test]
+    fn spatial_renders_stereo() {
+        let config = AudioConfig {
+            spatial: true,
+            ..AudioConfig::default()
+        };
+        assert_eq!(config.num_channels(), 2);
+        assert_eq!(generate_with_config("e4 e5", &config).len(), generate("e4 e5").len() * 2);
+    }
+
+    #[test]
+    fn spatial_far_ranks_are_quieter() {
+        let config = AudioConfig {
+            spatial: true,
+            ..AudioConfig::default()
+        };
+        let energy = |samples: Vec<i16>| -> i64 { samples.iter().map(|&sample| (sample as i64).abs()).sum() };
+        assert!(energy(generate_with_config("e8", &config)) < energy(generate_with_config("e1", &config)));
+    }
 }
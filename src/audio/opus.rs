@@ -0,0 +1,88 @@
+//! Opus export. Enabled with `--features opus`.
+//!
+//! A multi-minute game is tens of megabytes as WAV but a few hundred
+//! kilobytes as Opus. Writing an Opus encoder (SILK + CELT, range coder,
+//! Ogg framing) would dwarf the rest of the crate, so the WAV we already
+//! produce is piped through `opusenc` from opus-tools, the reference
+//! encoder. It reads our headers, LIST/INFO tags included, and resamples
+//! 44.1 kHz to Opus's 48 kHz itself.
+//!
+//! ```text
+//! WAV bytes ─→ opusenc --bitrate 96 - - ─→ Ogg Opus bytes
+//!              (stdin)              (stdout)
+//! ```
+
+use std::fmt;
+use std::io::{Read, Write};
+use std::process::{Command, ExitStatus, Stdio};
+
+const ENCODER: &str = "opusenc";
+/// Transparent for these sparse, mostly tonal signals.
+const BITRATE_KBPS: u32 = 96;
+
+#[derive(Debug)]
+pub enum EncodeOpusError {
+    /// `opusenc` is missing or its pipes broke
+    Io(std::io::Error),
+    /// `opusenc` ran but rejected the input
+    Failed(ExitStatus),
+}
+
+impl fmt::Display for EncodeOpusError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EncodeOpusError::Io(err) => write!(f, "cannot run {ENCODER} (install opus-tools): {err}"),
+            EncodeOpusError::Failed(status) => write!(f, "{ENCODER} failed: {status}"),
+        }
+    }
+}
+
+impl std::error::Error for EncodeOpusError {}
+
+impl From<std::io::Error> for EncodeOpusError {
+    fn from(err: std::io::Error) -> Self {
+        EncodeOpusError::Io(err)
+    }
+}
+
+/// Encodes a WAV file to Ogg Opus.
+pub fn encode(wav: &[u8]) -> Result<Vec<u8>, EncodeOpusError> {
+    encode_with(ENCODER, wav)
+}
+
+fn encode_with(program: &str, wav: &[u8]) -> Result<Vec<u8>, EncodeOpusError> {
+    let mut child = Command::new(program)
+        .args(["--quiet", "--bitrate", &BITRATE_KBPS.to_string(), "-", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()?;
+    let (Some(mut stdin), Some(mut stdout)) = (child.stdin.take(), child.stdout.take()) else {
+        return Err(std::io::Error::other("encoder pipes unavailable").into());
+    };
+    // Feed stdin from another thread: the encoder's output fills its pipe
+    // long before it has read all of a large input
+    let mut encoded = Vec::new();
+    let written = std::thread::scope(|scope| {
+        let writer = scope.spawn(move || stdin.write_all(wav));
+        let read = stdout.read_to_end(&mut encoded);
+        writer.join().unwrap_or_else(|_| Err(std::io::Error::other("encoder input thread panicked"))).and(read)
+    });
+    let status = child.wait()?;
+    if !status.success() {
+        return Err(EncodeOpusError::Failed(status));
+    }
+    written?;
+    Ok(encoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_encoder_is_an_io_error() {
+        let wav = crate::audio::to_wav(&[0; 16]);
+        assert!(matches!(encode_with("chesswav-no-such-encoder", &wav), Err(EncodeOpusError::Io(_))));
+    }
+}
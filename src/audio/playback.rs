@@ -0,0 +1,587 @@
+//! Playback through the system's audio player.
+//!
+//! An audio API binding (cpal, CoreAudio, ALSA, WASAPI) would be the
+//! crate's first dependency, so the WAV is handed to a player the OS
+//! already ships instead. Players that read stdin get the bytes piped,
+//! without touching the disk; the rest get a temp file. Each platform
+//! tries its players in order and uses the first one installed:
+//!
+//! ```text
+//! macOS     afplay <file>
+//! Linux     aplay -q  →  paplay  →  pw-play -        (stdin)
+//! Windows   powershell Media.SoundPlayer <file>
+//! ```
+//!
+//! The Linux players can also pick an output device, named as ALSA
+//! (`aplay -L`) or PulseAudio/PipeWire (`pactl list short sinks`) name
+//! them; see [`devices`] and [`play_on`].
+//!
+//! [`play`] blocks until the sound ends. [`PlaybackQueue`] plays on a
+//! background thread instead, so the REPL can take the next move while the
+//! last one is still sounding, and [`Playback`] does the same for one long
+//! sound, reporting its progress.
+
+use std::io::{self, Write};
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use super::{AudioError, wav};
+
+/// Sounds waiting behind the one playing. Beyond this the queue is more
+/// than a second behind the board, so new sounds are dropped instead.
+const QUEUE_LEN: usize = 4;
+
+/// Where a player reads the WAV from.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Input {
+    Stdin,
+    /// Path passed as the last argument
+    FileArg,
+    /// Path passed in the `CHESSWAV_WAV` environment variable
+    FileEnv,
+}
+
+/// How a player's output devices are listed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DeviceList {
+    /// The player always uses the system default
+    None,
+    /// `aplay -L`: a name line, then indented description lines
+    Alsa,
+    /// `pactl list short sinks`: tab-separated, name in the second column
+    Pulse,
+}
+
+/// An external command that plays a WAV file.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Player {
+    program: &'static str,
+    args: &'static [&'static str],
+    input: Input,
+    /// Option taking the output device name as the next argument
+    device_flag: Option<&'static str>,
+    devices: DeviceList,
+}
+
+const MACOS_PLAYERS: &[Player] = &[Player {
+    program: "afplay",
+    args: &[],
+    input: Input::FileArg,
+    device_flag: None,
+    devices: DeviceList::None,
+}];
+
+const LINUX_PLAYERS: &[Player] = &[
+    // ALSA, usually routed through PulseAudio or PipeWire when they run
+    Player {
+        program: "aplay",
+        args: &["-q"],
+        input: Input::Stdin,
+        device_flag: Some("-D"),
+        devices: DeviceList::Alsa,
+    },
+    Player {
+        program: "paplay",
+        args: &[],
+        input: Input::Stdin,
+        device_flag: Some("-d"),
+        devices: DeviceList::Pulse,
+    },
+    // PipeWire node names match the sink names of its PulseAudio server
+    Player {
+        program: "pw-play",
+        args: &["-"],
+        input: Input::Stdin,
+        device_flag: Some("--target"),
+        devices: DeviceList::Pulse,
+    },
+];
+
+const WINDOWS_PLAYERS: &[Player] = &[Player {
+    program: "powershell",
+    args: &["-NoProfile", "-Command", "(New-Object Media.SoundPlayer $env:CHESSWAV_WAV).PlaySync()"],
+    input: Input::FileEnv,
+    device_flag: None,
+    devices: DeviceList::None,
+}];
+
+/// An output device the system player can play on.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Device {
+    /// What `--device` takes
+    pub name: String,
+    pub description: String,
+}
+
+fn system_players() -> &'static [Player] {
+    if cfg!(target_os = "macos") {
+        MACOS_PLAYERS
+    } else if cfg!(target_os = "windows") {
+        WINDOWS_PLAYERS
+    } else if cfg!(unix) {
+        LINUX_PLAYERS
+    } else {
+        &[]
+    }
+}
+
+/// Plays a WAV file, blocking until it ends.
+pub fn play(wav: &[u8]) -> Result<(), AudioError> {
+    play_on(wav, None)
+}
+
+/// Like [`play`], on the output `device` (a [`Device::name`]) instead of
+/// the default.
+pub fn play_on(wav: &[u8], device: Option<&str>) -> Result<(), AudioError> {
+    let players = system_players();
+    match play_with(players, wav, device)? {
+        Some(_) => Ok(()),
+        None => Err(AudioError::NoPlayer {
+            tried: players.iter().map(|player| player.program).collect(),
+        }),
+    }
+}
+
+/// Plays WAV files one after another on a background thread.
+///
+/// Dropping the queue waits for the sounds already queued to finish.
+pub struct PlaybackQueue {
+    sender: Option<SyncSender<Vec<u8>>>,
+    errors: Receiver<AudioError>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl Default for PlaybackQueue {
+    fn default() -> Self {
+        Self::with_player(play)
+    }
+}
+
+impl PlaybackQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn with_player(player: impl Fn(&[u8]) -> Result<(), AudioError> + Send + 'static) -> Self {
+        let (sender, receiver) = mpsc::sync_channel::<Vec<u8>>(QUEUE_LEN);
+        let (error_sender, errors) = mpsc::channel();
+        let worker = std::thread::spawn(move || {
+            let mut failed = false;
+            for wav in receiver {
+                // Report only the first failure; the rest would repeat it
+                if let Err(err) = player(&wav)
+                    && !failed
+                {
+                    failed = true;
+                    error_sender.send(err).ok();
+                }
+            }
+        });
+        Self {
+            sender: Some(sender),
+            errors,
+            worker: Some(worker),
+        }
+    }
+
+    /// Queues `wav` without waiting. Returns false if the queue was full
+    /// and the sound was dropped.
+    pub fn enqueue(&self, wav: Vec<u8>) -> bool {
+        self.sender.as_ref().is_some_and(|sender| sender.try_send(wav).is_ok())
+    }
+
+    /// The first playback failure, once: later sounds fail quietly.
+    pub fn take_error(&self) -> Option<AudioError> {
+        self.errors.try_recv().ok()
+    }
+}
+
+impl Drop for PlaybackQueue {
+    fn drop(&mut self) {
+        // Closing the channel ends the worker's loop once it drains
+        self.sender.take();
+        if let Some(worker) = self.worker.take() {
+            worker.join().ok();
+        }
+    }
+}
+
+type Callback = Box<dyn FnOnce() + Send>;
+
+enum Completion {
+    Playing(Option<Callback>),
+    Done,
+}
+
+/// A sound playing on a background thread.
+///
+/// External players report nothing back, so the position is the time
+/// since playback started, capped at the sound's length.
+///
+/// ```ignore
+/// let playback = Playback::start(audio::to_wav(&audio::generate(game)));
+/// playback.on_complete(|| eprintln!("done"));
+/// while playback.is_playing() {
+///     draw_progress(playback.position(), playback.duration());
+/// }
+/// ```
+pub struct Playback {
+    started: Instant,
+    duration: Duration,
+    completion: Arc<Mutex<Completion>>,
+    worker: Option<JoinHandle<Result<(), AudioError>>>,
+}
+
+impl Playback {
+    pub fn start(wav: Vec<u8>) -> Self {
+        Self::with_player(wav, play)
+    }
+
+    fn with_player(wav: Vec<u8>, player: impl FnOnce(&[u8]) -> Result<(), AudioError> + Send + 'static) -> Self {
+        let duration = wav::decode(&wav).map_or(Duration::ZERO, |decoded| {
+            let frames = decoded.samples.len() / decoded.spec.num_channels as usize;
+            Duration::from_secs_f64(frames as f64 / decoded.spec.sample_rate.max(1) as f64)
+        });
+        let completion = Arc::new(Mutex::new(Completion::Playing(None)));
+        let shared = Arc::clone(&completion);
+        let worker = std::thread::spawn(move || {
+            let result = player(&wav);
+            let finished = std::mem::replace(&mut *lock(&shared), Completion::Done);
+            if let Completion::Playing(Some(callback)) = finished {
+                callback();
+            }
+            result
+        });
+        Self {
+            started: Instant::now(),
+            duration,
+            completion,
+            worker: Some(worker),
+        }
+    }
+
+    pub fn is_playing(&self) -> bool {
+        matches!(*lock(&self.completion), Completion::Playing(_))
+    }
+
+    /// How far playback has got: the sound's full length once it ends.
+    pub fn position(&self) -> Duration {
+        if self.is_playing() { self.started.elapsed().min(self.duration) } else { self.duration }
+    }
+
+    /// Length of the sound.
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// Runs `callback` on the playback thread when the sound ends, or right
+    /// away if it already has. Replaces any earlier callback.
+    pub fn on_complete(&self, callback: impl FnOnce() + Send + 'static) {
+        let mut completion = lock(&self.completion);
+        match &mut *completion {
+            Completion::Playing(slot) => *slot = Some(Box::new(callback)),
+            Completion::Done => {
+                drop(completion);
+                callback();
+            }
+        }
+    }
+
+    /// Blocks until the sound ends, reporting whether it could be played.
+    pub fn wait(mut self) -> Result<(), AudioError> {
+        match self.worker.take().map(JoinHandle::join) {
+            Some(Ok(result)) => result,
+            // The player panicked or we already waited: nothing to report
+            Some(Err(_)) | None => Ok(()),
+        }
+    }
+}
+
+/// Locks `completion`, which stays consistent even if a callback panicked.
+fn lock(completion: &Mutex<Completion>) -> MutexGuard<'_, Completion> {
+    completion.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Output devices of the system player, empty when it cannot choose one.
+pub fn devices() -> io::Result<Vec<Device>> {
+    for player in system_players() {
+        let listing = match player.devices {
+            DeviceList::None => return Ok(Vec::new()),
+            DeviceList::Alsa => Command::new("aplay").arg("-L").output(),
+            DeviceList::Pulse => Command::new("pactl").args(["list", "short", "sinks"]).output(),
+        };
+        match listing {
+            Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+            Err(err) => return Err(err),
+            Ok(output) => {
+                let text = String::from_utf8_lossy(&output.stdout);
+                return Ok(match player.devices {
+                    DeviceList::None => Vec::new(),
+                    DeviceList::Alsa => parse_alsa_devices(&text),
+                    DeviceList::Pulse => parse_pulse_sinks(&text),
+                });
+            }
+        }
+    }
+    Ok(Vec::new())
+}
+
+fn parse_alsa_devices(listing: &str) -> Vec<Device> {
+    let mut devices: Vec<Device> = Vec::new();
+    for line in listing.lines() {
+        match (line.starts_with(char::is_whitespace), devices.last_mut()) {
+            (true, Some(device)) => {
+                if !device.description.is_empty() {
+                    device.description.push_str(", ");
+                }
+                device.description.push_str(line.trim());
+            }
+            (true, None) => {}
+            (false, _) if !line.trim().is_empty() => devices.push(Device {
+                name: line.trim().to_string(),
+                description: String::new(),
+            }),
+            (false, _) => {}
+        }
+    }
+    devices
+}
+
+fn parse_pulse_sinks(listing: &str) -> Vec<Device> {
+    listing
+        .lines()
+        .filter_map(|line| {
+            let columns: Vec<&str> = line.split('\t').collect();
+            let name = columns.get(1)?;
+            Some(Device {
+                name: name.to_string(),
+                description: columns.get(3).map_or(String::new(), |spec| spec.to_string()),
+            })
+        })
+        .collect()
+}
+
+/// Plays `wav` with the first of `players` that is installed, returning
+/// its name, or None if none is.
+fn play_with(players: &[Player], wav: &[u8], device: Option<&str>) -> io::Result<Option<&'static str>> {
+    for player in players {
+        match player.play(wav, device) {
+            Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+            result => return result.map(|()| Some(player.program)),
+        }
+    }
+    Ok(None)
+}
+
+impl Player {
+    fn play(&self, wav: &[u8], device: Option<&str>) -> io::Result<()> {
+        let mut command = Command::new(self.program);
+        command.args(self.args).stdout(Stdio::null());
+        match (device, self.device_flag) {
+            (Some(name), Some(flag)) => {
+                command.args([flag, name]);
+            }
+            (Some(_), None) => eprintln!("{} cannot choose an output device; using the default", self.program),
+            (None, _) => {}
+        }
+        match self.input {
+            Input::Stdin => {
+                let mut child = command.stdin(Stdio::piped()).spawn()?;
+                if let Some(mut stdin) = child.stdin.take() {
+                    stdin.write_all(wav)?;
+                } // stdin dropped here, sends EOF
+                child.wait()?;
+                Ok(())
+            }
+            Input::FileArg | Input::FileEnv => {
+                let path = std::env::temp_dir().join("chesswav.wav");
+                std::fs::write(&path, wav)?;
+                if self.input == Input::FileArg {
+                    command.arg(&path);
+                } else {
+                    command.env("CHESSWAV_WAV", &path);
+                }
+                let status = command.status();
+                std::fs::remove_file(&path).ok();
+                status.map(|_| ())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    const MISSING: Player = Player {
+        program: "chesswav-no-such-player",
+        args: &[],
+        input: Input::Stdin,
+        device_flag: None,
+        devices: DeviceList::None,
+    };
+
+    #[test]
+    fn no_installed_player_plays_nothing() {
+        assert_eq!(play_with(&[MISSING], b"RIFF", None).ok(), Some(None));
+    }
+
+    #[test]
+    fn queue_returns_before_playback_ends() {
+        let (played, finished) = mpsc::channel();
+        let queue = PlaybackQueue::with_player(move |wav| {
+            std::thread::sleep(std::time::Duration::from_millis(50));
+            played.send(wav.to_vec()).ok();
+            Ok(())
+        });
+        let start = std::time::Instant::now();
+        assert!(queue.enqueue(vec![1]));
+        assert!(queue.enqueue(vec![2]));
+        assert!(start.elapsed() < std::time::Duration::from_millis(50));
+        drop(queue);
+        assert_eq!(finished.iter().collect::<Vec<_>>(), vec![vec![1], vec![2]]);
+    }
+
+    #[test]
+    fn full_queue_drops_new_sounds() {
+        let (release, blocked) = mpsc::channel::<()>();
+        let queue = PlaybackQueue::with_player(move |_| {
+            blocked.recv().ok();
+            Ok(())
+        });
+        let accepted = (0..QUEUE_LEN + 3).filter(|&idx| queue.enqueue(vec![idx as u8])).count();
+        // One sound may already be playing, the rest wait in the queue
+        assert!((QUEUE_LEN..=QUEUE_LEN + 1).contains(&accepted));
+        drop(release);
+    }
+
+    fn short_wav() -> Vec<u8> {
+        // 0.5 s of silence
+        crate::audio::to_wav(&vec![0; crate::audio::SAMPLE_RATE as usize / 2])
+    }
+
+    #[test]
+    fn playback_reports_progress_until_complete() {
+        let (release, blocked) = mpsc::channel::<()>();
+        let playback = Playback::with_player(short_wav(), move |_| {
+            blocked.recv().ok();
+            Ok(())
+        });
+        assert_eq!(playback.duration(), Duration::from_millis(500));
+        assert!(playback.is_playing());
+        assert!(playback.position() < playback.duration());
+        drop(release);
+        let (done, completed) = mpsc::channel();
+        playback.on_complete(move || {
+            done.send(()).ok();
+        });
+        completed.recv().ok();
+        assert!(!playback.is_playing());
+        assert_eq!(playback.position(), playback.duration());
+    }
+
+    #[test]
+    fn on_complete_after_the_end_runs_at_once() {
+        let playback = Playback::with_player(short_wav(), |_| Ok(()));
+        while playback.is_playing() {
+            std::thread::yield_now();
+        }
+        let called = Arc::new(AtomicBool::new(false));
+        let flag = Arc::clone(&called);
+        playback.on_complete(move || flag.store(true, Ordering::Relaxed));
+        assert!(called.load(Ordering::Relaxed));
+        assert!(playback.wait().is_ok());
+    }
+
+    #[test]
+    fn queue_reports_the_first_failure_once() {
+        let mut queue = PlaybackQueue::with_player(|_| Err(AudioError::NoPlayer { tried: vec!["aplay"] }));
+        queue.enqueue(vec![1]);
+        queue.enqueue(vec![2]);
+        // Let the worker drain both sounds
+        queue.sender.take();
+        queue.worker.take().map(JoinHandle::join);
+        assert!(matches!(queue.take_error(), Some(AudioError::NoPlayer { .. })));
+        assert!(queue.take_error().is_none());
+    }
+
+    #[test]
+    fn wait_returns_the_player_error() {
+        let playback = Playback::with_player(short_wav(), |_| Err(AudioError::Io(io::ErrorKind::BrokenPipe.into())));
+        assert!(matches!(playback.wait(), Err(AudioError::Io(_))));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn falls_back_to_the_next_installed_player() {
+        let cat = Player {
+            program: "cat",
+            args: &[],
+            input: Input::Stdin,
+            device_flag: None,
+            devices: DeviceList::None,
+        };
+        assert_eq!(play_with(&[MISSING, cat], b"RIFF", None).ok(), Some(Some("cat")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn file_players_run_on_a_temp_file() {
+        let test = Player {
+            program: "test",
+            args: &["-f"],
+            input: Input::FileArg,
+            device_flag: None,
+            devices: DeviceList::None,
+        };
+        assert_eq!(play_with(&[test], b"RIFF", None).ok(), Some(Some("test")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn device_goes_after_its_flag() {
+        // Succeeds only if called as `test -n <device>`
+        let test = Player {
+            program: "test",
+            args: &[],
+            input: Input::Stdin,
+            device_flag: Some("-n"),
+            devices: DeviceList::None,
+        };
+        assert_eq!(play_with(&[test], b"", Some("hw:1")).ok(), Some(Some("test")));
+    }
+
+    #[test]
+    fn parses_alsa_device_listing() {
+        let listing = "null\n    Discard all samples\ndefault:CARD=PCH\n    HDA Intel PCH, ALC257 Analog\n    Default Audio Device\n";
+        assert_eq!(
+            parse_alsa_devices(listing),
+            vec![
+                Device {
+                    name: "null".to_string(),
+                    description: "Discard all samples".to_string(),
+                },
+                Device {
+                    name: "default:CARD=PCH".to_string(),
+                    description: "HDA Intel PCH, ALC257 Analog, Default Audio Device".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_pulse_sink_listing() {
+        let listing = "47\talsa_output.pci.analog-stereo\tPipeWire\ts32le 2ch 48000Hz\tSUSPENDED\n";
+        assert_eq!(
+            parse_pulse_sinks(listing),
+            vec![Device {
+                name: "alsa_output.pci.analog-stereo".to_string(),
+                description: "s32le 2ch 48000Hz".to_string(),
+            }]
+        );
+    }
+}
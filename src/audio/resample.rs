@@ -0,0 +1,167 @@
+//! Sample-rate conversion of the finished mix, for `--sample-rate`.
+//!
+//! The synth renders at [`SAMPLE_RATE`]; other output rates are resampled
+//! from it with a Blackman-windowed sinc filter. Each output frame sums the
+//! input around its position, weighted by the filter:
+//!
+//! ```text
+//! input    ·   ·   ·   ·   ·   ·   ·   ·
+//! output     ↑ weights from a table, one row per 1/1024 of an input sample
+//!      ╭─────┴─────╮
+//!   ───╯  sinc     ╰───   16 zero crossings each side
+//! ```
+//!
+//! Downsampling lowers the filter's cutoff to the new Nyquist frequency, so
+//! nothing above it folds back as aliasing.
+
+use std::f64::consts::PI;
+
+#[cfg(doc)]
+use super::SAMPLE_RATE;
+
+/// Zero crossings of the sinc on each side of the output position.
+const ZERO_CROSSINGS: f64 = 16.0;
+/// Fractional positions between two input samples with their own weights.
+const PHASES: usize = 1024;
+
+/// Converts interleaved `samples` with `num_channels` channels from
+/// `from_rate` to `to_rate` Hz.
+pub fn resample(samples: &[i16], num_channels: u16, from_rate: u32, to_rate: u32) -> Vec<i16> {
+    if from_rate == to_rate || from_rate == 0 || to_rate == 0 {
+        return samples.to_vec();
+    }
+    let channels = num_channels.max(1) as usize;
+    let in_frames = samples.len() / channels;
+    let out_frames = (in_frames as u64 * to_rate as u64 / from_rate as u64) as usize;
+    let filter = Filter::new(from_rate, to_rate);
+
+    let mut out = Vec::with_capacity(out_frames * channels);
+    for frame in 0..out_frames {
+        // Exact position in input frames: whole part and phase
+        let position = frame as u64 * from_rate as u64;
+        let whole = (position / to_rate as u64) as i64;
+        let phase = ((position % to_rate as u64) * PHASES as u64 / to_rate as u64) as usize;
+        let weights = filter.weights(phase);
+        let first = whole + filter.first_offset;
+        for channel in 0..channels {
+            let sum: f64 = weights
+                .iter()
+                .enumerate()
+                .filter_map(|(tap, weight)| {
+                    let input = usize::try_from(first + tap as i64).ok().filter(|&input| input < in_frames)?;
+                    Some(samples[input * channels + channel] as f64 * weight)
+                })
+                .sum();
+            out.push(sum.round().clamp(i16::MIN as f64, i16::MAX as f64) as i16);
+        }
+    }
+    out
+}
+
+/// Filter weights for every phase, `taps` per row.
+struct Filter {
+    weights: Vec<f64>,
+    taps: usize,
+    /// Offset of the first tap from the input frame at or before the output
+    first_offset: i64,
+}
+
+impl Filter {
+    fn new(from_rate: u32, to_rate: u32) -> Self {
+        let cutoff = (to_rate as f64 / from_rate as f64).min(1.0);
+        let radius = ZERO_CROSSINGS / cutoff;
+        let reach = radius.ceil() as i64;
+        let first_offset = 1 - reach;
+        let taps = (2 * reach) as usize;
+        let mut weights = Vec::with_capacity((PHASES + 1) * taps);
+        for phase in 0..=PHASES {
+            let fraction = phase as f64 / PHASES as f64;
+            let row: Vec<f64> = (0..taps)
+                .map(|tap| {
+                    let distance = (first_offset + tap as i64) as f64 - fraction;
+                    if distance.abs() >= radius {
+                        0.0
+                    } else {
+                        sinc(distance * cutoff) * blackman(distance / radius)
+                    }
+                })
+                .collect();
+            // Unity gain at DC for every phase
+            let total: f64 = row.iter().sum();
+            weights.extend(row.iter().map(|weight| weight / total));
+        }
+        Self { weights, taps, first_offset }
+    }
+
+    fn weights(&self, phase: usize) -> &[f64] {
+        &self.weights[phase * self.taps..(phase + 1) * self.taps]
+    }
+}
+
+fn sinc(x: f64) -> f64 {
+    if x == 0.0 { 1.0 } else { (PI * x).sin() / (PI * x) }
+}
+
+/// Blackman window over `position` in -1.0..=1.0.
+fn blackman(position: f64) -> f64 {
+    0.42 + 0.5 * (PI * position).cos() + 0.08 * (2.0 * PI * position).cos()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(freq_hz: f64, rate: u32, frames: usize) -> Vec<i16> {
+        (0..frames)
+            .map(|frame| ((2.0 * PI * freq_hz * frame as f64 / rate as f64).sin() * 16000.0).round() as i16)
+            .collect()
+    }
+
+    fn peak(samples: &[i16]) -> i16 {
+        samples.iter().map(|sample| sample.saturating_abs()).max().unwrap_or(0)
+    }
+
+    #[test]
+    fn same_rate_is_untouched() {
+        let samples = sine(440.0, 44100, 1000);
+        assert_eq!(resample(&samples, 1, 44100, 44100), samples);
+    }
+
+    #[test]
+    fn length_follows_the_rate() {
+        assert_eq!(resample(&[0; 44100], 1, 44100, 48000).len(), 48000);
+        assert_eq!(resample(&[0; 2 * 44100], 2, 44100, 22050).len(), 2 * 22050);
+    }
+
+    #[test]
+    fn keeps_a_tone_at_its_pitch_and_level() {
+        let resampled = resample(&sine(440.0, 44100, 44100), 1, 44100, 48000);
+        let expected = sine(440.0, 48000, 48000);
+        // Away from the edges, within a few LSB of an ideal 48 kHz render
+        let error = resampled[1000..47000]
+            .iter()
+            .zip(&expected[1000..47000])
+            .map(|(got, want)| (got - want).abs())
+            .max()
+            .unwrap_or(0);
+        assert!(error < 40, "max error {error}");
+    }
+
+    #[test]
+    fn downsampling_removes_what_the_new_rate_cannot_hold() {
+        // 15 kHz is above the 11.025 kHz Nyquist frequency of 22.05 kHz
+        let resampled = resample(&sine(15000.0, 44100, 44100), 1, 44100, 22050);
+        assert!(peak(&resampled[1000..21000]) < 200, "peak {}", peak(&resampled[1000..21000]));
+        let kept = resample(&sine(1000.0, 44100, 44100), 1, 44100, 22050);
+        assert!(peak(&kept[1000..21000]) > 15500);
+    }
+
+    #[test]
+    fn channels_stay_apart() {
+        let left = sine(440.0, 44100, 4410);
+        let stereo: Vec<i16> = left.iter().flat_map(|&sample| [sample, 0]).collect();
+        let resampled = resample(&stereo, 2, 44100, 48000);
+        assert!(resampled.iter().skip(1).step_by(2).all(|&sample| sample == 0));
+        assert!(peak(&resampled.iter().step_by(2).copied().collect::<Vec<_>>()) > 15000);
+    }
+}
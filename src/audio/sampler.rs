@@ -0,0 +1,285 @@
+//! Sample-based instruments - user-recorded WAVs instead of oscillators.
+//!
+//! A directory holds one file per piece; any piece without a file keeps its
+//! synthesized voice:
+//!
+//! ```text
+//! samples/
+//! ├── pawn.wav     ├── rook.wav
+//! ├── knight.wav   ├── queen.wav
+//! ├── bishop.wav   └── king.wav
+//! ```
+//!
+//! Samples are expected to be recorded at A4 (440 Hz). Each note resamples
+//! its piece's recording by `freq / 440 × file_rate / 44100`, reading between
+//! source samples with linear interpolation:
+//!
+//! ```text
+//! step 1.5 (a fifth up):  source ●──●──●──●──●──●──●
+//!                         output ●────◐────●────◐────●    ◐ = interpolated
+//! ```
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use super::wav::{self, ReadWavError};
+use super::{MS_PER_SECOND, SAMPLE_RATE};
+use crate::engine::chess::Piece;
+
+/// Pitch every sample is assumed to be recorded at.
+const ROOT_FREQ_HZ: f64 = 440.0;
+/// Fade at the end of a note cut short of its sample, to avoid a click.
+const RELEASE_MS: u32 = 20;
+
+#[derive(Debug)]
+pub enum LoadSamplesError {
+    Io(std::io::Error),
+    /// A piece's file exists but is not a usable WAV
+    Read { path: PathBuf, error: ReadWavError },
+    /// The directory holds none of the expected `<piece>.wav` files
+    Empty(PathBuf),
+}
+
+impl fmt::Display for LoadSamplesError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LoadSamplesError::Io(err) => write!(f, "cannot read samples: {err}"),
+            LoadSamplesError::Read { path, error } => write!(f, "{}: {error}", path.display()),
+            LoadSamplesError::Empty(dir) => {
+                write!(f, "{} has no pawn.wav, knight.wav, bishop.wav, rook.wav, queen.wav or king.wav", dir.display())
+            }
+        }
+    }
+}
+
+impl std::error::Error for LoadSamplesError {}
+
+impl From<std::io::Error> for LoadSamplesError {
+    fn from(err: std::io::Error) -> Self {
+        LoadSamplesError::Io(err)
+    }
+}
+
+/// One recording, mixed down to mono on the float mix bus.
+#[derive(Debug, Clone, PartialEq)]
+struct Sample {
+    sample_rate: u32,
+    frames: Vec<f64>,
+}
+
+impl Sample {
+    fn new(spec: wav::Spec, samples: &[i16]) -> Self {
+        let channels = spec.num_channels as usize;
+        let frames = samples
+            .chunks_exact(channels)
+            .map(|frame| frame.iter().map(|&sample| sample as f64).sum::<f64>() / channels as f64 / i16::MAX as f64)
+            .collect();
+        Self {
+            sample_rate: spec.sample_rate,
+            frames,
+        }
+    }
+}
+
+/// Per-piece recordings loaded from a directory.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SampleSet {
+    pawn: Option<Sample>,
+    knight: Option<Sample>,
+    bishop: Option<Sample>,
+    rook: Option<Sample>,
+    queen: Option<Sample>,
+    king: Option<Sample>,
+}
+
+impl SampleSet {
+    /// Loads `<piece>.wav` for every piece present in `dir`.
+    pub fn load(dir: &Path) -> Result<SampleSet, LoadSamplesError> {
+        let load_piece = |piece: Piece| -> Result<Option<Sample>, LoadSamplesError> {
+            let path = dir.join(format!("{}.wav", file_stem(piece)));
+            if !path.is_file() {
+                return Ok(None);
+            }
+            let (spec, samples) = wav::read(&path).map_err(|error| LoadSamplesError::Read { path, error })?;
+            Ok(Some(Sample::new(spec, &samples)))
+        };
+        let set = SampleSet {
+            pawn: load_piece(Piece::Pawn)?,
+            knight: load_piece(Piece::Knight)?,
+            bishop: load_piece(Piece::Bishop)?,
+            rook: load_piece(Piece::Rook)?,
+            queen: load_piece(Piece::Queen)?,
+            king: load_piece(Piece::King)?,
+        };
+        if set == SampleSet::default() {
+            return Err(LoadSamplesError::Empty(dir.to_path_buf()));
+        }
+        Ok(set)
+    }
+
+    /// Plays the piece's recording at `freq` for `duration_ms`, or None if
+    /// the piece has no sample.
+    pub fn render(&self, piece: Piece, freq: u32, duration_ms: u32) -> Option<Vec<f64>> {
+        let sample = self.sample(piece)?;
+        let num_samples = (SAMPLE_RATE * duration_ms / MS_PER_SECOND) as usize;
+        let step = freq as f64 / ROOT_FREQ_HZ * sample.sample_rate as f64 / SAMPLE_RATE as f64;
+        let mut note = resample(&sample.frames, step, num_samples);
+
+        let release_samples = ((SAMPLE_RATE * RELEASE_MS / MS_PER_SECOND) as usize).min(num_samples);
+        let tail_start = num_samples - release_samples;
+        for (idx, value) in note[tail_start..].iter_mut().enumerate() {
+            *value *= 1.0 - idx as f64 / release_samples as f64;
+        }
+        Some(note)
+    }
+
+    fn sample(&self, piece: Piece) -> Option<&Sample> {
+        match piece {
+            Piece::Pawn => self.pawn.as_ref(),
+            Piece::Knight => self.knight.as_ref(),
+            Piece::Bishop => self.bishop.as_ref(),
+            Piece::Rook => self.rook.as_ref(),
+            Piece::Queen => self.queen.as_ref(),
+            Piece::King => self.king.as_ref(),
+        }
+    }
+}
+
+fn file_stem(piece: Piece) -> &'static str {
+    match piece {
+        Piece::Pawn => "pawn",
+        Piece::Knight => "knight",
+        Piece::Bishop => "bishop",
+        Piece::Rook => "rook",
+        Piece::Queen => "queen",
+        Piece::King => "king",
+    }
+}
+
+/// Reads `source` every `step` samples (step > 1 raises pitch), linearly
+/// interpolating between neighbours. Output past the source's end is silent.
+pub fn resample(source: &[f64], step: f64, num_samples: usize) -> Vec<f64> {
+    (0..num_samples)
+        .map(|idx| {
+            let position = idx as f64 * step;
+            let index = position as usize;
+            match (source.get(index), source.get(index + 1)) {
+                (Some(current), Some(next)) => current + (next - current) * (position - index as f64),
+                (Some(current), None) if position == index as f64 => *current,
+                _ => 0.0,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine_sample(freq: f64, sample_rate: u32, seconds: f64) -> Sample {
+        let len = (sample_rate as f64 * seconds) as usize;
+        Sample {
+            sample_rate,
+            frames: (0..len)
+                .map(|idx| (2.0 * std::f64::consts::PI * freq * idx as f64 / sample_rate as f64).sin())
+                .collect(),
+        }
+    }
+
+    fn zero_crossings(samples: &[f64]) -> usize {
+        samples.windows(2).filter(|pair| (pair[0] < 0.0) != (pair[1] < 0.0)).count()
+    }
+
+    #[test]
+    fn resample_unit_step_is_identity() {
+        let source = vec![0.0, 0.5, 1.0, 0.5];
+        assert_eq!(resample(&source, 1.0, 4), source);
+    }
+
+    #[test]
+    fn resample_interpolates_between_samples() {
+        assert_eq!(resample(&[0.0, 1.0, 0.0], 0.5, 4), vec![0.0, 0.5, 1.0, 0.5]);
+    }
+
+    #[test]
+    fn resample_pads_with_silence() {
+        assert_eq!(resample(&[1.0, 1.0], 1.0, 4), vec![1.0, 1.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn render_shifts_pitch_to_square() {
+        let set = SampleSet {
+            pawn: Some(sine_sample(440.0, SAMPLE_RATE, 1.0)),
+            ..SampleSet::default()
+        };
+        let root = set.render(Piece::Pawn, 440, 200).unwrap();
+        let octave_up = set.render(Piece::Pawn, 880, 200).unwrap();
+        let (root_crossings, up_crossings) = (zero_crossings(&root), zero_crossings(&octave_up));
+        assert!((up_crossings as i64 - 2 * root_crossings as i64).abs() <= 2);
+    }
+
+    #[test]
+    fn render_compensates_for_file_sample_rate() {
+        let native = SampleSet {
+            knight: Some(sine_sample(440.0, SAMPLE_RATE, 1.0)),
+            ..SampleSet::default()
+        };
+        let half_rate = SampleSet {
+            knight: Some(sine_sample(440.0, SAMPLE_RATE / 2, 1.0)),
+            ..SampleSet::default()
+        };
+        let native_crossings = zero_crossings(&native.render(Piece::Knight, 440, 200).unwrap());
+        let half_rate_crossings = zero_crossings(&half_rate.render(Piece::Knight, 440, 200).unwrap());
+        assert!((native_crossings as i64 - half_rate_crossings as i64).abs() <= 2);
+    }
+
+    #[test]
+    fn render_fades_out() {
+        let set = SampleSet {
+            rook: Some(Sample { sample_rate: SAMPLE_RATE, frames: vec![1.0; 44100] }),
+            ..SampleSet::default()
+        };
+        let note = set.render(Piece::Rook, 440, 100).unwrap();
+        assert_eq!(note.len(), 4410);
+        assert!(note[4409] < 0.01);
+    }
+
+    #[test]
+    fn missing_piece_has_no_render() {
+        assert_eq!(SampleSet::default().render(Piece::Queen, 440, 100), None);
+    }
+
+    #[test]
+    fn stereo_files_mix_down_to_mono() {
+        let spec = wav::Spec {
+            sample_rate: SAMPLE_RATE,
+            num_channels: 2,
+            bits_per_sample: 16,
+        };
+        assert_eq!(Sample::new(spec, &[i16::MAX, 0, 0, -i16::MAX]).frames, vec![0.5, -0.5]);
+    }
+
+    #[test]
+    fn loads_piece_files_from_directory() -> Result<(), LoadSamplesError> {
+        let dir = std::env::temp_dir().join(format!("chesswav-samples-{}", std::process::id()));
+        std::fs::create_dir_all(&dir)?;
+        let mut bytes = wav::header(2, 1).to_vec();
+        bytes.extend([1000i16, -1000].iter().flat_map(|sample| sample.to_le_bytes()));
+        std::fs::write(dir.join("king.wav"), &bytes)?;
+
+        let set = SampleSet::load(&dir)?;
+        std::fs::remove_dir_all(&dir)?;
+        assert!(set.king.is_some());
+        assert!(set.pawn.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn empty_directory_is_an_error() {
+        let dir = std::env::temp_dir().join(format!("chesswav-no-samples-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let result = SampleSet::load(&dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(matches!(result, Err(LoadSamplesError::Empty(_))));
+    }
+}
@@ -0,0 +1,178 @@
+//! Piece→sound mapping as data.
+//!
+//! Every piece has one [`Sound`] per threat level, 18 in all. The default
+//! map is the classic ChessWAV palette; build your own to redesign pieces
+//! without touching the synth:
+//!
+//! ```text
+//!           Threat::None      Threat::Check            Threat::Checkmate
+//! Pawn      sine              triangle ~ sine, vibrato triangle ~ sine, tremolo
+//! Knight    triangle          …                        …
+//! …
+//! King      harmonics         FM bell, vibrato         harmonics ~ sine, tremolo
+//! ```
+//!
+//! ```ignore
+//! let map = SoundMap::default().with(
+//!     Piece::Pawn,
+//!     Threat::None,
+//!     Sound::new(Timbre::Square, Blend::with_sine(0.5)).with_duration(150),
+//! );
+//! let samples = chesswav::audio::generate_with(&map, "e4 e5");
+//! ```
+
+use super::blend::{Blend, Filter, Lfo};
+use super::synth::Timbre;
+use super::waveform::{Additive, Fm};
+use super::{NOTE_MS, piece_channel};
+use crate::engine::chess::{Piece, Threat};
+
+// Low-pass cutoffs taming the buzz of square (rook) and sawtooth (bishop)
+const ROOK_CUTOFF_HZ: f64 = 3000.0;
+const BISHOP_CUTOFF_HZ: f64 = 5000.0;
+const BISHOP_END_CUTOFF_HZ: f64 = 1500.0;
+
+// Threat modulation: a gentle pitch waver for check, a deep pulse for mate
+const CHECK_VIBRATO: Lfo = Lfo { rate_hz: 5.5, depth: 0.012 };
+const CHECKMATE_TREMOLO: Lfo = Lfo { rate_hz: 7.0, depth: 0.8 };
+
+/// Pieces in table order, matching [`piece_channel`].
+pub(super) const PIECES: [Piece; 6] = [Piece::Pawn, Piece::Knight, Piece::Bishop, Piece::Rook, Piece::Queen, Piece::King];
+pub(super) const THREATS: [Threat; 3] = [Threat::None, Threat::Check, Threat::Checkmate];
+
+/// How one piece sounds at one threat level.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sound {
+    pub timbre: Timbre,
+    /// Blending, filter, modulation and envelope
+    pub blend: Blend,
+    /// Length of a detached note (legato notes always fill their slot)
+    pub duration_ms: u32,
+}
+
+impl Sound {
+    pub fn new(timbre: Timbre, blend: Blend) -> Self {
+        Self {
+            timbre,
+            blend,
+            duration_ms: NOTE_MS,
+        }
+    }
+
+    pub fn with_duration(self, duration_ms: u32) -> Self {
+        Self { duration_ms, ..self }
+    }
+}
+
+/// A [`Sound`] for every piece and threat level.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SoundMap {
+    sounds: [[Sound; THREATS.len()]; PIECES.len()],
+}
+
+impl Default for SoundMap {
+    fn default() -> Self {
+        Self {
+            sounds: PIECES.map(|piece| THREATS.map(|threat| classic(piece, threat))),
+        }
+    }
+}
+
+impl SoundMap {
+    pub fn get(&self, piece: Piece, threat: Threat) -> &Sound {
+        &self.sounds[piece_channel(piece)][threat_index(threat)]
+    }
+
+    pub fn set(&mut self, piece: Piece, threat: Threat, sound: Sound) {
+        self.sounds[piece_channel(piece)][threat_index(threat)] = sound;
+    }
+
+    /// Builder form of [`SoundMap::set`].
+    pub fn with(mut self, piece: Piece, threat: Threat, sound: Sound) -> Self {
+        self.set(piece, threat, sound);
+        self
+    }
+}
+
+fn threat_index(threat: Threat) -> usize {
+    match threat {
+        Threat::None => 0,
+        Threat::Check => 1,
+        Threat::Checkmate => 2,
+    }
+}
+
+/// The classic palette: each piece has its timbre, and threats shift it
+/// towards sine with fewer harmonics so checks sound tenser and purer.
+/// Checks waver (vibrato); checkmate throbs (deep tremolo).
+fn classic(piece: Piece, threat: Threat) -> Sound {
+    let (timbre, blend) = match (piece, threat) {
+        (Piece::Pawn, Threat::None) => (Timbre::Sine, Blend::none()),
+        (Piece::Pawn, Threat::Check) => (Timbre::Triangle, Blend::with_sine(0.7)),
+        (Piece::Pawn, Threat::Checkmate) => (Timbre::Triangle, Blend::with_sine(0.9)),
+        (Piece::Knight, Threat::None) => (Timbre::Triangle, Blend::none()),
+        (Piece::Knight, Threat::Check) => (Timbre::Triangle, Blend::with_sine(0.4)),
+        (Piece::Knight, Threat::Checkmate) => (Timbre::Triangle, Blend::with_sine(0.7)),
+        (Piece::Rook, Threat::None) => (
+            Timbre::Square,
+            Blend::with_sine_and_band_limit(0.4, 7).with_filter(Filter::low_pass(ROOK_CUTOFF_HZ)),
+        ),
+        (Piece::Rook, Threat::Check) => (Timbre::Square, Blend::with_sine_and_band_limit(0.6, 3)),
+        (Piece::Rook, Threat::Checkmate) => (Timbre::Square, Blend::with_sine_and_band_limit(0.8, 2)),
+        (Piece::Bishop, Threat::None) => (
+            Timbre::Sawtooth,
+            Blend::with_sine_and_band_limit(0.3, 8)
+                .with_filter(Filter::low_pass(BISHOP_CUTOFF_HZ).sweep_to(BISHOP_END_CUTOFF_HZ)),
+        ),
+        (Piece::Bishop, Threat::Check) => (Timbre::Sawtooth, Blend::with_sine_and_band_limit(0.5, 3)),
+        (Piece::Bishop, Threat::Checkmate) => (Timbre::Sawtooth, Blend::with_sine_and_band_limit(0.7, 2)),
+        (Piece::Queen, Threat::None) => (Timbre::Additive(Additive::composite()), Blend::none()),
+        (Piece::Queen, Threat::Check) => (Timbre::Additive(Additive::composite()), Blend::with_sine_and_band_limit(0.4, 3)),
+        (Piece::Queen, Threat::Checkmate) => (Timbre::Additive(Additive::composite()), Blend::with_sine_and_band_limit(0.6, 2)),
+        (Piece::King, Threat::None) => (Timbre::Additive(Additive::harmonics()), Blend::none()),
+        (Piece::King, Threat::Check) => (Timbre::Fm(Fm::bell()), Blend::none()),
+        (Piece::King, Threat::Checkmate) => (Timbre::Additive(Additive::harmonics()), Blend::with_sine(0.5)),
+    };
+    let modulated = match threat {
+        Threat::None => blend,
+        Threat::Check => blend.with_vibrato(CHECK_VIBRATO),
+        Threat::Checkmate => blend.with_tremolo(CHECKMATE_TREMOLO),
+    };
+    Sound::new(timbre, modulated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn table_order_matches_piece_channels() {
+        for (index, piece) in PIECES.into_iter().enumerate() {
+            assert_eq!(piece_channel(piece), index);
+        }
+    }
+
+    #[test]
+    fn default_is_the_classic_palette() {
+        let map = SoundMap::default();
+        assert_eq!(map.get(Piece::Knight, Threat::None).timbre, Timbre::Triangle);
+        assert_eq!(map.get(Piece::King, Threat::Check).timbre, Timbre::Fm(Fm::bell()));
+        assert_eq!(map.get(Piece::Queen, Threat::None).duration_ms, NOTE_MS);
+    }
+
+    #[test]
+    fn threats_modulate_the_classic_palette() {
+        let map = SoundMap::default();
+        assert_eq!(map.get(Piece::Rook, Threat::Check).blend.vibrato, Some(CHECK_VIBRATO));
+        assert_eq!(map.get(Piece::Rook, Threat::Checkmate).blend.tremolo, Some(CHECKMATE_TREMOLO));
+        assert_eq!(map.get(Piece::Rook, Threat::None).blend.vibrato, None);
+    }
+
+    #[test]
+    fn with_replaces_one_entry() {
+        let custom = Sound::new(Timbre::Square, Blend::none()).with_duration(100);
+        let map = SoundMap::default().with(Piece::Pawn, Threat::Check, custom.clone());
+        assert_eq!(map.get(Piece::Pawn, Threat::Check), &custom);
+        assert_eq!(map.get(Piece::Pawn, Threat::None), SoundMap::default().get(Piece::Pawn, Threat::None));
+    }
+}
@@ -0,0 +1,529 @@
+//! SoundFont (SF2) rendering backend. Enabled with `--features soundfont`.
+//!
+//! Pieces play General MIDI instruments from a user-supplied `.sf2` file,
+//! so games sound like piano, strings or brass instead of raw oscillators.
+//!
+//! # File layout
+//!
+//! ```text
+//! RIFF 'sfbk'
+//! ├── LIST 'INFO'   metadata (ignored)
+//! ├── LIST 'sdta'
+//! │   └── 'smpl'    every sample's 16-bit PCM, back to back
+//! └── LIST 'pdta'   the "hydra": nine tables linked by index
+//!     ├── phdr → pbag → pgen     preset (bank, program) → zones → instrument
+//!     ├── inst → ibag → igen     instrument → zones → key range, sample
+//!     └── shdr                   sample start/end/loop, rate, root key
+//! ```
+//!
+//! # Rendering a note
+//!
+//! ```text
+//! piece ─→ GM program ─→ preset zone for key ─→ instrument zone for key ─→ sample
+//!
+//! sample played back at  freq / root_freq × sample_rate / 44100
+//! samples per output sample, looping between loop points while the note is
+//! held, then faded out to avoid a click.
+//! ```
+//!
+//! Only what a single held note needs is read: key ranges, sample, root key
+//! and loop mode. Envelopes, modulators, velocity layers and 24-bit samples
+//! are ignored.
+
+use std::fmt;
+
+use super::midi::key_for_freq;
+use super::{MS_PER_SECOND, SAMPLE_RATE};
+
+// Generator operators (SF2 spec §8.1.2)
+const GEN_INSTRUMENT: u16 = 41;
+const GEN_KEY_RANGE: u16 = 43;
+const GEN_SAMPLE_ID: u16 = 53;
+const GEN_SAMPLE_MODES: u16 = 54;
+const GEN_OVERRIDING_ROOT_KEY: u16 = 58;
+
+// Record sizes in the pdta tables
+const PRESET_HEADER_SIZE: usize = 38;
+const BAG_SIZE: usize = 4;
+const GENERATOR_SIZE: usize = 4;
+const INSTRUMENT_SIZE: usize = 22;
+const SAMPLE_HEADER_SIZE: usize = 46;
+
+/// Sample headers use 255 for "no original pitch"; middle C is the convention.
+const DEFAULT_ROOT_KEY: u8 = 60;
+const RELEASE_MS: u32 = 20;
+
+#[derive(Debug)]
+pub enum LoadSoundFontError {
+    Io(std::io::Error),
+    /// Not a RIFF 'sfbk' file
+    NotSoundFont,
+    /// A required chunk is absent
+    MissingChunk(&'static str),
+    /// A chunk or record is truncated or points outside its table
+    Malformed(&'static str),
+}
+
+impl fmt::Display for LoadSoundFontError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LoadSoundFontError::Io(err) => write!(f, "cannot read soundfont: {err}"),
+            LoadSoundFontError::NotSoundFont => write!(f, "not a SoundFont 2 file"),
+            LoadSoundFontError::MissingChunk(name) => write!(f, "soundfont has no '{name}' chunk"),
+            LoadSoundFontError::Malformed(what) => write!(f, "malformed soundfont: {what}"),
+        }
+    }
+}
+
+impl std::error::Error for LoadSoundFontError {}
+
+impl From<std::io::Error> for LoadSoundFontError {
+    fn from(err: std::io::Error) -> Self {
+        LoadSoundFontError::Io(err)
+    }
+}
+
+/// A key range and its target (instrument index in presets, sample index
+/// in instruments), plus the sample-level overrides we honor.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Zone {
+    low_key: u8,
+    high_key: u8,
+    target: Option<u16>,
+    root_key: Option<u8>,
+    looped: bool,
+}
+
+impl Zone {
+    fn global() -> Self {
+        Self {
+            low_key: 0,
+            high_key: 127,
+            target: None,
+            root_key: None,
+            looped: false,
+        }
+    }
+
+    fn covers(&self, key: u8) -> bool {
+        (self.low_key..=self.high_key).contains(&key)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Preset {
+    bank: u16,
+    program: u16,
+    zones: Vec<Zone>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct SampleHeader {
+    start: usize,
+    end: usize,
+    loop_start: usize,
+    loop_end: usize,
+    sample_rate: u32,
+    original_key: u8,
+    pitch_correction_cents: i8,
+}
+
+/// A parsed SoundFont: presets, instruments and the raw sample pool.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SoundFont {
+    presets: Vec<Preset>,
+    instruments: Vec<Vec<Zone>>,
+    samples: Vec<SampleHeader>,
+    pool: Vec<i16>,
+}
+
+impl SoundFont {
+    pub fn load(path: &std::path::Path) -> Result<SoundFont, LoadSoundFontError> {
+        Self::parse(&std::fs::read(path)?)
+    }
+
+    pub fn parse(bytes: &[u8]) -> Result<SoundFont, LoadSoundFontError> {
+        if bytes.get(0..4) != Some(b"RIFF") || bytes.get(8..12) != Some(b"sfbk") {
+            return Err(LoadSoundFontError::NotSoundFont);
+        }
+        let lists = chunks(&bytes[12..])?;
+        let sdta = list_body(&lists, b"sdta").ok_or(LoadSoundFontError::MissingChunk("sdta"))?;
+        let pdta = list_body(&lists, b"pdta").ok_or(LoadSoundFontError::MissingChunk("pdta"))?;
+
+        let sample_chunks = chunks(sdta)?;
+        let smpl = find(&sample_chunks, b"smpl").ok_or(LoadSoundFontError::MissingChunk("smpl"))?;
+        let pool = smpl.chunks_exact(2).map(|pair| i16::from_le_bytes([pair[0], pair[1]])).collect();
+
+        let hydra = chunks(pdta)?;
+        let table = |id: &'static [u8; 4]| {
+            let name = std::str::from_utf8(id).unwrap_or("pdta");
+            find(&hydra, id).ok_or(LoadSoundFontError::MissingChunk(name))
+        };
+        let preset_zones = zones(table(b"pbag")?, table(b"pgen")?, GEN_INSTRUMENT)?;
+        let instrument_zones = zones(table(b"ibag")?, table(b"igen")?, GEN_SAMPLE_ID)?;
+
+        Ok(SoundFont {
+            presets: presets(table(b"phdr")?, &preset_zones)?,
+            instruments: instruments(table(b"inst")?, &instrument_zones)?,
+            samples: sample_headers(table(b"shdr")?)?,
+            pool,
+        })
+    }
+
+    /// Renders `program` (bank 0, falling back to the first preset) at
+    /// `freq` for `duration_ms`, on the float mix bus. Returns silence if
+    /// no zone covers the note.
+    pub fn render(&self, program: u8, freq: u32, duration_ms: u32) -> Vec<f64> {
+        let num_samples = (SAMPLE_RATE * duration_ms / MS_PER_SECOND) as usize;
+        let key = key_for_freq(freq);
+        let Some((zone, header)) = self.resolve(program, key) else {
+            return vec![0.0; num_samples];
+        };
+
+        let root_key = zone.root_key.unwrap_or(header.original_key);
+        let root_freq = 440.0 * 2f64.powf((root_key as f64 - 69.0 + header.pitch_correction_cents as f64 / 100.0) / 12.0);
+        let step = freq as f64 / root_freq * header.sample_rate as f64 / SAMPLE_RATE as f64;
+        let release_samples = ((SAMPLE_RATE * RELEASE_MS / MS_PER_SECOND) as usize).min(num_samples);
+        let loop_len = header.loop_end.saturating_sub(header.loop_start);
+        let mut position = header.start as f64;
+
+        (0..num_samples)
+            .map(|idx| {
+                let sample = self.interpolate(position, header.end);
+                position += step;
+                if zone.looped && loop_len > 0 && position >= header.loop_end as f64 {
+                    position -= loop_len as f64;
+                }
+                let remaining = num_samples - idx;
+                let release = (remaining.min(release_samples) as f64 / release_samples.max(1) as f64).min(1.0);
+                sample * release
+            })
+            .collect()
+    }
+
+    /// Linear interpolation between pool samples; silence past `end`.
+    fn interpolate(&self, position: f64, end: usize) -> f64 {
+        let index = position as usize;
+        if index + 1 >= end {
+            return 0.0;
+        }
+        let fraction = position - index as f64;
+        let current = self.pool.get(index).copied().unwrap_or(0) as f64;
+        let next = self.pool.get(index + 1).copied().unwrap_or(0) as f64;
+        (current + (next - current) * fraction) / i16::MAX as f64
+    }
+
+    fn resolve(&self, program: u8, key: u8) -> Option<(Zone, SampleHeader)> {
+        let preset = self
+            .presets
+            .iter()
+            .find(|preset| preset.bank == 0 && preset.program == program as u16)
+            .or_else(|| self.presets.first())?;
+        let instrument = preset
+            .zones
+            .iter()
+            .find(|zone| zone.covers(key) && zone.target.is_some())
+            .and_then(|zone| zone.target)?;
+        let zone = self
+            .instruments
+            .get(instrument as usize)?
+            .iter()
+            .find(|zone| zone.covers(key) && zone.target.is_some())?;
+        let header = self.samples.get(zone.target? as usize)?;
+        Some((*zone, *header))
+    }
+}
+
+/// A RIFF chunk: four-character id and its data.
+type Chunk<'a> = ([u8; 4], &'a [u8]);
+
+/// Splits a RIFF body into chunks, honoring word alignment.
+fn chunks(body: &[u8]) -> Result<Vec<Chunk<'_>>, LoadSoundFontError> {
+    let mut found = Vec::new();
+    let mut offset = 0;
+    while offset + 8 <= body.len() {
+        let id: [u8; 4] = body[offset..offset + 4].try_into().map_err(|_| LoadSoundFontError::Malformed("chunk id"))?;
+        let size = u32_at(body, offset + 4)? as usize;
+        let data = body
+            .get(offset + 8..offset + 8 + size)
+            .ok_or(LoadSoundFontError::Malformed("chunk runs past end of file"))?;
+        found.push((id, data));
+        offset += 8 + size + size % 2;
+    }
+    Ok(found)
+}
+
+fn find<'a>(chunks: &[Chunk<'a>], id: &[u8; 4]) -> Option<&'a [u8]> {
+    chunks.iter().find(|(chunk_id, _)| chunk_id == id).map(|(_, data)| *data)
+}
+
+/// Body of the `LIST` chunk whose form type is `form`.
+fn list_body<'a>(chunks: &[Chunk<'a>], form: &[u8; 4]) -> Option<&'a [u8]> {
+    chunks
+        .iter()
+        .filter(|(id, _)| id == b"LIST")
+        .find(|(_, data)| data.get(0..4) == Some(form))
+        .map(|(_, data)| &data[4..])
+}
+
+fn u16_at(bytes: &[u8], offset: usize) -> Result<u16, LoadSoundFontError> {
+    bytes
+        .get(offset..offset + 2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .ok_or(LoadSoundFontError::Malformed("truncated record"))
+}
+
+fn u32_at(bytes: &[u8], offset: usize) -> Result<u32, LoadSoundFontError> {
+    bytes
+        .get(offset..offset + 4)
+        .map(|quad| u32::from_le_bytes([quad[0], quad[1], quad[2], quad[3]]))
+        .ok_or(LoadSoundFontError::Malformed("truncated record"))
+}
+
+/// Builds every zone from a bag table and its generator table. `target` is
+/// taken from `target_operator` (instrument for presets, sample for
+/// instruments); zones without one are global, see [`zones_for`].
+fn zones(bags: &[u8], generators: &[u8], target_operator: u16) -> Result<Vec<Zone>, LoadSoundFontError> {
+    let bag_count = bags.len() / BAG_SIZE;
+    let mut built = Vec::with_capacity(bag_count);
+    for bag in 0..bag_count.saturating_sub(1) {
+        let first = u16_at(bags, bag * BAG_SIZE)? as usize;
+        let last = u16_at(bags, (bag + 1) * BAG_SIZE)? as usize;
+        let mut zone = Zone::global();
+        for generator in first..last {
+            let operator = u16_at(generators, generator * GENERATOR_SIZE)?;
+            let amount = u16_at(generators, generator * GENERATOR_SIZE + 2)?;
+            let [low, high] = amount.to_le_bytes();
+            match operator {
+                GEN_KEY_RANGE => (zone.low_key, zone.high_key) = (low, high),
+                GEN_OVERRIDING_ROOT_KEY if amount < 128 => zone.root_key = Some(low),
+                GEN_SAMPLE_MODES => zone.looped = amount & 1 == 1,
+                op if op == target_operator => zone.target = Some(amount),
+                _ => {}
+            }
+        }
+        built.push(zone);
+    }
+    Ok(built)
+}
+
+/// Zones `first..last` owned by one preset or instrument. A leading zone
+/// without a target is global: its settings become defaults for the rest.
+fn zones_for(all: &[Zone], first: usize, last: usize) -> Result<Vec<Zone>, LoadSoundFontError> {
+    let owned = all.get(first..last).ok_or(LoadSoundFontError::Malformed("bag index out of range"))?;
+    let Some((head, rest)) = owned.split_first() else {
+        return Ok(Vec::new());
+    };
+    if head.target.is_some() {
+        return Ok(owned.to_vec());
+    }
+    Ok(rest
+        .iter()
+        .map(|zone| Zone {
+            root_key: zone.root_key.or(head.root_key),
+            looped: zone.looped || head.looped,
+            ..*zone
+        })
+        .collect())
+}
+
+fn presets(headers: &[u8], zones: &[Zone]) -> Result<Vec<Preset>, LoadSoundFontError> {
+    // The last record is the terminal "EOP" sentinel
+    let count = (headers.len() / PRESET_HEADER_SIZE).saturating_sub(1);
+    (0..count)
+        .map(|idx| {
+            let record = idx * PRESET_HEADER_SIZE;
+            let first = u16_at(headers, record + 24)? as usize;
+            let last = u16_at(headers, record + PRESET_HEADER_SIZE + 24)? as usize;
+            Ok(Preset {
+                program: u16_at(headers, record + 20)?,
+                bank: u16_at(headers, record + 22)?,
+                zones: zones_for(zones, first, last)?,
+            })
+        })
+        .collect()
+}
+
+fn instruments(headers: &[u8], zones: &[Zone]) -> Result<Vec<Vec<Zone>>, LoadSoundFontError> {
+    // The last record is the terminal "EOI" sentinel
+    let count = (headers.len() / INSTRUMENT_SIZE).saturating_sub(1);
+    (0..count)
+        .map(|idx| {
+            let first = u16_at(headers, idx * INSTRUMENT_SIZE + 20)? as usize;
+            let last = u16_at(headers, (idx + 1) * INSTRUMENT_SIZE + 20)? as usize;
+            zones_for(zones, first, last)
+        })
+        .collect()
+}
+
+fn sample_headers(headers: &[u8]) -> Result<Vec<SampleHeader>, LoadSoundFontError> {
+    (0..headers.len() / SAMPLE_HEADER_SIZE)
+        .map(|idx| {
+            let record = idx * SAMPLE_HEADER_SIZE;
+            let original_key = headers.get(record + 40).copied().ok_or(LoadSoundFontError::Malformed("sample header"))?;
+            let correction = headers.get(record + 41).copied().ok_or(LoadSoundFontError::Malformed("sample header"))?;
+            Ok(SampleHeader {
+                start: u32_at(headers, record + 20)? as usize,
+                end: u32_at(headers, record + 24)? as usize,
+                loop_start: u32_at(headers, record + 28)? as usize,
+                loop_end: u32_at(headers, record + 32)? as usize,
+                sample_rate: u32_at(headers, record + 36)?,
+                original_key: if original_key > 127 { DEFAULT_ROOT_KEY } else { original_key },
+                pitch_correction_cents: correction as i8,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_RATE: u32 = 22050;
+    const TEST_LEN: u32 = 2205;
+
+    fn chunk(id: &[u8; 4], data: &[u8]) -> Vec<u8> {
+        let mut bytes = id.to_vec();
+        bytes.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(data);
+        if data.len() % 2 == 1 {
+            bytes.push(0);
+        }
+        bytes
+    }
+
+    fn list(form: &[u8; 4], children: &[Vec<u8>]) -> Vec<u8> {
+        let mut body = form.to_vec();
+        children.iter().for_each(|child| body.extend_from_slice(child));
+        chunk(b"LIST", &body)
+    }
+
+    fn named(name: &str, size: usize) -> Vec<u8> {
+        let mut record = vec![0; size];
+        record[..name.len()].copy_from_slice(name.as_bytes());
+        record
+    }
+
+    fn generator(operator: u16, amount: u16) -> Vec<u8> {
+        [operator.to_le_bytes(), amount.to_le_bytes()].concat()
+    }
+
+    fn bag(generator_index: u16) -> Vec<u8> {
+        [generator_index.to_le_bytes(), 0u16.to_le_bytes()].concat()
+    }
+
+    /// One preset (bank 0, program 0) → one instrument → one looped 440 Hz
+    /// sine sample at root key 69, recorded at 22.05 kHz.
+    fn sine_font() -> Vec<u8> {
+        let smpl: Vec<u8> = (0..TEST_LEN)
+            .flat_map(|idx| {
+                let phase = 2.0 * std::f64::consts::PI * 440.0 * idx as f64 / TEST_RATE as f64;
+                ((phase.sin() * 20000.0) as i16).to_le_bytes()
+            })
+            .collect();
+
+        let mut phdr = named("Sine", PRESET_HEADER_SIZE);
+        phdr[24..26].copy_from_slice(&0u16.to_le_bytes());
+        let mut eop = named("EOP", PRESET_HEADER_SIZE);
+        eop[24..26].copy_from_slice(&1u16.to_le_bytes());
+
+        let mut inst = named("Sine", INSTRUMENT_SIZE);
+        inst[20..22].copy_from_slice(&0u16.to_le_bytes());
+        let mut eoi = named("EOI", INSTRUMENT_SIZE);
+        eoi[20..22].copy_from_slice(&1u16.to_le_bytes());
+
+        let mut shdr = named("Sine", SAMPLE_HEADER_SIZE);
+        shdr[24..28].copy_from_slice(&TEST_LEN.to_le_bytes());
+        // Loop the first 1103 samples: almost exactly 22 cycles of 440 Hz at 22.05 kHz
+        shdr[28..32].copy_from_slice(&0u32.to_le_bytes());
+        shdr[32..36].copy_from_slice(&1103u32.to_le_bytes());
+        shdr[36..40].copy_from_slice(&TEST_RATE.to_le_bytes());
+        shdr[40] = 69;
+
+        let pdta = list(
+            b"pdta",
+            &[
+                chunk(b"phdr", &[phdr, eop].concat()),
+                chunk(b"pbag", &[bag(0), bag(1)].concat()),
+                chunk(b"pmod", &[0; 10]),
+                chunk(b"pgen", &[generator(GEN_INSTRUMENT, 0), generator(0, 0)].concat()),
+                chunk(b"inst", &[inst, eoi].concat()),
+                chunk(b"ibag", &[bag(0), bag(2)].concat()),
+                chunk(b"imod", &[0; 10]),
+                chunk(
+                    b"igen",
+                    &[generator(GEN_SAMPLE_MODES, 1), generator(GEN_SAMPLE_ID, 0), generator(0, 0)].concat(),
+                ),
+                chunk(b"shdr", &[shdr, named("EOS", SAMPLE_HEADER_SIZE)].concat()),
+            ],
+        );
+        let body = [b"sfbk".to_vec(), list(b"INFO", &[]), list(b"sdta", &[chunk(b"smpl", &smpl)]), pdta].concat();
+        chunk(b"RIFF", &body)
+    }
+
+    fn zero_crossings(samples: &[f64]) -> usize {
+        samples.windows(2).filter(|pair| (pair[0] < 0.0) != (pair[1] < 0.0)).count()
+    }
+
+    #[test]
+    fn parses_minimal_font() -> Result<(), LoadSoundFontError> {
+        let font = SoundFont::parse(&sine_font())?;
+        assert_eq!(font.presets.len(), 1);
+        assert_eq!(font.instruments.len(), 1);
+        assert_eq!(font.samples[0].original_key, 69);
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_non_riff_input() {
+        assert!(matches!(SoundFont::parse(b"not a soundfont"), Err(LoadSoundFontError::NotSoundFont)));
+    }
+
+    #[test]
+    fn reports_missing_chunks() {
+        let riff = chunk(b"RIFF", &[b"sfbk".to_vec(), list(b"INFO", &[])].concat());
+        assert!(matches!(SoundFont::parse(&riff), Err(LoadSoundFontError::MissingChunk("sdta"))));
+    }
+
+    #[test]
+    fn renders_requested_duration() -> Result<(), LoadSoundFontError> {
+        let font = SoundFont::parse(&sine_font())?;
+        assert_eq!(font.render(0, 440, 300).len(), 13230);
+        Ok(())
+    }
+
+    #[test]
+    fn pitch_follows_frequency() -> Result<(), LoadSoundFontError> {
+        let font = SoundFont::parse(&sine_font())?;
+        let root = zero_crossings(&font.render(0, 440, 50));
+        let octave_up = zero_crossings(&font.render(0, 880, 50));
+        assert!((octave_up as i64 - 2 * root as i64).abs() <= 2, "root {root}, octave up {octave_up}");
+        Ok(())
+    }
+
+    #[test]
+    fn looped_sample_sustains_past_its_length() -> Result<(), LoadSoundFontError> {
+        let font = SoundFont::parse(&sine_font())?;
+        // The sample is 100 ms long at its own rate; hold the note for 500 ms
+        let note = font.render(0, 440, 500);
+        assert!(note[15_000..20_000].iter().any(|sample| sample.abs() > 0.3));
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_program_falls_back_to_first_preset() -> Result<(), LoadSoundFontError> {
+        let font = SoundFont::parse(&sine_font())?;
+        assert_eq!(font.render(73, 440, 50), font.render(0, 440, 50));
+        Ok(())
+    }
+
+    #[test]
+    fn game_renders_through_soundfont() -> Result<(), LoadSoundFontError> {
+        let font = SoundFont::parse(&sine_font())?;
+        let config = crate::audio::AudioConfig::default();
+        let samples = crate::audio::generate_with_soundfont("e4 e5", &config, &font);
+        assert_eq!(samples.len(), crate::audio::generate("e4 e5").len());
+        assert_ne!(samples, crate::audio::generate("e4 e5"));
+        Ok(())
+    }
+}
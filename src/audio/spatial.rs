@@ -0,0 +1,119 @@
+//! Headphone spatialization - the board laid out around the listener.
+//!
+//! The listener sits behind White's first rank. Files spread across the
+//! azimuth and ranks recede into the distance:
+//!
+//! ```text
+//!          rank 8: far, -6 dB, low-passed at 2.5 kHz
+//!         ┌─────────────────────────┐
+//!         │ a8                   h8 │
+//!         │                         │
+//!         │ a1                   h1 │
+//!         └─────────────────────────┘
+//!          rank 1: near, full level, open
+//!
+//!     -60° ←──────  listener  ──────→ +60°
+//! ```
+//!
+//! Azimuth sets the pan and an interaural time difference (Woodworth's
+//! spherical-head formula, ITD = r/c × (θ + sin θ)); distance lowers the
+//! gain and closes a low-pass, as air and walls absorb highs over distance.
+
+use std::f64::consts::FRAC_PI_2;
+
+use super::blend::{Filter, FilterState};
+use super::mixer::Voice;
+use super::SAMPLE_RATE;
+use crate::engine::chess::Square;
+
+/// Azimuth of the a- and h-files, left and right of straight ahead.
+const MAX_AZIMUTH: f64 = std::f64::consts::FRAC_PI_3;
+/// Head radius over the speed of sound, in seconds.
+const HEAD_RADIUS_SECONDS: f64 = 0.0875 / 343.0;
+/// Gain of the farthest rank (-6 dB); the nearest plays at full level.
+const FAR_GAIN: f64 = 0.5;
+/// Low-pass cutoffs at the nearest and farthest ranks.
+const NEAR_CUTOFF_HZ: f64 = 16000.0;
+const FAR_CUTOFF_HZ: f64 = 2500.0;
+
+/// Where a square sits around the listener.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Placement {
+    /// Radians, negative = left
+    pub azimuth: f64,
+    /// 0.0 = first rank, 1.0 = eighth rank
+    pub distance: f64,
+}
+
+impl Placement {
+    pub fn of(square: &Square) -> Self {
+        Self {
+            azimuth: (square.file as f64 / 7.0 * 2.0 - 1.0) * MAX_AZIMUTH,
+            distance: square.rank as f64 / 7.0,
+        }
+    }
+
+    /// Places `voice` here: filtered and attenuated for distance, panned and
+    /// delayed between the ears for azimuth.
+    pub fn apply(&self, voice: Voice) -> Voice {
+        let cutoff_hz = NEAR_CUTOFF_HZ * (FAR_CUTOFF_HZ / NEAR_CUTOFF_HZ).powf(self.distance);
+        let filter = Filter::low_pass(cutoff_hz);
+        let mut state = FilterState::default();
+        let samples = voice.samples.iter().map(|&sample| state.process(&filter, sample, 0.0)).collect();
+        let gain = voice.gain * (1.0 - (1.0 - FAR_GAIN) * self.distance);
+        Voice { samples, ..voice }
+            .with_gain(gain)
+            .with_pan(self.azimuth / FRAC_PI_2)
+            .with_interaural_delay(self.interaural_delay())
+    }
+
+    /// Interaural time difference in samples.
+    fn interaural_delay(&self) -> usize {
+        let angle = self.azimuth.abs();
+        (HEAD_RADIUS_SECONDS * (angle + angle.sin()) * SAMPLE_RATE as f64).round() as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn energy(samples: &[f64]) -> f64 {
+        samples.iter().map(|sample| sample * sample).sum()
+    }
+
+    /// Alternating ±0.5: all energy at Nyquist, where a low-pass bites most.
+    fn nyquist_tone() -> Vec<f64> {
+        (0..4410).map(|idx| if idx % 2 == 0 { 0.5 } else { -0.5 }).collect()
+    }
+
+    #[test]
+    fn files_spread_left_to_right() {
+        let a1 = Placement::of(&Square { file: 0, rank: 0 });
+        let h1 = Placement::of(&Square { file: 7, rank: 0 });
+        assert_eq!(a1.azimuth, -MAX_AZIMUTH);
+        assert_eq!(h1.azimuth, MAX_AZIMUTH);
+    }
+
+    #[test]
+    fn edge_files_delay_the_far_ear_about_half_a_millisecond() {
+        let delay = Placement::of(&Square { file: 0, rank: 0 }).interaural_delay();
+        assert!((20..=23).contains(&delay), "delay {delay}");
+    }
+
+    #[test]
+    fn far_ranks_are_quieter_and_duller() {
+        let near = Placement::of(&Square { file: 4, rank: 0 }).apply(Voice::new(0, nyquist_tone()));
+        let far = Placement::of(&Square { file: 4, rank: 7 }).apply(Voice::new(0, nyquist_tone()));
+        assert_eq!(near.gain, 1.0);
+        assert_eq!(far.gain, FAR_GAIN);
+        assert!(energy(&far.samples) < energy(&near.samples) / 2.0);
+    }
+
+    #[test]
+    fn apply_keeps_voice_start_and_length() {
+        let placed = Placement::of(&Square { file: 2, rank: 5 }).apply(Voice::new(100, vec![0.5; 50]));
+        assert_eq!(placed.start, 100);
+        assert_eq!(placed.samples.len(), 50);
+    }
+}
@@ -0,0 +1,194 @@
+//! Game statistics for `--stats`: what happened on the board and what it
+//! became as sound.
+//!
+//! ```text
+//! Moves:       33
+//! Captures:    9
+//! Checks:      3 (1 mate)
+//! Promotions:  0
+//! Castling:    1
+//! Pitch range: 196–1568 Hz (G3–G6)
+//! Duration:    11.55 s
+//! ```
+//!
+//! Captures come from replaying the moves on the board, so en passant counts
+//! and a stray "x" on an empty square does not. The pitch range is what the
+//! game sounds like with the config's pitch map and side voicing.
+
+use std::fmt;
+
+use super::{AudioConfig, MS_PER_SECOND, Phrasing, SAMPLE_RATE, game_moves, midi, mover, play_on_board, sounding_freq};
+use crate::engine::chess::Threat;
+use crate::engine::hint::is_castling;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct GameStats {
+    pub moves: usize,
+    pub captures: usize,
+    /// Checks, checkmates included
+    pub checks: usize,
+    pub checkmates: usize,
+    pub promotions: usize,
+    pub castles: usize,
+    /// Lowest and highest note in Hz; None without moves
+    pub pitch_range: Option<(u32, u32)>,
+    /// Length of the rendered audio
+    pub duration_ms: u64,
+}
+
+impl GameStats {
+    /// Statistics of `input` as rendered with `config` into `samples`.
+    pub fn collect(input: &str, config: &AudioConfig, samples: &[i16], num_channels: u16) -> GameStats {
+        let mut stats = GameStats {
+            duration_ms: samples.len() as u64 / num_channels.max(1) as u64 * MS_PER_SECOND as u64 / SAMPLE_RATE as u64,
+            ..GameStats::default()
+        };
+        let mut board = config.start.board.clone();
+        let moves = game_moves(input, &config.start);
+
+        for (move_index, notation, m) in moves {
+            let color = mover(move_index);
+            let captured = play_on_board(&mut board, &m, notation, color);
+            let phrasing = Phrasing {
+                pitch: config.pitch,
+                side: config.color_voicing.map(|voicing| voicing.for_color(color)).unwrap_or_default(),
+                ..Phrasing::plain()
+            };
+            let freq = sounding_freq(&m, phrasing);
+
+            stats.moves += 1;
+            stats.captures += usize::from(captured.is_some());
+            stats.checks += usize::from(m.threat != Threat::None);
+            stats.checkmates += usize::from(m.threat == Threat::Checkmate);
+            stats.promotions += usize::from(m.promotion.is_some());
+            stats.castles += usize::from(is_castling(notation));
+            stats.pitch_range = Some(match stats.pitch_range {
+                Some((lowest, highest)) => (lowest.min(freq), highest.max(freq)),
+                None => (freq, freq),
+            });
+        }
+        stats
+    }
+
+    /// One JSON object, keys in snake_case; `lowest_hz`/`highest_hz` are
+    /// null without moves.
+    pub fn to_json(&self) -> String {
+        let (lowest, highest) = match self.pitch_range {
+            Some((lowest, highest)) => (lowest.to_string(), highest.to_string()),
+            None => ("null".to_string(), "null".to_string()),
+        };
+        format!(
+            "{{\"moves\":{},\"captures\":{},\"checks\":{},\"checkmates\":{},\"promotions\":{},\"castles\":{},\"lowest_hz\":{},\"highest_hz\":{},\"duration_ms\":{}}}",
+            self.moves,
+            self.captures,
+            self.checks,
+            self.checkmates,
+            self.promotions,
+            self.castles,
+            lowest,
+            highest,
+            self.duration_ms
+        )
+    }
+}
+
+impl fmt::Display for GameStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Moves:       {}", self.moves)?;
+        writeln!(f, "Captures:    {}", self.captures)?;
+        match self.checkmates {
+            0 => writeln!(f, "Checks:      {}", self.checks)?,
+            checkmates => writeln!(f, "Checks:      {} ({} mate)", self.checks, checkmates)?,
+        }
+        writeln!(f, "Promotions:  {}", self.promotions)?;
+        writeln!(f, "Castling:    {}", self.castles)?;
+        match self.pitch_range {
+            Some((lowest, highest)) => writeln!(
+                f,
+                "Pitch range: {}–{} Hz ({}–{})",
+                lowest,
+                highest,
+                midi::key_name(midi::key_for_freq(lowest)),
+                midi::key_name(midi::key_for_freq(highest))
+            )?,
+            None => writeln!(f, "Pitch range: none")?,
+        }
+        write!(f, "Duration:    {:.2} s", self.duration_ms as f64 / MS_PER_SECOND as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::board::Board;
+    use crate::engine::chess::NotationMove;
+
+    fn stats_of(input: &str) -> GameStats {
+        let config = AudioConfig::default();
+        let samples = crate::audio::generate_with_config(input, &config);
+        GameStats::collect(input, &config, &samples, config.num_channels())
+    }
+
+    #[test]
+    fn counts_what_happened_on_the_board() {
+        let stats = stats_of("e4 d5 exd5 Qxd5 Nc3 Qa5+ d4 Nf6 Nf3 Bg4 Be2 e6 O-O");
+        assert_eq!(stats.moves, 13);
+        assert_eq!(stats.captures, 2);
+        assert_eq!(stats.checks, 1);
+        assert_eq!(stats.checkmates, 0);
+        assert_eq!(stats.castles, 1);
+        assert_eq!(stats.promotions, 0);
+    }
+
+    #[test]
+    fn counts_from_the_start_position() {
+        // Black to move, with a White knight hanging on e5
+        let config = AudioConfig {
+            start: Board::from_fen("4k3/8/3p4/4N3/8/8/8/4K3 b - - 0 1").unwrap(),
+            ..AudioConfig::default()
+        };
+        let stats = GameStats::collect("dxe5 Kd2", &config, &[], 1);
+        assert_eq!(stats.captures, 1);
+    }
+
+    #[test]
+    fn duration_matches_the_rendered_audio() {
+        // 4 slots of 350 ms
+        assert_eq!(stats_of("e4 e5 Nf3 Nc6").duration_ms, 1400);
+    }
+
+    #[test]
+    fn pitch_range_spans_the_sounding_notes() {
+        let config = AudioConfig::default();
+        let low = sounding_freq(&NotationMove::parse("a1", 0).unwrap(), Phrasing::plain());
+        let high = sounding_freq(&NotationMove::parse("h8", 0).unwrap(), Phrasing::plain());
+        let stats = GameStats::collect("Ra1 Qh8+", &config, &[], 1);
+        assert_eq!(stats.pitch_range, Some((low.min(high), low.max(high))));
+        assert_eq!(GameStats::default().pitch_range, None);
+    }
+
+    #[test]
+    fn json_has_every_field() {
+        let stats = GameStats {
+            moves: 2,
+            checks: 1,
+            checkmates: 1,
+            pitch_range: Some((220, 440)),
+            duration_ms: 700,
+            ..GameStats::default()
+        };
+        assert_eq!(
+            stats.to_json(),
+            "{\"moves\":2,\"captures\":0,\"checks\":1,\"checkmates\":1,\"promotions\":0,\"castles\":0,\"lowest_hz\":220,\"highest_hz\":440,\"duration_ms\":700}"
+        );
+        assert!(GameStats::default().to_json().contains("\"lowest_hz\":null"));
+    }
+
+    #[test]
+    fn summary_names_the_pitch_range() {
+        let stats = GameStats { moves: 1, pitch_range: Some((262, 440)), duration_ms: 350, ..GameStats::default() };
+        let summary = stats.to_string();
+        assert!(summary.contains("Pitch range: 262–440 Hz (C4–A4)"));
+        assert!(summary.ends_with("Duration:    0.35 s"));
+    }
+}
@@ -3,28 +3,157 @@
 use std::f64::consts::PI;
 
 use super::{MS_PER_SECOND, SAMPLE_RATE};
-use super::blend::Blend;
-use super::waveform::{Composite, Harmonics, Sawtooth, Sine, Square, Triangle, Waveform};
+use super::blend::{Blend, FilterState};
+use super::waveform::{
+    Additive, Fm, PinkNoise, Sawtooth, Sine, Square, Triangle, Waveform, WhiteNoise,
+};
 
 const AMPLITUDE: f64 = i16::MAX as f64;
 
+/// Karplus–Strong feedback gain: closer to 1.0 = longer ringing string.
+const PLUCK_DAMPING: f64 = 0.996;
+
+/// Fixed seed so the same game always renders to the same bytes.
+const NOISE_SEED: u32 = 0x5EED;
+
+/// One-pole low-pass coefficient for bursts: lower = duller thud.
+const BURST_SMOOTHING: f64 = 0.35;
+
+/// Bursts decay by 1/e every this many milliseconds.
+const BURST_DECAY_MS: f64 = 12.0;
+
+/// Clicks are a short, high sine blip — like a metronome's tick.
+const CLICK_HZ: f64 = 2000.0;
+const CLICK_DECAY_MS: f64 = 4.0;
+
+/// Kicks drop from a punchy start pitch to a low thump.
+const KICK_START_HZ: f64 = 150.0;
+const KICK_END_HZ: f64 = 45.0;
+const KICK_PITCH_DECAY_MS: f64 = 30.0;
+const KICK_DECAY_MS: f64 = 60.0;
+
+/// Rewinds sweep down an octave, quietly, with a rounded envelope.
+const REWIND_START_HZ: f64 = 880.0;
+const REWIND_END_HZ: f64 = 440.0;
+const REWIND_LEVEL: f64 = 0.35;
+
+/// Chimes ring a quiet, high sine out, softer and longer than a click.
+const CHIME_HZ: f64 = 1320.0;
+const CHIME_DECAY_MS: f64 = 40.0;
+const CHIME_LEVEL: f64 = 0.25;
+
+/// Jingles are short sine notes, each ringing out before the next.
+const JINGLE_DECAY_MS: f64 = 120.0;
+const JINGLE_LEVEL: f64 = 0.4;
+
+/// Buzzes are a low square wave, quiet and cut short, for a rejected move.
+const BUZZ_HZ: f64 = 110.0;
+const BUZZ_DECAY_MS: f64 = 60.0;
+const BUZZ_LEVEL: f64 = 0.3;
+
 /// Generate samples from a waveform with blending options.
+///
+/// The carrier phase is accumulated one step per sample rather than
+/// recomputed as `angular_freq × idx`; vibrato and glide stay closed-form
+/// offsets on top of it. It is deliberately not wrapped at 2π: FM and
+/// additive partials multiply the phase by non-integer ratios, which would
+/// jump at every wrap. `lut::sin` reduces each argument itself.
 pub fn generate<W: Waveform>(wave: &W, freq: u32, duration_ms: u32, blend: Blend) -> Vec<i16> {
     let num_samples = (SAMPLE_RATE * duration_ms / MS_PER_SECOND) as usize;
     let angular_freq = 2.0 * PI * freq as f64 / SAMPLE_RATE as f64;
+    let mut filter_state = FilterState::default();
+    let mut carrier_phase = 0.0;
 
     (0..num_samples)
         .map(|idx| {
-            let phase = angular_freq * idx as f64;
-            let value = blend.apply(wave, phase);
+            let vibrato = blend.vibrato.map_or(0.0, |lfo| lfo.vibrato_offset(angular_freq, idx));
+            let glide = blend.glide.map_or(0.0, |glide| glide.phase_offset(angular_freq, idx));
+            let phase = carrier_phase + vibrato + glide;
+            carrier_phase += angular_freq;
+            let tremolo = blend.tremolo.map_or(1.0, |lfo| lfo.tremolo_gain(idx));
+            let blended = blend.apply(wave, phase) * tremolo * blend.envelope.gain(idx, num_samples);
+            let value = match &blend.filter {
+                Some(filter) => {
+                    let progress = idx as f64 / num_samples as f64;
+                    filter_state.process(filter, blended, progress)
+                }
+                None => blended,
+            };
             (value * AMPLITUDE) as i16
         })
         .collect()
 }
 
-/// Generates a sine wave at the given frequency.
-pub fn sine(freq: u32, duration_ms: u32) -> Vec<i16> {
-    generate(&Sine, freq, duration_ms, Blend::none())
+/// A piece's sound source, rendered by [`render`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Timbre {
+    Sine,
+    Triangle,
+    Square,
+    Sawtooth,
+    /// A weighted sum of sine partials, e.g. a theme's `partials`
+    Additive(Additive),
+    Fm(Fm),
+    /// Karplus–Strong plucked string ([`pluck`])
+    Pluck,
+    /// Pitchless pink noise ([`noise`]); `sine_mix` gives it a pitch
+    Noise,
+}
+
+impl Timbre {
+    /// Parses a waveform name as given in a theme, e.g. "sawtooth".
+    /// "composite" and "harmonics" are the [`Additive`] presets of those
+    /// names; "fm" is the struck-bell operator ([`Fm::bell`]).
+    pub fn from_name(name: &str) -> Option<Timbre> {
+        match name {
+            "sine" => Some(Timbre::Sine),
+            "triangle" => Some(Timbre::Triangle),
+            "square" => Some(Timbre::Square),
+            "sawtooth" => Some(Timbre::Sawtooth),
+            "composite" => Some(Timbre::Additive(Additive::composite())),
+            "harmonics" => Some(Timbre::Additive(Additive::harmonics())),
+            "fm" => Some(Timbre::Fm(Fm::bell())),
+            "pluck" => Some(Timbre::Pluck),
+            "noise" => Some(Timbre::Noise),
+            _ => None,
+        }
+    }
+
+    /// The waveform's name, as [`Timbre::from_name`] reads it; partials
+    /// other than the presets' are "additive".
+    pub fn name(&self) -> &'static str {
+        match self {
+            Timbre::Sine => "sine",
+            Timbre::Triangle => "triangle",
+            Timbre::Square => "square",
+            Timbre::Sawtooth => "sawtooth",
+            Timbre::Additive(additive) if *additive == Additive::composite() => "composite",
+            Timbre::Additive(additive) if *additive == Additive::harmonics() => "harmonics",
+            Timbre::Additive(_) => "additive",
+            Timbre::Fm(_) => "fm",
+            Timbre::Pluck => "pluck",
+            Timbre::Noise => "noise",
+        }
+    }
+}
+
+/// Renders a note of the given timbre.
+pub fn render(timbre: &Timbre, freq: u32, duration_ms: u32, blend: Blend) -> Vec<i16> {
+    match timbre {
+        Timbre::Sine => sine(freq, duration_ms, blend),
+        Timbre::Triangle => triangle(freq, duration_ms, blend),
+        Timbre::Square => square(freq, duration_ms, blend),
+        Timbre::Sawtooth => sawtooth(freq, duration_ms, blend),
+        Timbre::Additive(additive) => generate(additive, freq, duration_ms, blend),
+        Timbre::Fm(operator) => fm(freq, duration_ms, *operator, blend),
+        Timbre::Pluck => pluck(freq, duration_ms, blend),
+        Timbre::Noise => noise(freq, duration_ms, blend),
+    }
+}
+
+/// Generates a sine wave with optional blending.
+pub fn sine(freq: u32, duration_ms: u32, blend: Blend) -> Vec<i16> {
+    generate(&Sine, freq, duration_ms, blend)
 }
 
 /// Generates a square wave with optional blending.
@@ -42,45 +171,215 @@ pub fn sawtooth(freq: u32, duration_ms: u32, blend: Blend) -> Vec<i16> {
     generate(&Sawtooth, freq, duration_ms, blend)
 }
 
-/// Generates a composite wave with optional blending.
-pub fn composite(freq: u32, duration_ms: u32, blend: Blend) -> Vec<i16> {
-    generate(&Composite, freq, duration_ms, blend)
+/// Generates a two-operator FM tone with optional blending.
+pub fn fm(freq: u32, duration_ms: u32, operator: Fm, blend: Blend) -> Vec<i16> {
+    generate(&operator, freq, duration_ms, blend)
+}
+
+/// Generates pink noise as a pitchless timbre; blend in sine to give it pitch.
+pub fn noise(freq: u32, duration_ms: u32, blend: Blend) -> Vec<i16> {
+    generate(&PinkNoise::new(NOISE_SEED), freq, duration_ms, blend)
+}
+
+/// Generates a short percussive hit: low-passed white noise with a fast
+/// exponential decay, meant to be layered under a note (e.g. on captures).
+pub fn noise_burst(duration_ms: u32) -> Vec<i16> {
+    let num_samples = (SAMPLE_RATE * duration_ms / MS_PER_SECOND) as usize;
+    let decay_samples = BURST_DECAY_MS * SAMPLE_RATE as f64 / MS_PER_SECOND as f64;
+    let white = WhiteNoise::new(NOISE_SEED);
+    let mut filtered = 0.0;
+
+    (0..num_samples)
+        .map(|idx| {
+            filtered += BURST_SMOOTHING * (white.sample(0.0) - filtered);
+            let envelope = (-(idx as f64) / decay_samples).exp();
+            (filtered * envelope * AMPLITUDE) as i16
+        })
+        .collect()
+}
+
+/// Generates a metronome-style tick: a high sine blip with a very fast decay.
+pub fn click(duration_ms: u32) -> Vec<i16> {
+    let num_samples = (SAMPLE_RATE * duration_ms / MS_PER_SECOND) as usize;
+    let angular_freq = 2.0 * PI * CLICK_HZ / SAMPLE_RATE as f64;
+    let decay_samples = CLICK_DECAY_MS * SAMPLE_RATE as f64 / MS_PER_SECOND as f64;
+
+    (0..num_samples)
+        .map(|idx| {
+            let envelope = (-(idx as f64) / decay_samples).exp();
+            ((angular_freq * idx as f64).sin() * envelope * AMPLITUDE) as i16
+        })
+        .collect()
+}
+
+/// Generates a kick drum: a sine whose pitch falls exponentially from a
+/// punchy attack to a low thump while the level decays.
+///
+/// The frequency changes every sample, so phase is accumulated rather than
+/// computed as `freq × idx` (which would only hold for a constant pitch).
+pub fn kick(duration_ms: u32) -> Vec<i16> {
+    let num_samples = (SAMPLE_RATE * duration_ms / MS_PER_SECOND) as usize;
+    let samples_per_ms = SAMPLE_RATE as f64 / MS_PER_SECOND as f64;
+    let mut phase: f64 = 0.0;
+
+    (0..num_samples)
+        .map(|idx| {
+            let elapsed_ms = idx as f64 / samples_per_ms;
+            let freq = KICK_END_HZ + (KICK_START_HZ - KICK_END_HZ) * (-elapsed_ms / KICK_PITCH_DECAY_MS).exp();
+            let sample = phase.sin() * (-elapsed_ms / KICK_DECAY_MS).exp();
+            phase += 2.0 * PI * freq / SAMPLE_RATE as f64;
+            (sample * AMPLITUDE) as i16
+        })
+        .collect()
+}
+
+/// Generates the soft "rewind" of an undone move: a sine sweeping down an
+/// octave under a half-sine envelope, with no attack click.
+pub fn rewind(duration_ms: u32) -> Vec<i16> {
+    let num_samples = (SAMPLE_RATE * duration_ms / MS_PER_SECOND) as usize;
+    let mut phase: f64 = 0.0;
+
+    (0..num_samples)
+        .map(|idx| {
+            let progress = idx as f64 / num_samples as f64;
+            // Exponential sweep: equal pitch steps per unit of time
+            let freq = REWIND_START_HZ * (REWIND_END_HZ / REWIND_START_HZ).powf(progress);
+            let sample = phase.sin() * (PI * progress).sin() * REWIND_LEVEL;
+            phase += 2.0 * PI * freq / SAMPLE_RATE as f64;
+            (sample * AMPLITUDE) as i16
+        })
+        .collect()
+}
+
+/// Generates the chime that marks a comment on a move: a quiet high sine
+/// ringing out from its first sample.
+pub fn chime(duration_ms: u32) -> Vec<i16> {
+    let num_samples = (SAMPLE_RATE * duration_ms / MS_PER_SECOND) as usize;
+    let angular_freq = 2.0 * PI * CHIME_HZ / SAMPLE_RATE as f64;
+    let decay_samples = CHIME_DECAY_MS * SAMPLE_RATE as f64 / MS_PER_SECOND as f64;
+
+    (0..num_samples)
+        .map(|idx| {
+            let envelope = (-(idx as f64) / decay_samples).exp() * CHIME_LEVEL;
+            ((angular_freq * idx as f64).sin() * envelope * AMPLITUDE) as i16
+        })
+        .collect()
+}
+
+/// Generates the buzz that marks a rejected move: a quiet low square wave
+/// dying away from its first sample.
+pub fn buzz(duration_ms: u32) -> Vec<i16> {
+    let num_samples = (SAMPLE_RATE * duration_ms / MS_PER_SECOND) as usize;
+    let period_samples = SAMPLE_RATE as f64 / BUZZ_HZ;
+    let decay_samples = BUZZ_DECAY_MS * SAMPLE_RATE as f64 / MS_PER_SECOND as f64;
+
+    (0..num_samples)
+        .map(|idx| {
+            let envelope = (-(idx as f64) / decay_samples).exp() * BUZZ_LEVEL;
+            let level = if (idx as f64 % period_samples) < period_samples / 2.0 { 1.0 } else { -1.0 };
+            (level * envelope * AMPLITUDE) as i16
+        })
+        .collect()
 }
 
-/// Generates a harmonics wave with optional blending.
-pub fn harmonics(freq: u32, duration_ms: u32, blend: Blend) -> Vec<i16> {
-    generate(&Harmonics, freq, duration_ms, blend)
+/// Generates a jingle: `freqs` one after another, `note_ms` each, every
+/// note a sine dying away from its first sample.
+pub fn jingle(freqs: &[f64], note_ms: u32) -> Vec<i16> {
+    let note_samples = (SAMPLE_RATE * note_ms / MS_PER_SECOND) as usize;
+    let decay_samples = JINGLE_DECAY_MS * SAMPLE_RATE as f64 / MS_PER_SECOND as f64;
+
+    freqs
+        .iter()
+        .flat_map(|freq| {
+            let angular_freq = 2.0 * PI * freq / SAMPLE_RATE as f64;
+            (0..note_samples).map(move |idx| {
+                let envelope = (-(idx as f64) / decay_samples).exp() * JINGLE_LEVEL;
+                ((angular_freq * idx as f64).sin() * envelope * AMPLITUDE) as i16
+            })
+        })
+        .collect()
+}
+
+/// Generates a plucked string via Karplus–Strong synthesis.
+///
+/// A delay line one period long is filled with a noise burst, then played
+/// back while each sample is replaced by the damped average of itself and
+/// its neighbour. The averaging is a low-pass filter in the feedback loop,
+/// so high partials die first — exactly how a real string decays.
+///
+/// ```text
+///        ┌──────────── delay line (SAMPLE_RATE / freq) ───────────┐
+/// noise ─┤ ▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓├──→ output
+///        └──── ← damping × (current + next) / 2 ←─────────────────┘
+/// ```
+///
+/// The string sets its own pitch and tone, so of `blend` only the
+/// envelope, tremolo and filter apply.
+pub fn pluck(freq: u32, duration_ms: u32, blend: Blend) -> Vec<i16> {
+    let num_samples = (SAMPLE_RATE * duration_ms / MS_PER_SECOND) as usize;
+    // Integer delay rounds the period, slightly detuning very high notes
+    let period = (SAMPLE_RATE / freq.max(1)).max(2) as usize;
+    let excitation = WhiteNoise::new(NOISE_SEED);
+    let mut delay_line: Vec<f64> = (0..period).map(|_| excitation.sample(0.0)).collect();
+    let mut filter_state = FilterState::default();
+
+    (0..num_samples)
+        .map(|idx| {
+            let position = idx % period;
+            let current = delay_line[position];
+            let next = delay_line[(position + 1) % period];
+            delay_line[position] = PLUCK_DAMPING * (current + next) / 2.0;
+            let tremolo = blend.tremolo.map_or(1.0, |lfo| lfo.tremolo_gain(idx));
+            let shaped = current * tremolo * blend.envelope.gain(idx, num_samples);
+            let value = match &blend.filter {
+                Some(filter) => filter_state.process(filter, shaped, idx as f64 / num_samples as f64),
+                None => shaped,
+            };
+            (value * AMPLITUDE) as i16
+        })
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::audio::blend::{Filter, Glide, Lfo};
+
+    #[test]
+    fn timbre_names() {
+        assert_eq!(Timbre::from_name("sawtooth"), Some(Timbre::Sawtooth));
+        assert_eq!(Timbre::from_name("fm"), Some(Timbre::Fm(Fm::bell())));
+        assert_eq!(Timbre::from_name("pulse"), None);
+        for name in ["sine", "triangle", "square", "sawtooth", "composite", "harmonics", "fm", "pluck", "noise"] {
+            assert_eq!(Timbre::from_name(name).map(|timbre| timbre.name()), Some(name));
+        }
+    }
 
     #[test]
     fn sample_count_100ms() {
-        assert_eq!(sine(440, 100).len(), 4410);
+        assert_eq!(sine(440, 100, Blend::none()).len(), 4410);
     }
 
     #[test]
     fn sample_count_300ms() {
-        assert_eq!(sine(440, 300).len(), 13230);
+        assert_eq!(sine(440, 300, Blend::none()).len(), 13230);
     }
 
     #[test]
     fn samples_within_amplitude_range() {
-        for &s in &sine(440, 100) {
-            assert!(s >= i16::MIN && s <= i16::MAX);
+        for &s in &sine(440, 100, Blend::none()) {
+            assert!((i16::MIN..=i16::MAX).contains(&s));
         }
     }
 
     #[test]
     fn sine_wave_starts_near_zero() {
-        assert!(sine(440, 100)[0].abs() < 100);
+        assert!(sine(440, 100, Blend::none())[0].abs() < 100);
     }
 
     #[test]
     fn different_frequencies_differ() {
-        assert_ne!(sine(440, 50), sine(880, 50));
+        assert_ne!(sine(440, 50, Blend::none()), sine(880, 50, Blend::none()));
     }
 
     #[test]
@@ -91,13 +390,13 @@ mod tests {
     #[test]
     fn triangle_within_amplitude_range() {
         for &s in &triangle(440, 100, Blend::none()) {
-            assert!(s >= i16::MIN && s <= i16::MAX);
+            assert!((i16::MIN..=i16::MAX).contains(&s));
         }
     }
 
     #[test]
     fn triangle_differs_from_sine() {
-        assert_ne!(sine(440, 100), triangle(440, 100, Blend::none()));
+        assert_ne!(sine(440, 100, Blend::none()), triangle(440, 100, Blend::none()));
     }
 
     #[test]
@@ -108,13 +407,13 @@ mod tests {
     #[test]
     fn square_within_amplitude_range() {
         for &s in &square(440, 100, Blend::none()) {
-            assert!(s >= i16::MIN && s <= i16::MAX);
+            assert!((i16::MIN..=i16::MAX).contains(&s));
         }
     }
 
     #[test]
     fn square_differs_from_sine() {
-        assert_ne!(sine(440, 100), square(440, 100, Blend::none()));
+        assert_ne!(sine(440, 100, Blend::none()), square(440, 100, Blend::none()));
     }
 
     #[test]
@@ -125,36 +424,223 @@ mod tests {
     #[test]
     fn sawtooth_within_amplitude_range() {
         for &s in &sawtooth(440, 100, Blend::none()) {
-            assert!(s >= i16::MIN && s <= i16::MAX);
+            assert!((i16::MIN..=i16::MAX).contains(&s));
         }
     }
 
     #[test]
     fn sawtooth_differs_from_sine() {
-        assert_ne!(sine(440, 100), sawtooth(440, 100, Blend::none()));
+        assert_ne!(sine(440, 100, Blend::none()), sawtooth(440, 100, Blend::none()));
+    }
+
+    #[test]
+    fn additive_presets_render_with_their_partials() {
+        for additive in [Additive::composite(), Additive::harmonics()] {
+            let note = render(&Timbre::Additive(additive.clone()), 440, 100, Blend::none());
+            assert_eq!(note.len(), 4410);
+            assert_eq!(note, generate(&additive, 440, 100, Blend::none()));
+        }
     }
 
     #[test]
-    fn composite_sample_count() {
-        assert_eq!(composite(440, 100, Blend::none()).len(), 4410);
+    fn custom_partials_are_named_additive() {
+        let organ = Timbre::Additive(Additive { partials: vec![(1.0, 1.0), (4.0, 0.3)] });
+        assert_eq!(organ.name(), "additive");
+        assert_eq!(Timbre::from_name("additive"), None);
     }
 
     #[test]
-    fn composite_within_amplitude_range() {
-        for &s in &composite(440, 100, Blend::none()) {
-            assert!(s >= i16::MIN && s <= i16::MAX);
+    fn notes_start_and_end_silent() {
+        for note in [
+            square(440, 100, Blend::none()),
+            sawtooth(440, 100, Blend::none()),
+            pluck(440, 100, Blend::none()),
+        ] {
+            assert_eq!(note[0], 0);
+            assert_eq!(*note.last().unwrap(), 0);
         }
     }
 
     #[test]
-    fn harmonics_sample_count() {
-        assert_eq!(harmonics(440, 100, Blend::none()).len(), 4410);
+    fn fade_keeps_full_level_mid_note() {
+        let note = square(440, 100, Blend::none());
+        assert_eq!(note[note.len() / 2].unsigned_abs(), i16::MAX as u16);
     }
 
     #[test]
-    fn harmonics_within_amplitude_range() {
-        for &s in &harmonics(440, 100, Blend::none()) {
-            assert!(s >= i16::MIN && s <= i16::MAX);
-        }
+    fn vibrato_keeps_length_but_changes_samples() {
+        let plain = sine(440, 300, Blend::none());
+        let wavering = sine(440, 300, Blend::none().with_vibrato(Lfo { rate_hz: 5.0, depth: 0.02 }));
+        assert_eq!(plain.len(), wavering.len());
+        assert_ne!(plain, wavering);
+    }
+
+    #[test]
+    fn tremolo_lowers_peak_mid_cycle() {
+        let throbbing = sine(440, 300, Blend::none().with_tremolo(Lfo { rate_hz: 5.0, depth: 0.9 }));
+        // 5 Hz tremolo bottoms out at 100ms (sample 4410)
+        let trough = throbbing[4300..4500].iter().map(|&s| s.abs()).max().unwrap();
+        assert!(trough < i16::MAX / 5, "trough peak {trough} should be heavily attenuated");
+    }
+
+    #[test]
+    fn glide_changes_note_start_only() {
+        let plain = sine(440, 300, Blend::none());
+        let sliding = sine(440, 300, Blend::none().with_glide(Glide { from_freq: 330, duration_ms: 50 }));
+        assert_eq!(plain.len(), sliding.len());
+        assert_ne!(plain[..2205], sliding[..2205]);
+    }
+
+    #[test]
+    fn render_matches_direct_generator() {
+        assert_eq!(render(&Timbre::Square, 440, 50, Blend::none()), square(440, 50, Blend::none()));
+    }
+
+    #[test]
+    fn filter_changes_output() {
+        let raw = square(440, 100, Blend::none());
+        let filtered = square(440, 100, Blend::none().with_filter(Filter::low_pass(800.0)));
+        assert_eq!(raw.len(), filtered.len());
+        assert_ne!(raw, filtered);
+    }
+
+    #[test]
+    fn fm_sample_count() {
+        assert_eq!(fm(440, 100, Fm::bell(), Blend::none()).len(), 4410);
+    }
+
+    #[test]
+    fn fm_differs_from_sine() {
+        assert_ne!(sine(440, 100, Blend::none()), fm(440, 100, Fm::bell(), Blend::none()));
+    }
+
+    #[test]
+    fn pluck_sample_count() {
+        assert_eq!(pluck(440, 100, Blend::none()).len(), 4410);
+    }
+
+    #[test]
+    fn pluck_renders_as_a_timbre() {
+        assert_eq!(render(&Timbre::Pluck, 440, 100, Blend::none()), pluck(440, 100, Blend::none()));
+        let dulled = pluck(440, 100, Blend::none().with_filter(Filter::low_pass(500.0)));
+        assert_ne!(dulled, pluck(440, 100, Blend::none()));
+    }
+
+    #[test]
+    fn pluck_decays_over_time() {
+        let string = pluck(220, 1000, Blend::none());
+        let energy = |chunk: &[i16]| chunk.iter().map(|&s| (s as i64).abs()).sum::<i64>();
+        let head = energy(&string[..4410]);
+        let tail = energy(&string[string.len() - 4410..]);
+        assert!(tail < head / 2, "tail {tail} should be quieter than head {head}");
+    }
+
+    #[test]
+    fn pluck_repeats_with_string_period() {
+        let string = pluck(441, 50, Blend::none());
+        // 44100 / 441 = 100-sample period: the waveform roughly repeats
+        let drift: i64 = (100..200).map(|idx| (string[idx] as i64 - string[idx + 100] as i64).abs()).sum();
+        let level: i64 = (100..200).map(|idx| (string[idx] as i64).abs()).sum();
+        assert!(drift < level, "period-to-period drift {drift} should stay below level {level}");
+    }
+
+    #[test]
+    fn noise_sample_count() {
+        assert_eq!(noise(440, 100, Blend::none()).len(), 4410);
+    }
+
+    #[test]
+    fn noise_renders_as_a_timbre() {
+        assert_eq!(render(&Timbre::Noise, 440, 100, Blend::none()), noise(440, 100, Blend::none()));
+    }
+
+    #[test]
+    fn noise_differs_from_sine() {
+        assert_ne!(sine(440, 100, Blend::none()), noise(440, 100, Blend::none()));
+    }
+
+    #[test]
+    fn noise_burst_sample_count() {
+        assert_eq!(noise_burst(40).len(), 1764);
+    }
+
+    #[test]
+    fn noise_burst_decays() {
+        let burst = noise_burst(40);
+        let energy = |chunk: &[i16]| chunk.iter().map(|&s| (s as i64).abs()).sum::<i64>();
+        let head = energy(&burst[..400]);
+        let tail = energy(&burst[burst.len() - 400..]);
+        assert!(tail < head / 10, "tail {tail} should be far quieter than head {head}");
+    }
+
+    #[test]
+    fn click_decays_quickly() {
+        let samples = click(20);
+        assert_eq!(samples.len(), 882);
+        let head: i32 = samples[..100].iter().map(|&s| (s as i32).abs()).max().unwrap();
+        let tail: i32 = samples[700..].iter().map(|&s| (s as i32).abs()).max().unwrap();
+        assert!(head > 10_000 && tail < head / 20, "head {head}, tail {tail}");
+    }
+
+    #[test]
+    fn kick_starts_silent_and_decays() {
+        let samples = kick(200);
+        assert_eq!(samples[0], 0);
+        let early: i32 = samples[..2000].iter().map(|&s| (s as i32).abs()).max().unwrap();
+        let late: i32 = samples[7000..].iter().map(|&s| (s as i32).abs()).max().unwrap();
+        assert!(early > late * 4, "early {early}, late {late}");
+    }
+
+    #[test]
+    fn rewind_is_soft_and_falls() {
+        let samples = rewind(200);
+        assert_eq!(samples.len(), 8820);
+        assert_eq!(samples[0], 0);
+        let peak = samples.iter().map(|&s| (s as i32).abs()).max().unwrap();
+        assert!(peak < (AMPLITUDE * REWIND_LEVEL) as i32 + 1, "peak {peak}");
+        let crossings = |range: &[i16]| range.windows(2).filter(|pair| (pair[0] < 0) != (pair[1] < 0)).count();
+        assert!(crossings(&samples[..2205]) > crossings(&samples[6615..8820]));
+    }
+
+    #[test]
+    fn jingle_plays_each_note_in_turn() {
+        let samples = jingle(&[440.0, 880.0], 100);
+        assert_eq!(samples.len(), 2 * 4410);
+        let peak = samples.iter().map(|&s| (s as i32).abs()).max().unwrap();
+        assert!(peak <= (AMPLITUDE * JINGLE_LEVEL) as i32, "peak {peak}");
+        // Each note starts over from silence
+        assert_eq!(samples[0], 0);
+        assert_eq!(samples[4410], 0);
+        assert!(jingle(&[], 100).is_empty());
+    }
+
+    #[test]
+    fn buzz_is_low_quiet_and_short() {
+        let samples = buzz(200);
+        assert_eq!(samples.len(), 8820);
+        let peak = samples.iter().map(|&s| (s as i32).abs()).max().unwrap();
+        assert!(peak <= (AMPLITUDE * BUZZ_LEVEL) as i32, "peak {peak}");
+        // 110Hz flips sign about 220 times a second
+        let flips = samples.windows(2).filter(|pair| (pair[0] > 0) != (pair[1] > 0)).count();
+        assert!((40..=48).contains(&flips), "flips {flips}");
+        let tail: i32 = samples[8000..].iter().map(|&s| (s as i32).abs()).max().unwrap();
+        assert!(tail < peak / 10, "peak {peak}, tail {tail}");
+    }
+
+    #[test]
+    fn chime_is_quiet_and_rings_out() {
+        let samples = chime(150);
+        assert_eq!(samples.len(), 6615);
+        let peak = samples.iter().map(|&s| (s as i32).abs()).max().unwrap();
+        assert!(peak <= (AMPLITUDE * CHIME_LEVEL) as i32, "peak {peak}");
+        let tail: i32 = samples[6000..].iter().map(|&s| (s as i32).abs()).max().unwrap();
+        assert!(tail < peak / 10, "peak {peak}, tail {tail}");
+    }
+
+    #[test]
+    fn kick_pitch_falls() {
+        let samples = kick(200);
+        let crossings = |range: &[i16]| range.windows(2).filter(|pair| (pair[0] < 0) != (pair[1] < 0)).count();
+        assert!(crossings(&samples[..2205]) > crossings(&samples[6615..8820]));
     }
 }
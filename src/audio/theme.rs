@@ -0,0 +1,571 @@
+//! Sound themes - a [`SoundMap`] and pitch settings loaded from TOML.
+//!
+//! A theme starts from a built-in (classic unless `base` names another) and
+//! overrides only what it mentions. Tables apply in file order, so put broad
+//! ones first:
+//!
+//! ```toml
+//! base = "classic"          # classic, 8-bit or ambient
+//!
+//! [pitch]
+//! key = "D"                 # tonic: C, F#, Bb, …
+//! scale = "minor"           # major, minor, pentatonic, blues, whole-tone
+//! tuning = "just"           # equal, just, pythagorean
+//! layout = "diatonic"       # diatonic, chromatic
+//! fold = 3                  # octaves to fold the ranks into
+//!
+//! [all]                     # every piece, every threat level
+//! attack_ms = 10
+//!
+//! [knight]                  # one piece, every threat level
+//! waveform = "square"       # sine, triangle, square, sawtooth, composite, harmonics, fm, pluck, noise
+//! sine_mix = 0.3            # 0.0–1.0
+//! harmonics = 5             # band-limit (0 = raw)
+//! cutoff_hz = 3000          # low-pass (0 = unfiltered)
+//! cutoff_end_hz = 800       # sweep the cutoff over the note
+//! filter = "high-pass"      # after cutoff_hz: low-pass (default) or high-pass
+//! release_ms = 40
+//! duration_ms = 250         # detached note length
+//!
+//! [king.check]              # one piece at one threat level (none, check, checkmate)
+//! waveform = "fm"
+//! fm_ratio = 3.5
+//! fm_index = 1.2
+//! vibrato_hz = 6.0          # also vibrato_depth, tremolo_hz, tremolo_depth
+//!
+//! [queen.none]
+//! partials = [[1, 1.0], [2, 0.5], [4.2, 0.2]]   # additive: [ratio, amplitude] pairs
+//! ```
+//!
+//! Waveforms can also be overridden without a file, using the same table
+//! names: `pawn=sine,knight=fm,king.check=square` (see [`apply_waveforms`]).
+
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::path::Path;
+
+use super::blend::{Filter, FilterKind, Lfo};
+use super::freq::{Key, Layout, PitchMap, Scale, Tuning};
+use super::sound_map::{PIECES, Sound, SoundMap, THREATS};
+use super::synth::Timbre;
+use super::waveform::{Additive, Fm};
+use crate::engine::chess::{Piece, Threat};
+use crate::toml::{self, Entry, ParseTomlError};
+
+pub const BUILTIN_NAMES: [&str; 3] = ["classic", "8-bit", "ambient"];
+
+/// Rate of a vibrato or tremolo a theme gives a depth but no rate.
+const DEFAULT_LFO_HZ: f64 = 5.0;
+
+#[derive(Debug)]
+pub enum LoadThemeError {
+    Io(std::io::Error),
+    Parse(ParseTomlError),
+    /// A `[table]` that is not pitch, all, a piece or piece.threat
+    UnknownTable { line: usize, table: String },
+    UnknownKey { line: usize, key: String },
+    InvalidValue { line: usize, key: String, expected: &'static str },
+}
+
+impl fmt::Display for LoadThemeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LoadThemeError::Io(err) => write!(f, "cannot read theme: {err}"),
+            LoadThemeError::Parse(err) => write!(f, "{err}"),
+            LoadThemeError::UnknownTable { line, table } => {
+                write!(f, "line {line}: unknown table [{table}] (expected pitch, all, a piece or piece.threat)")
+            }
+            LoadThemeError::UnknownKey { line, key } => write!(f, "line {line}: unknown key '{key}'"),
+            LoadThemeError::InvalidValue { line, key, expected } => {
+                write!(f, "line {line}: {key} must be {expected}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for LoadThemeError {}
+
+/// A bad `target=waveform` pair in a waveform override list.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseWaveformsError {
+    /// A pair without `=`
+    MissingWaveform(String),
+    /// Not all, a piece or piece.threat
+    UnknownTarget(String),
+    UnknownWaveform(String),
+}
+
+impl fmt::Display for ParseWaveformsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseWaveformsError::MissingWaveform(pair) => write!(f, "'{pair}' is not target=waveform"),
+            ParseWaveformsError::UnknownTarget(target) => {
+                write!(f, "unknown target '{target}' (expected all, a piece or piece.threat)")
+            }
+            ParseWaveformsError::UnknownWaveform(name) => write!(
+                f,
+                "unknown waveform '{name}' (expected sine, triangle, square, sawtooth, composite, harmonics, fm, pluck or noise)"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ParseWaveformsError {}
+
+impl From<std::io::Error> for LoadThemeError {
+    fn from(err: std::io::Error) -> Self {
+        LoadThemeError::Io(err)
+    }
+}
+
+impl From<ParseTomlError> for LoadThemeError {
+    fn from(err: ParseTomlError) -> Self {
+        LoadThemeError::Parse(err)
+    }
+}
+
+/// How every piece sounds and how squares map to pitches.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Theme {
+    pub sounds: SoundMap,
+    pub pitch: PitchMap,
+}
+
+impl Theme {
+    /// One of the [`BUILTIN_NAMES`], or None.
+    pub fn from_name(name: &str) -> Option<Theme> {
+        let source = match name {
+            "classic" => return Some(Theme::default()),
+            "8-bit" => include_str!("../../themes/8-bit.toml"),
+            "ambient" => include_str!("../../themes/ambient.toml"),
+            _ => return None,
+        };
+        Theme::parse(source).ok()
+    }
+
+    /// One of the [`BUILTIN_NAMES`], otherwise the TOML file at `name`.
+    pub fn from_name_or_file(name: &str) -> Result<Theme, LoadThemeError> {
+        match Theme::from_name(name) {
+            Some(theme) => Ok(theme),
+            None => Theme::load(Path::new(name)),
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Theme, LoadThemeError> {
+        Theme::parse(&std::fs::read_to_string(path)?)
+    }
+
+    pub fn parse(text: &str) -> Result<Theme, LoadThemeError> {
+        let entries = toml::parse(text)?;
+        let mut theme = match entries.iter().find(|entry| entry.table.is_empty() && entry.key == "base") {
+            Some(entry) => entry
+                .value
+                .as_str()
+                .and_then(Theme::from_name)
+                .ok_or_else(|| invalid(entry, "\"classic\", \"8-bit\" or \"ambient\""))?,
+            None => Theme::default(),
+        };
+        let mut key = KeyParts::default();
+        for entry in &entries {
+            match entry.table.as_str() {
+                "" if entry.key == "base" => {}
+                "" => return Err(unknown_key(entry)),
+                "pitch" => apply_pitch(&mut theme.pitch, &mut key, entry)?,
+                table => {
+                    let targets = targets(table).ok_or_else(|| LoadThemeError::UnknownTable {
+                        line: entry.line,
+                        table: table.to_string(),
+                    })?;
+                    for (piece, threat) in targets {
+                        let mut sound = theme.sounds.get(piece, threat).clone();
+                        apply_sound(&mut sound, entry)?;
+                        theme.sounds.set(piece, threat, sound);
+                    }
+                }
+            }
+        }
+        if let Some(built) = key.build()? {
+            theme.pitch.key = Some(built);
+        }
+        Ok(theme)
+    }
+}
+
+/// `key` and `scale` may come in either order (or alone), so the key is
+/// assembled once the whole `[pitch]` table has been read.
+#[derive(Default)]
+struct KeyParts<'a> {
+    tonic: Option<&'a Entry>,
+    scale: Option<Scale>,
+}
+
+impl KeyParts<'_> {
+    fn build(&self) -> Result<Option<Key>, LoadThemeError> {
+        if self.tonic.is_none() && self.scale.is_none() {
+            return Ok(None);
+        }
+        let scale = self.scale.unwrap_or(Scale::Major);
+        match self.tonic {
+            Some(entry) => entry
+                .value
+                .as_str()
+                .and_then(|tonic| Key::from_tonic_name(tonic, scale))
+                .map(Some)
+                .ok_or_else(|| invalid(entry, "a note name, e.g. \"C\", \"F#\" or \"Bb\"")),
+            None => Ok(Key::from_tonic_name("C", scale)),
+        }
+    }
+}
+
+fn apply_pitch<'a>(pitch: &mut PitchMap, key: &mut KeyParts<'a>, entry: &'a Entry) -> Result<(), LoadThemeError> {
+    match entry.key.as_str() {
+        "key" => key.tonic = Some(entry),
+        "scale" => {
+            let scale = entry.value.as_str().and_then(Scale::from_name);
+            key.scale = Some(scale.ok_or_else(|| invalid(entry, "major, minor, pentatonic, blues or whole-tone"))?);
+        }
+        "tuning" => {
+            let tuning = entry.value.as_str().and_then(Tuning::from_name);
+            pitch.tuning = tuning.ok_or_else(|| invalid(entry, "equal, just or pythagorean"))?;
+        }
+        "layout" => {
+            let layout = entry.value.as_str().and_then(Layout::from_name);
+            pitch.layout = layout.ok_or_else(|| invalid(entry, "diatonic or chromatic"))?;
+        }
+        "fold" => pitch.fold_octaves = Some(whole(entry)?).filter(|&octaves| octaves > 0),
+        _ => return Err(unknown_key(entry)),
+    }
+    Ok(())
+}
+
+fn apply_sound(sound: &mut Sound, entry: &Entry) -> Result<(), LoadThemeError> {
+    let blend = &mut sound.blend;
+    match entry.key.as_str() {
+        "waveform" => {
+            let timbre = entry.value.as_str().and_then(Timbre::from_name);
+            sound.timbre = timbre
+                .ok_or_else(|| invalid(entry, "sine, triangle, square, sawtooth, composite, harmonics, fm, pluck or noise"))?;
+        }
+        "fm_ratio" | "fm_index" => {
+            let mut operator = if let Timbre::Fm(operator) = sound.timbre { operator } else { Fm::bell() };
+            if entry.key == "fm_ratio" {
+                operator.ratio = number(entry, f64::MIN_POSITIVE..=f64::MAX, "a positive number")?;
+            } else {
+                operator.index = number(entry, 0.0..=f64::MAX, "zero or more")?;
+            }
+            sound.timbre = Timbre::Fm(operator);
+        }
+        "partials" => sound.timbre = Timbre::Additive(partials(entry)?),
+        "sine_mix" => blend.sine_mix = number(entry, 0.0..=1.0, "between 0.0 and 1.0")?,
+        "harmonics" => blend.harmonics = Some(whole(entry)?).filter(|&harmonics| harmonics > 0),
+        "cutoff_hz" => {
+            let cutoff_hz = number(entry, 0.0..=f64::MAX, "zero or more")?;
+            // A new cutoff keeps the kind, so [all] can set high-pass for every piece
+            let filter = match blend.filter.map_or(FilterKind::LowPass, |filter| filter.kind) {
+                FilterKind::LowPass => Filter::low_pass(cutoff_hz),
+                FilterKind::HighPass => Filter::high_pass(cutoff_hz),
+            };
+            blend.filter = (cutoff_hz > 0.0).then_some(filter);
+        }
+        "filter" => {
+            let filter = blend.filter.ok_or_else(|| invalid(entry, "set after a cutoff_hz"))?;
+            let kind = match entry.value.as_str() {
+                Some("low-pass") => FilterKind::LowPass,
+                Some("high-pass") => FilterKind::HighPass,
+                _ => return Err(invalid(entry, "\"low-pass\" or \"high-pass\"")),
+            };
+            blend.filter = Some(Filter { kind, ..filter });
+        }
+        "cutoff_end_hz" => {
+            let filter = blend.filter.ok_or_else(|| invalid(entry, "set after a cutoff_hz"))?;
+            blend.filter = Some(filter.sweep_to(number(entry, f64::MIN_POSITIVE..=f64::MAX, "a positive number")?));
+        }
+        "vibrato_hz" | "vibrato_depth" => blend.vibrato = with_lfo(blend.vibrato, entry, 0.0..=f64::MAX)?,
+        "tremolo_hz" | "tremolo_depth" => blend.tremolo = with_lfo(blend.tremolo, entry, 0.0..=1.0)?,
+        "attack_ms" => blend.envelope.attack_ms = whole(entry)?,
+        "release_ms" => blend.envelope.release_ms = whole(entry)?,
+        "duration_ms" => sound.duration_ms = whole(entry)?,
+        _ => return Err(unknown_key(entry)),
+    }
+    Ok(())
+}
+
+/// An additive timbre from `[[ratio, amplitude], …]`: each partial's
+/// frequency as a multiple of the note's, and its weight.
+fn partials(entry: &Entry) -> Result<Additive, LoadThemeError> {
+    let expected = "a list of [ratio, amplitude] pairs with positive ratios";
+    let partial = |item: &toml::Value| match item.as_array()? {
+        [ratio, amplitude] => Some((ratio.as_f64().filter(|&ratio| ratio > 0.0)?, amplitude.as_f64()?)),
+        _ => None,
+    };
+    let items = entry.value.as_array().filter(|items| !items.is_empty()).ok_or_else(|| invalid(entry, expected))?;
+    let partials = items.iter().map(partial).collect::<Option<Vec<_>>>().ok_or_else(|| invalid(entry, expected))?;
+    Ok(Additive { partials })
+}
+
+/// Applies comma-separated `target=waveform` pairs to `sounds`, in order, so
+/// `all=square,pawn=sine` makes everything square but the pawns. Targets are
+/// theme table names: `all`, a piece, or `piece.threat`.
+pub fn apply_waveforms(sounds: &mut SoundMap, overrides: &str) -> Result<(), ParseWaveformsError> {
+    for pair in overrides.split(',').map(str::trim).filter(|pair| !pair.is_empty()) {
+        let (target, name) = pair
+            .split_once('=')
+            .ok_or_else(|| ParseWaveformsError::MissingWaveform(pair.to_string()))?;
+        let (target, name) = (target.trim(), name.trim());
+        let pairs = targets(target).ok_or_else(|| ParseWaveformsError::UnknownTarget(target.to_string()))?;
+        let timbre = Timbre::from_name(name).ok_or_else(|| ParseWaveformsError::UnknownWaveform(name.to_string()))?;
+        for (piece, threat) in pairs {
+            let sound = Sound {
+                timbre: timbre.clone(),
+                ..sounds.get(piece, threat).clone()
+            };
+            sounds.set(piece, threat, sound);
+        }
+    }
+    Ok(())
+}
+
+/// Sets the rate (`*_hz`) or depth (`*_depth`) of an LFO; a zero depth
+/// turns it off.
+fn with_lfo(lfo: Option<Lfo>, entry: &Entry, depths: RangeInclusive<f64>) -> Result<Option<Lfo>, LoadThemeError> {
+    let mut lfo = lfo.unwrap_or(Lfo { rate_hz: DEFAULT_LFO_HZ, depth: 0.0 });
+    if entry.key.ends_with("_hz") {
+        lfo.rate_hz = number(entry, f64::MIN_POSITIVE..=f64::MAX, "a positive number")?;
+    } else {
+        let expected = if *depths.end() == 1.0 { "between 0.0 and 1.0" } else { "zero or more" };
+        lfo.depth = number(entry, depths, expected)?;
+    }
+    Ok((lfo.depth > 0.0).then_some(lfo))
+}
+
+/// The (piece, threat) pairs a table such as `all`, `rook` or `king.check`
+/// applies to.
+fn targets(table: &str) -> Option<Vec<(Piece, Threat)>> {
+    let (piece_name, threat_name) = match table.split_once('.') {
+        Some((piece_name, threat_name)) => (piece_name, Some(threat_name)),
+        None => (table, None),
+    };
+    let pieces = match piece_name {
+        "all" => PIECES.to_vec(),
+        name => vec![piece_from_name(name)?],
+    };
+    let threats = match threat_name {
+        Some(name) => vec![threat_from_name(name)?],
+        None => THREATS.to_vec(),
+    };
+    Some(pieces.into_iter().flat_map(|piece| threats.iter().map(move |&threat| (piece, threat))).collect())
+}
+
+fn piece_from_name(name: &str) -> Option<Piece> {
+    match name {
+        "pawn" => Some(Piece::Pawn),
+        "knight" => Some(Piece::Knight),
+        "bishop" => Some(Piece::Bishop),
+        "rook" => Some(Piece::Rook),
+        "queen" => Some(Piece::Queen),
+        "king" => Some(Piece::King),
+        _ => None,
+    }
+}
+
+fn threat_from_name(name: &str) -> Option<Threat> {
+    match name {
+        "none" => Some(Threat::None),
+        "check" => Some(Threat::Check),
+        "checkmate" => Some(Threat::Checkmate),
+        _ => None,
+    }
+}
+
+fn number(entry: &Entry, range: RangeInclusive<f64>, expected: &'static str) -> Result<f64, LoadThemeError> {
+    entry
+        .value
+        .as_f64()
+        .filter(|value| range.contains(value))
+        .ok_or_else(|| invalid(entry, expected))
+}
+
+fn whole(entry: &Entry) -> Result<u32, LoadThemeError> {
+    entry.value.as_u32().ok_or_else(|| invalid(entry, "a whole number"))
+}
+
+fn invalid(entry: &Entry, expected: &'static str) -> LoadThemeError {
+    LoadThemeError::InvalidValue {
+        line: entry.line,
+        key: entry.key.clone(),
+        expected,
+    }
+}
+
+fn unknown_key(entry: &Entry) -> LoadThemeError {
+    LoadThemeError::UnknownKey {
+        line: entry.line,
+        key: entry.key.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn waveform_overrides_apply_in_order() {
+        let mut sounds = SoundMap::default();
+        apply_waveforms(&mut sounds, "all=square, pawn=sine,king.check=fm,bishop=pluck").unwrap();
+        assert_eq!(sounds.get(Piece::Rook, Threat::Checkmate).timbre, Timbre::Square);
+        assert_eq!(sounds.get(Piece::Pawn, Threat::None).timbre, Timbre::Sine);
+        assert_eq!(sounds.get(Piece::King, Threat::Check).timbre, Timbre::Fm(Fm::bell()));
+        assert_eq!(sounds.get(Piece::King, Threat::None).timbre, Timbre::Square);
+        assert_eq!(sounds.get(Piece::Bishop, Threat::Check).timbre, Timbre::Pluck);
+        let classic = SoundMap::default();
+        assert_eq!(sounds.get(Piece::Pawn, Threat::None).blend, classic.get(Piece::Pawn, Threat::None).blend);
+    }
+
+    #[test]
+    fn bad_waveform_overrides_are_reported() {
+        let mut sounds = SoundMap::default();
+        assert_eq!(
+            apply_waveforms(&mut sounds, "pawn"),
+            Err(ParseWaveformsError::MissingWaveform("pawn".to_string()))
+        );
+        assert_eq!(
+            apply_waveforms(&mut sounds, "horse=sine"),
+            Err(ParseWaveformsError::UnknownTarget("horse".to_string()))
+        );
+        assert_eq!(
+            apply_waveforms(&mut sounds, "pawn=kazoo"),
+            Err(ParseWaveformsError::UnknownWaveform("kazoo".to_string()))
+        );
+    }
+
+    #[test]
+    fn empty_theme_is_classic() {
+        assert_eq!(Theme::parse("# nothing here\n").unwrap(), Theme::default());
+    }
+
+    #[test]
+    fn builtins_parse() {
+        for name in BUILTIN_NAMES {
+            assert!(Theme::from_name(name).is_some(), "{name}");
+        }
+        assert_ne!(Theme::from_name("8-bit"), Theme::from_name("classic"));
+        assert_ne!(Theme::from_name("ambient"), Theme::from_name("classic"));
+        assert_eq!(Theme::from_name("jazz"), None);
+    }
+
+    #[test]
+    fn names_come_before_files() {
+        assert_eq!(Theme::from_name_or_file("8-bit").unwrap(), Theme::from_name("8-bit").unwrap());
+        assert!(matches!(Theme::from_name_or_file("no-such-theme.toml"), Err(LoadThemeError::Io(_))));
+    }
+
+    #[test]
+    fn piece_table_covers_every_threat() {
+        let theme = Theme::parse("[rook]\nwaveform = \"sine\"\nduration_ms = 120").unwrap();
+        for threat in THREATS {
+            assert_eq!(theme.sounds.get(Piece::Rook, threat).timbre, Timbre::Sine);
+            assert_eq!(theme.sounds.get(Piece::Rook, threat).duration_ms, 120);
+        }
+        assert_eq!(theme.sounds.get(Piece::Knight, Threat::None), SoundMap::default().get(Piece::Knight, Threat::None));
+    }
+
+    #[test]
+    fn noise_is_a_piece_waveform() {
+        let theme = Theme::parse("[queen.none]\nwaveform = \"noise\"\nsine_mix = 0.5").unwrap();
+        let sound = theme.sounds.get(Piece::Queen, Threat::None);
+        assert_eq!(sound.timbre, Timbre::Noise);
+        assert_eq!(sound.blend.sine_mix, 0.5);
+    }
+
+    #[test]
+    fn later_tables_refine_earlier_ones() {
+        let theme = Theme::parse("[all]\nattack_ms = 50\n[king.checkmate]\nattack_ms = 5").unwrap();
+        assert_eq!(theme.sounds.get(Piece::Pawn, Threat::Check).blend.envelope.attack_ms, 50);
+        assert_eq!(theme.sounds.get(Piece::King, Threat::Checkmate).blend.envelope.attack_ms, 5);
+    }
+
+    #[test]
+    fn sound_keys_shape_the_blend() {
+        let text = "[pawn.none]\nsine_mix = 0.25\nharmonics = 4\ncutoff_hz = 2000\ncutoff_end_hz = 500\ntremolo_depth = 0.5";
+        let blend = Theme::parse(text).unwrap().sounds.get(Piece::Pawn, Threat::None).blend;
+        assert_eq!(blend.sine_mix, 0.25);
+        assert_eq!(blend.harmonics, Some(4));
+        assert_eq!(blend.filter, Some(Filter::low_pass(2000.0).sweep_to(500.0)));
+        assert_eq!(blend.tremolo, Some(Lfo { rate_hz: DEFAULT_LFO_HZ, depth: 0.5 }));
+    }
+
+    #[test]
+    fn filter_key_picks_a_high_pass() {
+        let text = "[all]\ncutoff_hz = 400\nfilter = \"high-pass\"\n[bishop]\ncutoff_hz = 900\ncutoff_end_hz = 300";
+        let sounds = Theme::parse(text).unwrap().sounds;
+        assert_eq!(sounds.get(Piece::Pawn, Threat::None).blend.filter, Some(Filter::high_pass(400.0)));
+        assert_eq!(sounds.get(Piece::Bishop, Threat::Check).blend.filter, Some(Filter::high_pass(900.0).sweep_to(300.0)));
+        assert!(matches!(Theme::parse("[pawn]\nfilter = \"high-pass\""), Err(LoadThemeError::InvalidValue { .. })));
+        assert!(matches!(
+            Theme::parse("[pawn]\ncutoff_hz = 400\nfilter = \"band-pass\""),
+            Err(LoadThemeError::InvalidValue { .. })
+        ));
+    }
+
+    #[test]
+    fn zero_removes_filter_band_limit_and_modulation() {
+        let text = "[rook.check]\nharmonics = 0\ncutoff_hz = 0\nvibrato_depth = 0";
+        let blend = Theme::parse(text).unwrap().sounds.get(Piece::Rook, Threat::Check).blend;
+        assert_eq!(blend.harmonics, None);
+        assert_eq!(blend.filter, None);
+        assert_eq!(blend.vibrato, None);
+    }
+
+    #[test]
+    fn fm_keys_tune_the_operator() {
+        let theme = Theme::parse("[queen]\nfm_ratio = 2.0").unwrap();
+        assert_eq!(
+            theme.sounds.get(Piece::Queen, Threat::None).timbre,
+            Timbre::Fm(Fm { ratio: 2.0, ..Fm::bell() })
+        );
+    }
+
+    #[test]
+    fn partials_define_an_additive_timbre() {
+        let theme = Theme::parse("[queen.none]\npartials = [[1, 1.0], [2.5, 0.5]]").unwrap();
+        assert_eq!(
+            theme.sounds.get(Piece::Queen, Threat::None).timbre,
+            Timbre::Additive(Additive { partials: vec![(1.0, 1.0), (2.5, 0.5)] })
+        );
+        for bad in ["[]", "[[1, 1.0], [0, 0.5]]", "[[1]]", "[1, 2]", "\"organ\""] {
+            assert!(
+                matches!(Theme::parse(&format!("[queen]\npartials = {bad}")), Err(LoadThemeError::InvalidValue { .. })),
+                "{bad}"
+            );
+        }
+    }
+
+    #[test]
+    fn pitch_table_sets_the_pitch_map() {
+        let theme = Theme::parse("[pitch]\nscale = \"minor\"\nkey = \"D\"\ntuning = \"just\"\nfold = 3").unwrap();
+        assert_eq!(theme.pitch.key, Key::from_tonic_name("D", Scale::NaturalMinor));
+        assert_eq!(theme.pitch.tuning, Tuning::Just);
+        assert_eq!(theme.pitch.fold_octaves, Some(3));
+    }
+
+    #[test]
+    fn base_starts_from_another_builtin() {
+        let theme = Theme::parse("base = \"8-bit\"\n[pawn]\nduration_ms = 90").unwrap();
+        let eight_bit = Theme::from_name("8-bit").unwrap();
+        assert_eq!(theme.pitch, eight_bit.pitch);
+        assert_eq!(theme.sounds.get(Piece::Pawn, Threat::None).timbre, eight_bit.sounds.get(Piece::Pawn, Threat::None).timbre);
+    }
+
+    #[test]
+    fn reports_unknown_and_invalid_entries() {
+        assert!(matches!(Theme::parse("[castle]\nx = 1"), Err(LoadThemeError::UnknownTable { line: 2, .. })));
+        assert!(matches!(Theme::parse("[pawn.stalemate]\nx = 1"), Err(LoadThemeError::UnknownTable { .. })));
+        assert!(matches!(Theme::parse("[pawn]\nvolume = 1"), Err(LoadThemeError::UnknownKey { line: 2, .. })));
+        assert!(matches!(Theme::parse("[pawn]\nwaveform = \"pulse\""), Err(LoadThemeError::InvalidValue { .. })));
+        assert!(matches!(Theme::parse("[pawn]\nsine_mix = 1.5"), Err(LoadThemeError::InvalidValue { .. })));
+        assert!(matches!(Theme::parse("[pawn]\ncutoff_end_hz = 500"), Err(LoadThemeError::InvalidValue { .. })));
+        assert!(matches!(Theme::parse("[pitch]\nkey = \"H\""), Err(LoadThemeError::InvalidValue { .. })));
+        assert!(matches!(Theme::parse("base = \"jazz\""), Err(LoadThemeError::InvalidValue { .. })));
+        assert!(matches!(Theme::parse("[pawn"), Err(LoadThemeError::Parse(_))));
+    }
+}
@@ -0,0 +1,114 @@
+//! Per-move trace for `--verbose`: how each token was read, where the board
+//! put the move and what it sounds like.
+//!
+//! ```text
+//! 1. e4       e2-e4  G4    392 Hz  sine
+//! 1... d5     d7-d5  F5    698 Hz  sine
+//! 2. Bd5      ??-d5  F5    698 Hz  sawtooth  (not found on the board)
+//! skipped "1-0"  (not a move)
+//! ```
+//!
+//! An unresolved origin still sounds, but the board falls out of step with
+//! the game from there, so later captures may be missed.
+
+use std::fmt;
+
+use super::{AudioConfig, Phrasing, midi, mover, sounding_freq, sounding_piece};
+use crate::engine::board::Color;
+use crate::engine::chess::{NotationMove, Square};
+
+/// What happened to one input token.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MoveTrace {
+    /// Not a move; makes no sound
+    Skipped { token: String },
+    Played {
+        /// PGN-style, "12. Nxe5" for White, "12... Nc6" for Black
+        label: String,
+        /// None when no piece on the board can make the move
+        origin: Option<Square>,
+        dest: Square,
+        freq_hz: u32,
+        waveform: &'static str,
+    },
+}
+
+/// Traces every token of `input` as rendered with `config`.
+pub fn trace(input: &str, config: &AudioConfig) -> Vec<MoveTrace> {
+    let mut board = config.start.board.clone();
+    input
+        .split_whitespace()
+        .enumerate()
+        .map(|(idx, notation)| (config.start.ply + idx, notation))
+        .map(|(move_index, notation)| {
+            let Some(m) = NotationMove::parse(notation, move_index) else {
+                return MoveTrace::Skipped { token: notation.to_string() };
+            };
+            let color = mover(move_index);
+            let resolved =
+                board.resolve_move(&m, notation, color).ok().filter(|resolved| board.apply_move(resolved).is_ok());
+            let phrasing = Phrasing {
+                pitch: config.pitch,
+                side: config.color_voicing.map(|voicing| voicing.for_color(color)).unwrap_or_default(),
+                ..Phrasing::plain()
+            };
+            let dots = match color {
+                Color::White => ".",
+                Color::Black => "...",
+            };
+            MoveTrace::Played {
+                label: format!("{}{} {}", move_index / 2 + 1, dots, notation),
+                origin: resolved.map(|resolved| resolved.origin),
+                dest: m.dest,
+                freq_hz: sounding_freq(&m, phrasing),
+                waveform: config.sounds.get(sounding_piece(&m), m.threat).timbre.name(),
+            }
+        })
+        .collect()
+}
+
+impl fmt::Display for MoveTrace {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MoveTrace::Skipped { token } => write!(f, "skipped \"{}\"  (not a move)", token),
+            MoveTrace::Played { label, origin, dest, freq_hz, waveform } => {
+                let origin_name = origin.map_or_else(|| "??".to_string(), |origin| origin.name());
+                let note = midi::key_name(midi::key_for_freq(*freq_hz));
+                write!(f, "{:<11} {}-{}  {:<4} {:>4} Hz  {}", label, origin_name, dest.name(), note, freq_hz, waveform)?;
+                if origin.is_none() {
+                    write!(f, "  (not found on the board)")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::chess::{Piece, Threat};
+
+    #[test]
+    fn traces_origin_pitch_and_waveform() {
+        let config = AudioConfig::default();
+        let lines = trace("e4 Nf6", &config);
+        let MoveTrace::Played { label, origin, dest, waveform, .. } = &lines[1] else {
+            panic!("expected a played move, got {:?}", lines[1]);
+        };
+        assert_eq!(label, "1... Nf6");
+        assert_eq!(*origin, Square::from_name("g8"));
+        assert_eq!(dest.name(), "f6");
+        assert_eq!(*waveform, config.sounds.get(Piece::Knight, Threat::None).timbre.name());
+    }
+
+    #[test]
+    fn reports_skipped_tokens_and_unresolved_moves() {
+        let lines = trace("1. Bb5", &AudioConfig::default());
+        assert_eq!(lines[0], MoveTrace::Skipped { token: "1.".to_string() });
+        assert_eq!(lines[0].to_string(), "skipped \"1.\"  (not a move)");
+        assert!(matches!(lines[1], MoveTrace::Played { origin: None, .. }));
+        assert!(lines[1].to_string().contains("??-b5"));
+        assert!(lines[1].to_string().ends_with("(not found on the board)"));
+    }
+}
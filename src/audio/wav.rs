@@ -1,4 +1,4 @@
-//! WAV file format encoder.
+//! WAV file format encoder and decoder.
 //!
 //! # RIFF/WAVE Structure (44-byte header)
 //!
@@ -22,83 +22,762 @@
 //! 40      4     Data size
 //! 44      ...   Sample data (little-endian)
 //! ```
+//!
+//! Metadata chunks go between `fmt ` and `data`, shifting the data past
+//! offset 44 (see [`header_with_chunks`]):
+//!
+//! ```text
+//! LIST/INFO   game tags: title, players, date, result
+//! cue         one point per move, labelled by LIST/adtl ("12. Nxe5")
+//! bext        Broadcast WAV origination date, time and description
+//! ```
+//!
+//! Files from other tools may carry such chunks too, so [`decode`] walks the
+//! chunk list instead of assuming fixed offsets; [`read`] does the same for
+//! a file on disk.
+//!
+//! [`WavWriter`] streams samples out as they are rendered instead of
+//! building the whole file in memory first.
 
-use super::{BITS_PER_SAMPLE, NUM_CHANNELS, SAMPLE_RATE};
+use std::fmt;
+use std::io::{self, Seek, SeekFrom, Write};
+use std::path::Path;
 
+use super::{BITS_PER_SAMPLE, SAMPLE_RATE};
+
+const FORMAT_PCM: u16 = 1;
+const FORMAT_EXTENSIBLE: u16 = 0xFFFE;
+/// KSDATAFORMAT_SUBTYPE_PCM, the extensible format's sub-format GUID.
+const SUBTYPE_PCM: [u8; 16] = [
+    0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x80, 0x00, 0x00, 0xAA, 0x00, 0x38, 0x9B, 0x71,
+];
+/// Beyond stereo the plain PCM header leaves speaker placement undefined,
+/// and strict readers require WAVE_FORMAT_EXTENSIBLE.
+const MAX_PLAIN_CHANNELS: u16 = 2;
+
+/// Size of a header with no chunks besides `fmt ` and `data`.
 pub const HEADER_SIZE: usize = 44;
+/// RIFF chunk id plus size field.
+const CHUNK_HEADER_SIZE: usize = 8;
+/// Fixed part of a version 1 `bext` chunk body.
+const BEXT_SIZE: usize = 602;
+const SECONDS_PER_DAY: u64 = 86_400;
+
+/// A labelled marker at a frame of the data, e.g. where a move starts.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cue {
+    pub frame: u32,
+    pub label: String,
+}
+
+/// Broadcast WAV (EBU Tech 3285) origination metadata for the `bext` chunk.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Broadcast {
+    /// Free text, at most 256 ASCII characters
+    pub description: String,
+    /// Who made the file, at most 32 characters
+    pub originator: String,
+    /// UTC seconds since the Unix epoch
+    pub origination_time: u64,
+}
 
-/// Generates a 44-byte WAV header for the given number of samples.
-pub fn header(num_samples: u32) -> [u8; HEADER_SIZE] {
-    let block_align = NUM_CHANNELS * (BITS_PER_SAMPLE / 8);
-    let byte_rate = SAMPLE_RATE * block_align as u32;
-    let data_size = num_samples * block_align as u32;
-
-    let mut h = [0u8; HEADER_SIZE];
-
-    // RIFF chunk
-    h[0..4].copy_from_slice(b"RIFF");
-    h[4..8].copy_from_slice(&(36 + data_size).to_le_bytes());
-    h[8..12].copy_from_slice(b"WAVE");
-
-    // fmt subchunk
-    h[12..16].copy_from_slice(b"fmt ");
-    h[16..20].copy_from_slice(&16u32.to_le_bytes());
-    h[20..22].copy_from_slice(&1u16.to_le_bytes()); // PCM
-    h[22..24].copy_from_slice(&NUM_CHANNELS.to_le_bytes());
-    h[24..28].copy_from_slice(&SAMPLE_RATE.to_le_bytes());
-    h[28..32].copy_from_slice(&byte_rate.to_le_bytes());
-    h[32..34].copy_from_slice(&block_align.to_le_bytes());
-    h[34..36].copy_from_slice(&BITS_PER_SAMPLE.to_le_bytes());
-
-    // data subchunk
-    h[36..40].copy_from_slice(b"data");
-    h[40..44].copy_from_slice(&data_size.to_le_bytes());
+/// A RIFF sub-chunk: a four-character id and its body.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chunk {
+    pub id: [u8; 4],
+    pub body: Vec<u8>,
+}
+
+impl Chunk {
+    pub fn new(id: &[u8; 4], body: Vec<u8>) -> Self {
+        Self { id: *id, body }
+    }
+
+    /// A `LIST` chunk of type `INFO` from (tag, text) pairs such as
+    /// `(b"INAM", "Title")`. Empty texts are left out.
+    pub fn info(tags: &[(&[u8; 4], &str)]) -> Self {
+        let mut body = b"INFO".to_vec();
+        for (id, text) in tags.iter().filter(|(_, text)| !text.is_empty()) {
+            // INFO strings are NUL-terminated
+            Chunk::new(id, [text.as_bytes(), b"\0"].concat()).write_to(&mut body);
+        }
+        Chunk::new(b"LIST", body)
+    }
+
+    /// A `cue ` chunk with one point per [`Cue`], numbered from 1.
+    pub fn cue(cues: &[Cue]) -> Self {
+        let mut body = (cues.len() as u32).to_le_bytes().to_vec();
+        for (id, cue) in (1u32..).zip(cues) {
+            body.extend_from_slice(&id.to_le_bytes());
+            body.extend_from_slice(&cue.frame.to_le_bytes()); // play order position
+            body.extend_from_slice(b"data");
+            body.extend_from_slice(&0u32.to_le_bytes()); // chunk start
+            body.extend_from_slice(&0u32.to_le_bytes()); // block start
+            body.extend_from_slice(&cue.frame.to_le_bytes()); // sample offset
+        }
+        Chunk::new(b"cue ", body)
+    }
+
+    /// A `LIST` chunk of type `adtl` naming each point of [`Chunk::cue`]
+    /// with a `labl` sub-chunk.
+    pub fn cue_labels(cues: &[Cue]) -> Self {
+        let mut body = b"adtl".to_vec();
+        for (id, cue) in (1u32..).zip(cues) {
+            let text = [&id.to_le_bytes(), cue.label.as_bytes(), b"\0"].concat();
+            Chunk::new(b"labl", text).write_to(&mut body);
+        }
+        Chunk::new(b"LIST", body)
+    }
+
+    /// A version 1 `bext` chunk. Text fields are fixed-width ASCII, cut to
+    /// size and NUL-padded; the UMID, time reference and coding history are
+    /// left empty.
+    pub fn bext(broadcast: &Broadcast) -> Self {
+        let (date, time) = utc_date_time(broadcast.origination_time);
+        let mut body = Vec::with_capacity(BEXT_SIZE);
+        push_fixed(&mut body, &broadcast.description, 256);
+        push_fixed(&mut body, &broadcast.originator, 32);
+        push_fixed(&mut body, "", 32); // originator reference
+        push_fixed(&mut body, &date, 10);
+        push_fixed(&mut body, &time, 8);
+        body.extend_from_slice(&0u64.to_le_bytes()); // time reference, in samples since midnight
+        body.extend_from_slice(&1u16.to_le_bytes()); // version
+        body.resize(BEXT_SIZE, 0); // UMID and reserved
+        Chunk::new(b"bext", body)
+    }
 
+    /// Bytes this chunk takes in a file, padding included.
+    fn size(&self) -> usize {
+        CHUNK_HEADER_SIZE + self.body.len() + self.body.len() % 2
+    }
+
+    /// Appends id, size and body, padded to an even length as RIFF requires.
+    fn write_to(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.id);
+        out.extend_from_slice(&(self.body.len() as u32).to_le_bytes());
+        out.extend_from_slice(&self.body);
+        if self.body.len() % 2 == 1 {
+            out.push(0);
+        }
+    }
+}
+
+/// Generates a WAV header for the given number of sample frames: 44 bytes
+/// for mono and stereo, 68 with the extensible `fmt ` of more channels.
+///
+/// A frame holds one sample per channel, so `num_frames` is the length of
+/// the interleaved sample buffer divided by `num_channels`.
+pub fn header(num_frames: u32, num_channels: u16) -> Vec<u8> {
+    header_with_chunks(num_frames, num_channels, &[])
+}
+
+/// Like [`header`], with `chunks` (metadata such as [`Chunk::info`]) placed
+/// between `fmt ` and `data`.
+pub fn header_with_chunks(num_frames: u32, num_channels: u16, chunks: &[Chunk]) -> Vec<u8> {
+    header_with_rate(num_frames, num_channels, SAMPLE_RATE, chunks)
+}
+
+/// Like [`header_with_chunks`], for audio at `sample_rate` Hz instead of
+/// [`SAMPLE_RATE`].
+pub fn header_with_rate(num_frames: u32, num_channels: u16, sample_rate: u32, chunks: &[Chunk]) -> Vec<u8> {
+    let block_align = num_channels * (BITS_PER_SAMPLE / 8);
+    let byte_rate = sample_rate * block_align as u32;
+    let data_size = num_frames * block_align as u32;
+
+    let extensible = num_channels > MAX_PLAIN_CHANNELS;
+    let mut fmt = Vec::with_capacity(40);
+    fmt.extend_from_slice(&(if extensible { FORMAT_EXTENSIBLE } else { FORMAT_PCM }).to_le_bytes());
+    fmt.extend_from_slice(&num_channels.to_le_bytes());
+    fmt.extend_from_slice(&sample_rate.to_le_bytes());
+    fmt.extend_from_slice(&byte_rate.to_le_bytes());
+    fmt.extend_from_slice(&block_align.to_le_bytes());
+    fmt.extend_from_slice(&BITS_PER_SAMPLE.to_le_bytes());
+    if extensible {
+        fmt.extend_from_slice(&22u16.to_le_bytes()); // extension size
+        fmt.extend_from_slice(&BITS_PER_SAMPLE.to_le_bytes()); // valid bits
+        fmt.extend_from_slice(&speaker_mask(num_channels).to_le_bytes());
+        fmt.extend_from_slice(&SUBTYPE_PCM);
+    }
+    let fmt = Chunk::new(b"fmt ", fmt);
+
+    let chunks_size: usize = fmt.size() + chunks.iter().map(Chunk::size).sum::<usize>();
+    let riff_size = 4 + chunks_size + CHUNK_HEADER_SIZE + data_size as usize;
+
+    let mut h = Vec::with_capacity(CHUNK_HEADER_SIZE + 4 + chunks_size + CHUNK_HEADER_SIZE);
+    h.extend_from_slice(b"RIFF");
+    h.extend_from_slice(&(riff_size as u32).to_le_bytes());
+    h.extend_from_slice(b"WAVE");
+    fmt.write_to(&mut h);
+    for chunk in chunks {
+        chunk.write_to(&mut h);
+    }
+    // data subchunk; the samples follow
+    h.extend_from_slice(b"data");
+    h.extend_from_slice(&data_size.to_le_bytes());
     h
 }
 
+/// Standard speaker positions for `num_channels`, as `dwChannelMask` bits
+/// (FL, FR, FC, LFE, BL, BR, FLC, FRC, BC, SL, SR from bit 0). Counts
+/// without a usual layout get 0: channels not tied to speakers.
+///
+/// ```text
+/// 3 = FL FR FC    4 = FL FR BL BR    5 = 4 + FC
+/// 6 = 5.1         7 = 5.1 + BC       8 = 7.1 (side surrounds)
+/// ```
+fn speaker_mask(num_channels: u16) -> u32 {
+    match num_channels {
+        1 => 0x4,
+        2 => 0x3,
+        3 => 0x7,
+        4 => 0x33,
+        5 => 0x37,
+        6 => 0x3F,
+        7 => 0x13F,
+        8 => 0x63F,
+        _ => 0,
+    }
+}
+
+/// Appends `text` as a NUL-padded field of `width` bytes, non-ASCII
+/// characters replaced by '?'.
+fn push_fixed(out: &mut Vec<u8>, text: &str, width: usize) {
+    let start = out.len();
+    out.extend(text.chars().map(|ch| if ch.is_ascii() { ch as u8 } else { b'?' }).take(width));
+    out.resize(start + width, 0);
+}
+
+/// "yyyy-mm-dd" and "hh:mm:ss" of a Unix time, in UTC.
+///
+/// Days to civil date uses Howard Hinnant's algorithm: shift the epoch to
+/// 0000-03-01 so leap days fall at the end of each 400-year era.
+pub fn utc_date_time(unix_seconds: u64) -> (String, String) {
+    let days = unix_seconds / SECONDS_PER_DAY;
+    let seconds = unix_seconds % SECONDS_PER_DAY;
+    let shifted = days + 719_468;
+    let era = shifted / 146_097;
+    let day_of_era = shifted % 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let march_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * march_month + 2) / 5 + 1;
+    let month = if march_month < 10 { march_month + 3 } else { march_month - 9 };
+    let year = era * 400 + year_of_era + u64::from(month <= 2);
+    (
+        format!("{year:04}-{month:02}-{day:02}"),
+        format!("{:02}:{:02}:{:02}", seconds / 3600, seconds / 60 % 60, seconds % 60),
+    )
+}
+
+/// Size written for the RIFF and data chunks while their length is unknown.
+/// Readers treat it as "until end of stream", so it also stays in files
+/// sent through pipes.
+const UNKNOWN_SIZE: u32 = u32::MAX;
+
+/// Writes a WAV file incrementally: header first, then samples as they
+/// arrive.
+///
+/// The header starts with [`UNKNOWN_SIZE`] placeholders. Seekable outputs
+/// get the real sizes patched in by [`WavWriter::finish`]; pipes, which
+/// cannot seek back, end with [`WavWriter::finish_stream`] and keep them.
+///
+/// ```ignore
+/// let mut writer = WavWriter::new(File::create("game.wav")?, 1, &[])?;
+/// for chunk in rendered {
+///     writer.write_samples(&chunk)?;
+/// }
+/// writer.finish()?;
+/// ```
+pub struct WavWriter<W: Write> {
+    out: W,
+    header_len: usize,
+    data_bytes: u64,
+}
+
+impl<W: Write> WavWriter<W> {
+    /// Writes the header of a `num_channels`-channel file, with `chunks` ahead of
+    /// the samples.
+    pub fn new(mut out: W, num_channels: u16, chunks: &[Chunk]) -> io::Result<Self> {
+        let mut h = header_with_chunks(0, num_channels, chunks);
+        let header_len = h.len();
+        h[4..8].copy_from_slice(&UNKNOWN_SIZE.to_le_bytes());
+        h[header_len - 4..].copy_from_slice(&UNKNOWN_SIZE.to_le_bytes());
+        out.write_all(&h)?;
+        Ok(Self {
+            out,
+            header_len,
+            data_bytes: 0,
+        })
+    }
+
+    /// Appends interleaved samples.
+    pub fn write_samples(&mut self, samples: &[i16]) -> io::Result<()> {
+        let bytes: Vec<u8> = samples.iter().flat_map(|s| s.to_le_bytes()).collect();
+        self.out.write_all(&bytes)?;
+        self.data_bytes += bytes.len() as u64;
+        Ok(())
+    }
+
+    /// Flushes and returns the output, leaving the placeholder sizes.
+    pub fn finish_stream(mut self) -> io::Result<W> {
+        self.out.flush()?;
+        Ok(self.out)
+    }
+}
+
+impl<W: Write + Seek> WavWriter<W> {
+    /// Patches the RIFF and data sizes, then flushes and returns the output.
+    /// Files past the 4 GiB RIFF limit keep the placeholders.
+    pub fn finish(mut self) -> io::Result<W> {
+        let riff_size = (self.header_len - CHUNK_HEADER_SIZE) as u64 + self.data_bytes;
+        if let (Ok(riff_size), Ok(data_size)) = (u32::try_from(riff_size), u32::try_from(self.data_bytes)) {
+            let end = self.out.stream_position()?;
+            self.out.seek(SeekFrom::Start(4))?;
+            self.out.write_all(&riff_size.to_le_bytes())?;
+            self.out.seek(SeekFrom::Start((self.header_len - 4) as u64))?;
+            self.out.write_all(&data_size.to_le_bytes())?;
+            self.out.seek(SeekFrom::Start(end))?;
+        }
+        self.finish_stream()
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum DecodeWavError {
+    /// Not a RIFF 'WAVE' file
+    NotWav,
+    /// A required chunk is absent
+    MissingChunk(&'static str),
+    /// Compressed or float audio, or a bit depth other than 8/16/24
+    UnsupportedFormat { format: u16, bits_per_sample: u16 },
+    /// A chunk is truncated or its fields are inconsistent
+    Malformed(&'static str),
+}
+
+impl fmt::Display for DecodeWavError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DecodeWavError::NotWav => write!(f, "not a WAV file"),
+            DecodeWavError::MissingChunk(name) => write!(f, "WAV file has no '{name}' chunk"),
+            DecodeWavError::UnsupportedFormat { format, bits_per_sample } => {
+                write!(f, "unsupported WAV format {format:#06x} at {bits_per_sample} bits (need 8/16/24-bit PCM)")
+            }
+            DecodeWavError::Malformed(what) => write!(f, "malformed WAV file: {what}"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeWavError {}
+
+#[derive(Debug)]
+pub enum ReadWavError {
+    Io(io::Error),
+    Decode(DecodeWavError),
+}
+
+impl fmt::Display for ReadWavError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReadWavError::Io(err) => write!(f, "cannot read WAV file: {err}"),
+            ReadWavError::Decode(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl std::error::Error for ReadWavError {}
+
+impl From<io::Error> for ReadWavError {
+    fn from(err: io::Error) -> Self {
+        ReadWavError::Io(err)
+    }
+}
+
+impl From<DecodeWavError> for ReadWavError {
+    fn from(err: DecodeWavError) -> Self {
+        ReadWavError::Decode(err)
+    }
+}
+
+/// Format of a decoded WAV file.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Spec {
+    pub sample_rate: u32,
+    pub num_channels: u16,
+    /// Bit depth stored in the file, before conversion to 16-bit
+    pub bits_per_sample: u16,
+}
+
+/// PCM audio decoded from a WAV file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Decoded {
+    pub spec: Spec,
+    /// Interleaved frames, converted to 16-bit
+    pub samples: Vec<i16>,
+}
+
+/// Reads and decodes the WAV file at `path`.
+pub fn read(path: &Path) -> Result<(Spec, Vec<i16>), ReadWavError> {
+    let Decoded { spec, samples } = decode(&std::fs::read(path)?)?;
+    Ok((spec, samples))
+}
+
+/// Decodes an 8-, 16- or 24-bit PCM WAV file (plain or extensible format).
+pub fn decode(bytes: &[u8]) -> Result<Decoded, DecodeWavError> {
+    if bytes.get(0..4) != Some(b"RIFF") || bytes.get(8..12) != Some(b"WAVE") {
+        return Err(DecodeWavError::NotWav);
+    }
+    let mut fmt_chunk = None;
+    let mut data_chunk = None;
+    let mut offset = 12;
+    while let Some(id) = bytes.get(offset..offset + 4) {
+        let size = read_u32(bytes, offset + 4)? as usize;
+        // Streams cut short (or with a placeholder size) keep whatever is there
+        let body = &bytes[(offset + 8).min(bytes.len())..(offset + 8 + size).min(bytes.len())];
+        match id {
+            b"fmt " => fmt_chunk = Some(body),
+            b"data" => data_chunk = Some(body),
+            _ => {}
+        }
+        offset += 8 + size + size % 2;
+    }
+    let fmt_chunk = fmt_chunk.ok_or(DecodeWavError::MissingChunk("fmt "))?;
+    let data = data_chunk.ok_or(DecodeWavError::MissingChunk("data"))?;
+
+    let mut format = read_u16(fmt_chunk, 0)?;
+    let num_channels = read_u16(fmt_chunk, 2)?;
+    let sample_rate = read_u32(fmt_chunk, 4)?;
+    let bits_per_sample = read_u16(fmt_chunk, 14)?;
+    if format == FORMAT_EXTENSIBLE {
+        // The sub-format GUID starts with the plain format code
+        format = read_u16(fmt_chunk, 24)?;
+    }
+    if num_channels == 0 {
+        return Err(DecodeWavError::Malformed("zero channels"));
+    }
+
+    let samples = match (format, bits_per_sample) {
+        (FORMAT_PCM, 8) => data.iter().map(|&byte| (byte as i16 - 128) << 8).collect(),
+        (FORMAT_PCM, 16) => data.chunks_exact(2).map(|pair| i16::from_le_bytes([pair[0], pair[1]])).collect(),
+        // Keep the top 16 of 24 bits
+        (FORMAT_PCM, 24) => data.chunks_exact(3).map(|triple| i16::from_le_bytes([triple[1], triple[2]])).collect(),
+        _ => return Err(DecodeWavError::UnsupportedFormat { format, bits_per_sample }),
+    };
+    let spec = Spec {
+        sample_rate,
+        num_channels,
+        bits_per_sample,
+    };
+    Ok(Decoded { spec, samples })
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> Result<u16, DecodeWavError> {
+    bytes
+        .get(offset..offset + 2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .ok_or(DecodeWavError::Malformed("truncated chunk"))
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Result<u32, DecodeWavError> {
+    bytes
+        .get(offset..offset + 4)
+        .map(|quad| u32::from_le_bytes([quad[0], quad[1], quad[2], quad[3]]))
+        .ok_or(DecodeWavError::Malformed("truncated chunk"))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn riff_marker() {
-        assert_eq!(&header(1000)[0..4], b"RIFF");
+        assert_eq!(&header(1000, 1)[0..4], b"RIFF");
     }
 
     #[test]
     fn wave_marker() {
-        assert_eq!(&header(1000)[8..12], b"WAVE");
+        assert_eq!(&header(1000, 1)[8..12], b"WAVE");
     }
 
     #[test]
     fn fmt_marker() {
-        assert_eq!(&header(1000)[12..16], b"fmt ");
+        assert_eq!(&header(1000, 1)[12..16], b"fmt ");
     }
 
     #[test]
     fn data_marker() {
-        assert_eq!(&header(1000)[36..40], b"data");
+        assert_eq!(&header(1000, 1)[36..40], b"data");
     }
 
     #[test]
     fn chunk_size() {
-        let h = header(1000);
+        let h = header(1000, 1);
         let size = u32::from_le_bytes([h[4], h[5], h[6], h[7]]);
         assert_eq!(size, 36 + 2000); // 1000 samples * 2 bytes
     }
 
     #[test]
     fn data_size() {
-        let h = header(1000);
+        let h = header(1000, 1);
         let size = u32::from_le_bytes([h[40], h[41], h[42], h[43]]);
         assert_eq!(size, 2000);
     }
 
     #[test]
     fn sample_rate() {
-        let h = header(1000);
+        let h = header(1000, 1);
         let sr = u32::from_le_bytes([h[24], h[25], h[26], h[27]]);
         assert_eq!(sr, 44100);
     }
+
+    #[test]
+    fn multichannel_block_align() {
+        let h = header(1000, 6);
+        let channels = u16::from_le_bytes([h[22], h[23]]);
+        let block_align = u16::from_le_bytes([h[32], h[33]]);
+        assert_eq!(channels, 6);
+        assert_eq!(block_align, 12);
+    }
+
+    #[test]
+    fn multichannel_data_size() {
+        let h = header(1000, 6);
+        let size = u32::from_le_bytes([h[64], h[65], h[66], h[67]]);
+        assert_eq!(size, 12000); // 1000 frames * 6 channels * 2 bytes
+    }
+
+    #[test]
+    fn stereo_keeps_the_plain_header() {
+        let h = header(1000, 2);
+        assert_eq!(h.len(), HEADER_SIZE);
+        assert_eq!(u16::from_le_bytes([h[20], h[21]]), FORMAT_PCM);
+    }
+
+    #[test]
+    fn multichannel_header_is_extensible() {
+        let h = header(1000, 6);
+        assert_eq!(h.len(), HEADER_SIZE + 24);
+        assert_eq!(u32::from_le_bytes([h[16], h[17], h[18], h[19]]), 40);
+        assert_eq!(u16::from_le_bytes([h[20], h[21]]), FORMAT_EXTENSIBLE);
+        assert_eq!(u16::from_le_bytes([h[36], h[37]]), 22);
+        assert_eq!(u16::from_le_bytes([h[38], h[39]]), 16);
+        assert_eq!(u32::from_le_bytes([h[40], h[41], h[42], h[43]]), 0x3F);
+        assert_eq!(h[44..60], SUBTYPE_PCM);
+        assert_eq!(&h[60..64], b"data");
+    }
+
+    #[test]
+    fn header_with_rate_sets_rate_and_byte_rate() {
+        let h = header_with_rate(10, 2, 48_000, &[]);
+        assert_eq!(u32::from_le_bytes([h[24], h[25], h[26], h[27]]), 48_000);
+        assert_eq!(u32::from_le_bytes([h[28], h[29], h[30], h[31]]), 48_000 * 4);
+        assert_eq!(header_with_rate(10, 2, SAMPLE_RATE, &[]), header(10, 2));
+    }
+
+    #[test]
+    fn unusual_channel_counts_have_no_speaker_mask() {
+        assert_eq!(speaker_mask(12), 0);
+    }
+
+    fn encode(samples: &[i16], num_channels: u16) -> Vec<u8> {
+        let mut bytes = header((samples.len() / num_channels as usize) as u32, num_channels).to_vec();
+        bytes.extend(samples.iter().flat_map(|sample| sample.to_le_bytes()));
+        bytes
+    }
+
+    #[test]
+    fn decodes_own_output() {
+        let samples = vec![0, 1000, -1000, i16::MAX, i16::MIN];
+        let decoded = decode(&encode(&samples, 1)).unwrap();
+        let spec = Spec {
+            sample_rate: SAMPLE_RATE,
+            num_channels: 1,
+            bits_per_sample: BITS_PER_SAMPLE,
+        };
+        assert_eq!(decoded, Decoded { spec, samples });
+    }
+
+    #[test]
+    fn decodes_multichannel_interleaved() {
+        let decoded = decode(&encode(&[1, 2, 3, 4, 5, 6], 6)).unwrap();
+        assert_eq!(decoded.spec.num_channels, 6);
+        assert_eq!(decoded.samples, vec![1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn skips_unknown_chunks() {
+        let mut bytes = encode(&[7, 8], 1);
+        let list = [b"LIST".as_slice(), &3u32.to_le_bytes(), b"abc\0"].concat();
+        bytes.splice(36..36, list);
+        assert_eq!(decode(&bytes).unwrap().samples, vec![7, 8]);
+    }
+
+    #[test]
+    fn info_chunk_layout() {
+        let chunk = Chunk::info(&[(b"INAM", "Game"), (b"IART", ""), (b"ICMT", "1-0")]);
+        assert_eq!(chunk.id, *b"LIST");
+        // "Game\0" is odd-length, so it gets a pad byte
+        let inam = b"INAM\x05\0\0\0Game\0\0".as_slice();
+        let icmt = [b"ICMT\x04\0\0\0".as_slice(), b"1-0\0"].concat();
+        assert_eq!(chunk.body, [b"INFO".as_slice(), inam, &icmt].concat());
+    }
+
+    #[test]
+    fn header_with_chunks_places_them_before_data() {
+        let chunk = Chunk::new(b"test", vec![1, 2, 3]);
+        let h = header_with_chunks(10, 1, &[chunk]);
+        assert_eq!(h.len(), HEADER_SIZE + 8 + 4);
+        assert_eq!(&h[36..40], b"test");
+        assert_eq!(&h[h.len() - 8..h.len() - 4], b"data");
+        let riff_size = u32::from_le_bytes([h[4], h[5], h[6], h[7]]) as usize;
+        assert_eq!(riff_size, h.len() - 8 + 20);
+    }
+
+    #[test]
+    fn decodes_past_metadata_chunks() {
+        let mut bytes = header_with_chunks(2, 1, &[Chunk::info(&[(b"INAM", "Game")])]);
+        bytes.extend([7i16, 8].iter().flat_map(|sample| sample.to_le_bytes()));
+        assert_eq!(decode(&bytes).unwrap().samples, vec![7, 8]);
+    }
+
+    #[test]
+    fn cue_chunk_layout() {
+        let chunk = Chunk::cue(&[Cue { frame: 0, label: "1. e4".to_string() }, Cue { frame: 500, label: "1... e5".to_string() }]);
+        assert_eq!(chunk.id, *b"cue ");
+        assert_eq!(chunk.body.len(), 4 + 2 * 24);
+        assert_eq!(&chunk.body[0..4], &2u32.to_le_bytes());
+        let second = &chunk.body[28..52];
+        assert_eq!(&second[0..4], &2u32.to_le_bytes());
+        assert_eq!(&second[8..12], b"data");
+        assert_eq!(&second[20..24], &500u32.to_le_bytes());
+    }
+
+    #[test]
+    fn cue_labels_name_each_point() {
+        let chunk = Chunk::cue_labels(&[Cue { frame: 0, label: "1. e4".to_string() }]);
+        let labl = [b"labl\x0a\0\0\0".as_slice(), &1u32.to_le_bytes(), b"1. e4\0"].concat();
+        assert_eq!(chunk.body, [b"adtl".as_slice(), &labl].concat());
+    }
+
+    #[test]
+    fn utc_date_time_of_known_instants() {
+        assert_eq!(utc_date_time(0), ("1970-01-01".to_string(), "00:00:00".to_string()));
+        assert_eq!(utc_date_time(951_782_400), ("2000-02-29".to_string(), "00:00:00".to_string()));
+        assert_eq!(utc_date_time(1_700_000_000), ("2023-11-14".to_string(), "22:13:20".to_string()));
+    }
+
+    #[test]
+    fn bext_chunk_layout() {
+        let chunk = Chunk::bext(&Broadcast {
+            description: "Anderssen vs Kieseritzky".to_string(),
+            originator: "ChessWAV".to_string(),
+            origination_time: 1_700_000_000,
+        });
+        assert_eq!(chunk.id, *b"bext");
+        assert_eq!(chunk.body.len(), BEXT_SIZE);
+        assert!(chunk.body.starts_with(b"Anderssen vs Kieseritzky\0"));
+        assert_eq!(&chunk.body[256..264], b"ChessWAV");
+        assert_eq!(&chunk.body[320..338], b"2023-11-1422:13:20");
+        assert_eq!(&chunk.body[346..348], &1u16.to_le_bytes());
+    }
+
+    #[test]
+    fn fixed_fields_truncate_and_replace_non_ascii() {
+        let mut field = Vec::new();
+        push_fixed(&mut field, "Réti", 3);
+        assert_eq!(field, b"R?t");
+    }
+
+    fn with_raw_data(bits_per_sample: u16, data: &[u8]) -> Vec<u8> {
+        let mut bytes = header(0, 1).to_vec();
+        bytes[34..36].copy_from_slice(&bits_per_sample.to_le_bytes());
+        bytes[40..44].copy_from_slice(&(data.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(data);
+        bytes
+    }
+
+    #[test]
+    fn reads_files_from_disk() -> Result<(), ReadWavError> {
+        let path = std::env::temp_dir().join(format!("chesswav-read-{}.wav", std::process::id()));
+        std::fs::write(&path, encode(&[5, -5, 6, -6], 2))?;
+        let (spec, samples) = read(&path)?;
+        std::fs::remove_file(&path)?;
+        assert_eq!(spec.num_channels, 2);
+        assert_eq!(samples, vec![5, -5, 6, -6]);
+        Ok(())
+    }
+
+    #[test]
+    fn read_reports_missing_files() {
+        let missing = std::env::temp_dir().join("chesswav-no-such-file.wav");
+        assert!(matches!(read(&missing), Err(ReadWavError::Io(_))));
+    }
+
+    #[test]
+    fn decodes_8_bit_unsigned() {
+        let decoded = decode(&with_raw_data(8, &[0, 128, 255])).unwrap();
+        assert_eq!(decoded.spec.bits_per_sample, 8);
+        assert_eq!(decoded.samples, vec![-32768, 0, 32512]);
+    }
+
+    #[test]
+    fn decodes_24_bit_keeping_top_bytes() {
+        let decoded = decode(&with_raw_data(24, &[0xFF, 0x34, 0x12])).unwrap();
+        assert_eq!(decoded.samples, vec![0x1234]);
+    }
+
+    #[test]
+    fn rejects_non_wav() {
+        assert_eq!(decode(b"RIFF....AVI "), Err(DecodeWavError::NotWav));
+    }
+
+    #[test]
+    fn rejects_float_format() {
+        let mut bytes = encode(&[0], 1);
+        bytes[20..22].copy_from_slice(&3u16.to_le_bytes());
+        bytes[34..36].copy_from_slice(&32u16.to_le_bytes());
+        assert_eq!(
+            decode(&bytes),
+            Err(DecodeWavError::UnsupportedFormat { format: 3, bits_per_sample: 32 })
+        );
+    }
+
+    #[test]
+    fn reports_missing_data_chunk() {
+        let bytes = header(0, 1)[..36].to_vec();
+        assert_eq!(decode(&bytes), Err(DecodeWavError::MissingChunk("data")));
+    }
+
+    fn written(samples: &[i16], num_channels: u16, chunks: &[Chunk]) -> Vec<u8> {
+        let mut writer = WavWriter::new(io::Cursor::new(Vec::new()), num_channels, chunks).unwrap();
+        for part in samples.chunks(3) {
+            writer.write_samples(part).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    fn buffered(samples: &[i16], num_channels: u16, chunks: &[Chunk]) -> Vec<u8> {
+        let num_frames = (samples.len() / num_channels as usize) as u32;
+        let mut bytes = header_with_chunks(num_frames, num_channels, chunks);
+        bytes.extend(samples.iter().flat_map(|s| s.to_le_bytes()));
+        bytes
+    }
+
+    #[test]
+    fn writer_matches_buffered_file() {
+        let samples = [1, -2, 3, -4, 5, -6, 7, -8];
+        let info = [Chunk::info(&[(b"INAM", "Game")])];
+        assert_eq!(written(&samples, 1, &[]), buffered(&samples, 1, &[]));
+        assert_eq!(written(&samples, 2, &info), buffered(&samples, 2, &info));
+        assert_eq!(written(&samples[..6], 6, &[]), buffered(&samples[..6], 6, &[]));
+    }
+
+    #[test]
+    fn streamed_writer_keeps_unknown_sizes() {
+        let mut writer = WavWriter::new(Vec::new(), 1, &[]).unwrap();
+        writer.write_samples(&[100, -100]).unwrap();
+        let bytes = writer.finish_stream().unwrap();
+        assert_eq!(&bytes[4..8], &UNKNOWN_SIZE.to_le_bytes());
+        assert_eq!(&bytes[40..44], &UNKNOWN_SIZE.to_le_bytes());
+        assert_eq!(decode(&bytes).unwrap().samples, vec![100, -100]);
+    }
 }
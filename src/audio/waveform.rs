@@ -54,9 +54,31 @@
 //!          └─────────────────────────────────────────────────────┘
 //!             odd harmonics, alternating sign, amplitude ∝ 1/n²
 //! ```
+//!
+//! # FM Synthesis
+//!
+//! Two-operator FM: a modulator sine wobbles the carrier's phase.
+//!
+//! ```text
+//! f(t) = sin(ωt + index × sin(ratio × ωt))
+//!
+//! ratio = modulator/carrier frequency (integer = harmonic, else inharmonic/bell)
+//! index = modulation depth (0 = pure sine, higher = more sidebands/brighter)
+//! ```
+//!
+//! # Noise
+//!
+//! Noise has no pitch, so its generators ignore the phase and draw from a
+//! seeded [`Xorshift32`] instead (deterministic output for the same seed).
+//! White noise has equal energy at every frequency (hiss); pink noise falls
+//! 3 dB per octave (rumble), which sounds more natural for percussion.
 
+use std::cell::Cell;
 use std::f64::consts::PI;
 
+use super::lut;
+use crate::rng::Xorshift32;
+
 /// A waveform that can generate samples at a given phase.
 pub trait Waveform {
     /// Generate a sample value (-1.0 to 1.0) at the given phase (radians).
@@ -95,21 +117,111 @@ pub struct Triangle;
 #[derive(Clone, Copy)]
 pub struct Sawtooth;
 
-/// Composite wave - fundamental + many overtones, rich/full timbre.
+/// Additive wave - a weighted sum of sine partials, defined as data.
 ///
-/// Formula: `sin(f) + sin(2f)/2 + sin(3f)/3 + sin(4f)/4 + sin(5f)/5`
-#[derive(Clone, Copy)]
-pub struct Composite;
+/// Each partial is a `(ratio, amplitude)` pair: `ratio` is the frequency as a
+/// multiple of the fundamental, `amplitude` its weight. The sum is divided by
+/// the total amplitude so the output stays within [-1, 1].
+///
+/// Formula: `Σ amplitude × sin(ratio × phase) / Σ amplitude`
+#[derive(Clone, Debug, PartialEq)]
+pub struct Additive {
+    pub partials: Vec<(f64, f64)>,
+}
 
-/// Harmonics wave - sine with added overtones, warm/noble timbre.
+impl Additive {
+    /// Fundamental + four overtones at 1/n amplitude, rich/full timbre.
+    ///
+    /// Formula: `sin(f) + sin(2f)/2 + sin(3f)/3 + sin(4f)/4 + sin(5f)/5`
+    pub fn composite() -> Self {
+        Self {
+            partials: (1..=5).map(|n| (n as f64, 1.0 / n as f64)).collect(),
+        }
+    }
+
+    /// Sine with two soft overtones, warm/noble timbre.
+    ///
+    /// Formula: `sin(f) + 0.5*sin(2f) + 0.25*sin(3f)`
+    pub fn harmonics() -> Self {
+        Self {
+            partials: vec![(1.0, 1.0), (2.0, 0.5), (3.0, 0.25)],
+        }
+    }
+
+    /// Normalized sum of the partials whose ratio passes `keep`.
+    fn sum_partials(&self, phase: f64, keep: impl Fn(f64) -> bool) -> f64 {
+        let (value, total_amplitude) = self
+            .partials
+            .iter()
+            .filter(|(ratio, _)| keep(*ratio))
+            .fold((0.0, 0.0), |(value, total), (ratio, amplitude)| {
+                (value + amplitude * lut::sin(phase * ratio), total + amplitude.abs())
+            });
+        if total_amplitude == 0.0 { 0.0 } else { value / total_amplitude }
+    }
+}
+
+/// Two-operator FM - bell-like or metallic depending on `ratio`.
 ///
-/// Formula: `sin(f) + 0.5*sin(2f) + 0.25*sin(3f)`
-#[derive(Clone, Copy)]
-pub struct Harmonics;
+/// Formula: `sin(phase + index × sin(ratio × phase))`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Fm {
+    /// Modulator frequency as a multiple of the carrier frequency
+    pub ratio: f64,
+    /// Modulation depth in radians
+    pub index: f64,
+}
+
+impl Fm {
+    /// Inharmonic ratio with moderate depth: struck-bell timbre.
+    pub fn bell() -> Self {
+        Self { ratio: 1.4, index: 2.0 }
+    }
+}
+
+/// White noise - flat spectrum, harsh hiss.
+///
+/// Each sample is an independent uniform value in [-1, 1].
+#[derive(Clone)]
+pub struct WhiteNoise {
+    rng: Cell<Xorshift32>,
+}
+
+/// Pink noise - energy falls 3 dB per octave, softer than white.
+///
+/// White noise through Paul Kellett's three-pole "economy" filter.
+#[derive(Clone)]
+pub struct PinkNoise {
+    white: WhiteNoise,
+    poles: Cell<[f64; 3]>,
+}
+
+impl WhiteNoise {
+    pub fn new(seed: u32) -> Self {
+        Self { rng: Cell::new(Xorshift32::new(seed)) }
+    }
+
+    /// Next uniform value in [-1, 1].
+    fn next(&self) -> f64 {
+        let mut rng = self.rng.get();
+        let value = rng.next_signed();
+        self.rng.set(rng);
+        value
+    }
+}
+
+impl PinkNoise {
+    pub fn new(seed: u32) -> Self {
+        Self {
+            white: WhiteNoise::new(seed),
+            poles: Cell::new([0.0; 3]),
+        }
+    }
+}
 
 impl Waveform for Sine {
     fn sample(&self, phase: f64) -> f64 {
-        phase.sin()
+        lut::sin(phase)
     }
 
     fn sample_band_limited(&self, phase: f64, _harmonics: u32) -> f64 {
@@ -121,7 +233,7 @@ impl Waveform for Sine {
 impl Waveform for Square {
     /// Raw square: +1 when sin(phase) >= 0, else -1
     fn sample(&self, phase: f64) -> f64 {
-        if phase.sin() >= 0.0 { 1.0 } else { -1.0 }
+        if lut::sin(phase) >= 0.0 { 1.0 } else { -1.0 }
     }
 
     /// Band-limited square using Fourier series:
@@ -131,7 +243,7 @@ impl Waveform for Square {
         // Only odd harmonics: 1, 3, 5, 7, ...
         for n in (1..=harmonics).step_by(2) {
             // Each harmonic: sin(n × phase) / n
-            val += (phase * n as f64).sin() / n as f64;
+            val += lut::sin(phase * n as f64) / n as f64;
         }
         // Scale factor: 4/π normalizes amplitude to [-1, 1]
         val * 4.0 / PI
@@ -160,7 +272,7 @@ impl Waveform for Triangle {
             // Alternating sign: +, -, +, -, ...
             let sign = if i % 2 == 0 { 1.0 } else { -1.0 };
             // Amplitude falls as 1/n² (faster than square's 1/n)
-            val += sign * (phase * n as f64).sin() / (n * n) as f64;
+            val += sign * lut::sin(phase * n as f64) / (n * n) as f64;
         }
         // Scale factor: 8/π² normalizes amplitude to [-1, 1]
         val * 8.0 / (PI * PI)
@@ -181,43 +293,162 @@ impl Waveform for Sawtooth {
         let mut val = 0.0;
         // ALL harmonics (not just odd like square/triangle)
         for n in 1..=harmonics {
-            val += (phase * n as f64).sin() / n as f64;
+            val += lut::sin(phase * n as f64) / n as f64;
         }
         val * -2.0 / PI
     }
 }
 
-impl Waveform for Composite {
+impl Waveform for Additive {
     fn sample(&self, phase: f64) -> f64 {
-        let h1 = phase.sin();
-        let h2 = (phase * 2.0).sin() / 2.0;
-        let h3 = (phase * 3.0).sin() / 3.0;
-        let h4 = (phase * 4.0).sin() / 4.0;
-        let h5 = (phase * 5.0).sin() / 5.0;
-        (h1 + h2 + h3 + h4 + h5) / 2.283
+        self.sum_partials(phase, |_| true)
     }
 
+    /// Drops partials above the `harmonics`-th multiple of the fundamental.
     fn sample_band_limited(&self, phase: f64, harmonics: u32) -> f64 {
-        let mut val = 0.0;
-        let mut total_amp = 0.0;
-        for n in 1..=harmonics.min(5) {
-            let amp = 1.0 / n as f64;
-            val += (phase * n as f64).sin() * amp;
-            total_amp += amp;
-        }
-        val / total_amp
+        self.sum_partials(phase, |ratio| ratio <= harmonics as f64)
     }
 }
 
-impl Waveform for Harmonics {
+impl Waveform for Fm {
     fn sample(&self, phase: f64) -> f64 {
-        let h1 = phase.sin();
-        let h2 = (phase * 2.0).sin() * 0.5;
-        let h3 = (phase * 3.0).sin() * 0.25;
-        (h1 + h2 + h3) / 1.75
+        lut::sin(phase + self.index * lut::sin(phase * self.ratio))
+    }
+
+    /// Carson's rule puts significant sidebands up to about `index + 1`,
+    /// so capping the index to `harmonics - 1` bounds the bandwidth.
+    fn sample_band_limited(&self, phase: f64, harmonics: u32) -> f64 {
+        let limited_index = self.index.min(harmonics.saturating_sub(1) as f64);
+        Fm { index: limited_index, ..*self }.sample(phase)
+    }
+}
+
+impl Waveform for WhiteNoise {
+    fn sample(&self, _phase: f64) -> f64 {
+        self.next()
+    }
+
+    fn sample_band_limited(&self, phase: f64, _harmonics: u32) -> f64 {
+        // Noise has no harmonic series to truncate
+        self.sample(phase)
+    }
+}
+
+impl Waveform for PinkNoise {
+    fn sample(&self, _phase: f64) -> f64 {
+        let white = self.white.next();
+        let [b0, b1, b2] = self.poles.get();
+        let poles = [
+            0.99765 * b0 + white * 0.0990460,
+            0.96300 * b1 + white * 0.2965164,
+            0.57000 * b2 + white * 1.0526913,
+        ];
+        self.poles.set(poles);
+        // The filter gains roughly 3-4x, scale back into [-1, 1]
+        ((poles[0] + poles[1] + poles[2] + white * 0.1848) * 0.25).clamp(-1.0, 1.0)
     }
 
     fn sample_band_limited(&self, phase: f64, _harmonics: u32) -> f64 {
         self.sample(phase)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mean_step(wave: &impl Waveform) -> f64 {
+        let samples: Vec<f64> = (0..4096).map(|_| wave.sample(0.0)).collect();
+        let total: f64 = samples.windows(2).map(|pair| (pair[1] - pair[0]).abs()).sum();
+        total / (samples.len() - 1) as f64
+    }
+
+    #[test]
+    fn additive_single_partial_is_sine() {
+        let additive = Additive { partials: vec![(1.0, 0.3)] };
+        for step in 0..16 {
+            let phase = step as f64 * 0.4;
+            assert!((additive.sample(phase) - Sine.sample(phase)).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn additive_without_partials_is_silent() {
+        assert_eq!(Additive { partials: vec![] }.sample(1.0), 0.0);
+    }
+
+    #[test]
+    fn additive_within_range() {
+        for additive in [Additive::composite(), Additive::harmonics()] {
+            for step in 0..1000 {
+                assert!((-1.0..=1.0).contains(&additive.sample(step as f64 * 0.01)));
+            }
+        }
+    }
+
+    #[test]
+    fn additive_band_limit_drops_high_partials() {
+        let composite = Additive::composite();
+        assert!((composite.sample_band_limited(0.7, 1) - Sine.sample(0.7)).abs() < 1e-12);
+        assert_ne!(composite.sample_band_limited(0.7, 3), composite.sample(0.7));
+    }
+
+    #[test]
+    fn fm_without_modulation_is_sine() {
+        let fm = Fm { ratio: 2.0, index: 0.0 };
+        for step in 0..16 {
+            let phase = step as f64 * 0.4;
+            assert!((fm.sample(phase) - Sine.sample(phase)).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn fm_within_range() {
+        let fm = Fm::bell();
+        for step in 0..1000 {
+            assert!((-1.0..=1.0).contains(&fm.sample(step as f64 * 0.01)));
+        }
+    }
+
+    #[test]
+    fn fm_band_limit_of_one_removes_modulation() {
+        let fm = Fm::bell();
+        assert_eq!(fm.sample_band_limited(1.0, 1), Sine.sample(1.0));
+    }
+
+    #[test]
+    fn white_noise_within_range() {
+        let noise = WhiteNoise::new(7);
+        for _ in 0..4096 {
+            assert!((-1.0..=1.0).contains(&noise.sample(0.0)));
+        }
+    }
+
+    #[test]
+    fn white_noise_is_deterministic_per_seed() {
+        let first = WhiteNoise::new(42);
+        let second = WhiteNoise::new(42);
+        for _ in 0..100 {
+            assert_eq!(first.sample(0.0), second.sample(0.0));
+        }
+    }
+
+    #[test]
+    fn white_noise_zero_seed_still_varies() {
+        let noise = WhiteNoise::new(0);
+        assert_ne!(noise.sample(0.0), noise.sample(0.0));
+    }
+
+    #[test]
+    fn pink_noise_within_range() {
+        let noise = PinkNoise::new(7);
+        for _ in 0..4096 {
+            assert!((-1.0..=1.0).contains(&noise.sample(0.0)));
+        }
+    }
+
+    #[test]
+    fn pink_noise_is_smoother_than_white() {
+        assert!(mean_step(&PinkNoise::new(3)) < mean_step(&WhiteNoise::new(3)));
+    }
+}
@@ -0,0 +1,479 @@
+//! Command-line flags, read once into [`Args`].
+//!
+//! Each flag is parsed here and nowhere else, so it has one meaning and
+//! one error message in every mode. Flags with an optional value (`--lufs`,
+//! `--fold`, `--stereo`, `--loop`, `--stats`, `--parallel`, `--bwf`) take
+//! the next argument unless it is another flag; a negative number such as
+//! `--lufs -14` is a value. Defaults that come from the config file or a
+//! sound theme are left as None for `main` to fill in.
+
+use std::fmt;
+
+use chesswav::audio::{self, Format, Key, Panning, Scale, Tuning};
+use chesswav::engine::board::{Board, ParseFenError, Position};
+use chesswav::engine::search;
+use chesswav::render::Verbosity;
+use chesswav::tui::display::{self, ColorMode, DisplayMode};
+use chesswav::tui::watch;
+
+/// Pause between autoplay moves, in milliseconds.
+const DEFAULT_AUTOPLAY_DELAY_MS: u64 = 1000;
+/// Autoplay looks two half-moves ahead: quick, and still sees a hanging piece.
+const DEFAULT_AUTOPLAY_DEPTH: u8 = 2;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseArgsError {
+    /// A value of the wrong kind, e.g. `--gain loud`
+    Invalid { name: &'static str, value: String, expected: String },
+    /// A name that is not one of the options, e.g. `--scale dorian`
+    Unknown { name: &'static str, value: String, options: &'static str },
+    Fen(ParseFenError),
+    /// Flags that cannot be used together, with why
+    Conflict(&'static str),
+}
+
+impl fmt::Display for ParseArgsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseArgsError::Invalid { name, value, expected } => write!(f, "Invalid {name}: {value}. Expected {expected}"),
+            ParseArgsError::Unknown { name, value, options } => write!(f, "Unknown {name}: {value}. Options: {options}"),
+            ParseArgsError::Fen(err) => write!(f, "Invalid FEN: {err}"),
+            ParseArgsError::Conflict(reason) => write!(f, "{reason}"),
+        }
+    }
+}
+
+impl std::error::Error for ParseArgsError {}
+
+impl From<ParseFenError> for ParseArgsError {
+    fn from(err: ParseFenError) -> Self {
+        ParseArgsError::Fen(err)
+    }
+}
+
+/// What to do with the moves.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    /// Write or play the game's audio (no subcommand)
+    Render,
+    /// `tui` or `--interactive`: the REPL
+    Interactive,
+    /// `autoplay`: the engine plays itself
+    Autoplay,
+    /// `watch [file]`: follow a game as it is written
+    Watch(watch::Source),
+}
+
+/// How many times `--play` plays the game.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Repeats {
+    Times(u32),
+    /// Until interrupted
+    Forever,
+}
+
+/// The command line, parsed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Args {
+    pub command: Command,
+    pub config_path: Option<String>,
+    pub list_devices: bool,
+
+    // Output
+    pub play: bool,
+    /// `--loop`; None plays once
+    pub repeats: Option<Repeats>,
+    pub device: Option<String>,
+    pub output: Option<String>,
+    pub stdout: bool,
+    /// `--format`; None leaves it to `-o`'s extension or the config file
+    pub format: Option<Format>,
+    pub sample_rate: u32,
+    pub outdir: Option<String>,
+    /// Games rendered at once with `--outdir`
+    pub workers: usize,
+    pub multichannel: bool,
+    pub soundfont: Option<String>,
+    pub samples: Option<String>,
+    /// `--bwf` description; None without --bwf
+    pub broadcast: Option<String>,
+    pub cues: bool,
+
+    // Input and reports
+    pub pgn: Option<String>,
+    /// `--game`, 1-based
+    pub game: Option<usize>,
+    pub validate: bool,
+    pub verbosity: Verbosity,
+    /// `--stats`: Some(true) for JSON, Some(false) for the summary
+    pub stats_json: Option<bool>,
+    pub start: Position,
+
+    // Sound
+    pub sound_theme: Option<String>,
+    /// Waveform overrides in the order they apply: `--all-waveform`, then `--waveform`
+    pub waveforms: Vec<String>,
+    pub chromatic: bool,
+    pub key: Option<Key>,
+    pub tuning: Option<Tuning>,
+    pub fold_octaves: Option<u32>,
+    /// Moves per minute
+    pub tempo: Option<f64>,
+    pub note_ms: Option<u32>,
+    pub gap_ms: Option<u32>,
+    /// `--humanize`: the largest timing offset, in milliseconds
+    pub humanize_ms: Option<u32>,
+    /// Shared by `--humanize` and autoplay; None seeds from the clock
+    pub seed: Option<u32>,
+    pub portamento_ms: Option<u32>,
+    pub loudness_target: Option<f64>,
+    /// `--gain` as a linear factor
+    pub gain: Option<f64>,
+    pub normalize: bool,
+    pub soft_limit: bool,
+    pub reverb: bool,
+    pub color_voicing: bool,
+    pub dynamics: bool,
+    pub percussion: bool,
+    pub legato: bool,
+    pub stereo: Option<Panning>,
+    pub spatial: bool,
+
+    // Board
+    pub display: Option<DisplayMode>,
+    pub color_mode: Option<ColorMode>,
+    pub delay_ms: u64,
+    pub depth: u8,
+}
+
+/// Parses the arguments after the program name.
+pub fn parse(args: &[String]) -> Result<Args, ParseArgsError> {
+    let flags = Flags(args);
+    let command = match args.first().map(String::as_str) {
+        // `watch <file>` follows a file; without one, or with `-`, stdin
+        Some("watch") => Command::Watch(match args.get(1) {
+            Some(path) if !path.starts_with('-') => watch::Source::File(path.into()),
+            Some(_) | None => watch::Source::Stdin,
+        }),
+        Some("autoplay") => Command::Autoplay,
+        // `chesswav tui` is the subcommand spelling of --interactive
+        Some("tui") => Command::Interactive,
+        _ if flags.has(&["--interactive", "-i"]) => Command::Interactive,
+        _ => Command::Render,
+    };
+
+    let play = flags.has(&["--play", "-p"]);
+    // --loop plays until interrupted, --loop N plays N times
+    let repeats = flags
+        .optional("--loop")
+        .map(|count| match count {
+            Some(value) => value
+                .parse::<u32>()
+                .ok()
+                .filter(|&times| times > 0)
+                .map(Repeats::Times)
+                .ok_or_else(|| invalid("loop count", value, "a number of plays, e.g. 3")),
+            None => Ok(Repeats::Forever),
+        })
+        .transpose()?;
+    if repeats.is_some() && !play {
+        return Err(ParseArgsError::Conflict("--loop repeats playback and needs --play"));
+    }
+    let output = flags.value(&["--output", "-o"]).map(str::to_string);
+    let outdir = flags.value(&["--outdir"]).map(str::to_string);
+    if outdir.is_some() && (output.is_some() || play) {
+        return Err(ParseArgsError::Conflict("--outdir writes one file per game and cannot be combined with -o or --play"));
+    }
+    let workers = match flags.optional("--parallel") {
+        Some(Some(value)) => value
+            .parse::<usize>()
+            .ok()
+            .filter(|&workers| workers > 0)
+            .ok_or_else(|| invalid("parallel", value, "a number of games at once, e.g. 4"))?,
+        Some(None) => std::thread::available_parallelism().map_or(1, |workers| workers.get()),
+        None => 1,
+    };
+    let stereo = flags
+        .optional("--stereo")
+        .map(|panning| match panning {
+            Some(value) => Panning::from_name(value).ok_or_else(|| unknown("panning", value, "file, side")),
+            None => Ok(Panning::default()),
+        })
+        .transpose()?;
+    let spatial = flags.has(&["--spatial"]);
+    let multichannel = flags.has(&["--multichannel"]);
+    let soundfont = flags.value(&["--soundfont"]).map(str::to_string);
+    let samples = flags.value(&["--samples"]).map(str::to_string);
+    if multichannel && (stereo.is_some() || spatial || soundfont.is_some() || samples.is_some()) {
+        return Err(ParseArgsError::Conflict(
+            "--multichannel writes a mono track per piece and cannot be combined with --stereo, --spatial, --soundfont or --samples",
+        ));
+    }
+
+    let verbosity = match (flags.has(&["--quiet", "-q"]), flags.has(&["--verbose", "-v"])) {
+        (false, false) => Verbosity::Normal,
+        (true, false) => Verbosity::Quiet,
+        (false, true) => Verbosity::Verbose,
+        (true, true) => return Err(ParseArgsError::Conflict("--quiet and --verbose cannot be combined")),
+    };
+    // --stats prints a summary, --stats json one JSON object, both on stderr
+    let stats_json = flags
+        .optional("--stats")
+        .map(|format| match format {
+            Some("json") => Ok(true),
+            Some("text") | None => Ok(false),
+            Some(value) => Err(unknown("stats format", value, "text, json")),
+        })
+        .transpose()?;
+
+    let scale = flags.lookup(&["--scale"], |value| {
+        Scale::from_name(value).ok_or_else(|| unknown("scale", value, "major, minor, pentatonic, blues, whole-tone"))
+    })?;
+    let tonic = flags.value(&["--key"]);
+    let key = match (tonic, scale) {
+        (None, None) => None,
+        (tonic, scale) => {
+            let tonic = tonic.unwrap_or("C");
+            let key = Key::from_tonic_name(tonic, scale.unwrap_or(Scale::Major));
+            Some(key.ok_or_else(|| invalid("key", tonic, "a note name, e.g. C, F# or Bb"))?)
+        }
+    };
+
+    Ok(Args {
+        command,
+        config_path: flags.value(&["--config"]).map(str::to_string),
+        list_devices: flags.has(&["--list-devices"]),
+        play,
+        repeats,
+        device: flags.value(&["--device"]).map(str::to_string),
+        output,
+        stdout: flags.has(&["--stdout"]),
+        format: flags.lookup(&["--format"], |value| {
+            Format::from_name(value).ok_or_else(|| unknown("format", value, "wav, midi, ogg, flac, raw"))
+        })?,
+        sample_rate: flags
+            .lookup(&["--sample-rate"], |value| {
+                let rate = value.parse::<u32>().ok().filter(|rate| (8_000..=192_000).contains(rate));
+                rate.ok_or_else(|| invalid("sample rate", value, "8000–192000 Hz, e.g. 48000"))
+            })?
+            .unwrap_or(audio::SAMPLE_RATE),
+        outdir,
+        workers,
+        multichannel,
+        soundfont,
+        samples,
+        broadcast: flags.optional("--bwf").map(|description| description.unwrap_or_default().to_string()),
+        cues: flags.has(&["--cues"]),
+        pgn: flags.value(&["--pgn"]).map(str::to_string),
+        game: flags.lookup(&["--game"], |value| {
+            let number = value.parse::<usize>().ok().filter(|&number| number > 0);
+            number.ok_or_else(|| invalid("game", value, "a game number, e.g. 2"))
+        })?,
+        validate: flags.has(&["--validate"]),
+        verbosity,
+        stats_json,
+        start: flags.lookup(&["--fen"], Board::from_fen)?.unwrap_or_default(),
+        sound_theme: flags.value(&["--sound-theme"]).map(str::to_string),
+        waveforms: flags
+            .value(&["--all-waveform"])
+            .map(|waveform| format!("all={waveform}"))
+            .into_iter()
+            .chain(flags.value(&["--waveform"]).map(str::to_string))
+            .collect(),
+        chromatic: flags.has(&["--chromatic"]),
+        key,
+        tuning: flags.lookup(&["--tuning"], |value| {
+            Tuning::from_name(value).ok_or_else(|| unknown("tuning", value, "equal, just, pythagorean"))
+        })?,
+        fold_octaves: flags
+            .optional("--fold")
+            .map(|octaves| match octaves {
+                Some(value) => number(value, "fold", "octaves, e.g. 3"),
+                None => Ok(audio::DEFAULT_FOLD_OCTAVES),
+            })
+            .transpose()?,
+        tempo: flags.lookup(&["--tempo"], |value| {
+            let tempo = value.parse::<f64>().ok().filter(|&tempo| tempo > 0.0);
+            tempo.ok_or_else(|| invalid("tempo", value, "moves per minute, e.g. 120"))
+        })?,
+        note_ms: flags.lookup(&["--note-ms"], |value| number(value, "note length", "milliseconds, e.g. 200"))?,
+        gap_ms: flags.lookup(&["--gap-ms"], |value| number(value, "gap", "milliseconds, e.g. 200"))?,
+        humanize_ms: flags.lookup(&["--humanize"], |value| number(value, "humanize", "milliseconds, e.g. 15"))?,
+        seed: flags.lookup(&["--seed"], |value| number(value, "seed", "a whole number, e.g. 42"))?,
+        portamento_ms: flags.lookup(&["--portamento"], |value| number(value, "portamento", "milliseconds, e.g. 80"))?,
+        loudness_target: flags
+            .optional("--lufs")
+            .map(|target| match target {
+                Some(value) => number(value, "loudness target", "LUFS, e.g. -16"),
+                None => Ok(audio::DEFAULT_TARGET_LUFS),
+            })
+            .transpose()?,
+        gain: flags
+            .lookup(&["--gain"], |value| number::<f64>(value, "gain", "decibels, e.g. -6"))?
+            .map(|gain_db| 10f64.powf(gain_db / 20.0)),
+        normalize: flags.has(&["--normalize"]),
+        soft_limit: flags.has(&["--limit"]),
+        reverb: flags.has(&["--reverb"]),
+        color_voicing: flags.has(&["--color-voicing"]),
+        dynamics: flags.has(&["--dynamics"]),
+        percussion: flags.has(&["--percussion"]),
+        legato: flags.has(&["--legato"]),
+        stereo,
+        spatial,
+        display: flags.lookup(&["--display", "-d"], |value| {
+            display::parse_display_mode(value).ok_or_else(|| unknown("display mode", value, display::DISPLAY_MODE_NAMES))
+        })?,
+        color_mode: flags.lookup(&["--color-mode"], |value| {
+            display::parse_color_mode(value).ok_or_else(|| unknown("color mode", value, "truecolor, 256"))
+        })?,
+        delay_ms: flags
+            .lookup(&["--delay"], |value| number(value, "delay", "milliseconds, e.g. 1000"))?
+            .unwrap_or(DEFAULT_AUTOPLAY_DELAY_MS),
+        depth: flags
+            .lookup(&["--depth"], |value| {
+                let depth = value.parse::<u8>().ok().filter(|depth| (1..=search::MAX_DEPTH).contains(depth));
+                depth.ok_or_else(|| invalid("depth", value, format!("1 to {}", search::MAX_DEPTH)))
+            })?
+            .unwrap_or(DEFAULT_AUTOPLAY_DEPTH),
+    })
+}
+
+/// The arguments, looked up by flag name.
+struct Flags<'a>(&'a [String]);
+
+impl<'a> Flags<'a> {
+    fn has(&self, names: &[&str]) -> bool {
+        self.0.iter().any(|arg| names.contains(&arg.as_str()))
+    }
+
+    /// The argument after the first of `names`; None if the flag is absent
+    /// or last.
+    fn value(&self, names: &[&str]) -> Option<&'a str> {
+        self.0.windows(2).find(|pair| names.contains(&pair[0].as_str())).map(|pair| pair[1].as_str())
+    }
+
+    /// [`Flags::value`] read with `read`.
+    fn lookup<T, E>(&self, names: &[&str], read: impl FnOnce(&'a str) -> Result<T, E>) -> Result<Option<T>, E> {
+        self.value(names).map(read).transpose()
+    }
+
+    /// A flag whose value may be left out: Some(None) when it is given
+    /// without one, as when the next argument is another flag.
+    fn optional(&self, name: &str) -> Option<Option<&'a str>> {
+        let idx = self.0.iter().position(|arg| arg == name)?;
+        // Negative numbers, e.g. a LUFS target, are values despite the minus
+        let value = self.0.get(idx + 1).filter(|value| !value.starts_with('-') || value.parse::<f64>().is_ok());
+        Some(value.map(String::as_str))
+    }
+}
+
+fn number<T: std::str::FromStr>(value: &str, name: &'static str, expected: &str) -> Result<T, ParseArgsError> {
+    value.parse().map_err(|_| invalid(name, value, expected))
+}
+
+fn invalid(name: &'static str, value: &str, expected: impl Into<String>) -> ParseArgsError {
+    ParseArgsError::Invalid { name, value: value.to_string(), expected: expected.into() }
+}
+
+fn unknown(name: &'static str, value: &str, options: &'static str) -> ParseArgsError {
+    ParseArgsError::Unknown { name, value: value.to_string(), options }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parsed(line: &str) -> Result<Args, ParseArgsError> {
+        parse(&line.split_whitespace().map(str::to_string).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn no_flags_render_with_the_defaults() {
+        let args = parsed("").unwrap();
+        assert_eq!(args.command, Command::Render);
+        assert_eq!(args.sample_rate, audio::SAMPLE_RATE);
+        assert_eq!(args.verbosity, Verbosity::Normal);
+        assert_eq!((args.workers, args.delay_ms, args.depth), (1, DEFAULT_AUTOPLAY_DELAY_MS, DEFAULT_AUTOPLAY_DEPTH));
+        assert_eq!((args.repeats, args.stats_json, args.seed), (None, None, None));
+        assert_eq!(args.start, Position::default());
+    }
+
+    #[test]
+    fn reads_subcommands() {
+        assert_eq!(parsed("tui").unwrap().command, Command::Interactive);
+        assert_eq!(parsed("-d ascii --interactive").unwrap().command, Command::Interactive);
+        assert_eq!(parsed("autoplay -i").unwrap().command, Command::Autoplay);
+        assert_eq!(parsed("watch live.pgn").unwrap().command, Command::Watch(watch::Source::File("live.pgn".into())));
+        assert_eq!(parsed("watch -").unwrap().command, Command::Watch(watch::Source::Stdin));
+        assert_eq!(parsed("watch").unwrap().command, Command::Watch(watch::Source::Stdin));
+    }
+
+    #[test]
+    fn optional_values_leave_the_next_flag_alone() {
+        let args = parsed("--play --loop --bwf --lufs --stats --fold --reverb").unwrap();
+        assert_eq!(args.repeats, Some(Repeats::Forever));
+        assert_eq!(args.broadcast.as_deref(), Some(""));
+        assert_eq!(args.loudness_target, Some(audio::DEFAULT_TARGET_LUFS));
+        assert_eq!(args.stats_json, Some(false));
+        assert_eq!(args.fold_octaves, Some(audio::DEFAULT_FOLD_OCTAVES));
+        assert!(args.reverb);
+
+        let args = parsed("-p --loop 3 --bwf Opening --lufs -14 --stats json --fold 2 --stereo side").unwrap();
+        assert_eq!(args.repeats, Some(Repeats::Times(3)));
+        assert_eq!(args.broadcast.as_deref(), Some("Opening"));
+        assert_eq!(args.loudness_target, Some(-14.0));
+        assert_eq!(args.stats_json, Some(true));
+        assert_eq!(args.fold_octaves, Some(2));
+        assert_eq!(args.stereo, Some(Panning::Side));
+    }
+
+    #[test]
+    fn one_seed_for_humanize_and_autoplay() {
+        let args = parsed("--humanize 15 --seed 42").unwrap();
+        assert_eq!((args.humanize_ms, args.seed), (Some(15), Some(42)));
+        let err = parsed("autoplay --seed forty-two").unwrap_err();
+        assert_eq!(err.to_string(), "Invalid seed: forty-two. Expected a whole number, e.g. 42");
+        assert_eq!(parsed("--seed -1").unwrap_err(), parsed("--humanize 5 --seed -1").unwrap_err());
+    }
+
+    #[test]
+    fn key_defaults_to_c_major() {
+        assert_eq!(parsed("").unwrap().key, None);
+        assert_eq!(parsed("--scale minor").unwrap().key, Key::from_tonic_name("C", Scale::NaturalMinor));
+        assert_eq!(parsed("--key F#").unwrap().key, Key::from_tonic_name("F#", Scale::Major));
+        assert_eq!(parsed("--key H").unwrap_err().to_string(), "Invalid key: H. Expected a note name, e.g. C, F# or Bb");
+    }
+
+    #[test]
+    fn converts_gain_to_a_factor() {
+        let gain = parsed("--gain -6").unwrap().gain.unwrap();
+        assert!((gain - 0.501).abs() < 0.001, "gain {gain}");
+    }
+
+    #[test]
+    fn reports_bad_values() {
+        assert_eq!(parsed("--scale dorian").unwrap_err().to_string(), "Unknown scale: dorian. Options: major, minor, pentatonic, blues, whole-tone");
+        assert_eq!(parsed("--sample-rate fast").unwrap_err().to_string(), "Invalid sample rate: fast. Expected 8000–192000 Hz, e.g. 48000");
+        assert_eq!(parsed("autoplay --depth 9").unwrap_err().to_string(), "Invalid depth: 9. Expected 1 to 5");
+        assert_eq!(parsed("-p --loop 0").unwrap_err().to_string(), "Invalid loop count: 0. Expected a number of plays, e.g. 3");
+        assert_eq!(parsed("--stats yaml").unwrap_err().to_string(), "Unknown stats format: yaml. Options: text, json");
+        let fen = parse(&["--fen".to_string(), "8/8/8 w - - 0 1".to_string()]).unwrap_err();
+        assert_eq!(fen.to_string(), "Invalid FEN: expected 8 ranks, found 3");
+    }
+
+    #[test]
+    fn rejects_flags_that_conflict() {
+        assert_eq!(parsed("--loop 2").unwrap_err(), ParseArgsError::Conflict("--loop repeats playback and needs --play"));
+        assert!(matches!(parsed("-q -v"), Err(ParseArgsError::Conflict(_))));
+        assert!(matches!(parsed("--outdir games -o game.wav"), Err(ParseArgsError::Conflict(_))));
+        assert!(matches!(parsed("--outdir games --play"), Err(ParseArgsError::Conflict(_))));
+        assert!(matches!(parsed("--multichannel --stereo"), Err(ParseArgsError::Conflict(_))));
+        assert_eq!(parsed("--outdir games --parallel 4").unwrap().workers, 4);
+    }
+
+    #[test]
+    fn all_waveform_applies_before_waveform() {
+        let args = parsed("--waveform Q=square --all-waveform sine").unwrap();
+        assert_eq!(args.waveforms, ["all=sine", "Q=square"]);
+    }
+}
@@ -0,0 +1,244 @@
+//! User defaults from `~/.config/chesswav/config.toml`, shared by the CLI
+//! and the interactive mode. Every key is optional and command-line flags
+//! override it:
+//!
+//! ```toml
+//! display = "unicode"       # sprite[:large|:small], unicode, ascii (interactive mode)
+//! color_mode = "truecolor"  # truecolor, 256 (default: from $COLORTERM)
+//! sound_theme = "8-bit"     # built-in name or path to a theme file
+//! tempo = 120               # moves per minute
+//! format = "flac"           # wav, midi, ogg, flac, raw
+//! error_sound = false       # buzz on a rejected move (interactive mode)
+//! board_theme = "blue"      # green, blue, brown, high-contrast (interactive mode)
+//! volume = 60               # 0 to 100 (interactive mode)
+//! coordinates = false       # rank and file labels (interactive mode)
+//! ```
+//!
+//! The file lives in `$XDG_CONFIG_HOME/chesswav/` when that is set, else in
+//! `$HOME/.config/chesswav/`. A missing file means no defaults. The REPL
+//! writes `display`, `board_theme`, `sound_theme`, `volume` and
+//! `coordinates` back with [`save`] as they change.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::audio::Format;
+use crate::toml::{self, Entry, ParseTomlError};
+use crate::tui::display::{self, ColorMode, DisplayMode, Palette};
+
+const APP_DIR: &str = "chesswav";
+const FILE_NAME: &str = "config.toml";
+
+#[derive(Debug)]
+pub enum LoadConfigError {
+    Io(std::io::Error),
+    Parse(ParseTomlError),
+    UnknownKey { line: usize, key: String },
+    InvalidValue { line: usize, key: String, expected: &'static str },
+}
+
+impl fmt::Display for LoadConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LoadConfigError::Io(err) => write!(f, "cannot read config: {err}"),
+            LoadConfigError::Parse(err) => write!(f, "{err}"),
+            LoadConfigError::UnknownKey { line, key } => write!(f, "line {line}: unknown key '{key}'"),
+            LoadConfigError::InvalidValue { line, key, expected } => {
+                write!(f, "line {line}: {key} must be {expected}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for LoadConfigError {}
+
+impl From<std::io::Error> for LoadConfigError {
+    fn from(err: std::io::Error) -> Self {
+        LoadConfigError::Io(err)
+    }
+}
+
+impl From<ParseTomlError> for LoadConfigError {
+    fn from(err: ParseTomlError) -> Self {
+        LoadConfigError::Parse(err)
+    }
+}
+
+/// Defaults read from the config file; None where the file is silent.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Config {
+    pub display: Option<DisplayMode>,
+    pub color_mode: Option<ColorMode>,
+    /// Built-in theme name or theme file path, as given to `--sound-theme`
+    pub sound_theme: Option<String>,
+    /// Moves per minute
+    pub tempo: Option<f64>,
+    pub format: Option<Format>,
+    /// Whether the REPL buzzes when a move is rejected
+    pub error_sound: Option<bool>,
+    /// The REPL's `theme`
+    pub board_theme: Option<Palette>,
+    /// The REPL's `volume`, in percent
+    pub volume: Option<u8>,
+    /// The REPL's `coords`
+    pub coordinates: Option<bool>,
+}
+
+impl Config {
+    /// The user's config file, or an empty [`Config`] if there is none.
+    pub fn load_default() -> Result<Config, LoadConfigError> {
+        match default_path() {
+            Some(path) if path.exists() => Config::load(&path),
+            Some(_) | None => Ok(Config::default()),
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Config, LoadConfigError> {
+        Config::parse(&std::fs::read_to_string(path)?)
+    }
+
+    pub fn parse(text: &str) -> Result<Config, LoadConfigError> {
+        let mut config = Config::default();
+        for entry in toml::parse(text)? {
+            if !entry.table.is_empty() {
+                return Err(unknown_key(&entry));
+            }
+            match entry.key.as_str() {
+                "display" => {
+                    let mode = entry.value.as_str().and_then(display::parse_display_mode);
+                    config.display = Some(mode.ok_or_else(|| invalid(&entry, "sprite, sprite:large, sprite:small, unicode or ascii"))?);
+                }
+                "color_mode" => {
+                    let mode = entry.value.as_str().and_then(display::parse_color_mode);
+                    config.color_mode = Some(mode.ok_or_else(|| invalid(&entry, "truecolor or 256"))?);
+                }
+                "sound_theme" => {
+                    let theme = entry.value.as_str().ok_or_else(|| invalid(&entry, "a theme name or path"))?;
+                    config.sound_theme = Some(theme.to_string());
+                }
+                "tempo" => {
+                    let tempo = entry.value.as_f64().filter(|&tempo| tempo > 0.0);
+                    config.tempo = Some(tempo.ok_or_else(|| invalid(&entry, "a positive number of moves per minute"))?);
+                }
+                "format" => {
+                    let format = entry.value.as_str().and_then(Format::from_name);
+                    config.format = Some(format.ok_or_else(|| invalid(&entry, "wav, midi, ogg, flac or raw"))?);
+                }
+                "error_sound" => {
+                    let enabled = entry.value.as_bool().ok_or_else(|| invalid(&entry, "true or false"))?;
+                    config.error_sound = Some(enabled);
+                }
+                "board_theme" => {
+                    let palette = entry.value.as_str().and_then(Palette::from_name);
+                    config.board_theme = Some(palette.ok_or_else(|| invalid(&entry, "green, blue, brown or high-contrast"))?);
+                }
+                "volume" => {
+                    let volume = entry.value.as_u32().filter(|&volume| volume <= 100).map(|volume| volume as u8);
+                    config.volume = Some(volume.ok_or_else(|| invalid(&entry, "a percentage from 0 to 100"))?);
+                }
+                "coordinates" => {
+                    let shown = entry.value.as_bool().ok_or_else(|| invalid(&entry, "true or false"))?;
+                    config.coordinates = Some(shown);
+                }
+                _ => return Err(unknown_key(&entry)),
+            }
+        }
+        Ok(config)
+    }
+}
+
+/// `$XDG_CONFIG_HOME/chesswav/config.toml`, falling back to
+/// `$HOME/.config/chesswav/config.toml`; None without either variable.
+pub fn default_path() -> Option<PathBuf> {
+    let config_home = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+    Some(config_home.join(APP_DIR).join(FILE_NAME))
+}
+
+/// Sets `key` in the config file at `path`, creating the file if needed
+/// and keeping the rest of it, comments included.
+pub fn save(path: &Path, key: &str, value: &toml::Value) -> std::io::Result<()> {
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(err) => return Err(err),
+    };
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(path, toml::set_top_level(&text, key, value))
+}
+
+fn invalid(entry: &Entry, expected: &'static str) -> LoadConfigError {
+    LoadConfigError::InvalidValue {
+        line: entry.line,
+        key: entry.key.clone(),
+        expected,
+    }
+}
+
+fn unknown_key(entry: &Entry) -> LoadConfigError {
+    let key = if entry.table.is_empty() { entry.key.clone() } else { format!("{}.{}", entry.table, entry.key) };
+    LoadConfigError::UnknownKey { line: entry.line, key }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_file_has_no_defaults() {
+        assert_eq!(Config::parse("# nothing yet\n").unwrap(), Config::default());
+    }
+
+    #[test]
+    fn reads_every_key() {
+        let text = "display = \"ascii\"\ncolor_mode = \"256\"\nsound_theme = \"8-bit\"\ntempo = 90\nformat = \"flac\"\nerror_sound = false\nboard_theme = \"brown\"\nvolume = 35\ncoordinates = false\n";
+        let config = Config::parse(text).unwrap();
+        assert_eq!(config.display, Some(DisplayMode::Ascii));
+        assert_eq!(config.color_mode, Some(ColorMode::Color256));
+        assert_eq!(config.sound_theme.as_deref(), Some("8-bit"));
+        assert_eq!(config.tempo, Some(90.0));
+        assert_eq!(config.format, Some(Format::Flac));
+        assert_eq!(config.error_sound, Some(false));
+        assert_eq!(config.board_theme, Some(Palette::Brown));
+        assert_eq!(config.volume, Some(35));
+        assert_eq!(config.coordinates, Some(false));
+    }
+
+    #[test]
+    fn saved_settings_read_back() {
+        let path = std::env::temp_dir().join(format!("chesswav-saved-config-{}", std::process::id())).join(FILE_NAME);
+        save(&path, "volume", &toml::Value::Integer(40)).unwrap();
+        save(&path, "display", &toml::Value::String("sprite:large".to_string())).unwrap();
+        save(&path, "volume", &toml::Value::Integer(75)).unwrap();
+        let config = Config::load(&path).unwrap();
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+        assert_eq!(config.volume, Some(75));
+        assert_eq!(config.display, Some(DisplayMode::Sprite(display::SpriteSet::Large)));
+    }
+
+    #[test]
+    fn reports_unknown_keys_and_tables() {
+        let err = Config::parse("speed = 3").unwrap_err();
+        assert_eq!(err.to_string(), "line 1: unknown key 'speed'");
+        let err = Config::parse("[audio]\nformat = \"wav\"").unwrap_err();
+        assert_eq!(err.to_string(), "line 2: unknown key 'audio.format'");
+    }
+
+    #[test]
+    fn reports_invalid_values() {
+        let err = Config::parse("\ntempo = 0").unwrap_err();
+        assert_eq!(err.to_string(), "line 2: tempo must be a positive number of moves per minute");
+        assert!(matches!(
+            Config::parse("format = \"mp3\""),
+            Err(LoadConfigError::InvalidValue { line: 1, .. })
+        ));
+        let err = Config::parse("error_sound = \"no\"").unwrap_err();
+        assert_eq!(err.to_string(), "line 1: error_sound must be true or false");
+        let err = Config::parse("volume = 101").unwrap_err();
+        assert_eq!(err.to_string(), "line 1: volume must be a percentage from 0 to 100");
+    }
+}
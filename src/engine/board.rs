@@ -1,4 +1,6 @@
-use super::chess::{NotationMove, Piece, ResolvedMove, Square};
+use std::fmt;
+
+use super::chess::{Capture, NotationMove, Piece, ResolvedMove, Square};
 use super::hint::{extract_hints, is_castling, resolve_castling, strip_annotations};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -7,7 +9,159 @@ pub enum Color {
     Black,
 }
 
-#[derive(Debug, Clone)]
+impl Color {
+    pub fn opponent(self) -> Color {
+        match self {
+            Color::White => Color::Black,
+            Color::Black => Color::White,
+        }
+    }
+}
+
+/// Why a move could not be resolved ([`Board::resolve_move`],
+/// [`Board::resolve_legal_move`]) or played ([`Board::apply_move`]).
+#[derive(Debug, Clone, PartialEq)]
+pub enum ResolveMoveError {
+    /// No piece of the side to move can make it
+    Illegal,
+    /// Several pieces can make it and the notation does not say which
+    Ambiguous(Vec<Square>),
+    /// Nothing stands on the move's origin square
+    EmptyOrigin(Square),
+}
+
+impl fmt::Display for ResolveMoveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ResolveMoveError::Illegal => write!(f, "illegal move"),
+            ResolveMoveError::Ambiguous(origins) => {
+                let names: Vec<String> = origins.iter().map(Square::name).collect();
+                write!(f, "ambiguous move, could come from {}", names.join(" or "))
+            }
+            ResolveMoveError::EmptyOrigin(origin) => write!(f, "no piece on {}", origin.name()),
+        }
+    }
+}
+
+impl std::error::Error for ResolveMoveError {}
+
+/// Why [`Board::from_fen`] rejected a FEN record.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseFenError {
+    /// The placement field does not have 8 ranks separated by `/`
+    RankCount(usize),
+    /// A rank covers more or fewer than 8 squares, e.g. "pppp"
+    RankWidth(String),
+    /// Not one of PNBRQK or pnbrqk
+    UnknownPiece(char),
+    /// Neither `w` nor `b`
+    SideToMove(String),
+    /// Not a move number from 1 up
+    FullMoveNumber(String),
+}
+
+impl fmt::Display for ParseFenError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseFenError::RankCount(count) => write!(f, "expected 8 ranks, found {}", count),
+            ParseFenError::RankWidth(rank) => write!(f, "rank \"{}\" does not cover 8 squares", rank),
+            ParseFenError::UnknownPiece(letter) => write!(f, "unknown piece '{}'", letter),
+            ParseFenError::SideToMove(side) => write!(f, "side to move must be w or b, found \"{}\"", side),
+            ParseFenError::FullMoveNumber(number) => write!(f, "invalid full move number \"{}\"", number),
+        }
+    }
+}
+
+impl std::error::Error for ParseFenError {}
+
+/// Where a game starts: the pieces, and how many half-moves came before,
+/// which sets the side to move and the move numbers that follow.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Position {
+    pub board: Board,
+    /// Half-moves played before this position; odd when Black is to move
+    pub ply: usize,
+}
+
+impl Position {
+    pub fn side_to_move(&self) -> Color {
+        if self.ply.is_multiple_of(2) { Color::White } else { Color::Black }
+    }
+
+    /// The position as a FEN record. The board keeps no castling rights or
+    /// en passant square, so castling is written as available wherever king
+    /// and rook stand on their starting squares, en passant as `-` and the
+    /// halfmove clock as 0.
+    pub fn to_fen(&self) -> String {
+        let ranks: Vec<String> = (0..8u8)
+            .rev()
+            .map(|rank| {
+                let mut text = String::new();
+                let mut empty = 0;
+                for file in 0..8u8 {
+                    match self.board.get(file, rank) {
+                        Some((piece, color)) => {
+                            if empty > 0 {
+                                text.push_str(&empty.to_string());
+                                empty = 0;
+                            }
+                            let letter = piece.letter();
+                            text.push(if color == Color::White { letter } else { letter.to_ascii_lowercase() });
+                        }
+                        None => empty += 1,
+                    }
+                }
+                if empty > 0 {
+                    text.push_str(&empty.to_string());
+                }
+                text
+            })
+            .collect();
+
+        let at_home = |file: u8, rank: u8, piece: Piece, color: Color| self.board.get(file, rank) == Some((piece, color));
+        let castling: String = [
+            ('K', 7, 0, Color::White),
+            ('Q', 0, 0, Color::White),
+            ('k', 7, 7, Color::Black),
+            ('q', 0, 7, Color::Black),
+        ]
+        .iter()
+        .filter(|&&(_, rook_file, rank, color)| at_home(4, rank, Piece::King, color) && at_home(rook_file, rank, Piece::Rook, color))
+        .map(|&(letter, ..)| letter)
+        .collect();
+
+        let side = match self.side_to_move() {
+            Color::White => "w",
+            Color::Black => "b",
+        };
+        format!(
+            "{} {} {} - 0 {}",
+            ranks.join("/"),
+            side,
+            if castling.is_empty() { "-" } else { &castling },
+            self.ply / 2 + 1
+        )
+    }
+}
+
+/// What [`Board::make_move`] replaced, so [`Board::unmake_move`] can put it
+/// back: the piece that moved (a pawn, for promotions) and whatever stood
+/// on the destination square.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Undo {
+    moved: Option<(Piece, Color)>,
+    captured: Option<(Piece, Color)>,
+}
+
+impl Undo {
+    /// The piece the move took, if any. En passant is not tracked, so a
+    /// pawn taken that way is missed.
+    pub fn captured(&self) -> Option<(Piece, Color)> {
+        self.captured
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct Board {
     squares: [[Option<(Piece, Color)>; 8]; 8],
 }
@@ -43,6 +197,64 @@ impl Board {
         Board { squares }
     }
 
+    /// Reads a FEN record: piece placement, then optionally the side to move
+    /// and, after castling, en passant and halfmove clock fields (ignored),
+    /// the full move number.
+    ///
+    /// ```text
+    /// rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1
+    /// └──────────── rank 8 to rank 1 ─────────────┘ └ Black to move, move 1
+    /// ```
+    pub fn from_fen(fen: &str) -> Result<Position, ParseFenError> {
+        let mut fields = fen.split_whitespace();
+        let placement = fields.next().unwrap_or_default();
+        let ranks: Vec<&str> = placement.split('/').collect();
+        if ranks.len() != 8 {
+            return Err(ParseFenError::RankCount(ranks.len()));
+        }
+
+        let mut squares = [[None; 8]; 8];
+        for (row, rank_text) in ranks.iter().enumerate() {
+            let rank = 7 - row;
+            let mut file = 0;
+            for letter in rank_text.chars() {
+                if let Some(empty) = letter.to_digit(10) {
+                    file += empty as usize;
+                    continue;
+                }
+                let color = if letter.is_ascii_uppercase() { Color::White } else { Color::Black };
+                let piece = match letter.to_ascii_uppercase() {
+                    'P' => Piece::Pawn,
+                    upper => Piece::from_char(upper).ok_or(ParseFenError::UnknownPiece(letter))?,
+                };
+                let square = squares[rank].get_mut(file).ok_or_else(|| ParseFenError::RankWidth(rank_text.to_string()))?;
+                *square = Some((piece, color));
+                file += 1;
+            }
+            if file != 8 {
+                return Err(ParseFenError::RankWidth(rank_text.to_string()));
+            }
+        }
+
+        let black_to_move = match fields.next() {
+            None | Some("w") => false,
+            Some("b") => true,
+            Some(side) => return Err(ParseFenError::SideToMove(side.to_string())),
+        };
+        let full_move = match fields.nth(3) {
+            None => 1,
+            Some(number) => number
+                .parse::<usize>()
+                .ok()
+                .filter(|&number| number > 0)
+                .ok_or_else(|| ParseFenError::FullMoveNumber(number.to_string()))?,
+        };
+        Ok(Position {
+            board: Board { squares },
+            ply: (full_move - 1) * 2 + usize::from(black_to_move),
+        })
+    }
+
     pub fn get(&self, file: u8, rank: u8) -> Option<(Piece, Color)> {
         self.squares[rank as usize][file as usize]
     }
@@ -56,29 +268,27 @@ impl Board {
     }
 
     /// Resolves algebraic notation into a fully-specified move with origin, destination,
-    /// and any special move data (castling rook, promotion).
+    /// and any special move data (castling rook, promotion). Lenient: the first piece
+    /// that can reach the square is taken, and only [`ResolveMoveError::Illegal`] is
+    /// returned, when none can.
     pub fn resolve_move(
         &self,
         chess_move: &NotationMove,
         notation: &str,
         color: Color,
-    ) -> Option<ResolvedMove> {
+    ) -> Result<ResolvedMove, ResolveMoveError> {
         if is_castling(notation) {
-            return resolve_castling(chess_move, color);
+            return resolve_castling(chess_move, color).ok_or(ResolveMoveError::Illegal);
         }
 
         let clean = strip_annotations(notation);
         let (file_hint, rank_hint) = extract_hints(&clean, chess_move.piece);
 
-        let origin = self.find_origin(
-            chess_move.piece,
-            &chess_move.dest,
-            color,
-            file_hint,
-            rank_hint,
-        )?;
+        let origin = self
+            .find_origin(chess_move.piece, &chess_move.dest, color, file_hint, rank_hint)
+            .ok_or(ResolveMoveError::Illegal)?;
 
-        Some(ResolvedMove {
+        Ok(ResolvedMove {
             origin,
             dest: chess_move.dest,
             promotion: chess_move.promotion,
@@ -86,20 +296,204 @@ impl Board {
         })
     }
 
-    pub fn apply_move(&mut self, parsed: &ResolvedMove) {
-        // Move the piece from origin to destination (handles king in castling too)
-        let piece_on_origin = self.get(parsed.origin.file, parsed.origin.rank);
-        self.clear_square(parsed.origin.file, parsed.origin.rank);
+    /// Like [`Board::resolve_move`], but strict: exactly one piece must be able
+    /// to make the move, without landing on a piece of its own side or leaving
+    /// its king in check. A pinned piece does not count, so "Nd2" is not
+    /// ambiguous while the other knight is pinned. Castling rights and en
+    /// passant are not tracked; castling only needs the king and rook in place,
+    /// nothing between them and no attacked square on the king's path.
+    pub fn resolve_legal_move(
+        &self,
+        chess_move: &NotationMove,
+        notation: &str,
+        color: Color,
+    ) -> Result<ResolvedMove, ResolveMoveError> {
+        if is_castling(notation) {
+            return self.resolve_legal_castling(chess_move, color);
+        }
+        if matches!(self.get(chess_move.dest.file, chess_move.dest.rank), Some((_, occupant)) if occupant == color) {
+            return Err(ResolveMoveError::Illegal);
+        }
+
+        let clean = strip_annotations(notation);
+        let (file_hint, rank_hint) = extract_hints(&clean, chess_move.piece);
+        let is_capture = chess_move.capture == Capture::Taken;
+        let mut candidates: Vec<ResolvedMove> = self
+            .origins(chess_move.piece, &chess_move.dest, color, file_hint, rank_hint)
+            // A pawn leaves its file only to capture
+            .filter(|origin| chess_move.piece != Piece::Pawn || (origin.file != chess_move.dest.file) == is_capture)
+            .map(|origin| ResolvedMove {
+                origin,
+                dest: chess_move.dest,
+                promotion: chess_move.promotion,
+                castling_rook: None,
+            })
+            .filter(|candidate| !self.leaves_king_in_check(candidate, color))
+            .collect();
+
+        if candidates.len() > 1 {
+            return Err(ResolveMoveError::Ambiguous(candidates.iter().map(|candidate| candidate.origin).collect()));
+        }
+        candidates.pop().ok_or(ResolveMoveError::Illegal)
+    }
 
-        if let Some(promoted_piece) = parsed.promotion {
-            let color = piece_on_origin
-                .map(|(_, color)| color)
-                .expect("piece must exist at origin for promotion");
-            self.set(parsed.dest.file, parsed.dest.rank, (promoted_piece, color));
-        } else {
-            // Captured pieces (if any) are simply overwritten — no tracking yet
-            self.squares[parsed.dest.rank as usize][parsed.dest.file as usize] = piece_on_origin;
+    fn resolve_legal_castling(&self, chess_move: &NotationMove, color: Color) -> Result<ResolvedMove, ResolveMoveError> {
+        let castling = resolve_castling(chess_move, color).ok_or(ResolveMoveError::Illegal)?;
+        let (rook_from, _) = castling.castling_rook.ok_or(ResolveMoveError::Illegal)?;
+        let king = castling.origin;
+        let in_place = self.get(king.file, king.rank) == Some((Piece::King, color))
+            && self.get(rook_from.file, rook_from.rank) == Some((Piece::Rook, color));
+        let between_clear = self.rook_can_reach(rook_from.file, rook_from.rank, &king);
+        let (first_file, last_file) = (king.file.min(castling.dest.file), king.file.max(castling.dest.file));
+        let path_safe = (first_file..=last_file)
+            .all(|file| !self.attacked_by(color.opponent(), &Square { file, rank: king.rank }));
+        if in_place && between_clear && path_safe { Ok(castling) } else { Err(ResolveMoveError::Illegal) }
+    }
+
+    /// Every legal move of the piece on `origin`, one per destination, rank
+    /// by rank from a1; none for an empty square. Legality is that of
+    /// [`Board::resolve_legal_move`], castling included. A pawn reaching the
+    /// last rank is listed once, promoting to a queen.
+    pub fn legal_moves_from(&self, origin: Square) -> Vec<ResolvedMove> {
+        let Some((piece, color)) = self.get(origin.file, origin.rank) else {
+            return Vec::new();
+        };
+        let last_rank = match color {
+            Color::White => 7,
+            Color::Black => 0,
+        };
+        let mut moves: Vec<ResolvedMove> = (0..8u8)
+            .flat_map(|rank| (0..8u8).map(move |file| Square { file, rank }))
+            .filter(|dest| !matches!(self.get(dest.file, dest.rank), Some((_, occupant)) if occupant == color))
+            .filter(|dest| self.can_reach(piece, color, origin.file, origin.rank, dest))
+            // A pawn leaves its file only to capture
+            .filter(|dest| piece != Piece::Pawn || (dest.file != origin.file) == self.get(dest.file, dest.rank).is_some())
+            .map(|dest| ResolvedMove {
+                origin,
+                dest,
+                promotion: (piece == Piece::Pawn && dest.rank == last_rank).then_some(Piece::Queen),
+                castling_rook: None,
+            })
+            .filter(|candidate| !self.leaves_king_in_check(candidate, color))
+            .collect();
+
+        if piece == Piece::King {
+            let move_index = match color {
+                Color::White => 0,
+                Color::Black => 1,
+            };
+            let castles = ["O-O", "O-O-O"]
+                .iter()
+                .filter_map(|notation| NotationMove::parse(notation, move_index))
+                .filter_map(|chess_move| self.resolve_legal_castling(&chess_move, color).ok())
+                .filter(|castling| castling.origin == origin);
+            moves.extend(castles);
+        }
+        moves
+    }
+
+    /// Every legal move of `color`, piece by piece from a1.
+    pub fn legal_moves(&self, color: Color) -> Vec<ResolvedMove> {
+        (0..8u8)
+            .flat_map(|rank| (0..8u8).map(move |file| Square { file, rank }))
+            .filter(|square| matches!(self.get(square.file, square.rank), Some((_, occupant)) if occupant == color))
+            .flat_map(|square| self.legal_moves_from(square))
+            .collect()
+    }
+
+    /// Standard algebraic notation for `resolved`, a legal move on this
+    /// board: "Nbd7", "exd5", "e8=Q+", "O-O#". A rival piece that could
+    /// also reach the square adds the origin's file, else its rank, else both.
+    pub fn san(&self, resolved: &ResolvedMove) -> String {
+        let (origin, dest) = (resolved.origin, resolved.dest);
+        let Some((piece, color)) = self.get(origin.file, origin.rank) else {
+            return dest.name();
+        };
+        let mut san = match resolved.castling_rook {
+            Some((rook_from, _)) if rook_from.file == 7 => "O-O".to_string(),
+            Some(_) => "O-O-O".to_string(),
+            None => {
+                let is_capture = self.get(dest.file, dest.rank).is_some() || (piece == Piece::Pawn && origin.file != dest.file);
+                let origin_name = origin.name();
+                let mut san = String::new();
+                match piece {
+                    Piece::Pawn if is_capture => san.push_str(&origin_name[..1]),
+                    Piece::Pawn => {}
+                    Piece::Knight | Piece::Bishop | Piece::Rook | Piece::Queen | Piece::King => {
+                        san.push(piece.letter());
+                        let rivals: Vec<Square> = (0..8u8)
+                            .flat_map(|rank| (0..8u8).map(move |file| Square { file, rank }))
+                            .filter(|square| *square != origin && self.get(square.file, square.rank) == Some((piece, color)))
+                            .filter(|square| self.legal_moves_from(*square).iter().any(|rival| rival.dest == dest))
+                            .collect();
+                        if rivals.iter().all(|rival| rival.file != origin.file) {
+                            san.push_str(if rivals.is_empty() { "" } else { &origin_name[..1] });
+                        } else if rivals.iter().all(|rival| rival.rank != origin.rank) {
+                            san.push_str(&origin_name[1..]);
+                        } else {
+                            san.push_str(&origin_name);
+                        }
+                    }
+                }
+                if is_capture {
+                    san.push('x');
+                }
+                san.push_str(&dest.name());
+                if let Some(promoted) = resolved.promotion {
+                    san.push('=');
+                    san.push(promoted.letter());
+                }
+                san
+            }
+        };
+        let mut after = self.clone();
+        if after.apply_move(resolved).is_ok() && after.in_check(color.opponent()) {
+            san.push(if after.legal_moves(color.opponent()).is_empty() { '#' } else { '+' });
         }
+        san
+    }
+
+    /// Whether `color`'s king is attacked after playing `resolved`; a move
+    /// that cannot be played at all counts as doing so.
+    fn leaves_king_in_check(&self, resolved: &ResolvedMove, color: Color) -> bool {
+        let mut after = self.clone();
+        after.apply_move(resolved).is_err() || after.in_check(color)
+    }
+
+    /// Whether `color`'s king is attacked. Boards without that king never are.
+    pub fn in_check(&self, color: Color) -> bool {
+        let king = (0..8u8)
+            .flat_map(|rank| (0..8u8).map(move |file| Square { file, rank }))
+            .find(|square| self.get(square.file, square.rank) == Some((Piece::King, color)));
+        king.is_some_and(|king| self.attacked_by(color.opponent(), &king))
+    }
+
+    fn attacked_by(&self, attacker: Color, target: &Square) -> bool {
+        (0..8u8).flat_map(|rank| (0..8u8).map(move |file| (file, rank))).any(|(file, rank)| {
+            match self.get(file, rank) {
+                Some((piece, color)) if color == attacker => match piece {
+                    // Pawns attack diagonally only, whatever stands there
+                    Piece::Pawn => {
+                        target.file.abs_diff(file) == 1 && target.rank as i8 - rank as i8 == pawn_direction(attacker)
+                    }
+                    Piece::Knight | Piece::Bishop | Piece::Rook | Piece::Queen | Piece::King => {
+                        self.can_reach(piece, attacker, file, rank, target)
+                    }
+                },
+                Some(_) | None => false,
+            }
+        })
+    }
+
+    /// Plays `parsed`, whatever the rules say; the board is left untouched
+    /// when its origin square is empty.
+    pub fn apply_move(&mut self, parsed: &ResolvedMove) -> Result<(), ResolveMoveError> {
+        // Move the piece from origin to destination (handles king in castling too)
+        let (piece, color) =
+            self.get(parsed.origin.file, parsed.origin.rank).ok_or(ResolveMoveError::EmptyOrigin(parsed.origin))?;
+        self.clear_square(parsed.origin.file, parsed.origin.rank);
+        // Captured pieces (if any) are simply overwritten — no tracking yet
+        self.set(parsed.dest.file, parsed.dest.rank, (parsed.promotion.unwrap_or(piece), color));
 
         // Castling: the king was already moved above; now move the rook
         if let Some((rook_from, rook_to)) = parsed.castling_rook {
@@ -107,6 +501,28 @@ impl Board {
             self.clear_square(rook_from.file, rook_from.rank);
             self.squares[rook_to.rank as usize][rook_to.file as usize] = rook;
         }
+        Ok(())
+    }
+
+    /// [`Board::apply_move`], returning what it takes to reverse it.
+    pub fn make_move(&mut self, parsed: &ResolvedMove) -> Result<Undo, ResolveMoveError> {
+        let undo = Undo {
+            moved: self.get(parsed.origin.file, parsed.origin.rank),
+            captured: self.get(parsed.dest.file, parsed.dest.rank),
+        };
+        self.apply_move(parsed)?;
+        Ok(undo)
+    }
+
+    /// Takes back `parsed`, the last move made with [`Board::make_move`].
+    pub fn unmake_move(&mut self, parsed: &ResolvedMove, undo: &Undo) {
+        if let Some((rook_from, rook_to)) = parsed.castling_rook {
+            let rook = self.get(rook_to.file, rook_to.rank);
+            self.clear_square(rook_to.file, rook_to.rank);
+            self.squares[rook_from.rank as usize][rook_from.file as usize] = rook;
+        }
+        self.squares[parsed.dest.rank as usize][parsed.dest.file as usize] = undo.captured;
+        self.squares[parsed.origin.rank as usize][parsed.origin.file as usize] = undo.moved;
     }
 
     fn find_origin(
@@ -117,30 +533,26 @@ impl Board {
         file_hint: Option<u8>,
         rank_hint: Option<u8>,
     ) -> Option<Square> {
-        for rank in 0..8u8 {
-            for file in 0..8u8 {
-                if let Some((found_piece, found_color)) = self.get(file, rank) {
-                    if found_piece != piece || found_color != color {
-                        continue;
-                    }
-                    // Skip square if disambiguation hint doesn't match
-                    if let Some(hint_file) = file_hint
-                        && file != hint_file
-                    {
-                        continue;
-                    }
-                    if let Some(hint_rank) = rank_hint
-                        && rank != hint_rank
-                    {
-                        continue;
-                    }
-                    if self.can_reach(piece, color, file, rank, dest) {
-                        return Some(Square { file, rank });
-                    }
-                }
-            }
-        }
-        None
+        self.origins(piece, dest, color, file_hint, rank_hint).next()
+    }
+
+    /// Squares holding a `color` `piece` that can reach `dest` and match the
+    /// disambiguation hints, rank by rank from a1.
+    fn origins(
+        &self,
+        piece: Piece,
+        dest: &Square,
+        color: Color,
+        file_hint: Option<u8>,
+        rank_hint: Option<u8>,
+    ) -> impl Iterator<Item = Square> {
+        (0..8u8)
+            .flat_map(|rank| (0..8u8).map(move |file| Square { file, rank }))
+            .filter(move |square| self.get(square.file, square.rank) == Some((piece, color)))
+            // Skip square if disambiguation hint doesn't match
+            .filter(move |square| file_hint.is_none_or(|hint_file| square.file == hint_file))
+            .filter(move |square| rank_hint.is_none_or(|hint_rank| square.rank == hint_rank))
+            .filter(move |square| self.can_reach(piece, color, square.file, square.rank, dest))
     }
 
     fn can_reach(&self, piece: Piece, color: Color, file: u8, rank: u8, dest: &Square) -> bool {
@@ -157,9 +569,10 @@ impl Board {
     }
 
     fn pawn_can_reach(&self, color: Color, file: u8, rank: u8, dest: &Square) -> bool {
-        let (direction, start_rank): (i8, u8) = match color {
-            Color::White => (1, 1),
-            Color::Black => (-1, 6),
+        let direction = pawn_direction(color);
+        let start_rank = match color {
+            Color::White => 1,
+            Color::Black => 6,
         };
         let file_distance = (dest.file as i8) - (file as i8);
         let rank_distance = (dest.rank as i8) - (rank as i8);
@@ -239,6 +652,14 @@ impl Board {
     }
 }
 
+/// +1 rank for White pawns, -1 for Black.
+fn pawn_direction(color: Color) -> i8 {
+    match color {
+        Color::White => 1,
+        Color::Black => -1,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -251,6 +672,83 @@ mod tests {
         }
     }
 
+    #[test]
+    fn from_fen_reads_the_starting_position() {
+        let position = Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").unwrap();
+        assert_eq!(position, Position::default());
+        assert_eq!(position.side_to_move(), Color::White);
+    }
+
+    #[test]
+    fn from_fen_reads_side_to_move_and_move_number() {
+        let position = Board::from_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1").unwrap();
+        assert_eq!(position.ply, 1);
+        assert_eq!(position.side_to_move(), Color::Black);
+        assert_eq!(position.board.get(4, 3), Some((Piece::Pawn, Color::White)));
+        assert_eq!(position.board.get(4, 1), None);
+
+        let endgame = Board::from_fen("8/8/4k3/8/8/4K3/4P3/8 w - - 12 40").unwrap();
+        assert_eq!(endgame.ply, 78);
+        assert_eq!(endgame.board.get(4, 5), Some((Piece::King, Color::Black)));
+        // Placement alone means White to move at move 1
+        assert_eq!(Board::from_fen("8/8/4k3/8/8/4K3/4P3/8").unwrap().ply, 0);
+    }
+
+    #[test]
+    fn to_fen_round_trips() {
+        assert_eq!(Position::default().to_fen(), "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+        for fen in ["r3k2r/8/8/8/4P3/8/8/4K2R b Kkq - 0 1", "8/8/4k3/8/8/4K3/4P3/8 w - - 0 40"] {
+            assert_eq!(Board::from_fen(fen).unwrap().to_fen(), fen);
+        }
+    }
+
+    #[test]
+    fn from_fen_rejects_malformed_records() {
+        assert_eq!(Board::from_fen("8/8/8 w"), Err(ParseFenError::RankCount(3)));
+        assert_eq!(Board::from_fen("9/8/8/8/8/8/8/8"), Err(ParseFenError::RankWidth("9".to_string())));
+        assert_eq!(Board::from_fen("ppppppppp/8/8/8/8/8/8/8"), Err(ParseFenError::RankWidth("ppppppppp".to_string())));
+        assert_eq!(Board::from_fen("7x/8/8/8/8/8/8/8"), Err(ParseFenError::UnknownPiece('x')));
+        let err = Board::from_fen("8/8/8/8/8/8/8/8 white").unwrap_err();
+        assert_eq!(err.to_string(), "side to move must be w or b, found \"white\"");
+        assert_eq!(
+            Board::from_fen("8/8/8/8/8/8/8/8 w - - 0 0"),
+            Err(ParseFenError::FullMoveNumber("0".to_string()))
+        );
+    }
+
+    #[test]
+    fn unmake_move_restores_the_board_and_reports_captures() {
+        let mut board = Board::new();
+        let mut played = Vec::new();
+        for (move_index, notation) in ["e4", "d5", "exd5", "Qxd5", "Nf3", "Bg4", "Be2", "Nc6", "O-O"].iter().enumerate() {
+            let color = if move_index.is_multiple_of(2) { Color::White } else { Color::Black };
+            let m = NotationMove::parse(notation, move_index).unwrap();
+            let resolved = board.resolve_move(&m, notation, color).unwrap();
+            let before = board.clone();
+            let undo = board.make_move(&resolved).unwrap();
+            played.push((resolved, undo, before));
+        }
+        let captured: Vec<(Piece, Color)> = played.iter().filter_map(|(_, undo, _)| undo.captured()).collect();
+        assert_eq!(captured, [(Piece::Pawn, Color::Black), (Piece::Pawn, Color::White)]);
+        while let Some((resolved, undo, before)) = played.pop() {
+            board.unmake_move(&resolved, &undo);
+            assert_eq!(board, before);
+        }
+        assert_eq!(board, Board::new());
+    }
+
+    #[test]
+    fn unmake_move_takes_back_a_promotion() {
+        let mut position = Board::from_fen("1r5k/P7/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+        let m = NotationMove::parse("axb8=Q", 0).unwrap();
+        let resolved = position.board.resolve_move(&m, "axb8=Q", Color::White).unwrap();
+        let undo = position.board.make_move(&resolved).unwrap();
+        assert_eq!(position.board.get(1, 7), Some((Piece::Queen, Color::White)));
+        position.board.unmake_move(&resolved, &undo);
+        assert_eq!(position.board.get(0, 6), Some((Piece::Pawn, Color::White)));
+        assert_eq!(position.board.get(1, 7), Some((Piece::Rook, Color::Black)));
+    }
+
     #[test]
     fn initial_position_black_pawns() {
         let board = Board::new();
@@ -288,7 +786,7 @@ mod tests {
             promotion: None,
             castling_rook: None,
         };
-        board.apply_move(&parsed);
+        board.apply_move(&parsed).unwrap();
         assert_eq!(board.get(4, 1), None);
         assert_eq!(board.get(4, 3), Some((Piece::Pawn, Color::White)));
     }
@@ -304,13 +802,28 @@ mod tests {
             promotion: None,
             castling_rook: Some((Square { file: 7, rank: 0 }, Square { file: 5, rank: 0 })),
         };
-        board.apply_move(&parsed);
+        board.apply_move(&parsed).unwrap();
         assert_eq!(board.get(6, 0), Some((Piece::King, Color::White)));
         assert_eq!(board.get(5, 0), Some((Piece::Rook, Color::White)));
         assert_eq!(board.get(4, 0), None);
         assert_eq!(board.get(7, 0), None);
     }
 
+    #[test]
+    fn apply_move_from_an_empty_square_fails() {
+        let mut board = Board::new();
+        let parsed = ResolvedMove {
+            origin: Square { file: 4, rank: 3 },
+            dest: Square { file: 4, rank: 7 },
+            promotion: Some(Piece::Queen),
+            castling_rook: None,
+        };
+        let err = board.apply_move(&parsed).unwrap_err();
+        assert_eq!(err, ResolveMoveError::EmptyOrigin(Square { file: 4, rank: 3 }));
+        assert_eq!(err.to_string(), "no piece on e4");
+        assert_eq!(board, Board::new());
+    }
+
     #[test]
     fn apply_promotion() {
         let mut board = Board::new();
@@ -322,7 +835,7 @@ mod tests {
             promotion: Some(Piece::Queen),
             castling_rook: None,
         };
-        board.apply_move(&parsed);
+        board.apply_move(&parsed).unwrap();
         assert_eq!(board.get(4, 7), Some((Piece::Queen, Color::White)));
         assert_eq!(board.get(4, 6), None);
     }
@@ -369,4 +882,167 @@ mod tests {
         let origin = board.find_origin(Piece::Bishop, &dest, Color::White, None, None);
         assert_eq!(origin, None);
     }
+
+    fn empty_board() -> Board {
+        Board { squares: [[None; 8]; 8] }
+    }
+
+    fn legal(board: &Board, notation: &str, color: Color) -> Result<ResolvedMove, ResolveMoveError> {
+        let move_index = if color == Color::White { 0 } else { 1 };
+        let chess_move = NotationMove::parse(notation, move_index).unwrap();
+        board.resolve_legal_move(&chess_move, notation, color)
+    }
+
+    #[test]
+    fn legal_move_from_the_start() {
+        let board = Board::new();
+        assert_eq!(legal(&board, "e4", Color::White).unwrap().origin, Square { file: 4, rank: 1 });
+        assert_eq!(legal(&board, "Nf6", Color::Black).unwrap().origin, Square { file: 6, rank: 7 });
+    }
+
+    #[test]
+    fn two_knights_reaching_one_square_are_ambiguous() {
+        let mut board = empty_board();
+        board.set(1, 0, (Piece::Knight, Color::White));
+        board.set(5, 2, (Piece::Knight, Color::White));
+        assert_eq!(
+            legal(&board, "Nd2", Color::White).unwrap_err(),
+            ResolveMoveError::Ambiguous(vec![Square { file: 1, rank: 0 }, Square { file: 5, rank: 2 }])
+        );
+        assert_eq!(legal(&board, "Nbd2", Color::White).unwrap().origin, Square { file: 1, rank: 0 });
+    }
+
+    #[test]
+    fn pinned_piece_does_not_make_a_move_ambiguous() {
+        let mut board = empty_board();
+        board.set(4, 0, (Piece::King, Color::White));
+        board.set(4, 2, (Piece::Knight, Color::White));
+        board.set(4, 7, (Piece::Rook, Color::Black));
+        board.set(2, 2, (Piece::Knight, Color::White));
+        assert_eq!(legal(&board, "Nd5", Color::White).unwrap().origin, Square { file: 2, rank: 2 });
+    }
+
+    #[test]
+    fn unreachable_or_self_capturing_moves_are_illegal() {
+        let board = Board::new();
+        assert_eq!(legal(&board, "e5", Color::White).unwrap_err(), ResolveMoveError::Illegal);
+        assert_eq!(legal(&board, "Nd2", Color::White).unwrap_err(), ResolveMoveError::Illegal);
+        assert_eq!(legal(&board, "Ke2", Color::White).unwrap_err(), ResolveMoveError::Illegal);
+    }
+
+    #[test]
+    fn pawns_leave_their_file_only_to_capture() {
+        let mut board = Board::new();
+        board.set(3, 2, (Piece::Pawn, Color::Black));
+        assert_eq!(legal(&board, "exd3", Color::White).unwrap().origin, Square { file: 4, rank: 1 });
+        assert_eq!(legal(&board, "d3", Color::White).unwrap_err(), ResolveMoveError::Illegal);
+    }
+
+    #[test]
+    fn moving_into_check_is_illegal() {
+        let mut board = empty_board();
+        board.set(4, 0, (Piece::King, Color::White));
+        board.set(3, 7, (Piece::Rook, Color::Black));
+        assert_eq!(legal(&board, "Kd1", Color::White).unwrap_err(), ResolveMoveError::Illegal);
+        assert!(legal(&board, "Kf1", Color::White).is_ok());
+    }
+
+    #[test]
+    fn castling_needs_a_clear_and_safe_path() {
+        let mut board = Board::new();
+        assert_eq!(legal(&board, "O-O", Color::White).unwrap_err(), ResolveMoveError::Illegal);
+        board.clear_square(5, 0);
+        board.clear_square(6, 0);
+        assert!(legal(&board, "O-O", Color::White).is_ok());
+        board.clear_square(5, 1);
+        board.set(5, 5, (Piece::Rook, Color::Black));
+        assert_eq!(legal(&board, "O-O", Color::White).unwrap_err(), ResolveMoveError::Illegal);
+    }
+
+    fn destinations(board: &Board, origin: &str) -> Vec<String> {
+        let origin = Square::from_name(origin).unwrap();
+        board.legal_moves_from(origin).iter().map(|resolved| resolved.dest.name()).collect()
+    }
+
+    #[test]
+    fn legal_moves_from_the_start() {
+        let board = Board::new();
+        assert_eq!(destinations(&board, "e2"), ["e3", "e4"]);
+        assert_eq!(destinations(&board, "g1"), ["f3", "h3"]);
+        assert!(destinations(&board, "a1").is_empty());
+        assert!(destinations(&board, "e4").is_empty());
+    }
+
+    #[test]
+    fn legal_moves_from_respect_pins_and_captures() {
+        // The e2 knight is pinned by the e8 rook; the d4 pawn can take on c5
+        let board = Board::from_fen("k3r3/8/8/2p5/3P4/8/4N3/4K3 w - - 0 1").unwrap().board;
+        assert!(destinations(&board, "e2").is_empty());
+        assert_eq!(destinations(&board, "d4"), ["c5", "d5"]);
+        assert_eq!(destinations(&board, "e1"), ["d1", "f1", "d2", "f2"]);
+    }
+
+    #[test]
+    fn legal_moves_from_include_castling_and_promotion() {
+        let board = Board::from_fen("4k3/1P6/8/8/8/8/8/R3K2R w - - 0 1").unwrap().board;
+        let king_moves = board.legal_moves_from(Square::from_name("e1").unwrap());
+        let castles: Vec<String> = king_moves
+            .iter()
+            .filter(|resolved| resolved.castling_rook.is_some())
+            .map(|resolved| resolved.dest.name())
+            .collect();
+        assert_eq!(castles, ["g1", "c1"]);
+        let pawn_moves = board.legal_moves_from(Square::from_name("b7").unwrap());
+        assert_eq!(pawn_moves.len(), 1);
+        assert_eq!(pawn_moves[0].promotion, Some(Piece::Queen));
+    }
+
+    #[test]
+    fn legal_moves_cover_every_piece() {
+        assert_eq!(Board::new().legal_moves(Color::White).len(), 20);
+        assert_eq!(Board::new().legal_moves(Color::Black).len(), 20);
+    }
+
+    fn san_of(board: &Board, origin: &str, dest: &str) -> String {
+        let (origin, dest) = (Square::from_name(origin).unwrap(), Square::from_name(dest).unwrap());
+        let resolved = board.legal_moves_from(origin).into_iter().find(|resolved| resolved.dest == dest).unwrap();
+        board.san(&resolved)
+    }
+
+    #[test]
+    fn san_names_pawn_and_piece_moves() {
+        let board = Board::from_fen("rnbqkbnr/ppp1pppp/8/3p4/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 0 2").unwrap().board;
+        assert_eq!(san_of(&board, "e4", "e5"), "e5");
+        assert_eq!(san_of(&board, "e4", "d5"), "exd5");
+        assert_eq!(san_of(&board, "g1", "f3"), "Nf3");
+        assert_eq!(san_of(&board, "f1", "b5"), "Bb5+");
+    }
+
+    #[test]
+    fn san_disambiguates_by_file_then_rank() {
+        let board = Board::from_fen("2k5/8/8/8/R6R/8/8/R3K3 w - - 0 1").unwrap().board;
+        assert_eq!(san_of(&board, "a4", "d4"), "Rad4");
+        assert_eq!(san_of(&board, "a4", "a2"), "R4a2");
+        assert_eq!(san_of(&board, "a1", "a2"), "R1a2");
+        assert_eq!(san_of(&board, "h4", "h1"), "Rh1");
+    }
+
+    #[test]
+    fn san_marks_castling_promotion_and_mate() {
+        let board = Board::from_fen("k7/7P/8/8/8/8/8/4K2R w - - 0 1").unwrap().board;
+        assert_eq!(san_of(&board, "e1", "g1"), "O-O");
+        assert_eq!(san_of(&board, "h7", "h8"), "h8=Q+");
+        let board = Board::from_fen("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1").unwrap().board;
+        assert_eq!(san_of(&board, "a1", "a8"), "Ra8#");
+    }
+
+    #[test]
+    fn in_check_by_a_diagonal_pawn() {
+        let mut board = empty_board();
+        board.set(4, 0, (Piece::King, Color::White));
+        board.set(4, 1, (Piece::Pawn, Color::Black));
+        assert!(!board.in_check(Color::White));
+        board.set(3, 1, (Piece::Pawn, Color::Black));
+        assert!(board.in_check(Color::White));
+    }
 }
@@ -8,7 +8,7 @@
 //! Capture: "x", Annotations: "+", "#", "!", "?" (stripped during parse)
 //! ```
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Threat {
     None,
     Check,
@@ -21,7 +21,7 @@ pub enum Capture {
     Taken,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Piece {
     Pawn,
     Knight,
@@ -32,7 +32,31 @@ pub enum Piece {
 }
 
 impl Piece {
-    fn from_char(c: char) -> Option<Piece> {
+    /// Conventional material value in pawns. The king is never captured, so 0.
+    pub fn value(self) -> u32 {
+        match self {
+            Piece::Pawn => 1,
+            Piece::Knight | Piece::Bishop => 3,
+            Piece::Rook => 5,
+            Piece::Queen => 9,
+            Piece::King => 0,
+        }
+    }
+
+    /// Uppercase letter as in FEN, including P for pawns.
+    pub fn letter(self) -> char {
+        match self {
+            Piece::Pawn => 'P',
+            Piece::Knight => 'N',
+            Piece::Bishop => 'B',
+            Piece::Rook => 'R',
+            Piece::Queen => 'Q',
+            Piece::King => 'K',
+        }
+    }
+
+    /// Piece for a notation letter (K, Q, R, B, N). Pawns have no letter.
+    pub fn from_char(c: char) -> Option<Piece> {
         match c {
             'N' => Some(Piece::Knight),
             'R' => Some(Piece::Rook),
@@ -54,6 +78,20 @@ pub struct Square {
 }
 
 impl Square {
+    /// Parses a square name such as "e4".
+    pub fn from_name(name: &str) -> Option<Square> {
+        let mut chars = name.chars();
+        match (chars.next(), chars.next(), chars.next()) {
+            (Some(file_char), Some(rank_char), None) => Self::parse(file_char, rank_char),
+            _ => None,
+        }
+    }
+
+    /// The square's name, e.g. "e4".
+    pub fn name(&self) -> String {
+        format!("{}{}", (b'a' + self.file) as char, self.rank + 1)
+    }
+
     fn parse(file_char: char, rank_char: char) -> Option<Square> {
         let file = Self::parse_file(file_char)?;
         let rank = Self::parse_rank(rank_char)?;
@@ -92,6 +130,7 @@ impl Square {
 ///
 /// Produced by `Board::resolve_move` after finding the origin square
 /// on the board. This is the final form consumed by `Board::apply_move`.
+#[derive(Debug, Clone, PartialEq)]
 pub struct ResolvedMove {
     pub origin: Square,
     pub dest: Square,
@@ -125,7 +164,7 @@ impl NotationMove {
         let capture = if input.contains('x') { Capture::Taken } else { Capture::None };
         let promotion = Self::parse_promotion(input);
         let clean = Self::strip_annotations(input);
-        let rank = if move_index % 2 == 0 { 0 } else { 7 };
+        let rank = if move_index.is_multiple_of(2) { 0 } else { 7 };
 
         if let Some(m) = Self::parse_castling(&clean, rank, threat, capture) {
             return Some(m);
@@ -190,6 +229,36 @@ impl NotationMove {
 mod tests {
     use super::*;
 
+    #[test]
+    fn piece_values() {
+        assert_eq!(Piece::Pawn.value(), 1);
+        assert_eq!(Piece::Knight.value(), Piece::Bishop.value());
+        assert_eq!(Piece::Rook.value(), 5);
+        assert_eq!(Piece::Queen.value(), 9);
+        assert_eq!(Piece::King.value(), 0);
+    }
+
+    #[test]
+    fn square_from_name() {
+        assert_eq!(Square::from_name("e4"), Some(Square { file: 4, rank: 3 }));
+        assert_eq!(Square::from_name("h8"), Some(Square { file: 7, rank: 7 }));
+    }
+
+    #[test]
+    fn square_from_name_rejects_invalid() {
+        assert_eq!(Square::from_name("i1"), None);
+        assert_eq!(Square::from_name("e9"), None);
+        assert_eq!(Square::from_name("e44"), None);
+        assert_eq!(Square::from_name(""), None);
+    }
+
+    #[test]
+    fn square_name_round_trips() {
+        for name in ["a1", "e4", "h8"] {
+            assert_eq!(Square::from_name(name).unwrap().name(), name);
+        }
+    }
+
     #[test]
     fn move_pawn_e4() {
         let m = NotationMove::parse("e4", 0).unwrap();
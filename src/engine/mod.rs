@@ -1,3 +1,6 @@
 pub mod board;
 pub mod chess;
 pub mod hint;
+pub mod pgn;
+pub mod search;
+pub mod validate;
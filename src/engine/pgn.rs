@@ -0,0 +1,411 @@
+//! PGN games - the `[Name "value"]` header and the movetext after it.
+//!
+//! ```text
+//! [Event "World Championship"]     ─┐
+//! [White "Carlsen, Magnus"]         │ tags
+//! [Result "1-0"]                   ─┘
+//!
+//! 1. e4 e5 2. Nf3 {comment} 1-0    movetext
+//! ```
+//!
+//! [`split_tags`] peels the tags off a single game and returns the movetext
+//! untouched; [`parse_games`] reads a whole PGN file and reduces each game's
+//! movetext to bare SAN moves. [`Game::to_pgn`] writes a game back out.
+
+use super::board::{Board, ParseFenError, Position};
+
+/// Movetext lines are wrapped to this width, as PGN export format asks.
+const MOVETEXT_WIDTH: usize = 79;
+
+/// Tags of one game, in the order they appear.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Tags {
+    pairs: Vec<(String, String)>,
+}
+
+impl Tags {
+    /// Tags from name/value pairs, kept in order.
+    pub fn new(pairs: Vec<(String, String)>) -> Self {
+        Tags { pairs }
+    }
+
+    /// The value of tag `name`, or None if absent or unknown ("?", "????.??.??").
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.pairs
+            .iter()
+            .find(|(tag, _)| tag == name)
+            .map(|(_, value)| value.as_str())
+            .filter(|value| !value.is_empty() && !value.chars().all(|ch| matches!(ch, '?' | '.')))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+
+    /// A file name part from the players' surnames, e.g.
+    /// "carlsen-nepomniachtchi" for White "Carlsen, Magnus" and Black
+    /// "Nepomniachtchi, Ian"; the event's name without players, None
+    /// without either.
+    pub fn slug(&self) -> Option<String> {
+        let players: Vec<&str> = ["White", "Black"].iter().filter_map(|tag| self.get(tag)).map(surname).collect();
+        let words = if players.is_empty() { self.get("Event")?.to_string() } else { players.join(" ") };
+        let slug = words
+            .split(|ch: char| !ch.is_ascii_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .map(str::to_ascii_lowercase)
+            .collect::<Vec<_>>()
+            .join("-");
+        (!slug.is_empty()).then_some(slug)
+    }
+}
+
+/// "Carlsen, Magnus" → "Carlsen"; "Magnus Carlsen" → "Carlsen".
+fn surname(player: &str) -> &str {
+    match player.split_once(',') {
+        Some((surname, _)) => surname,
+        None => player.split_whitespace().last().unwrap_or(player),
+    }
+}
+
+/// Splits leading tag pair lines off `input`, returning them and the rest.
+/// Input without tags comes back whole, with empty [`Tags`].
+pub fn split_tags(input: &str) -> (Tags, &str) {
+    let mut pairs = Vec::new();
+    let mut rest = input;
+    loop {
+        let trimmed = rest.trim_start();
+        let (line, after) = trimmed.split_once('\n').unwrap_or((trimmed, ""));
+        match parse_tag_pair(line.trim_end()) {
+            Some(pair) => {
+                pairs.push(pair);
+                rest = after;
+            }
+            None => return (Tags { pairs }, rest),
+        }
+    }
+}
+
+/// Whether `input` is PGN rather than bare moves: it starts with `[Event`
+/// or has a tag pair line anywhere.
+pub fn is_pgn(input: &str) -> bool {
+    input.trim_start().starts_with("[Event") || input.lines().any(|line| parse_tag_pair(line.trim()).is_some())
+}
+
+/// One game of a PGN file.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Game {
+    pub tags: Tags,
+    /// Bare SAN moves separated by spaces, e.g. "e4 e5 Nf3"
+    pub moves: String,
+    /// Comments on moves, by the move's index in `moves`. Written out as
+    /// `{text}` after the move; reading a file drops them.
+    pub comments: Vec<(usize, String)>,
+}
+
+impl Game {
+    /// Where the game starts: its FEN tag, or the initial position.
+    pub fn start(&self) -> Result<Position, ParseFenError> {
+        self.tags.get("FEN").map_or(Ok(Position::default()), Board::from_fen)
+    }
+
+    /// The game as PGN: its tags, a blank line, then the moves numbered
+    /// "1. e4 e5 2. Nf3" and ending with the Result tag ("*" without one).
+    /// A FEN tag sets where numbering starts, so a game from a position
+    /// with Black to move opens "12... Nf6". Comments follow their move,
+    /// and a Black move after one is numbered again: "1. e4 {best} 1... e5".
+    pub fn to_pgn(&self) -> String {
+        let mut pgn = String::new();
+        for (name, value) in &self.tags.pairs {
+            let escaped = value.replace('\\', "\\\\").replace('"', "\\\"");
+            pgn.push_str(&format!("[{} \"{}\"]\n", name, escaped));
+        }
+        if !self.tags.is_empty() {
+            pgn.push('\n');
+        }
+
+        let first_ply = self.start().map_or(0, |position| position.ply);
+        let mut tokens = Vec::new();
+        let mut after_comment = false;
+        for (idx, notation) in self.moves.split_whitespace().enumerate() {
+            let ply = first_ply + idx;
+            let full_move = ply / 2 + 1;
+            if ply.is_multiple_of(2) {
+                tokens.push(format!("{full_move}."));
+            } else if idx == 0 || after_comment {
+                tokens.push(format!("{full_move}..."));
+            }
+            tokens.push(notation.to_string());
+            after_comment = false;
+            for (_, text) in self.comments.iter().filter(|(commented, _)| *commented == idx) {
+                // A brace would end the comment early
+                tokens.push(format!("{{{}}}", text.replace('}', ")")));
+                after_comment = true;
+            }
+        }
+        tokens.push(self.tags.get("Result").unwrap_or("*").to_string());
+
+        let mut line = String::new();
+        for token in tokens {
+            if !line.is_empty() && line.len() + 1 + token.len() > MOVETEXT_WIDTH {
+                pgn.push_str(&line);
+                pgn.push('\n');
+                line.clear();
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(&token);
+        }
+        pgn.push_str(&line);
+        pgn.push('\n');
+        pgn
+    }
+}
+
+/// Reads every game in a PGN file. A new game starts at each tag section
+/// that follows movetext; games with neither tags nor moves are dropped.
+pub fn parse_games(input: &str) -> Vec<Game> {
+    let mut games = Vec::new();
+    let mut rest = input;
+    while !rest.trim().is_empty() {
+        let (tags, after_tags) = split_tags(rest);
+        let movetext_len = next_tag_section(after_tags).unwrap_or(after_tags.len());
+        let moves = san_moves(&after_tags[..movetext_len]);
+        if !tags.is_empty() || !moves.is_empty() {
+            games.push(Game { tags, moves, comments: Vec::new() });
+        }
+        rest = &after_tags[movetext_len..];
+    }
+    games
+}
+
+/// Byte offset of the first line of `movetext` that is a tag pair.
+fn next_tag_section(movetext: &str) -> Option<usize> {
+    let mut offset = 0;
+    for line in movetext.split_inclusive('\n') {
+        if parse_tag_pair(line.trim()).is_some() {
+            return Some(offset);
+        }
+        offset += line.len();
+    }
+    None
+}
+
+/// Reduces movetext to its moves: drops move numbers ("12." and "12..."),
+/// `{comments}`, `; comments`, `(variations)`, `$n` annotation glyphs and the
+/// game result.
+fn san_moves(movetext: &str) -> String {
+    let mut mainline = String::new();
+    let mut depth = 0;
+    let mut chars = movetext.chars();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '{' => {
+                chars.by_ref().find(|&next| next == '}');
+                mainline.push(' ');
+            }
+            ';' => {
+                chars.by_ref().find(|&next| next == '\n');
+                mainline.push(' ');
+            }
+            '(' => depth += 1,
+            ')' => {
+                depth = (depth - 1).max(0);
+                mainline.push(' ');
+            }
+            _ if depth > 0 => {}
+            _ => mainline.push(ch),
+        }
+    }
+    mainline
+        .split_whitespace()
+        .filter(|token| !matches!(*token, "1-0" | "0-1" | "1/2-1/2" | "*"))
+        .map(strip_move_number)
+        .filter(|token| !token.is_empty() && !token.starts_with('$'))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// "12." / "12..." / "12.Nf3" → "" / "" / "Nf3". Tokens without a trailing
+/// dot after the digits (e.g. "0-0") are moves and come back unchanged.
+fn strip_move_number(token: &str) -> &str {
+    let after_digits = token.trim_start_matches(|ch: char| ch.is_ascii_digit());
+    if after_digits.len() < token.len() && after_digits.starts_with('.') {
+        after_digits.trim_start_matches('.')
+    } else {
+        token
+    }
+}
+
+/// `[Name "value"]`, with `\"` and `\\` escapes in the value.
+fn parse_tag_pair(line: &str) -> Option<(String, String)> {
+    let inner = line.strip_prefix('[')?.strip_suffix(']')?;
+    let (name, quoted) = inner.split_once(char::is_whitespace)?;
+    let quoted = quoted.trim().strip_prefix('"')?.strip_suffix('"')?;
+    let mut value = String::new();
+    let mut chars = quoted.chars();
+    while let Some(ch) = chars.next() {
+        value.push(if ch == '\\' { chars.next()? } else { ch });
+    }
+    Some((name.to_string(), value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GAME: &str = "[Event \"Casual\"]\n[White \"Anderssen, Adolf\"]\n[Date \"1851.??.??\"]\n\n1. e4 e5 2. f4";
+
+    #[test]
+    fn splits_tags_from_movetext() {
+        let (tags, movetext) = split_tags(GAME);
+        assert_eq!(tags.get("Event"), Some("Casual"));
+        assert_eq!(tags.get("White"), Some("Anderssen, Adolf"));
+        assert_eq!(movetext.trim(), "1. e4 e5 2. f4");
+    }
+
+    #[test]
+    fn slug_from_surnames_then_event() {
+        let (tags, _) = split_tags("[White \"Carlsen, Magnus\"]\n[Black \"Ian Nepomniachtchi\"]");
+        assert_eq!(tags.slug().as_deref(), Some("carlsen-nepomniachtchi"));
+        let (tags, _) = split_tags("[Event \"Tata Steel (Masters)\"]\n[White \"?\"]");
+        assert_eq!(tags.slug().as_deref(), Some("tata-steel-masters"));
+        assert_eq!(Tags::default().slug(), None);
+    }
+
+    #[test]
+    fn detects_pgn_by_its_tags() {
+        assert!(is_pgn(GAME));
+        assert!(is_pgn("\n  [Event \"Casual\"\n1. e4"));
+        assert!(is_pgn("1. e4 e5\n\n[White \"Morphy\"]\n1. d4"));
+        assert!(!is_pgn("e4 e5 Nf3"));
+        assert!(!is_pgn("1. e4 e5 2. Nf3 {book}"));
+    }
+
+    #[test]
+    fn writes_tags_then_numbered_movetext() {
+        let game = Game {
+            tags: Tags::new(vec![
+                ("Event".to_string(), "Casual \"blitz\"".to_string()),
+                ("Result".to_string(), "1-0".to_string()),
+            ]),
+            moves: "e4 e5 Qh5 Nc6 Bc4 Nf6 Qxf7#".to_string(),
+            comments: Vec::new(),
+        };
+        let pgn = game.to_pgn();
+        assert_eq!(
+            pgn,
+            "[Event \"Casual \\\"blitz\\\"\"]\n[Result \"1-0\"]\n\n1. e4 e5 2. Qh5 Nc6 3. Bc4 Nf6 4. Qxf7# 1-0\n"
+        );
+        let read_back = parse_games(&pgn);
+        assert_eq!(read_back, vec![game]);
+    }
+
+    #[test]
+    fn numbering_follows_the_fen_tag() {
+        let game = Game {
+            tags: Tags::new(vec![("FEN".to_string(), "4k3/8/8/8/8/8/4P3/4K3 b - - 0 40".to_string())]),
+            moves: "Kd7 e4".to_string(),
+            comments: Vec::new(),
+        };
+        assert!(game.to_pgn().ends_with("\n\n40... Kd7 41. e4 *\n"));
+    }
+
+    #[test]
+    fn start_comes_from_the_fen_tag() {
+        assert_eq!(Game::default().start(), Ok(Position::default()));
+        let game = Game {
+            tags: Tags::new(vec![("FEN".to_string(), "4k3/8/8/8/8/8/4P3/4K3 b - - 0 40".to_string())]),
+            moves: String::new(),
+            comments: Vec::new(),
+        };
+        assert_eq!(game.start().map(|position| position.ply), Ok(79));
+        let game = Game { tags: Tags::new(vec![("FEN".to_string(), "8/8 w".to_string())]), moves: String::new(), comments: Vec::new() };
+        assert!(game.start().is_err());
+    }
+
+    #[test]
+    fn comments_follow_their_moves() {
+        let game = Game {
+            tags: Tags::default(),
+            moves: "e4 e5 Nf3".to_string(),
+            comments: vec![(0, "best by test".to_string()), (2, "a {brace}".to_string())],
+        };
+        assert_eq!(game.to_pgn(), "1. e4 {best by test} 1... e5 2. Nf3 {a {brace)} *\n");
+        assert_eq!(parse_games(&game.to_pgn())[0].moves, game.moves);
+    }
+
+    #[test]
+    fn long_movetext_is_wrapped() {
+        let game = Game { tags: Tags::default(), moves: "Nf3 Nf6 Ng1 Ng8 ".repeat(20), comments: Vec::new() };
+        let pgn = game.to_pgn();
+        assert!(pgn.lines().count() > 1);
+        assert!(pgn.lines().all(|line| line.len() <= MOVETEXT_WIDTH));
+        assert_eq!(parse_games(&pgn)[0].moves, game.moves.trim());
+    }
+
+    #[test]
+    fn bare_moves_have_no_tags() {
+        let (tags, movetext) = split_tags("e4 e5 Nf3");
+        assert!(tags.is_empty());
+        assert_eq!(movetext, "e4 e5 Nf3");
+    }
+
+    #[test]
+    fn unknown_values_read_as_absent() {
+        let (tags, _) = split_tags("[Site \"?\"]\n[Date \"????.??.??\"]\n[Round \"3.1\"]");
+        assert_eq!(tags.get("Site"), None);
+        assert_eq!(tags.get("Date"), None);
+        assert_eq!(tags.get("Round"), Some("3.1"));
+        assert_eq!(tags.get("Black"), None);
+    }
+
+    #[test]
+    fn moves_drop_numbers_comments_and_result() {
+        let games = parse_games("1. e4 {best by test} e5 2.Nf3 $1 Nc6 3... a6 ; aside\n4. Bb5 1/2-1/2");
+        assert_eq!(games.len(), 1);
+        assert_eq!(games[0].moves, "e4 e5 Nf3 Nc6 a6 Bb5");
+    }
+
+    #[test]
+    fn variations_are_skipped() {
+        let games = parse_games("1. e4 e5 (1... c5 2. Nf3 (2. c3) d6) 2. Nf3 *");
+        assert_eq!(games[0].moves, "e4 e5 Nf3");
+    }
+
+    #[test]
+    fn results_that_start_with_digits_are_not_moves() {
+        // "0-1" must not be trimmed to "-1" by the move-number rule
+        let games = parse_games("1. f3 e5 2. g4 Qh4# 0-1");
+        assert_eq!(games[0].moves, "f3 e5 g4 Qh4#");
+    }
+
+    #[test]
+    fn castling_with_zeros_keeps_its_digits() {
+        assert_eq!(strip_move_number("0-0"), "0-0");
+        assert_eq!(strip_move_number("5.0-0-0"), "0-0-0");
+        assert_eq!(strip_move_number("12..."), "");
+    }
+
+    #[test]
+    fn splits_a_file_into_games() {
+        let file = format!("{GAME} 1-0\n\n[Event \"Rematch\"]\n\n1. d4 d5 0-1\n");
+        let games = parse_games(&file);
+        assert_eq!(games.len(), 2);
+        assert_eq!(games[0].tags.get("White"), Some("Anderssen, Adolf"));
+        assert_eq!(games[0].moves, "e4 e5 f4");
+        assert_eq!(games[1].tags.get("Event"), Some("Rematch"));
+        assert_eq!(games[1].moves, "d4 d5");
+    }
+
+    #[test]
+    fn empty_input_has_no_games() {
+        assert!(parse_games("  \n\n").is_empty());
+    }
+
+    #[test]
+    fn unescapes_values() {
+        let (tags, _) = split_tags(r#"[Annotator "the \"Doctor\" \\ co"]"#);
+        assert_eq!(tags.get("Annotator"), Some("the \"Doctor\" \\ co"));
+    }
+}
@@ -0,0 +1,168 @@
+//! The built-in opponent for the REPL's `play engine`: a fixed-depth
+//! negamax search with alpha-beta pruning.
+//!
+//! ```text
+//! score = material + centre bonus, side to move minus the other side
+//! mated = -MATE + plies from the root     (a sooner mate scores higher)
+//! stalemate = 0
+//! ```
+//!
+//! Moves come from [`Board::legal_moves`], so castling rights and en passant
+//! are as loose as the rest of the board. Captures are searched first, the
+//! most valuable victim first, so the pruning cuts more.
+
+use std::cmp::Reverse;
+
+use super::board::{Board, Color};
+use super::chess::{Piece, ResolvedMove, Square};
+
+/// Search depth in plies when `play engine` is given none.
+pub const DEFAULT_DEPTH: u8 = 3;
+/// Deepest search `play engine` accepts; each ply multiplies the time.
+pub const MAX_DEPTH: u8 = 5;
+
+const MATE: i32 = 100_000;
+const CENTIPAWNS: i32 = 100;
+const CENTRE_BONUS: i32 = 5;
+
+/// The move `color` should play, searching `depth` plies ahead (at least
+/// one). None when `color` has no legal move. Ties go to the first move
+/// found, so the same position always gets the same reply.
+pub fn best_move(board: &Board, color: Color, depth: u8) -> Option<ResolvedMove> {
+    good_moves(board, color, depth, 0).into_iter().next()
+}
+
+/// Every move of `color` scoring within `margin` centipawns of the best,
+/// best first; ties keep the order they were found in. Empty when `color`
+/// has no legal move.
+pub fn good_moves(board: &Board, color: Color, depth: u8, margin: i32) -> Vec<ResolvedMove> {
+    let mut scored: Vec<(i32, ResolvedMove)> = Vec::new();
+    let mut best = -MATE - 1;
+    for candidate in ordered_moves(board, color) {
+        // Moves that cannot come within the margin only need a bound
+        let alpha = best.saturating_sub(margin + 1).max(-MATE - 1);
+        let mut after = board.clone();
+        if after.apply_move(&candidate).is_err() {
+            continue;
+        }
+        let score = -negamax(&after, color.opponent(), depth.saturating_sub(1), 1, -MATE - 1, -alpha);
+        best = best.max(score);
+        scored.push((score, candidate));
+    }
+    scored.retain(|(score, _)| *score >= best - margin);
+    scored.sort_by_key(|(score, _)| Reverse(*score));
+    scored.into_iter().map(|(_, candidate)| candidate).collect()
+}
+
+/// Score of the position for `color`, to move, `ply` plies below the root.
+fn negamax(board: &Board, color: Color, depth: u8, ply: i32, mut alpha: i32, beta: i32) -> i32 {
+    if depth == 0 {
+        return evaluate(board, color);
+    }
+    let moves = ordered_moves(board, color);
+    if moves.is_empty() {
+        return if board.in_check(color) { -MATE + ply } else { 0 };
+    }
+    for candidate in moves {
+        let mut after = board.clone();
+        if after.apply_move(&candidate).is_err() {
+            continue;
+        }
+        let score = -negamax(&after, color.opponent(), depth - 1, ply + 1, -beta, -alpha);
+        if score >= beta {
+            return beta;
+        }
+        alpha = alpha.max(score);
+    }
+    alpha
+}
+
+/// Legal moves, captures of the most valuable pieces first.
+fn ordered_moves(board: &Board, color: Color) -> Vec<ResolvedMove> {
+    let mut moves = board.legal_moves(color);
+    moves.sort_by_key(|candidate| {
+        Reverse(board.get(candidate.dest.file, candidate.dest.rank).map_or(0, |(piece, _)| piece.value()))
+    });
+    moves
+}
+
+/// Material in centipawns plus a small bonus for minor pieces and pawns
+/// near the centre, for `color` minus the other side.
+fn evaluate(board: &Board, color: Color) -> i32 {
+    (0..8u8)
+        .flat_map(|rank| (0..8u8).map(move |file| Square { file, rank }))
+        .filter_map(|square| board.get(square.file, square.rank).map(|(piece, owner)| (square, piece, owner)))
+        .map(|(square, piece, owner)| {
+            let worth = piece.value() as i32 * CENTIPAWNS + centre_bonus(piece, square);
+            if owner == color { worth } else { -worth }
+        })
+        .sum()
+}
+
+/// 0 on the rim up to 3 × [`CENTRE_BONUS`] on d4, e4, d5 and e5.
+fn centre_bonus(piece: Piece, square: Square) -> i32 {
+    match piece {
+        Piece::Pawn | Piece::Knight | Piece::Bishop => {
+            // Twice the distance from the centre line, 1 to 7
+            let file_distance = (2 * square.file as i32 - 7).abs();
+            let rank_distance = (2 * square.rank as i32 - 7).abs();
+            (3 - file_distance.max(rank_distance) / 2) * CENTRE_BONUS
+        }
+        Piece::Rook | Piece::Queen | Piece::King => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reply(fen: &str, depth: u8) -> String {
+        let position = Board::from_fen(fen).unwrap();
+        let chosen = best_move(&position.board, position.side_to_move(), depth).unwrap();
+        position.board.san(&chosen)
+    }
+
+    #[test]
+    fn takes_a_hanging_queen() {
+        assert_eq!(reply("4k3/8/8/3q4/4P3/8/8/4K3 w - - 0 1", 1), "exd5");
+    }
+
+    #[test]
+    fn finds_mate_in_one() {
+        assert_eq!(reply("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1", 2), "Ra8#");
+    }
+
+    #[test]
+    fn does_not_take_a_defended_pawn_with_the_queen() {
+        // Qxd5 wins a pawn, then the e6 pawn takes the queen
+        let chosen = reply("4k3/8/4p3/3p4/8/8/8/3QK3 w - - 0 1", 2);
+        assert_ne!(chosen, "Qxd5");
+    }
+
+    #[test]
+    fn good_moves_keep_the_near_best() {
+        let position = Board::from_fen("4k3/8/8/3q4/4P3/8/8/4K3 w - - 0 1").unwrap();
+        let only_best = good_moves(&position.board, Color::White, 1, 0);
+        assert_eq!(only_best.len(), 1);
+        assert_eq!(position.board.san(&only_best[0]), "exd5");
+        // Every move is within a queen of the best, nothing within a pawn
+        assert_eq!(good_moves(&position.board, Color::White, 1, 2000).len(), position.board.legal_moves(Color::White).len());
+        assert_eq!(good_moves(&position.board, Color::White, 1, 50).len(), 1);
+    }
+
+    #[test]
+    fn no_move_when_mated_or_stalemated() {
+        let mated = Board::from_fen("R5k1/5ppp/8/8/8/8/8/6K1 b - - 0 1").unwrap();
+        assert!(best_move(&mated.board, Color::Black, 2).is_none());
+        let stalemated = Board::from_fen("k7/2Q5/8/8/8/8/8/K7 b - - 0 1").unwrap();
+        assert!(best_move(&stalemated.board, Color::Black, 2).is_none());
+    }
+
+    #[test]
+    fn centre_is_worth_more_than_the_rim() {
+        let centre = centre_bonus(Piece::Knight, Square::from_name("e4").unwrap());
+        let rim = centre_bonus(Piece::Knight, Square::from_name("a1").unwrap());
+        assert_eq!((centre, rim), (3 * CENTRE_BONUS, 0));
+        assert_eq!(centre_bonus(Piece::Rook, Square::from_name("e4").unwrap()), 0);
+    }
+}
@@ -0,0 +1,112 @@
+//! Dry-run validation - replays a game on the board without rendering it and
+//! stops at the first move that cannot be played.
+//!
+//! ```text
+//! e4 e5 Nf3 Nc6 Bb5            → Ok(5)
+//! e4 e5 Ke3                    → 2. Ke3: illegal move
+//! Nf3 a6 d3 a5 Nd2            → 3. Nd2: ambiguous move, could come from b1 or f3
+//! ```
+//!
+//! The moves are played with [`Game::push_san`], so a move must name
+//! exactly one piece that can make it without leaving its king in check.
+
+use std::fmt;
+
+use super::board::Position;
+use crate::game::{Game, PushMoveError};
+
+/// The first move of a game that cannot be played, labelled PGN-style.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidateGameError {
+    pub move_index: usize,
+    pub notation: String,
+    pub reason: PushMoveError,
+}
+
+impl fmt::Display for ValidateGameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let label = move_label(self.move_index, &self.notation);
+        match &self.reason {
+            PushMoveError::Unreadable(_) => write!(f, "{label}: not a move"),
+            PushMoveError::Unplayable(reason) => write!(f, "{label}: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for ValidateGameError {}
+
+/// Plays `moves` (bare SAN separated by whitespace, as in
+/// [`super::pgn::Game::moves`]) from the starting position and returns how
+/// many there were, or the first one that is unreadable, illegal or ambiguous.
+pub fn validate(moves: &str) -> Result<usize, ValidateGameError> {
+    validate_from(moves, &Position::default())
+}
+
+/// Like [`validate`], playing from `start`; errors are labelled with the
+/// game's move numbers, e.g. "40... Kf7".
+pub fn validate_from(moves: &str, start: &Position) -> Result<usize, ValidateGameError> {
+    let mut game = Game::from_position(start.clone());
+    for notation in moves.split_whitespace() {
+        let move_index = game.ply();
+        game.push_san(notation).map_err(|reason| ValidateGameError { move_index, notation: notation.to_string(), reason })?;
+    }
+    Ok(game.history().len())
+}
+
+/// "12. Nxe5" for White, "12... Nc6" for Black.
+fn move_label(move_index: usize, notation: &str) -> String {
+    let full_move = move_index / 2 + 1;
+    if move_index.is_multiple_of(2) {
+        format!("{full_move}. {notation}")
+    } else {
+        format!("{full_move}... {notation}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::board::{Board, ResolveMoveError};
+
+    #[test]
+    fn legal_game_counts_its_moves() {
+        assert_eq!(validate("e4 e5 Nf3 Nc6 Bb5 a6 O-O Nf6"), Ok(8));
+        assert_eq!(validate(""), Ok(0));
+        let opera_game = "e4 e5 Nf3 d6 d4 Bg4 dxe5 Bxf3 Qxf3 dxe5 Bc4 Nf6 Qb3 Qe7 Nc3 c6 Bg5 b5 Nxb5 cxb5 \
+                          Bxb5+ Nbd7 O-O-O Rd8 Rxd7 Rxd7 Rd1 Qe6 Bxd7+ Nxd7 Qb8+ Nxb8 Rd8#";
+        assert_eq!(validate(opera_game), Ok(33));
+    }
+
+    #[test]
+    fn reports_the_first_illegal_move() {
+        let err = validate("e4 e5 Ke3 Nc6").unwrap_err();
+        assert_eq!(err.to_string(), "2. Ke3: illegal move");
+        let err = validate("e4 e5 Nf3 Nf3").unwrap_err();
+        assert_eq!(err.to_string(), "2... Nf3: illegal move");
+    }
+
+    #[test]
+    fn reports_ambiguous_moves() {
+        let err = validate("Nf3 a6 d3 a5 Nd2").unwrap_err();
+        assert!(matches!(err.reason, PushMoveError::Unplayable(ResolveMoveError::Ambiguous(_))));
+        assert_eq!(err.move_index, 4);
+        assert_eq!(err.to_string(), "3. Nd2: ambiguous move, could come from b1 or f3");
+        assert_eq!(validate("Nf3 a6 d3 a5 Nbd2"), Ok(5));
+    }
+
+    #[test]
+    fn validates_from_a_position() {
+        let start = Board::from_fen("4k3/8/8/8/8/8/4P3/4K3 b - - 0 40").unwrap();
+        assert_eq!(validate_from("Kd7 e4 Kc6", &start), Ok(3));
+        let err = validate_from("Kd7 e5", &start).unwrap_err();
+        assert_eq!(err.to_string(), "41. e5: illegal move");
+    }
+
+    #[test]
+    fn reports_unreadable_tokens() {
+        let err = validate("e4 hello").unwrap_err();
+        let reason = PushMoveError::Unreadable("hello".to_string());
+        assert_eq!(err, ValidateGameError { move_index: 1, notation: "hello".to_string(), reason });
+        assert_eq!(err.to_string(), "1... hello: not a move");
+    }
+}
@@ -0,0 +1,109 @@
+//! One error type for applications embedding the crate, so a single `?`
+//! carries failures from the engine, the audio output and the file system:
+//!
+//! ```text
+//! fn run() -> Result<(), ChessWavError> {
+//!     let mut game = Game::new();
+//!     game.push_san("e4")?;                                  PushMoveError
+//!     let wav = audio::to_wav(&game.to_samples(&config));
+//!     audio::play(&wav)?;                                    AudioError
+//!     std::fs::write("game.wav", wav)?;                      io::Error
+//!     Ok(())
+//! }
+//! ```
+//!
+//! Each module keeps its own error enum for callers that want to match on
+//! the details; they all convert into [`ChessWavError`].
+
+use std::fmt;
+use std::io;
+
+use crate::audio::AudioError;
+use crate::engine::board::ResolveMoveError;
+use crate::game::PushMoveError;
+
+#[derive(Debug)]
+pub enum ChessWavError {
+    /// Text that is not algebraic notation, e.g. "Nf9"
+    Notation(String),
+    /// A move the board cannot resolve or play
+    Resolution(ResolveMoveError),
+    /// The audio could not be played
+    Audio(AudioError),
+    Io(io::Error),
+}
+
+impl fmt::Display for ChessWavError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ChessWavError::Notation(notation) => write!(f, "{notation}: not a move"),
+            ChessWavError::Resolution(err) => write!(f, "{err}"),
+            ChessWavError::Audio(err) => write!(f, "{err}"),
+            ChessWavError::Io(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl std::error::Error for ChessWavError {}
+
+impl From<ResolveMoveError> for ChessWavError {
+    fn from(err: ResolveMoveError) -> Self {
+        ChessWavError::Resolution(err)
+    }
+}
+
+impl From<PushMoveError> for ChessWavError {
+    fn from(err: PushMoveError) -> Self {
+        match err {
+            PushMoveError::Unreadable(notation) => ChessWavError::Notation(notation),
+            PushMoveError::Unplayable(reason) => ChessWavError::Resolution(reason),
+        }
+    }
+}
+
+impl From<AudioError> for ChessWavError {
+    fn from(err: AudioError) -> Self {
+        match err {
+            // An I/O failure is one whichever part of the crate hit it
+            AudioError::Io(err) => ChessWavError::Io(err),
+            AudioError::NoPlayer { .. } => ChessWavError::Audio(err),
+        }
+    }
+}
+
+impl From<io::Error> for ChessWavError {
+    fn from(err: io::Error) -> Self {
+        ChessWavError::Io(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Game;
+
+    fn push(moves: &[&str]) -> Result<Game, ChessWavError> {
+        let mut game = Game::new();
+        for notation in moves {
+            game.push_san(notation)?;
+        }
+        Ok(game)
+    }
+
+    #[test]
+    fn engine_errors_convert() {
+        assert!(matches!(push(&["e4", "Nf9"]), Err(ChessWavError::Notation(notation)) if notation == "Nf9"));
+        let err = push(&["e4", "Ke7"]).unwrap_err();
+        assert!(matches!(err, ChessWavError::Resolution(ResolveMoveError::Illegal)));
+        assert_eq!(err.to_string(), "illegal move");
+        assert_eq!(push(&["e4", "e5"]).unwrap().moves(), ["e4", "e5"]);
+    }
+
+    #[test]
+    fn audio_io_errors_are_io_errors() {
+        let err = ChessWavError::from(AudioError::Io(io::Error::other("broken pipe")));
+        assert!(matches!(err, ChessWavError::Io(_)));
+        let err = ChessWavError::from(AudioError::NoPlayer { tried: vec!["aplay"] });
+        assert_eq!(err.to_string(), "no audio player found (install one of: aplay)");
+    }
+}
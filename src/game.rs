@@ -0,0 +1,299 @@
+//! A game of chess as a library consumer sees it: SAN in, PGN and audio
+//! out, with the board, history and result kept together.
+//!
+//! ```text
+//! let mut game = Game::new();
+//! game.push_san("e4")?;        parse → resolve → play, in one call
+//! game.push_san("e5")?;
+//! game.undo();                 → Some("e5")
+//! game.resign(Color::Black);   → result(): Some(Resigned(Black)), "1-0"
+//! game.to_pgn();               → "[Result \"1-0\"]\n\n1. e4 1-0\n"
+//! game.to_samples(&config);    → the moves as `chesswav` renders them
+//! ```
+//!
+//! Moves are checked with [`Board::resolve_legal_move`] and kept as the
+//! board writes them ([`Board::san`]), so "Nf3" and "Ng1f3" land in the
+//! history alike and checks carry their "+" into the audio.
+
+use std::fmt;
+
+use crate::audio::{self, AudioConfig};
+use crate::engine::board::{Board, Color, Position, ResolveMoveError, Undo};
+use crate::engine::chess::{NotationMove, ResolvedMove};
+use crate::engine::pgn;
+
+/// Why [`Game::push_san`] rejected a move.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PushMoveError {
+    /// Not algebraic notation
+    Unreadable(String),
+    /// Notation the position does not allow
+    Unplayable(ResolveMoveError),
+}
+
+impl fmt::Display for PushMoveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PushMoveError::Unreadable(notation) => write!(f, "{notation}: not a move"),
+            PushMoveError::Unplayable(reason) => write!(f, "{reason}"),
+        }
+    }
+}
+
+impl std::error::Error for PushMoveError {}
+
+impl From<ResolveMoveError> for PushMoveError {
+    fn from(err: ResolveMoveError) -> Self {
+        PushMoveError::Unplayable(err)
+    }
+}
+
+/// How the game ended: on the board, or by the players.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GameResult {
+    Checkmate { winner: Color },
+    Stalemate,
+    /// The side that resigned
+    Resigned(Color),
+    DrawAgreed,
+}
+
+impl GameResult {
+    /// The PGN result: "1-0", "0-1" or "1/2-1/2".
+    pub fn tag(self) -> &'static str {
+        match self {
+            GameResult::Checkmate { winner: Color::White } | GameResult::Resigned(Color::Black) => "1-0",
+            GameResult::Checkmate { winner: Color::Black } | GameResult::Resigned(Color::White) => "0-1",
+            GameResult::Stalemate | GameResult::DrawAgreed => "1/2-1/2",
+        }
+    }
+
+    /// The result in words: "White resigns - Black wins".
+    pub fn describe(self) -> String {
+        match self {
+            GameResult::Checkmate { winner: Color::White } => "Checkmate - White wins".to_string(),
+            GameResult::Checkmate { winner: Color::Black } => "Checkmate - Black wins".to_string(),
+            GameResult::Stalemate => "Stalemate".to_string(),
+            GameResult::Resigned(Color::White) => "White resigns - Black wins".to_string(),
+            GameResult::Resigned(Color::Black) => "Black resigns - White wins".to_string(),
+            GameResult::DrawAgreed => "Draw agreed".to_string(),
+        }
+    }
+}
+
+/// A move of the game: how the board writes it, the move itself, and what
+/// takes it back.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlayedMove {
+    pub san: String,
+    pub resolved: ResolvedMove,
+    pub undo: Undo,
+}
+
+/// A board, the moves that led to it from `start`, and a way back.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Game {
+    start: Position,
+    board: Board,
+    /// Oldest first
+    history: Vec<PlayedMove>,
+    /// Set by [`Game::resign`] and [`Game::agree_draw`]
+    concluded: Option<GameResult>,
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Game {
+    /// A game from the initial position, White to move.
+    pub fn new() -> Self {
+        Self::from_position(Position::default())
+    }
+
+    /// A game from `start`, e.g. a position read with [`Board::from_fen`].
+    pub fn from_position(start: Position) -> Self {
+        Game { board: start.board.clone(), start, history: Vec::new(), concluded: None }
+    }
+
+    pub fn start(&self) -> &Position {
+        &self.start
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    /// Half-moves since the initial position, counting those before `start`.
+    pub fn ply(&self) -> usize {
+        self.start.ply + self.history.len()
+    }
+
+    pub fn side_to_move(&self) -> Color {
+        if self.ply().is_multiple_of(2) { Color::White } else { Color::Black }
+    }
+
+    /// The moves played so far in SAN, oldest first.
+    pub fn moves(&self) -> Vec<&str> {
+        self.history.iter().map(|played| played.san.as_str()).collect()
+    }
+
+    /// The moves played so far, oldest first.
+    pub fn history(&self) -> &[PlayedMove] {
+        &self.history
+    }
+
+    /// Plays `notation` for the side to move. It must name exactly one
+    /// piece that can make it without leaving its king in check.
+    pub fn push_san(&mut self, notation: &str) -> Result<(), PushMoveError> {
+        let chess_move =
+            NotationMove::parse(notation, self.ply()).ok_or_else(|| PushMoveError::Unreadable(notation.to_string()))?;
+        let resolved = self.board.resolve_legal_move(&chess_move, notation, self.side_to_move())?;
+        Ok(self.push(resolved)?)
+    }
+
+    /// Plays `resolved`, a move already checked against the board, e.g. one
+    /// from [`Board::legal_moves`].
+    pub fn push(&mut self, resolved: ResolvedMove) -> Result<(), ResolveMoveError> {
+        let san = self.board.san(&resolved);
+        let undo = self.board.make_move(&resolved)?;
+        self.history.push(PlayedMove { san, resolved, undo });
+        Ok(())
+    }
+
+    /// Takes back the last move, returning it in SAN; None at the start.
+    /// A resignation or agreed draw goes with it.
+    pub fn undo(&mut self) -> Option<String> {
+        let played = self.history.pop()?;
+        self.board.unmake_move(&played.resolved, &played.undo);
+        self.concluded = None;
+        Some(played.san)
+    }
+
+    /// `color` gives up the game.
+    pub fn resign(&mut self, color: Color) {
+        self.concluded = Some(GameResult::Resigned(color));
+    }
+
+    /// Both sides settle for a draw.
+    pub fn agree_draw(&mut self) {
+        self.concluded = Some(GameResult::DrawAgreed);
+    }
+
+    /// A resignation, an agreed draw, or checkmate or stalemate on the
+    /// board; None while the game goes on.
+    pub fn result(&self) -> Option<GameResult> {
+        if self.concluded.is_some() {
+            return self.concluded;
+        }
+        let color = self.side_to_move();
+        if !self.board.legal_moves(color).is_empty() {
+            return None;
+        }
+        if self.board.in_check(color) {
+            Some(GameResult::Checkmate { winner: color.opponent() })
+        } else {
+            Some(GameResult::Stalemate)
+        }
+    }
+
+    /// The game as PGN, with its Result tag ("*" while it goes on) and, from
+    /// a set-up position, its SetUp and FEN tags.
+    pub fn to_pgn(&self) -> String {
+        let mut pairs = vec![("Result".to_string(), self.result().map_or("*", GameResult::tag).to_string())];
+        if self.start != Position::default() {
+            pairs.push(("SetUp".to_string(), "1".to_string()));
+            pairs.push(("FEN".to_string(), self.start.to_fen()));
+        }
+        pgn::Game { tags: pgn::Tags::new(pairs), moves: self.moves().join(" "), comments: Vec::new() }.to_pgn()
+    }
+
+    /// The moves rendered with `config`, from this game's start whatever
+    /// `config.start` says.
+    pub fn to_samples(&self, config: &AudioConfig) -> Vec<i16> {
+        let config = AudioConfig { start: self.start.clone(), ..config.clone() };
+        audio::generate_with_config(&self.moves().join(" "), &config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn played(moves: &str) -> Game {
+        let mut game = Game::new();
+        for notation in moves.split_whitespace() {
+            game.push_san(notation).unwrap();
+        }
+        game
+    }
+
+    #[test]
+    fn pushes_moves_in_turn_as_the_board_writes_them() {
+        let game = played("e4 e5 Ng1f3 Nc6 Bb5 a6 Bxc6 dxc6");
+        assert_eq!(game.moves(), ["e4", "e5", "Nf3", "Nc6", "Bb5", "a6", "Bxc6", "dxc6"]);
+        assert_eq!(game.side_to_move(), Color::White);
+        assert_eq!(game.result(), None);
+    }
+
+    #[test]
+    fn rejects_unreadable_and_illegal_moves() {
+        let mut game = played("e4");
+        assert_eq!(game.push_san("xyz"), Err(PushMoveError::Unreadable("xyz".to_string())));
+        assert_eq!(game.push_san("Ke2"), Err(PushMoveError::Unplayable(ResolveMoveError::Illegal)));
+        assert_eq!(game.moves(), ["e4"]);
+        let mut knights = played("Nf3 a6 d3 a5");
+        assert!(matches!(knights.push_san("Nd2"), Err(PushMoveError::Unplayable(ResolveMoveError::Ambiguous(_)))));
+    }
+
+    #[test]
+    fn undo_takes_back_moves_in_reverse() {
+        let mut game = played("e4 d5 exd5");
+        assert_eq!(game.undo(), Some("exd5".to_string()));
+        assert_eq!(game.board(), played("e4 d5").board());
+        assert_eq!(game.undo(), Some("d5".to_string()));
+        assert_eq!(game.undo(), Some("e4".to_string()));
+        assert_eq!(game.undo(), None);
+        assert_eq!(game.board(), &Board::new());
+    }
+
+    #[test]
+    fn checkmate_and_stalemate_end_the_game() {
+        let mate = played("f3 e5 g4 Qh4");
+        assert_eq!(mate.moves().last(), Some(&"Qh4#"));
+        assert_eq!(mate.result(), Some(GameResult::Checkmate { winner: Color::Black }));
+        assert_eq!(mate.to_pgn(), "[Result \"0-1\"]\n\n1. f3 e5 2. g4 Qh4# 0-1\n");
+        let mut stalemate = Game::from_position(Board::from_fen("k7/8/1Q6/8/8/8/8/K7 w - - 0 1").unwrap());
+        stalemate.push_san("Qc7").unwrap();
+        assert_eq!(stalemate.result(), Some(GameResult::Stalemate));
+    }
+
+    #[test]
+    fn resignations_and_agreed_draws_end_the_game_until_undone() {
+        let mut game = played("e4 e5");
+        game.resign(Color::White);
+        assert_eq!(game.result(), Some(GameResult::Resigned(Color::White)));
+        assert_eq!(game.to_pgn(), "[Result \"0-1\"]\n\n1. e4 e5 0-1\n");
+        assert_eq!(GameResult::Resigned(Color::White).describe(), "White resigns - Black wins");
+        game.undo();
+        assert_eq!(game.result(), None);
+        game.agree_draw();
+        assert_eq!(game.result().map(GameResult::tag), Some("1/2-1/2"));
+    }
+
+    #[test]
+    fn pgn_from_a_position_carries_its_fen() {
+        let fen = "4k3/8/8/8/8/8/4P3/4K3 b - - 0 40";
+        let mut game = Game::from_position(Board::from_fen(fen).unwrap());
+        game.push_san("Kd7").unwrap();
+        assert_eq!(game.to_pgn(), format!("[Result \"*\"]\n[SetUp \"1\"]\n[FEN \"{fen}\"]\n\n40... Kd7 *\n"));
+    }
+
+    #[test]
+    fn samples_match_the_cli_render() {
+        let game = played("e4 e5 Qh5 Nc6 Bc4 Nf6 Qxf7");
+        assert_eq!(game.to_samples(&AudioConfig::default()), audio::generate("e4 e5 Qh5 Nc6 Bc4 Nf6 Qxf7#"));
+    }
+}
@@ -1,3 +1,9 @@
 pub mod audio;
+pub mod config;
 pub mod engine;
+pub mod error;
+pub mod game;
+pub mod render;
+pub mod rng;
+pub mod toml;
 pub mod tui;
@@ -5,64 +5,577 @@
 //! ```text
 //! # Generate WAV file
 //! echo "e4 e5 Nf3 Nc6" | cargo run --release > game.wav
+//! echo "e4 e5 Nf3 Nc6" | cargo run --release -- -o game.wav
 //!
-//! # Play audio directly (macOS/Linux)
+//! # Binary output to a terminal needs an explicit --stdout
+//! echo "e4 e5 Nf3 Nc6" | cargo run --release -- --stdout | xxd | head
+//!
+//! # Play audio directly (afplay, aplay, paplay, pw-play or PowerShell)
 //! echo "e4 e5 Nf3 Nc6" | cargo run --release -- --play
 //! echo "e4 e5 Nf3 Nc6" | cargo run --release -- -p
 //!
+//! # Loop playback for drilling a tactic: forever (Ctrl-C to stop) or N times
+//! echo "Nxe5 Bxd1 Bxf7+ Ke7 Nd5#" | cargo run --release -- --play --loop
+//! echo "Nxe5 Bxd1 Bxf7+ Ke7 Nd5#" | cargo run --release -- --play --loop 3
+//!
+//! # List output devices, then play on one (Linux players only)
+//! cargo run --release -- --list-devices
+//! echo "e4 e5 Nf3 Nc6" | cargo run --release -- --play --device hw:1,0
+//!
+//! # One channel per piece type (6-channel WAV for remixing)
+//! # timed and voiced like the mono render; not with --stereo, --spatial,
+//! # --soundfont or --samples
+//! echo "e4 e5 Nf3 Nc6" | cargo run --release -- --multichannel > game.wav
+//!
+//! # Stereo, each note panned by its file (a = left, h = right), or by side
+//! # (White left, Black right)
+//! echo "e4 e5 Nf3 Nc6" | cargo run --release -- --stereo > game.wav
+//! echo "e4 e5 Nf3 Nc6" | cargo run --release -- --stereo side > game.wav
+//!
+//! # Headphones: files in azimuth, far ranks quieter and duller
+//! echo "e4 e5 Nf3 Nc6" | cargo run --release -- --spatial > game.wav
+//!
+//! # Add room reverb
+//! echo "e4 e5 Nf3 Nc6" | cargo run --release -- --reverb > game.wav
+//!
+//! # Glide between successive notes over 80 ms
+//! echo "e4 e5 Nf3 Nc6" | cargo run --release -- --portamento 80 > game.wav
+//!
+//! # Voice Black an octave lower and darker than White
+//! echo "e4 e5 Nf3 Nc6" | cargo run --release -- --color-voicing > game.wav
+//!
+//! # Louder notes for captures, checks and promotions
+//! echo "e4 e5 Nf3 Nc6" | cargo run --release -- --dynamics > game.wav
+//!
+//! # Tick on every full move, kick on moves 10, 20, …
+//! echo "e4 e5 Nf3 Nc6" | cargo run --release -- --percussion > game.wav
+//!
+//! # Crossfade notes into each other instead of separating them
+//! echo "e4 e5 Nf3 Nc6" | cargo run --release -- --legato > game.wav
+//!
+//! # Note length and gap in ms (default 300 + 50), or a tempo in moves per
+//! # minute; --note-ms/--gap-ms override the tempo's split
+//! echo "e4 e5 Nf3 Nc6" | cargo run --release -- --note-ms 120 --gap-ms 30 > game.wav
+//! echo "e4 e5 Nf3 Nc6" | cargo run --release -- --tempo 40 > game.wav
+//!
+//! # Random ±ms offsets on onsets and lengths; the same --seed repeats a take
+//! echo "e4 e5 Nf3 Nc6" | cargo run --release -- --humanize 15 --seed 7 > game.wav
+//!
+//! # One semitone per square, a1 (C2) to h8 (D#7), for ear training
+//! echo "e4 e5 Nf3 Nc6" | cargo run --release -- --chromatic > game.wav
+//!
+//! # Fold the ranks into 3 octaves (C3–C6) for small speakers, or pass a count
+//! echo "e4 e5 Nf3 Nc6" | cargo run --release -- --fold > game.wav
+//! echo "e4 e5 Nf3 Nc6" | cargo run --release -- --fold 4 > game.wav
+//!
+//! # Snap every pitch into a key (major, minor, pentatonic, blues, whole-tone)
+//! echo "e4 e5 Nf3 Nc6" | cargo run --release -- --key D --scale minor > game.wav
+//!
+//! # Just intonation or Pythagorean tuning, relative to the key's tonic
+//! echo "e4 e5 Nf3 Nc6" | cargo run --release -- --tuning just --key G > game.wav
+//!
+//! # Built-in sound theme (classic, 8-bit, ambient) or your own TOML file
+//! echo "e4 e5 Nf3 Nc6" | cargo run --release -- --sound-theme 8-bit > game.wav
+//! echo "e4 e5 Nf3 Nc6" | cargo run --release -- --sound-theme my-theme.toml > game.wav
+//!
+//! # Override waveforms per piece (or piece.threat), or for all pieces
+//! echo "e4 e5 Nf3 Nc6" | cargo run --release -- --waveform pawn=sine,knight=fm > game.wav
+//! echo "e4 e5 Nf3 Nc6" | cargo run --release -- --all-waveform square > game.wav
+//!
+//! # Normalize integrated loudness (default -16 LUFS, or pass a target)
+//! echo "e4 e5 Nf3 Nc6" | cargo run --release -- --lufs > game.wav
+//! echo "e4 e5 Nf3 Nc6" | cargo run --release -- --lufs -23 > game.wav
+//!
+//! # Normalize peaks to -1 dBFS and soft-limit overs
+//! echo "e4 e5 Nf3 Nc6" | cargo run --release -- --normalize --limit > game.wav
+//!
+//! # Master gain in dB, applied before quantization (add --limit when boosting)
+//! echo "e4 e5 Nf3 Nc6" | cargo run --release -- --gain -6 > game.wav
+//!
+//! # Render with a SoundFont (build with `--features soundfont`)
+//! echo "e4 e5 Nf3 Nc6" | cargo run --release --features soundfont -- --soundfont piano.sf2 > game.wav
+//!
+//! # Output format: wav (default), midi, flac, raw or ogg; -o infers it from
+//! # the extension unless --format is given
+//! echo "e4 e5 Nf3 Nc6" | cargo run --release -- -o game.flac
+//! echo "e4 e5 Nf3 Nc6" | cargo run --release -- --format midi > game.mid
+//!
+//! # Output sample rate in Hz (default 44100)
+//! echo "e4 e5 Nf3 Nc6" | cargo run --release -- --sample-rate 48000 > game.wav
+//!
+//! # Export Ogg Opus via opusenc (build with `--features opus`, needs opus-tools)
+//! echo "e4 e5 Nf3 Nc6" | cargo run --release --features opus -- --format ogg > game.opus
+//!
+//! # Play your own recordings (pawn.wav, knight.wav, … recorded at A4)
+//! echo "e4 e5 Nf3 Nc6" | cargo run --release -- --samples ./samples > game.wav
+//!
 //! # Interactive mode
+//! cargo run --release -- tui
 //! cargo run --release -- --interactive
 //! cargo run --release -- -i
 //!
-//! # Interactive with display mode (sprite, unicode, ascii)
+//! # Interactive with display mode (sprite, sprite:large, sprite:small, unicode, ascii)
 //! cargo run --release -- --interactive --display sprite
 //! cargo run --release -- -i -d unicode
 //!
+//! # The engine plays both sides, endlessly (Ctrl-C to stop): one move a
+//! # second by default, --depth half-moves of look-ahead (1-5, default 2),
+//! # --seed to replay the same games
+//! cargo run --release -- autoplay --delay 500 --depth 3 -d unicode
+//!
+//! # Follow a game another program writes, playing each move as it lands
+//! # (a PGN or a plain move list; without a file, stdin line by line)
+//! cargo run --release -- watch live.pgn -d unicode
+//! relay-client | cargo run --release -- watch
+//!
+//! # Force the color depth instead of detecting it from COLORTERM
+//! cargo run --release -- -i --color-mode 256
+//!
+//! # Defaults (display, color_mode, sound_theme, tempo, format) come from
+//! # ~/.config/chesswav/config.toml; flags override them. The REPL saves
+//! # its display, theme, soundtheme, volume and coords there as they change
+//! cargo run --release -- --config my-config.toml -o game.wav
+//!
 //! # From a file
 //! cargo run --release < moves.txt > game.wav
 //!
+//! # PGN on stdin (recognised by its tag pairs) is read like --pgn; the tags
+//! # ([Event], [White], [Black], [Date], …) become WAV metadata
+//! cargo run --release < game.pgn > game.wav
+//!
+//! # Read a PGN file (move numbers, comments, variations skipped); pick a
+//! # game from a multi-game file with --game N (default: the first). A game
+//! # with a FEN tag starts from it, as with --fen
+//! cargo run --release -- --pgn games.pgn --game 2 -o game.wav
+//!
+//! # Continue from a FEN position (side to move and move number included);
+//! # also sets the starting board of -i
+//! echo "e5 Nf3" | cargo run --release -- --fen "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1" > game.wav
+//!
+//! # Batch: every game of a PGN file (or stdin) to its own file, named from
+//! # its number and players (001-carlsen-nepomniachtchi.wav, …); --parallel
+//! # renders several at once (default: one per CPU, or pass a count)
+//! cargo run --release -- --pgn games.pgn --outdir out/ --parallel
+//!
+//! # Trace every move (origin, destination, pitch, waveform) on stderr, or
+//! # silence notes such as "rendering the first game"
+//! echo "e4 e5 Nf3 Nc6" | cargo run --release -- --verbose > game.wav
+//! cargo run --release -- --pgn games.pgn --quiet -o game.wav
+//!
+//! # Tokens that are not moves are skipped with a warning; the audio is still
+//! # written and the exit status is 2
+//! echo "e4 e5 Nf9 Nc6" | cargo run --release > game.wav
+//!
+//! # Game statistics (moves, captures, checks, pitch range, length) on stderr,
+//! # as a summary or as JSON
+//! echo "e4 e5 Nf3 Nc6" | cargo run --release -- --stats > game.wav
+//! echo "e4 e5 Nf3 Nc6" | cargo run --release -- --stats json -o game.wav 2> stats.json
+//!
+//! # Check a game or a whole PGN database without rendering: prints the first
+//! # illegal or ambiguous move of each bad game and exits non-zero
+//! echo "e4 e5 Ke3" | cargo run --release -- --validate
+//! cargo run --release -- --validate --pgn games.pgn
+//!
+//! # Cue markers labelled "1. e4", "1... e5", … at each move, for DAWs
+//! echo "e4 e5 Nf3 Nc6" | cargo run --release -- --cues > game.wav
+//!
+//! # Broadcast WAV: origination date/time and an optional description
+//! echo "e4 e5 Nf3 Nc6" | cargo run --release -- --bwf "Ruy Lopez, move 2" > game.wav
+//!
 //! # After `cargo install --path .`
 //! echo "e4 e5 Nf3 Nc6" | chesswav > game.wav
 //! echo "e4 e5 Nf3 Nc6" | chesswav --play
-//! chesswav --interactive
+//! chesswav tui
+//! chesswav autoplay
+//! chesswav watch live.pgn
 //! chesswav --interactive --display ascii
 //! ```
 
-use std::io::{self, Read, Write};
+mod cli;
+
+use std::io::{self, IsTerminal, Read};
 
 use chesswav::audio;
-use chesswav::tui::display;
+use chesswav::config;
+use chesswav::engine::board::Position;
+use chesswav::engine::{pgn, validate};
+use chesswav::render::{self, Renderer, Verbosity};
+use chesswav::tui::{autoplay, display, watch};
 use chesswav::tui::repl;
 
+use cli::{Args, Command, Repeats};
+
+/// Exit status when the audio was written but some tokens were not moves.
+const EXIT_SKIPPED_MOVES: i32 = 2;
+
 fn main() {
-    let args: Vec<String> = std::env::args().collect();
-    let play_mode: bool = args.iter().any(|a| a == "--play" || a == "-p");
-    let interactive: bool = args.iter().any(|a| a == "--interactive" || a == "-i");
-
-    let display_mode = args
-        .windows(2)
-        .find(|w| w[0] == "--display" || w[0] == "-d")
-        .map(|w| {
-            display::parse_display_mode(&w[1]).unwrap_or_else(|| {
-                eprintln!("Unknown display mode: {}. Options: sprite, unicode, ascii", w[1]);
-                std::process::exit(1);
-            })
+    let args = cli::parse(&std::env::args().skip(1).collect::<Vec<_>>()).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    });
+    let user_config = load_config(args.config_path.as_deref());
+    let (sound_theme, theme) = choose_theme(&args, &user_config);
+    let config = audio_config(&args, user_config.tempo, theme);
+
+    if args.list_devices {
+        list_devices();
+        return;
+    }
+
+    match &args.command {
+        Command::Render => render(&args, &user_config, &config),
+        Command::Interactive => {
+            let (display_mode, color_mode) = board_modes(&args, &user_config);
+            let preferences = repl::Preferences {
+                palette: user_config.board_theme.unwrap_or_default(),
+                coordinates: user_config.coordinates.unwrap_or(true),
+                volume: user_config.volume.unwrap_or(100),
+                sound_theme: sound_theme.map(str::to_string),
+                error_sound: user_config.error_sound.unwrap_or(true),
+                timing: config.timing,
+                config_path: args.config_path.as_deref().map(std::path::PathBuf::from).or_else(config::default_path),
+            };
+            repl::run(display_mode, color_mode, config.start.clone(), preferences);
+        }
+        Command::Autoplay => {
+            let (display_mode, color_mode) = board_modes(&args, &user_config);
+            let autoplay = autoplay::Autoplay {
+                delay_ms: args.delay_ms,
+                depth: args.depth,
+                seed: args.seed.unwrap_or_else(clock_seed),
+            };
+            autoplay::run(display_mode, color_mode, config.start.clone(), autoplay);
+        }
+        Command::Watch(source) => {
+            let (display_mode, color_mode) = board_modes(&args, &user_config);
+            watch::run(display_mode, color_mode, config.start.clone(), source.clone());
+        }
+    }
+}
+
+/// The sound theme named by --sound-theme or the config file, with the
+/// --waveform overrides applied, and its name as the REPL saves it.
+fn choose_theme<'a>(args: &'a Args, user_config: &'a config::Config) -> (Option<&'a str>, audio::theme::Theme) {
+    let (sound_theme, mut theme) = match (args.sound_theme.as_deref(), user_config.sound_theme.as_deref()) {
+        (Some(name), _) => (Some(name), load_theme(name)),
+        (None, Some(name)) => match audio::theme::Theme::from_name_or_file(name) {
+            Ok(theme) => (Some(name), theme),
+            // A theme file gone since the config named it costs only its sounds
+            Err(err) => {
+                eprintln!("warning: sound_theme {}: {}. Using the default sounds", name, err);
+                (None, audio::theme::Theme::default())
+            }
+        },
+        (None, None) => (None, audio::theme::Theme::default()),
+    };
+    for overrides in &args.waveforms {
+        audio::theme::apply_waveforms(&mut theme.sounds, overrides).unwrap_or_else(|err| {
+            eprintln!("Invalid waveform override: {}", err);
+            std::process::exit(1);
         });
+    }
+    (sound_theme, theme)
+}
+
+/// The flags' audio settings over `theme`'s, timed by the flags or else
+/// the config file's `tempo`.
+fn audio_config(args: &Args, tempo: Option<f64>, theme: audio::theme::Theme) -> audio::AudioConfig {
+    let base_timing = args.tempo.or(tempo).map(audio::Timing::from_tempo).unwrap_or_default();
+    let timing = audio::Timing {
+        note_ms: args.note_ms.unwrap_or(base_timing.note_ms),
+        gap_ms: args.gap_ms.unwrap_or(base_timing.gap_ms),
+    };
+    if timing.slot_ms() == 0 {
+        eprintln!("--note-ms and --gap-ms cannot both be 0");
+        std::process::exit(1);
+    }
+    audio::AudioConfig {
+        reverb: args.reverb.then(audio::Reverb::default),
+        portamento_ms: args.portamento_ms,
+        loudness_target: args.loudness_target,
+        normalize_peak: args.normalize.then_some(audio::DEFAULT_PEAK),
+        gain: args.gain,
+        soft_limit: args.soft_limit,
+        color_voicing: args.color_voicing.then(audio::ColorVoicing::default),
+        dynamics: args.dynamics,
+        percussion: args.percussion,
+        legato: args.legato,
+        pitch: audio::PitchMap {
+            layout: if args.chromatic { audio::Layout::Chromatic } else { theme.pitch.layout },
+            key: args.key.or(theme.pitch.key),
+            tuning: args.tuning.unwrap_or(theme.pitch.tuning),
+            fold_octaves: args.fold_octaves.or(theme.pitch.fold_octaves),
+        },
+        sounds: theme.sounds,
+        stereo: args.stereo,
+        spatial: args.spatial,
+        timing,
+        // Without --seed every run is a new performance
+        humanize: args.humanize_ms.map(|max_ms| audio::Humanize { max_ms, seed: args.seed.unwrap_or_else(clock_seed) }),
+        start: args.start.clone(),
+    }
+}
 
-    if interactive {
-        repl::run(display_mode.unwrap_or(display::DisplayMode::Sprite));
+/// The board's display and color mode: from the flags, else the config
+/// file, else sprites in the colors the terminal supports.
+fn board_modes(args: &Args, user_config: &config::Config) -> (display::DisplayMode, display::ColorMode) {
+    let display_mode = args.display.or(user_config.display).unwrap_or(display::DisplayMode::Sprite(display::SpriteSet::Classic));
+    let color_mode = args.color_mode.or(user_config.color_mode).unwrap_or_else(display::detect_color_mode);
+    (display_mode, color_mode)
+}
+
+/// Renders the game from stdin or --pgn to stdout, a file or the speakers,
+/// or with --outdir every game to its own file. --validate only checks them.
+fn render(args: &Args, user_config: &config::Config, config: &audio::AudioConfig) {
+    let output_path = args.output.as_deref();
+    // An explicit --format wins, then -o's extension, then the config file
+    let format = args
+        .format
+        .or_else(|| output_path.and_then(|path| audio::Format::from_path(std::path::Path::new(path))))
+        .or(user_config.format)
+        .unwrap_or_default();
+    let pgn_path = args.pgn.as_deref();
+
+    if args.validate {
+        validate_games(pgn_path, args.game, &config.start);
         return;
     }
 
+    // Loaded once, however many games are rendered
+    let instruments = render::Instruments::load(
+        args.soundfont.as_deref().map(std::path::Path::new),
+        args.samples.as_deref().map(std::path::Path::new),
+    )
+    .unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    });
+    let renderer = Renderer {
+        config,
+        multichannel: args.multichannel,
+        instruments: &instruments,
+        broadcast: args.broadcast.as_deref(),
+        cues: args.cues,
+        sample_rate: args.sample_rate,
+        stats_json: args.stats_json,
+        verbosity: args.verbosity,
+    };
+
+    if let Some(dir) = &args.outdir {
+        let contents = match pgn_path {
+            Some(path) => read_pgn(path),
+            None => read_stdin(),
+        };
+        render_outdir(&renderer, &pgn::parse_games(&contents), args, std::path::Path::new(dir), format);
+        return;
+    }
+
+    let to_stdout = !args.play && output_path.is_none();
+    if to_stdout && io::stdout().is_terminal() && !args.stdout {
+        eprintln!("Refusing to write binary audio to the terminal. Use -o game.wav, redirect with > game.wav, or pass --stdout");
+        std::process::exit(1);
+    }
+
+    // PGN on stdin is read like a --pgn file; anything else is bare moves
+    let game = match pgn_path {
+        Some(path) => choose_game(pgn::parse_games(&read_pgn(path)), path, args.game, args.verbosity),
+        None => {
+            let input = read_stdin();
+            if pgn::is_pgn(&input) {
+                choose_game(pgn::parse_games(&input), "Input", args.game, args.verbosity)
+            } else {
+                pgn::Game { tags: pgn::Tags::default(), moves: input, comments: Vec::new() }
+            }
+        }
+    };
+    let rendered = renderer.render_game(&game).unwrap_or_else(|err| {
+        eprintln!("Invalid FEN tag: {}", err);
+        std::process::exit(1);
+    });
+    for line in &rendered.report {
+        eprintln!("{}", line);
+    }
+    let written = if args.play {
+        play(&rendered.to_wav(), args.device.as_deref(), args.repeats.unwrap_or(Repeats::Times(1))).map_err(|err| err.to_string())
+    } else if let Some(path) = output_path {
+        render::write_file(std::path::Path::new(path), format, &rendered).map_err(|err| err.to_string())
+    } else {
+        render::write_output(&mut io::stdout().lock(), format, &rendered).map_err(|err| err.to_string())
+    };
+    if let Err(err) = written {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    }
+    if !audio::skipped_tokens(&rendered.moves).is_empty() {
+        std::process::exit(EXIT_SKIPPED_MOVES);
+    }
+}
+
+/// Plays `wav` on `device` (the default one if None), `repeats` times.
+fn play(wav: &[u8], device: Option<&str>, repeats: Repeats) -> Result<(), audio::AudioError> {
+    match repeats {
+        Repeats::Times(times) => (0..times).try_for_each(|_| audio::play_on(wav, device)),
+        Repeats::Forever => loop {
+            audio::play_on(wav, device)?;
+        },
+    }
+}
+
+/// `--outdir`: renders the games with [`render::render_batch`] and prints
+/// each one's report in game order, exiting non-zero if any failed.
+fn render_outdir(renderer: &Renderer, games: &[pgn::Game], args: &Args, dir: &std::path::Path, format: audio::Format) {
+    let reports = render::render_batch(renderer, games, args.game, dir, format, args.workers).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    });
+    for report in &reports {
+        let path = report.path.display();
+        match &report.outcome {
+            Ok(lines) => {
+                // Bare JSON stats stay one object per line, in game order
+                let json_only = renderer.stats_json == Some(true) && lines.len() == 1;
+                if !lines.is_empty() && !json_only {
+                    eprintln!("{}:", path);
+                }
+                for line in lines {
+                    eprintln!("{}", line);
+                }
+            }
+            Err(err) => eprintln!("{}: {}", path, err),
+        }
+    }
+    let written = reports.iter().filter(|report| report.outcome.is_ok()).count();
+    if renderer.verbosity != Verbosity::Quiet {
+        eprintln!("Wrote {} of {} games to {}", written, reports.len(), dir.display());
+    }
+    if written < reports.len() {
+        std::process::exit(1);
+    }
+    if reports.iter().any(|report| report.skipped_moves) {
+        std::process::exit(EXIT_SKIPPED_MOVES);
+    }
+}
+
+/// Picks game `number` (1-based, default the first) of `games`, read from
+/// `source` (a path, or "Input" for stdin), exiting if there is no such game.
+fn choose_game(games: Vec<pgn::Game>, source: &str, number: Option<usize>, verbosity: Verbosity) -> pgn::Game {
+    if games.len() > 1 && number.is_none() && verbosity != Verbosity::Quiet {
+        eprintln!("{} has {} games; rendering the first (choose with --game N)", source, games.len());
+    }
+    let total = games.len();
+    let number = number.unwrap_or(1);
+    games.into_iter().nth(number - 1).unwrap_or_else(|| {
+        eprintln!("{} has {} games; there is no game {}", source, total, number);
+        std::process::exit(1);
+    })
+}
+
+/// `--validate`: replays every game of the PGN file at `path` (stdin if
+/// None), or only game `number`, without rendering audio. Each game starts
+/// from its FEN tag, or from `start` without one.
+/// Prints the first bad move of each invalid game and exits 1 if there is one.
+fn validate_games(path: Option<&str>, number: Option<usize>, start: &Position) {
+    let contents = match path {
+        Some(path) => read_pgn(path),
+        None => read_stdin(),
+    };
+    let source = path.unwrap_or("Input");
+    let games = pgn::parse_games(&contents);
+    let chosen: Vec<(usize, &pgn::Game)> = games
+        .iter()
+        .enumerate()
+        .map(|(idx, game)| (idx + 1, game))
+        .filter(|(game_number, _)| number.is_none_or(|wanted| wanted == *game_number))
+        .collect();
+    if chosen.is_empty() {
+        match number {
+            Some(number) => eprintln!("{} has {} games; there is no game {}", source, games.len(), number),
+            None => eprintln!("{} has no moves to validate", source),
+        }
+        std::process::exit(1);
+    }
+
+    let mut invalid = 0;
+    for (game_number, game) in &chosen {
+        let validated = render::game_start(game, start).map_err(|err| format!("Invalid FEN tag: {}", err));
+        match validated.and_then(|start| validate::validate_from(&game.moves, &start).map_err(|err| err.to_string())) {
+            Ok(count) if chosen.len() == 1 => println!("{} moves, all legal", count),
+            Ok(_) => {}
+            Err(err) if chosen.len() == 1 => {
+                eprintln!("{}", err);
+                invalid += 1;
+            }
+            Err(err) => {
+                eprintln!("Game {}: {}", game_number, err);
+                invalid += 1;
+            }
+        }
+    }
+    if chosen.len() > 1 {
+        println!("{} of {} games valid", chosen.len() - invalid, chosen.len());
+    }
+    if invalid > 0 {
+        std::process::exit(1);
+    }
+}
+
+fn read_stdin() -> String {
     let mut input = String::new();
     io::stdin().read_to_string(&mut input).ok();
+    input
+}
 
-    let samples: Vec<i16> = audio::generate(&input);
-    let wav: Vec<u8> = audio::to_wav(&samples);
+fn read_pgn(path: &str) -> String {
+    std::fs::read_to_string(path).unwrap_or_else(|err| {
+        eprintln!("Cannot read {}: {}", path, err);
+        std::process::exit(1);
+    })
+}
 
-    if play_mode {
-        audio::play(&wav);
-    } else {
-        io::stdout().lock().write_all(&wav).ok();
+fn list_devices() {
+    match audio::devices() {
+        Ok(devices) if devices.is_empty() => eprintln!("No selectable output devices found"),
+        Ok(devices) => {
+            for device in devices {
+                println!("{}\t{}", device.name, device.description);
+            }
+        }
+        Err(err) => {
+            eprintln!("Cannot list audio devices: {}", err);
+            std::process::exit(1);
+        }
     }
 }
+
+/// A seed that differs from run to run, from the clock's sub-second part.
+fn clock_seed() -> u32 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.subsec_nanos())
+}
+
+/// The config file at `path`, or the user's default one if no path is given.
+fn load_config(path: Option<&str>) -> config::Config {
+    let loaded = match path {
+        Some(path) => config::Config::load(std::path::Path::new(path)),
+        None => config::Config::load_default(),
+    };
+    loaded.unwrap_or_else(|err| {
+        let shown = path.map(std::path::PathBuf::from).or_else(config::default_path).unwrap_or_default();
+        eprintln!("{}: {}", shown.display(), err);
+        std::process::exit(1);
+    })
+}
+
+/// A built-in theme by name, otherwise a TOML file at `name`.
+fn load_theme(name: &str) -> audio::theme::Theme {
+    audio::theme::Theme::from_name_or_file(name).unwrap_or_else(|err| {
+        match err {
+            audio::theme::LoadThemeError::Io(_) => {
+                eprintln!("{}: {}. Built-in themes: {}", name, err, audio::theme::BUILTIN_NAMES.join(", "))
+            }
+            _ => eprintln!("{}: {}", name, err),
+        }
+        std::process::exit(1);
+    })
+}
@@ -0,0 +1,505 @@
+//! Rendering games the way the command line asks for them: the instrument,
+//! the output rate, WAV metadata, the report for stderr, and `--outdir`
+//! batches spread over worker threads.
+//!
+//! ```text
+//! Instruments::load(soundfont, samples)   read from disk once
+//!        │
+//! Renderer { config, instruments, … }     Copy, shared by every worker
+//!        │ render_game(&pgn::Game)
+//! RenderedGame ── write_output / write_file ──▶ wav, midi, flac, raw, ogg
+//! ```
+
+use std::fmt;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::audio::{self, AudioConfig, AudioError};
+use crate::engine::board::{Board, ParseFenError, Position};
+use crate::engine::pgn;
+
+/// How much goes to stderr besides errors and the reports asked for.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Verbosity {
+    /// Errors only (`--quiet`)
+    Quiet,
+    /// Errors and notes, e.g. which game of a PGN file is rendered
+    Normal,
+    /// Also a trace of every move (`--verbose`)
+    Verbose,
+}
+
+/// Why [`Instruments::load`] failed.
+#[derive(Debug)]
+pub enum LoadInstrumentError {
+    #[cfg(feature = "soundfont")]
+    SoundFont { path: PathBuf, error: audio::soundfont::LoadSoundFontError },
+    /// A SoundFont was asked for in a build without the `soundfont` feature
+    SoundFontUnsupported,
+    Samples(audio::sampler::LoadSamplesError),
+}
+
+impl fmt::Display for LoadInstrumentError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            #[cfg(feature = "soundfont")]
+            LoadInstrumentError::SoundFont { path, error } => write!(f, "{}: {error}", path.display()),
+            LoadInstrumentError::SoundFontUnsupported => write!(f, "--soundfont needs a build with `--features soundfont`"),
+            LoadInstrumentError::Samples(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl std::error::Error for LoadInstrumentError {}
+
+impl From<audio::sampler::LoadSamplesError> for LoadInstrumentError {
+    fn from(err: audio::sampler::LoadSamplesError) -> Self {
+        LoadInstrumentError::Samples(err)
+    }
+}
+
+/// Why [`write_output`] could not write a game.
+#[derive(Debug)]
+pub enum WriteGameError {
+    Audio(AudioError),
+    #[cfg(feature = "opus")]
+    Opus(audio::opus::EncodeOpusError),
+    /// Ogg was asked for in a build without the `opus` feature
+    OpusUnsupported,
+}
+
+impl fmt::Display for WriteGameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WriteGameError::Audio(err) => write!(f, "{err}"),
+            #[cfg(feature = "opus")]
+            WriteGameError::Opus(err) => write!(f, "{err}"),
+            WriteGameError::OpusUnsupported => write!(f, "--format ogg needs a build with `--features opus`"),
+        }
+    }
+}
+
+impl std::error::Error for WriteGameError {}
+
+impl From<AudioError> for WriteGameError {
+    fn from(err: AudioError) -> Self {
+        WriteGameError::Audio(err)
+    }
+}
+
+impl From<io::Error> for WriteGameError {
+    fn from(err: io::Error) -> Self {
+        WriteGameError::Audio(AudioError::Io(err))
+    }
+}
+
+#[cfg(feature = "opus")]
+impl From<audio::opus::EncodeOpusError> for WriteGameError {
+    fn from(err: audio::opus::EncodeOpusError) -> Self {
+        WriteGameError::Opus(err)
+    }
+}
+
+/// Why [`render_batch`] rendered nothing.
+#[derive(Debug)]
+pub enum RenderBatchError {
+    /// `--game N` past the end of the input
+    NoSuchGame { games: usize, number: usize },
+    CreateDir { path: PathBuf, error: io::Error },
+}
+
+impl fmt::Display for RenderBatchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RenderBatchError::NoSuchGame { games, number } => write!(f, "Input has {games} games; there is no game {number}"),
+            RenderBatchError::CreateDir { path, error } => write!(f, "Cannot create {}: {error}", path.display()),
+        }
+    }
+}
+
+impl std::error::Error for RenderBatchError {}
+
+/// The SoundFont and recorded samples a render plays instead of the
+/// synth, read once and shared by every game of a batch.
+#[derive(Default)]
+pub struct Instruments {
+    #[cfg(feature = "soundfont")]
+    soundfont: Option<audio::soundfont::SoundFont>,
+    sample_set: Option<audio::sampler::SampleSet>,
+}
+
+impl Instruments {
+    /// Loads the SoundFont at `soundfont` and the `<piece>.wav` files in
+    /// `samples`, whichever are given.
+    pub fn load(soundfont: Option<&Path>, samples: Option<&Path>) -> Result<Instruments, LoadInstrumentError> {
+        #[cfg(not(feature = "soundfont"))]
+        if soundfont.is_some() {
+            return Err(LoadInstrumentError::SoundFontUnsupported);
+        }
+        Ok(Instruments {
+            #[cfg(feature = "soundfont")]
+            soundfont: soundfont
+                .map(|path| {
+                    audio::soundfont::SoundFont::load(path)
+                        .map_err(|error| LoadInstrumentError::SoundFont { path: path.to_path_buf(), error })
+                })
+                .transpose()?,
+            sample_set: samples.map(audio::sampler::SampleSet::load).transpose()?,
+        })
+    }
+}
+
+/// How every game is turned into audio and metadata, as set on the command line.
+#[derive(Clone, Copy)]
+pub struct Renderer<'a> {
+    pub config: &'a AudioConfig,
+    pub multichannel: bool,
+    pub instruments: &'a Instruments,
+    /// `--bwf` description; None without --bwf
+    pub broadcast: Option<&'a str>,
+    pub cues: bool,
+    /// Output rate in Hz; the synth renders at [`audio::SAMPLE_RATE`]
+    pub sample_rate: u32,
+    /// `--stats`: Some(true) for JSON, Some(false) for the summary
+    pub stats_json: Option<bool>,
+    pub verbosity: Verbosity,
+}
+
+/// A game rendered at the output rate, ready to be written in any [`audio::Format`].
+pub struct RenderedGame {
+    pub moves: String,
+    /// The renderer's config, starting where the game does
+    pub config: AudioConfig,
+    pub samples: Vec<i16>,
+    pub num_channels: u16,
+    pub sample_rate: u32,
+    pub metadata: Vec<audio::wav::Chunk>,
+    /// Lines for stderr: skipped tokens, the --verbose trace, --stats
+    pub report: Vec<String>,
+}
+
+impl RenderedGame {
+    /// The game as a WAV file in memory, metadata included.
+    pub fn to_wav(&self) -> Vec<u8> {
+        audio::to_wav_with_chunks(&self.samples, self.num_channels, self.sample_rate, &self.metadata)
+    }
+}
+
+impl<'a> Renderer<'a> {
+    /// Renders `game` from its FEN tag, or from the config's start without one.
+    pub fn render_game(&self, game: &pgn::Game) -> Result<RenderedGame, ParseFenError> {
+        let config = AudioConfig { start: game_start(game, &self.config.start)?, ..self.config.clone() };
+        let renderer = Renderer { config: &config, ..*self };
+        let (samples, num_channels) = renderer.render(&game.moves);
+        let report = renderer.report(&game.moves, &samples, num_channels);
+        let samples = renderer.resample(samples, num_channels);
+        let metadata = renderer.metadata(&game.moves, &game.tags);
+        Ok(RenderedGame {
+            moves: game.moves.clone(),
+            config,
+            samples,
+            num_channels,
+            sample_rate: self.sample_rate,
+            metadata,
+            report,
+        })
+    }
+
+    /// The game's samples and their channel count.
+    fn render(&self, moves: &str) -> (Vec<i16>, u16) {
+        if self.multichannel {
+            return (audio::generate_multichannel(moves, self.config), audio::PIECE_CHANNELS);
+        }
+        #[cfg(feature = "soundfont")]
+        if let Some(soundfont) = &self.instruments.soundfont {
+            return (audio::generate_with_soundfont(moves, self.config, soundfont), self.config.num_channels());
+        }
+        match &self.instruments.sample_set {
+            Some(sample_set) => (audio::generate_with_samples(moves, self.config, sample_set), self.config.num_channels()),
+            None => (audio::generate_with_config(moves, self.config), self.config.num_channels()),
+        }
+    }
+
+    /// `samples` as rendered by [`Renderer::render`], converted to the output rate.
+    fn resample(&self, samples: Vec<i16>, num_channels: u16) -> Vec<i16> {
+        if self.sample_rate == audio::SAMPLE_RATE {
+            samples
+        } else {
+            audio::resample::resample(&samples, num_channels, audio::SAMPLE_RATE, self.sample_rate)
+        }
+    }
+
+    /// WAV chunks: the PGN tags, plus BWF and cue markers when asked for.
+    fn metadata(&self, moves: &str, tags: &pgn::Tags) -> Vec<audio::wav::Chunk> {
+        let mut metadata: Vec<audio::wav::Chunk> = audio::game_info(tags).into_iter().collect();
+        if let Some(description) = self.broadcast {
+            let origination_time = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_secs());
+            metadata.push(audio::wav::Chunk::bext(&audio::wav::Broadcast {
+                description: description.to_string(),
+                originator: "ChessWAV".to_string(),
+                origination_time,
+            }));
+        }
+        if self.cues {
+            let mut cues = audio::move_cues_with_config(moves, self.config);
+            for cue in &mut cues {
+                cue.frame = (cue.frame as u64 * self.sample_rate as u64 / audio::SAMPLE_RATE as u64) as u32;
+            }
+            metadata.extend([audio::wav::Chunk::cue(&cues), audio::wav::Chunk::cue_labels(&cues)]);
+        }
+        metadata
+    }
+
+    /// Lines for stderr about a rendered game: warnings for tokens that are
+    /// not moves, the per-move trace with --verbose, then the --stats report.
+    fn report(&self, moves: &str, samples: &[i16], num_channels: u16) -> Vec<String> {
+        let mut lines = Vec::new();
+        let skipped = audio::skipped_tokens(moves);
+        // The trace already lists them with --verbose
+        if self.verbosity == Verbosity::Normal {
+            lines.extend(skipped.iter().map(|token| format!("warning: skipped \"{}\" (not a move)", token)));
+        }
+        if !skipped.is_empty() && self.verbosity != Verbosity::Quiet {
+            let plural = if skipped.len() == 1 { "" } else { "s" };
+            lines.push(format!("warning: {} token{} skipped; the audio leaves them out", skipped.len(), plural));
+        }
+        if self.verbosity == Verbosity::Verbose {
+            lines.extend(audio::trace::trace(moves, self.config).iter().map(ToString::to_string));
+        }
+        if let Some(json) = self.stats_json {
+            let stats = audio::stats::GameStats::collect(moves, self.config, samples, num_channels);
+            lines.push(if json { stats.to_json() } else { stats.to_string() });
+        }
+        lines
+    }
+}
+
+/// Where `game` starts: its FEN tag, or `fallback` (the --fen position)
+/// for a game without one.
+pub fn game_start(game: &pgn::Game, fallback: &Position) -> Result<Position, ParseFenError> {
+    match game.tags.get("FEN") {
+        Some(fen) => Board::from_fen(fen),
+        None => Ok(fallback.clone()),
+    }
+}
+
+/// Writes `game` to `out` as `format`.
+pub fn write_output(out: &mut impl Write, format: audio::Format, game: &RenderedGame) -> Result<(), WriteGameError> {
+    match format {
+        audio::Format::Wav => {
+            return Ok(audio::write_wav(out, &game.samples, game.num_channels, game.sample_rate, &game.metadata)?);
+        }
+        audio::Format::Midi => out.write_all(&audio::generate_midi(&game.moves, &game.config))?,
+        audio::Format::Ogg => out.write_all(&encode_opus(&game.to_wav())?)?,
+        audio::Format::Flac => out.write_all(&audio::flac::encode(&game.samples, game.num_channels, game.sample_rate))?,
+        audio::Format::Raw => {
+            let bytes: Vec<u8> = game.samples.iter().flat_map(|sample| sample.to_le_bytes()).collect();
+            out.write_all(&bytes)?;
+        }
+    }
+    out.flush()?;
+    Ok(())
+}
+
+/// Writes `game` to a new file at `path` as `format`.
+pub fn write_file(path: &Path, format: audio::Format, game: &RenderedGame) -> Result<(), WriteGameError> {
+    let file = std::fs::File::create(path)?;
+    write_output(&mut io::BufWriter::new(file), format, game)
+}
+
+#[cfg(feature = "opus")]
+fn encode_opus(wav: &[u8]) -> Result<Vec<u8>, WriteGameError> {
+    Ok(audio::opus::encode(wav)?)
+}
+
+#[cfg(not(feature = "opus"))]
+fn encode_opus(_wav: &[u8]) -> Result<Vec<u8>, WriteGameError> {
+    Err(WriteGameError::OpusUnsupported)
+}
+
+/// One game of an `--outdir` batch: the file it went to and how that went.
+#[derive(Debug)]
+pub struct BatchReport {
+    pub path: PathBuf,
+    /// The game's report lines, or why it could not be written
+    pub outcome: Result<Vec<String>, String>,
+    /// Some of its tokens were not moves
+    pub skipped_moves: bool,
+}
+
+/// `--outdir`: renders each game (or only game `number`) to its own file in
+/// `dir`, named from its number and players, e.g. `001-carlsen-nepomniachtchi.wav`.
+/// `workers` games are rendered at once; the reports come back in game order.
+pub fn render_batch(
+    renderer: &Renderer,
+    games: &[pgn::Game],
+    number: Option<usize>,
+    dir: &Path,
+    format: audio::Format,
+    workers: usize,
+) -> Result<Vec<BatchReport>, RenderBatchError> {
+    if let Some(number) = number.filter(|&number| number > games.len()) {
+        return Err(RenderBatchError::NoSuchGame { games: games.len(), number });
+    }
+    std::fs::create_dir_all(dir).map_err(|error| RenderBatchError::CreateDir { path: dir.to_path_buf(), error })?;
+    let jobs = batch_jobs(games, number, dir, format);
+
+    // Workers take the next job until none are left
+    let next_job = AtomicUsize::new(0);
+    let mut outcomes: Vec<(usize, Result<Vec<String>, String>)> = std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..workers.min(jobs.len()))
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut outcomes = Vec::new();
+                    loop {
+                        let idx = next_job.fetch_add(1, Ordering::Relaxed);
+                        let Some((path, game)) = jobs.get(idx) else { break };
+                        outcomes.push((idx, render_job(renderer, path, game, format)));
+                    }
+                    outcomes
+                })
+            })
+            .collect();
+        handles.into_iter().flat_map(|handle| handle.join().unwrap_or_default()).collect()
+    });
+    outcomes.sort_by_key(|(idx, _)| *idx);
+
+    Ok(outcomes
+        .into_iter()
+        .map(|(idx, outcome)| {
+            let (path, game) = &jobs[idx];
+            BatchReport { path: path.clone(), outcome, skipped_moves: !audio::skipped_tokens(&game.moves).is_empty() }
+        })
+        .collect())
+}
+
+/// The games of a batch with the file each is written to.
+fn batch_jobs<'g>(games: &'g [pgn::Game], number: Option<usize>, dir: &Path, format: audio::Format) -> Vec<(PathBuf, &'g pgn::Game)> {
+    let digits = games.len().to_string().len().max(3);
+    games
+        .iter()
+        .enumerate()
+        .map(|(idx, game)| (idx + 1, game))
+        .filter(|(game_number, _)| number.is_none_or(|wanted| wanted == *game_number))
+        .map(|(game_number, game)| {
+            let name = match game.tags.slug() {
+                Some(slug) => format!("{:0digits$}-{}.{}", game_number, slug, format.extension()),
+                None => format!("{:0digits$}.{}", game_number, format.extension()),
+            };
+            (dir.join(name), game)
+        })
+        .collect()
+}
+
+/// One `--outdir` game written to `path`; its report lines, or why it
+/// could not be written.
+fn render_job(renderer: &Renderer, path: &Path, game: &pgn::Game, format: audio::Format) -> Result<Vec<String>, String> {
+    let rendered = renderer.render_game(game).map_err(|err| format!("Invalid FEN tag: {}", err))?;
+    write_file(path, format, &rendered).map_err(|err| err.to_string())?;
+    Ok(rendered.report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn renderer<'a>(config: &'a AudioConfig, instruments: &'a Instruments) -> Renderer<'a> {
+        Renderer {
+            config,
+            multichannel: false,
+            instruments,
+            broadcast: None,
+            cues: false,
+            sample_rate: audio::SAMPLE_RATE,
+            stats_json: None,
+            verbosity: Verbosity::Normal,
+        }
+    }
+
+    fn game(tags: &[(&str, &str)], moves: &str) -> pgn::Game {
+        let pgn: String = tags.iter().map(|(name, value)| format!("[{} \"{}\"]\n", name, value)).collect();
+        let mut games = pgn::parse_games(&format!("{}\n{}", pgn, moves));
+        games.remove(0)
+    }
+
+    #[test]
+    fn games_start_from_their_fen_tag() {
+        let config = AudioConfig::default();
+        let instruments = Instruments::default();
+        let fen = "4k3/8/8/8/8/8/8/4K2R w K - 0 1";
+        let rendered = renderer(&config, &instruments).render_game(&game(&[("FEN", fen)], "O-O")).unwrap();
+        assert_eq!(rendered.config.start, Board::from_fen(fen).unwrap());
+        assert!(rendered.report.is_empty());
+
+        let bad = renderer(&config, &instruments).render_game(&game(&[("FEN", "8/8/8 w - - 0 1")], "e4"));
+        assert!(bad.is_err());
+    }
+
+    #[test]
+    fn reports_skipped_tokens_unless_quiet() {
+        let config = AudioConfig::default();
+        let instruments = Instruments::default();
+        let normal = renderer(&config, &instruments).render_game(&game(&[], "e4 Nf9")).unwrap();
+        assert_eq!(
+            normal.report,
+            ["warning: skipped \"Nf9\" (not a move)", "warning: 1 token skipped; the audio leaves them out"]
+        );
+
+        let quiet = Renderer { verbosity: Verbosity::Quiet, ..renderer(&config, &instruments) };
+        assert!(quiet.render_game(&game(&[], "e4 Nf9")).unwrap().report.is_empty());
+    }
+
+    #[test]
+    fn resamples_to_the_output_rate() {
+        let config = AudioConfig::default();
+        let instruments = Instruments::default();
+        let native = renderer(&config, &instruments).render_game(&game(&[], "e4")).unwrap();
+        let halved = Renderer { sample_rate: audio::SAMPLE_RATE / 2, ..renderer(&config, &instruments) };
+        let halved = halved.render_game(&game(&[], "e4")).unwrap();
+        assert_eq!(halved.samples.len(), native.samples.len() / 2);
+    }
+
+    #[test]
+    fn raw_output_is_little_endian_samples() {
+        let config = AudioConfig::default();
+        let instruments = Instruments::default();
+        let rendered = renderer(&config, &instruments).render_game(&game(&[], "e4")).unwrap();
+        let mut out = Vec::new();
+        write_output(&mut out, audio::Format::Raw, &rendered).unwrap();
+        assert_eq!(out.len(), rendered.samples.len() * 2);
+        assert_eq!(&out[..2], &rendered.samples[0].to_le_bytes());
+    }
+
+    #[test]
+    fn batch_files_are_numbered_and_named_from_the_players() {
+        let games = [game(&[("White", "Carlsen"), ("Black", "Nepomniachtchi")], "e4"), game(&[], "d4")];
+        let dir = Path::new("out");
+        let jobs = batch_jobs(&games, None, dir, audio::Format::Flac);
+        let paths: Vec<&PathBuf> = jobs.iter().map(|(path, _)| path).collect();
+        assert_eq!(paths, [&dir.join("001-carlsen-nepomniachtchi.flac"), &dir.join("002.flac")]);
+
+        let only_second = batch_jobs(&games, Some(2), dir, audio::Format::Wav);
+        assert_eq!(only_second.len(), 1);
+        assert_eq!(only_second[0].0, dir.join("002.wav"));
+    }
+
+    #[test]
+    fn batches_render_every_game_in_order() {
+        let config = AudioConfig::default();
+        let instruments = Instruments::default();
+        let games = [game(&[], "e4 e5"), game(&[], "d4 Nf9"), game(&[("FEN", "8/8 w - - 0 1")], "e4")];
+        let dir = std::env::temp_dir().join(format!("chesswav-batch-{}", std::process::id()));
+        let reports = render_batch(&renderer(&config, &instruments), &games, None, &dir, audio::Format::Wav, 2).unwrap();
+        let outcomes: Vec<bool> = reports.iter().map(|report| report.outcome.is_ok()).collect();
+        assert_eq!(outcomes, [true, true, false]);
+        assert_eq!(reports.iter().map(|report| report.skipped_moves).collect::<Vec<_>>(), [false, true, false]);
+        assert!(reports[0].path.is_file());
+        std::fs::remove_dir_all(&dir).ok();
+
+        let missing = render_batch(&renderer(&config, &instruments), &games, Some(4), &dir, audio::Format::Wav, 2);
+        assert!(matches!(missing, Err(RenderBatchError::NoSuchGame { games: 3, number: 4 })));
+    }
+}
@@ -0,0 +1,77 @@
+//! Xorshift32, the seeded generator behind the noise waveforms, humanized
+//! timing and autoplay's move picks.
+//!
+//! ```text
+//! x ^= x << 13
+//! x ^= x >> 17
+//! x ^= x << 5      → the next state, and the draw
+//! ```
+//!
+//! Fast and tiny, not for anything that must be unpredictable: the same
+//! seed always gives the same sequence, which is what makes a noise hit, a
+//! humanized take or an engine game repeatable.
+
+/// A xorshift32 sequence; Copy so it can sit in a [`std::cell::Cell`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Xorshift32 {
+    state: u32,
+}
+
+impl Xorshift32 {
+    pub fn new(seed: u32) -> Self {
+        // xorshift gets stuck at zero forever, so never seed it with zero
+        Self { state: seed.max(1) }
+    }
+
+    /// The next value of the sequence, never zero.
+    pub fn next_u32(&mut self) -> u32 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 17;
+        self.state ^= self.state << 5;
+        self.state
+    }
+
+    /// Next uniform value in [-1, 1].
+    pub fn next_signed(&mut self) -> f64 {
+        self.next_u32() as f64 / u32::MAX as f64 * 2.0 - 1.0
+    }
+
+    /// An index below `len`, which must not be zero.
+    pub fn below(&mut self, len: usize) -> usize {
+        self.next_u32() as usize % len
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn follows_the_xorshift32_sequence() {
+        let mut rng = Xorshift32::new(1);
+        assert_eq!([rng.next_u32(), rng.next_u32(), rng.next_u32()], [270369, 67634689, 2647435461]);
+    }
+
+    #[test]
+    fn zero_seed_still_varies() {
+        let mut rng = Xorshift32::new(0);
+        assert_ne!(rng.next_u32(), rng.next_u32());
+    }
+
+    #[test]
+    fn same_seed_same_sequence() {
+        let mut first = Xorshift32::new(42);
+        let mut second = Xorshift32::new(42);
+        let draws: Vec<u32> = (0..50).map(|_| first.next_u32()).collect();
+        assert_eq!(draws, (0..50).map(|_| second.next_u32()).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn draws_stay_in_range() {
+        let mut rng = Xorshift32::new(7);
+        for _ in 0..1000 {
+            assert!((-1.0..=1.0).contains(&rng.next_signed()));
+            assert!(rng.below(7) < 7);
+        }
+    }
+}
@@ -0,0 +1,416 @@
+//! Minimal TOML reader for sound theme and config files.
+//!
+//! Covers the subset those files use, keeping the crate dependency-free:
+//!
+//! ```text
+//! # comment
+//! base = "classic"          top-level key (table "")
+//!
+//! [pawn.check]              table header, dotted names allowed
+//! waveform = "triangle"     string, with \" \\ \n \t escapes
+//! harmonics = 5             integer
+//! sine_mix = 0.7            float
+//! enabled = true            boolean
+//! partials = [[1, 1.0], [2, 0.5]]   array, nested, on one line
+//! ```
+//!
+//! Inline tables, multi-line strings and arrays and dates are not supported.
+//! Entries come back flat and in file order, each with its table and line
+//! number, so callers can report errors where they occur. [`set_top_level`]
+//! writes one key back, leaving the rest of the file as it was.
+
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    String(String),
+    Integer(i64),
+    Float(f64),
+    Boolean(bool),
+    Array(Vec<Value>),
+}
+
+impl Value {
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(text) => Some(text),
+            Value::Integer(_) | Value::Float(_) | Value::Boolean(_) | Value::Array(_) => None,
+        }
+    }
+
+    /// Floats and integers alike, as TOML writers rarely add `.0`.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Integer(number) => Some(*number as f64),
+            Value::Float(number) => Some(*number),
+            Value::String(_) | Value::Boolean(_) | Value::Array(_) => None,
+        }
+    }
+
+    /// Non-negative integers that fit in a `u32`.
+    pub fn as_u32(&self) -> Option<u32> {
+        match self {
+            Value::Integer(number) => u32::try_from(*number).ok(),
+            Value::String(_) | Value::Float(_) | Value::Boolean(_) | Value::Array(_) => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::Boolean(flag) => Some(*flag),
+            Value::String(_) | Value::Integer(_) | Value::Float(_) | Value::Array(_) => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[Value]> {
+        match self {
+            Value::Array(items) => Some(items),
+            Value::String(_) | Value::Integer(_) | Value::Float(_) | Value::Boolean(_) => None,
+        }
+    }
+}
+
+/// A value as written in a file: strings quoted and escaped.
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::String(text) => {
+                f.write_str("\"")?;
+                for ch in text.chars() {
+                    match ch {
+                        '"' => f.write_str("\\\"")?,
+                        '\\' => f.write_str("\\\\")?,
+                        '\n' => f.write_str("\\n")?,
+                        '\t' => f.write_str("\\t")?,
+                        _ => write!(f, "{ch}")?,
+                    }
+                }
+                f.write_str("\"")
+            }
+            Value::Integer(number) => write!(f, "{number}"),
+            // Debug keeps the `.0` that makes a whole number a float
+            Value::Float(number) => write!(f, "{number:?}"),
+            Value::Boolean(flag) => write!(f, "{flag}"),
+            Value::Array(items) => {
+                f.write_str("[")?;
+                for (idx, item) in items.iter().enumerate() {
+                    let separator = if idx == 0 { "" } else { ", " };
+                    write!(f, "{separator}{item}")?;
+                }
+                f.write_str("]")
+            }
+        }
+    }
+}
+
+/// One `key = value` line.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    /// Name of the enclosing `[table]` ("" before the first header)
+    pub table: String,
+    pub key: String,
+    pub value: Value,
+    /// 1-based line number
+    pub line: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseTomlError {
+    /// A `[table]` header that is unterminated or has an invalid name
+    InvalidTable { line: usize },
+    /// A line that is neither a header nor `key = value`
+    MissingEquals { line: usize },
+    /// A key that is empty or has characters outside `A-Z a-z 0-9 _ -`
+    InvalidKey { line: usize },
+    /// A value that is not a string, number, boolean or array
+    InvalidValue { line: usize },
+    /// The same key twice in one table
+    DuplicateKey { line: usize, key: String },
+}
+
+impl fmt::Display for ParseTomlError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseTomlError::InvalidTable { line } => write!(f, "line {line}: invalid [table] header"),
+            ParseTomlError::MissingEquals { line } => write!(f, "line {line}: expected `key = value`"),
+            ParseTomlError::InvalidKey { line } => write!(f, "line {line}: invalid key"),
+            ParseTomlError::InvalidValue { line } => {
+                write!(f, "line {line}: expected a \"string\", number, true, false or [array]")
+            }
+            ParseTomlError::DuplicateKey { line, key } => write!(f, "line {line}: duplicate key '{key}'"),
+        }
+    }
+}
+
+impl std::error::Error for ParseTomlError {}
+
+/// Parses `text` into its entries, in file order.
+pub fn parse(text: &str) -> Result<Vec<Entry>, ParseTomlError> {
+    let mut entries: Vec<Entry> = Vec::new();
+    let mut table = String::new();
+    for (idx, raw_line) in text.lines().enumerate() {
+        let line = idx + 1;
+        let content = strip_comment(raw_line).trim();
+        if content.is_empty() {
+            continue;
+        }
+        if let Some(header) = content.strip_prefix('[') {
+            let name = header.strip_suffix(']').map(str::trim).ok_or(ParseTomlError::InvalidTable { line })?;
+            if !name.split('.').all(is_bare_key) {
+                return Err(ParseTomlError::InvalidTable { line });
+            }
+            table = name.to_string();
+            continue;
+        }
+        let (key, value) = content.split_once('=').ok_or(ParseTomlError::MissingEquals { line })?;
+        let key = key.trim();
+        if !is_bare_key(key) {
+            return Err(ParseTomlError::InvalidKey { line });
+        }
+        if entries.iter().any(|entry| entry.table == table && entry.key == key) {
+            return Err(ParseTomlError::DuplicateKey { line, key: key.to_string() });
+        }
+        let value = parse_value(value.trim()).ok_or(ParseTomlError::InvalidValue { line })?;
+        entries.push(Entry {
+            table: table.clone(),
+            key: key.to_string(),
+            value,
+            line,
+        });
+    }
+    Ok(entries)
+}
+
+/// `text` with `key = value` at its top level: the existing line for `key`
+/// is replaced, keeping its comment, or a new one goes before the first
+/// `[table]`. Other lines are left as they are.
+pub fn set_top_level(text: &str, key: &str, value: &Value) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    let mut written = false;
+    for line in text.lines() {
+        let content = strip_comment(line);
+        if !written && content.trim_start().starts_with('[') {
+            lines.push(format!("{key} = {value}"));
+            written = true;
+        }
+        let is_key = content.split_once('=').is_some_and(|(name, _)| name.trim() == key);
+        if !written && is_key {
+            let comment = &line[content.len()..];
+            let spacing = if comment.is_empty() { "" } else { " " };
+            lines.push(format!("{key} = {value}{spacing}{comment}"));
+            written = true;
+        } else {
+            lines.push(line.to_string());
+        }
+    }
+    if !written {
+        lines.push(format!("{key} = {value}"));
+    }
+    lines.join("\n") + "\n"
+}
+
+/// Drops a trailing `# comment`, ignoring `#` inside strings.
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    let mut escaped = false;
+    for (idx, ch) in line.char_indices() {
+        match ch {
+            '\\' if in_string && !escaped => {
+                escaped = true;
+                continue;
+            }
+            '"' if !escaped => in_string = !in_string,
+            '#' if !in_string => return &line[..idx],
+            _ => {}
+        }
+        escaped = false;
+    }
+    line
+}
+
+fn is_bare_key(key: &str) -> bool {
+    !key.is_empty() && key.chars().all(|ch| ch.is_ascii_alphanumeric() || ch == '_' || ch == '-')
+}
+
+fn parse_value(text: &str) -> Option<Value> {
+    match text {
+        "true" => return Some(Value::Boolean(true)),
+        "false" => return Some(Value::Boolean(false)),
+        _ => {}
+    }
+    if let Some(quoted) = text.strip_prefix('"') {
+        return parse_string(quoted).map(Value::String);
+    }
+    if let Some(items) = text.strip_prefix('[') {
+        return parse_array(items.strip_suffix(']')?).map(Value::Array);
+    }
+    let digits = text.replace('_', "");
+    if let Ok(number) = digits.parse::<i64>() {
+        return Some(Value::Integer(number));
+    }
+    // Rust also accepts "inf" and "NaN", which TOML spells differently
+    let numeric = digits.chars().all(|ch| ch.is_ascii_digit() || matches!(ch, '.' | 'e' | 'E' | '+' | '-'));
+    digits.parse::<f64>().ok().filter(|_| numeric).map(Value::Float)
+}
+
+/// Reads the items between an array's brackets: values separated by
+/// commas outside nested brackets and strings, with an optional trailing one.
+fn parse_array(items: &str) -> Option<Vec<Value>> {
+    let mut values = Vec::new();
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut start = 0;
+    for (idx, ch) in items.char_indices() {
+        match ch {
+            '\\' if in_string && !escaped => {
+                escaped = true;
+                continue;
+            }
+            '"' if !escaped => in_string = !in_string,
+            '[' if !in_string => depth += 1,
+            ']' if !in_string => depth -= 1,
+            ',' if !in_string && depth == 0 => {
+                values.push(parse_value(items[start..idx].trim())?);
+                start = idx + 1;
+            }
+            _ => {}
+        }
+        escaped = false;
+    }
+    let last = items[start..].trim();
+    if !last.is_empty() {
+        values.push(parse_value(last)?);
+    }
+    Some(values)
+}
+
+/// Reads a basic string after its opening quote; nothing may follow the
+/// closing quote.
+fn parse_string(quoted: &str) -> Option<String> {
+    let mut text = String::new();
+    let mut chars = quoted.chars();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '"' => return chars.as_str().trim().is_empty().then_some(text),
+            '\\' => text.push(match chars.next()? {
+                '"' => '"',
+                '\\' => '\\',
+                'n' => '\n',
+                't' => '\t',
+                _ => return None,
+            }),
+            _ => text.push(ch),
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(text: &str) -> Vec<Value> {
+        parse(text).unwrap().into_iter().map(|entry| entry.value).collect()
+    }
+
+    #[test]
+    fn parses_value_types() {
+        assert_eq!(
+            values("a = \"hi\"\nb = 42\nc = -0.5\nd = true\ne = 1_000\nf = 2e3"),
+            vec![
+                Value::String("hi".to_string()),
+                Value::Integer(42),
+                Value::Float(-0.5),
+                Value::Boolean(true),
+                Value::Integer(1000),
+                Value::Float(2000.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_nested_arrays() {
+        assert_eq!(
+            values("a = [[1, 0.5], [2, \"x, ]\"],]\nb = []"),
+            vec![
+                Value::Array(vec![
+                    Value::Array(vec![Value::Integer(1), Value::Float(0.5)]),
+                    Value::Array(vec![Value::Integer(2), Value::String("x, ]".to_string())]),
+                ]),
+                Value::Array(Vec::new()),
+            ]
+        );
+        assert_eq!(parse("a = [1, 2"), Err(ParseTomlError::InvalidValue { line: 1 }));
+        assert_eq!(parse("a = [1,, 2]"), Err(ParseTomlError::InvalidValue { line: 1 }));
+    }
+
+    #[test]
+    fn entries_carry_table_and_line() {
+        let entries = parse("top = 1\n\n[pawn.check]\nwaveform = \"sine\"").unwrap();
+        assert_eq!(entries[0].table, "");
+        assert_eq!(entries[1].table, "pawn.check");
+        assert_eq!(entries[1].key, "waveform");
+        assert_eq!(entries[1].line, 4);
+    }
+
+    #[test]
+    fn strips_comments_outside_strings() {
+        assert_eq!(
+            values("# header\nname = \"a # b\" # trailing"),
+            vec![Value::String("a # b".to_string())]
+        );
+    }
+
+    #[test]
+    fn unescapes_strings() {
+        assert_eq!(values(r#"path = "say \"hi\"\\""#), vec![Value::String("say \"hi\"\\".to_string())]);
+    }
+
+    #[test]
+    fn writes_values_back_as_they_parse() {
+        for value in [
+            Value::String("say \"hi\"\\\n".to_string()),
+            Value::Integer(-3),
+            Value::Float(2.0),
+            Value::Boolean(false),
+            Value::Array(vec![Value::Array(vec![Value::Integer(1), Value::Float(0.5)])]),
+        ] {
+            assert_eq!(values(&format!("a = {value}")), vec![value]);
+        }
+    }
+
+    #[test]
+    fn sets_a_top_level_key_in_place() {
+        let text = "# mine\ntempo = 90\nvolume = 20   # quiet\n\n[extra]\nvolume = 1\n";
+        assert_eq!(
+            set_top_level(text, "volume", &Value::Integer(70)),
+            "# mine\ntempo = 90\nvolume = 70 # quiet\n\n[extra]\nvolume = 1\n"
+        );
+        assert_eq!(
+            set_top_level(text, "coordinates", &Value::Boolean(false)),
+            "# mine\ntempo = 90\nvolume = 20   # quiet\n\ncoordinates = false\n[extra]\nvolume = 1\n"
+        );
+        assert_eq!(set_top_level("", "display", &Value::String("ascii".to_string())), "display = \"ascii\"\n");
+    }
+
+    #[test]
+    fn same_key_in_different_tables_is_allowed() {
+        assert_eq!(parse("[pawn]\nattack_ms = 1\n[king]\nattack_ms = 2").unwrap().len(), 2);
+    }
+
+    #[test]
+    fn reports_errors_with_line_numbers() {
+        assert_eq!(parse("\n[pawn"), Err(ParseTomlError::InvalidTable { line: 2 }));
+        assert_eq!(parse("[a b]"), Err(ParseTomlError::InvalidTable { line: 1 }));
+        assert_eq!(parse("waveform"), Err(ParseTomlError::MissingEquals { line: 1 }));
+        assert_eq!(parse("= 1"), Err(ParseTomlError::InvalidKey { line: 1 }));
+        assert_eq!(parse("a = sine"), Err(ParseTomlError::InvalidValue { line: 1 }));
+        assert_eq!(parse("a = \"open"), Err(ParseTomlError::InvalidValue { line: 1 }));
+        assert_eq!(parse("a = inf"), Err(ParseTomlError::InvalidValue { line: 1 }));
+        assert_eq!(
+            parse("a = 1\na = 2"),
+            Err(ParseTomlError::DuplicateKey { line: 2, key: "a".to_string() })
+        );
+    }
+}
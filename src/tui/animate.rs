@@ -0,0 +1,92 @@
+//! Moves sliding across the board in the colored displays: the piece is
+//! drawn on each square between its origin and destination, FRAME_MS
+//! apart, while its note plays.
+//!
+//! ```text
+//! Ra1-a4:  a2 → a3 → a4       frames on the squares between, then the move
+//! Nb1-c3:  c3                 knights jump: no frames
+//! ```
+
+use crate::engine::board::Board;
+use crate::engine::chess::{ResolvedMove, Square};
+use super::display::DisplayMode;
+
+/// Time each in-between frame stays on screen.
+pub const FRAME_MS: u64 = 30;
+
+/// Whether moves slide in `mode`; ASCII stays still.
+pub fn slides_in(mode: DisplayMode) -> bool {
+    match mode {
+        DisplayMode::Sprite(_) | DisplayMode::Unicode => true,
+        DisplayMode::Ascii => false,
+    }
+}
+
+/// The boards to draw before `resolved` lands on `board`: the piece on each
+/// empty square along its line, origin excluded. Empty for a knight's
+/// jump, or a step to the next square.
+pub fn frames(board: &Board, resolved: &ResolvedMove) -> Vec<Board> {
+    let (origin, dest) = (resolved.origin, resolved.dest);
+    let files = dest.file as i8 - origin.file as i8;
+    let ranks = dest.rank as i8 - origin.rank as i8;
+    if files != 0 && ranks != 0 && files.abs() != ranks.abs() {
+        return Vec::new();
+    }
+    let steps = files.abs().max(ranks.abs());
+    let mut frames = Vec::new();
+    for step in 1..steps {
+        let square = Square {
+            file: origin.file.saturating_add_signed(files.signum() * step),
+            rank: origin.rank.saturating_add_signed(ranks.signum() * step),
+        };
+        if board.get(square.file, square.rank).is_some() {
+            break;
+        }
+        let mut frame = board.clone();
+        if frame.apply_move(&ResolvedMove { origin, dest: square, promotion: None, castling_rook: None }).is_err() {
+            break;
+        }
+        frames.push(frame);
+    }
+    frames
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::board::Color;
+    use crate::engine::chess::{NotationMove, Piece};
+    use super::super::display::SpriteSet;
+
+    fn resolved(board: &Board, notation: &str, color: Color) -> ResolvedMove {
+        let move_index = if color == Color::White { 0 } else { 1 };
+        let chess_move = NotationMove::parse(notation, move_index).unwrap();
+        board.resolve_legal_move(&chess_move, notation, color).unwrap()
+    }
+
+    #[test]
+    fn a_slider_passes_every_square_between() {
+        let board = Board::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 0 1").unwrap().board;
+        let frames = frames(&board, &resolved(&board, "Ra4", Color::White));
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].get(0, 1), Some((Piece::Rook, Color::White)));
+        assert_eq!(frames[0].get(0, 0), None);
+        assert_eq!(frames[1].get(0, 2), Some((Piece::Rook, Color::White)));
+        assert_eq!(frames[1].get(0, 1), None);
+    }
+
+    #[test]
+    fn knights_and_single_steps_jump() {
+        let board = Board::new();
+        assert!(frames(&board, &resolved(&board, "Nc3", Color::White)).is_empty());
+        assert!(frames(&board, &resolved(&board, "e3", Color::White)).is_empty());
+        assert_eq!(frames(&board, &resolved(&board, "e4", Color::White)).len(), 1);
+    }
+
+    #[test]
+    fn ascii_stays_still() {
+        assert!(slides_in(DisplayMode::Sprite(SpriteSet::Large)));
+        assert!(slides_in(DisplayMode::Unicode));
+        assert!(!slides_in(DisplayMode::Ascii));
+    }
+}
@@ -0,0 +1,174 @@
+//! `chesswav autoplay`: the built-in engine plays both sides, one move
+//! every `delay_ms`, each with its sound, until interrupted with Ctrl-C.
+//!
+//! ```text
+//! pick  ← random among the engine's moves within VARIETY of the best
+//! play  → sound, board, move list
+//! end   → "Checkmate" / "Stalemate" / move cap, then a new game
+//! ```
+//!
+//! The random pick keeps the games apart; the same seed plays the same
+//! games again.
+
+use std::io::{self, BufWriter, Write};
+use std::thread;
+use std::time::Duration;
+
+use crate::audio;
+use crate::engine::board::{Board, Color, Position};
+use crate::engine::chess::{NotationMove, Piece, ResolvedMove};
+use crate::engine::search;
+use crate::rng::Xorshift32;
+use super::display;
+
+/// Centipawns a move may trail the best and still be picked.
+const VARIETY: i32 = 20;
+/// Half-moves after which a game is called off and a new one starts.
+const MAX_PLIES: usize = 200;
+/// Pause after a game ends, in moves.
+const GAME_OVER_PAUSE: u32 = 3;
+
+/// How the engine plays: milliseconds between moves, look-ahead in
+/// half-moves, and the seed of its picks.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Autoplay {
+    pub delay_ms: u64,
+    pub depth: u8,
+    pub seed: u32,
+}
+
+/// One engine game from `start`, move by move.
+struct Game {
+    board: Board,
+    move_index: usize,
+    moves: Vec<String>,
+    captured: Vec<(Piece, Color)>,
+    last_move: Option<ResolvedMove>,
+}
+
+impl Game {
+    fn new(start: &Position) -> Self {
+        Self { board: start.board.clone(), move_index: start.ply, moves: Vec::new(), captured: Vec::new(), last_move: None }
+    }
+
+    fn side_to_move(&self) -> Color {
+        if self.move_index.is_multiple_of(2) { Color::White } else { Color::Black }
+    }
+
+    /// Plays the engine's move and returns its notation; None when the
+    /// side to move has none.
+    fn advance(&mut self, depth: u8, picker: &mut Xorshift32) -> Option<String> {
+        let mut candidates = search::good_moves(&self.board, self.side_to_move(), depth, VARIETY);
+        if candidates.is_empty() {
+            return None;
+        }
+        let chosen = candidates.swap_remove(picker.below(candidates.len()));
+        let notation = self.board.san(&chosen);
+        let undo = self.board.make_move(&chosen).ok()?;
+        if let Some(captured) = undo.captured() {
+            self.captured.push(captured);
+        }
+        self.moves.push(notation.clone());
+        self.last_move = Some(chosen);
+        self.move_index += 1;
+        Some(notation)
+    }
+
+    /// Why the game is over, if it is.
+    fn ending(&self) -> Option<&'static str> {
+        if self.moves.len() >= MAX_PLIES {
+            Some("Move limit reached")
+        } else if !self.board.legal_moves(self.side_to_move()).is_empty() {
+            None
+        } else if self.board.in_check(self.side_to_move()) {
+            Some("Checkmate")
+        } else {
+            Some("Stalemate")
+        }
+    }
+
+    fn draw(&self, writer: &mut impl Write, strategy: &dyn display::DisplayStrategy, first_ply: usize) -> io::Result<()> {
+        let markers: Vec<_> = self
+            .last_move
+            .iter()
+            .flat_map(|last| [(last.origin, display::SquareMarker::LastMove), (last.dest, display::SquareMarker::LastMove)])
+            .collect();
+        let sidebar = display::Sidebar { moves: &self.moves, first_ply, captured: &self.captured, comments: &[], notes: &[] };
+        display::render(&self.board, writer, strategy, &sidebar, &markers, Color::White)?;
+        writer.flush()
+    }
+}
+
+/// Plays engine games from `start` forever.
+pub fn run(mode: display::DisplayMode, color_mode: display::ColorMode, start: Position, settings: Autoplay) {
+    let strategy = display::create_strategy(mode, color_mode, display::Palette::default(), true);
+    let mut stdout = BufWriter::new(io::stdout());
+    let playback = audio::PlaybackQueue::new();
+    let mut picker = Xorshift32::new(settings.seed);
+    let delay = Duration::from_millis(settings.delay_ms);
+
+    println!();
+    println!("  ChessWAV Autoplay - the engine plays both sides. Ctrl-C to stop");
+    println!();
+
+    // Lines under the board to clear on the next redraw
+    let mut first_draw = true;
+    let mut message_lines = 0;
+    loop {
+        let mut game = Game::new(&start);
+        loop {
+            if !first_draw {
+                display::cursor_up_and_clear(&mut stdout, display::layout_height(&*strategy) + message_lines).ok();
+            }
+            first_draw = false;
+            message_lines = 0;
+            if let Err(err) = game.draw(&mut stdout, &*strategy, start.ply) {
+                eprintln!("  Display error: {err}");
+                return;
+            }
+            if let Some(ending) = game.ending() {
+                writeln!(stdout, "  {ending}. A new game starts").ok();
+                stdout.flush().ok();
+                message_lines = 1;
+                thread::sleep(delay * GAME_OVER_PAUSE);
+                break;
+            }
+            thread::sleep(delay);
+            let move_index = game.move_index;
+            let Some(notation) = game.advance(settings.depth, &mut picker) else {
+                break;
+            };
+            if let Some(chess_move) = NotationMove::parse(&notation, move_index) {
+                playback.enqueue(audio::to_wav(&audio::synthesize_move(&chess_move)));
+                if let Some(err) = playback.take_error() {
+                    eprintln!("  Sound unavailable: {err}");
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn engine_games_play_legal_moves_to_the_end() {
+        let mut picker = Xorshift32::new(7);
+        let mut game = Game::new(&Position::default());
+        while game.ending().is_none() {
+            game.advance(1, &mut picker).unwrap();
+        }
+        let moves = game.moves.join(" ");
+        assert_eq!(crate::engine::validate::validate(&moves), Ok(game.moves.len()));
+    }
+
+    #[test]
+    fn ending_names_mate_and_stalemate() {
+        let mated = Game::new(&Board::from_fen("R5k1/5ppp/8/8/8/8/8/6K1 b - - 0 1").unwrap());
+        assert_eq!(mated.ending(), Some("Checkmate"));
+        let stalemated = Game::new(&Board::from_fen("k7/2Q5/8/8/8/8/8/K7 b - - 0 1").unwrap());
+        assert_eq!(stalemated.ending(), Some("Stalemate"));
+        assert_eq!(Game::new(&Position::default()).ending(), None);
+    }
+}
@@ -3,7 +3,7 @@ use std::io::{self, Write};
 use crate::engine::board::Color;
 use crate::engine::chess::Piece;
 
-use super::{DisplayStrategy, SquareShade, FILE_LABELS};
+use super::{DisplayStrategy, SquareMarker, SquareShade};
 
 pub fn piece_symbol(piece: Piece, color: Color) -> char {
     let symbol = match piece {
@@ -23,7 +23,10 @@ pub fn piece_symbol(piece: Piece, color: Color) -> char {
 /// Plain ASCII display — no colors, no Unicode.
 ///
 /// Renders pieces as uppercase (white) or lowercase (black) letters.
-/// Empty squares show as dots. Useful for terminals without color support
+/// Empty squares show as dots. Hinted squares are starred: " * " when
+/// empty, "*p*" when the move would capture. The last move's squares are
+/// bracketed, "[.]" and "[P]", and the selection cursor's angled, "<.>"
+/// and "<P>". Useful for terminals without color support
 /// or for piping output to text files.
 pub struct AsciiDisplay;
 
@@ -41,14 +44,18 @@ impl DisplayStrategy for AsciiDisplay {
         writer: &mut dyn Write,
         square: Option<(Piece, Color)>,
         _shade: SquareShade,
+        marker: SquareMarker,
         _row: usize,
     ) -> io::Result<()> {
-        match square {
-            None => write!(writer, " . "),
-            Some((piece, color)) => {
-                let symbol = piece_symbol(piece, color);
-                write!(writer, " {symbol} ")
-            }
+        match (square, marker) {
+            (None, SquareMarker::None) => write!(writer, " . "),
+            (None, SquareMarker::Hint) => write!(writer, " * "),
+            (Some((piece, color)), SquareMarker::None) => write!(writer, " {} ", piece_symbol(piece, color)),
+            (None, SquareMarker::LastMove) => write!(writer, "[.]"),
+            (Some((piece, color)), SquareMarker::Hint) => write!(writer, "*{}*", piece_symbol(piece, color)),
+            (Some((piece, color)), SquareMarker::LastMove) => write!(writer, "[{}]", piece_symbol(piece, color)),
+            (None, SquareMarker::Cursor) => write!(writer, "<.>"),
+            (Some((piece, color)), SquareMarker::Cursor) => write!(writer, "<{}>", piece_symbol(piece, color)),
         }
     }
 
@@ -61,13 +68,17 @@ impl DisplayStrategy for AsciiDisplay {
         write!(writer, " {} ", rank + 1)
     }
 
-    fn render_file_labels(&self, writer: &mut dyn Write) -> io::Result<()> {
+    fn render_file_labels(&self, writer: &mut dyn Write, labels: &[char]) -> io::Result<()> {
         write!(writer, "   ")?;
-        for label in FILE_LABELS {
+        for label in labels {
             write!(writer, " {label} ")?;
         }
         writeln!(writer)
     }
+
+    fn piece_symbol(&self, piece: Piece, color: Color) -> char {
+        piece_symbol(piece, color)
+    }
 }
 
 #[cfg(test)]
@@ -86,7 +97,7 @@ mod tests {
         let strategy = AsciiDisplay;
         let mut buf = Vec::new();
         strategy
-            .render_square_row(&mut buf, None, SquareShade::Light, 0)
+            .render_square_row(&mut buf, None, SquareShade::Light, SquareMarker::None, 0)
             .unwrap();
         let output = String::from_utf8(buf).unwrap();
         assert_eq!(output, " . ");
@@ -101,6 +112,7 @@ mod tests {
                 &mut buf,
                 Some((Piece::King, Color::White)),
                 SquareShade::Dark,
+                SquareMarker::None,
                 0,
             )
             .unwrap();
@@ -108,6 +120,28 @@ mod tests {
         assert_eq!(output, " K ");
     }
 
+    #[test]
+    fn renders_hinted_squares() {
+        let strategy = AsciiDisplay;
+        let mut buf = Vec::new();
+        strategy.render_square_row(&mut buf, None, SquareShade::Light, SquareMarker::Hint, 0).unwrap();
+        strategy
+            .render_square_row(&mut buf, Some((Piece::Knight, Color::Black)), SquareShade::Dark, SquareMarker::Hint, 0)
+            .unwrap();
+        assert_eq!(String::from_utf8(buf).unwrap(), " * *n*");
+    }
+
+    #[test]
+    fn brackets_the_last_move() {
+        let strategy = AsciiDisplay;
+        let mut buf = Vec::new();
+        strategy.render_square_row(&mut buf, None, SquareShade::Light, SquareMarker::LastMove, 0).unwrap();
+        strategy
+            .render_square_row(&mut buf, Some((Piece::Pawn, Color::White)), SquareShade::Dark, SquareMarker::LastMove, 0)
+            .unwrap();
+        assert_eq!(String::from_utf8(buf).unwrap(), "[.][P]");
+    }
+
     #[test]
     fn white_pawn_symbol() {
         assert_eq!(piece_symbol(Piece::Pawn, Color::White), 'P');
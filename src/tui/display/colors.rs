@@ -1,26 +1,74 @@
 use crate::engine::board::Color;
 
-use super::{ColorMode, SquareShade};
+use super::{ColorMode, Palette, SquareMarker, SquareShade};
 
 pub const RESET: &str = "\x1b[0m";
 
-/// ANSI foreground escape for piece color (white=#FFF, black=#000).
-pub fn piece_foreground(color: Color, mode: ColorMode) -> &'static str {
-    match (color, mode) {
-        (Color::White, ColorMode::TrueColor) => "\x1b[38;2;255;255;255m",
-        (Color::Black, ColorMode::TrueColor) => "\x1b[38;2;0;0;0m",
-        (Color::White, ColorMode::Color256) => "\x1b[38;5;231m",
-        (Color::Black, ColorMode::Color256) => "\x1b[38;5;16m",
+/// ANSI foreground escape for piece color (white=#FFF, black=#000); the
+/// high-contrast palette has orange (#FF5500) and blue (#0087FF) pieces,
+/// which read on both its white and its black squares.
+pub fn piece_foreground(color: Color, palette: Palette, mode: ColorMode) -> &'static str {
+    match (palette, color, mode) {
+        (Palette::HighContrast, Color::White, ColorMode::TrueColor) => "\x1b[38;2;255;85;0m",
+        (Palette::HighContrast, Color::Black, ColorMode::TrueColor) => "\x1b[38;2;0;135;255m",
+        (Palette::HighContrast, Color::White, ColorMode::Color256) => "\x1b[38;5;202m",
+        (Palette::HighContrast, Color::Black, ColorMode::Color256) => "\x1b[38;5;33m",
+        (Palette::Green | Palette::Blue | Palette::Brown, Color::White, ColorMode::TrueColor) => "\x1b[38;2;255;255;255m",
+        (Palette::Green | Palette::Blue | Palette::Brown, Color::Black, ColorMode::TrueColor) => "\x1b[38;2;0;0;0m",
+        (Palette::Green | Palette::Blue | Palette::Brown, Color::White, ColorMode::Color256) => "\x1b[38;5;231m",
+        (Palette::Green | Palette::Blue | Palette::Brown, Color::Black, ColorMode::Color256) => "\x1b[38;5;16m",
     }
 }
 
-/// ANSI background escape for square shade (light=#EBECD0, dark=#779556).
-pub fn square_background(shade: SquareShade, mode: ColorMode) -> &'static str {
-    match (shade, mode) {
-        (SquareShade::Light, ColorMode::TrueColor) => "\x1b[48;2;235;236;208m",
-        (SquareShade::Dark, ColorMode::TrueColor) => "\x1b[48;2;119;149;86m",
-        (SquareShade::Light, ColorMode::Color256) => "\x1b[48;5;187m",
-        (SquareShade::Dark, ColorMode::Color256) => "\x1b[48;5;65m",
+/// ANSI background escape for square shade in `palette`:
+///
+/// | Palette | Light | Dark |
+/// |---------|-------|------|
+/// | green | #EBECD0 | #779556 |
+/// | blue | #DEE3E6 | #8CA2AD |
+/// | brown | #F0D9B5 | #B58863 |
+/// | high-contrast | #FFFFFF | #000000 |
+pub fn square_background(shade: SquareShade, palette: Palette, mode: ColorMode) -> &'static str {
+    match (palette, shade, mode) {
+        (Palette::Green, SquareShade::Light, ColorMode::TrueColor) => "\x1b[48;2;235;236;208m",
+        (Palette::Green, SquareShade::Dark, ColorMode::TrueColor) => "\x1b[48;2;119;149;86m",
+        (Palette::Green, SquareShade::Light, ColorMode::Color256) => "\x1b[48;5;187m",
+        (Palette::Green, SquareShade::Dark, ColorMode::Color256) => "\x1b[48;5;65m",
+        (Palette::Blue, SquareShade::Light, ColorMode::TrueColor) => "\x1b[48;2;222;227;230m",
+        (Palette::Blue, SquareShade::Dark, ColorMode::TrueColor) => "\x1b[48;2;140;162;173m",
+        (Palette::Blue, SquareShade::Light, ColorMode::Color256) => "\x1b[48;5;254m",
+        (Palette::Blue, SquareShade::Dark, ColorMode::Color256) => "\x1b[48;5;109m",
+        (Palette::Brown, SquareShade::Light, ColorMode::TrueColor) => "\x1b[48;2;240;217;181m",
+        (Palette::Brown, SquareShade::Dark, ColorMode::TrueColor) => "\x1b[48;2;181;136;99m",
+        (Palette::Brown, SquareShade::Light, ColorMode::Color256) => "\x1b[48;5;223m",
+        (Palette::Brown, SquareShade::Dark, ColorMode::Color256) => "\x1b[48;5;137m",
+        (Palette::HighContrast, SquareShade::Light, ColorMode::TrueColor) => "\x1b[48;2;255;255;255m",
+        (Palette::HighContrast, SquareShade::Dark, ColorMode::TrueColor) => "\x1b[48;2;0;0;0m",
+        (Palette::HighContrast, SquareShade::Light, ColorMode::Color256) => "\x1b[48;5;231m",
+        (Palette::HighContrast, SquareShade::Dark, ColorMode::Color256) => "\x1b[48;5;16m",
+    }
+}
+
+/// ANSI background escape for a square with `marker`: its shade, a blue
+/// tint of it for a hint (light=#AAC8EB, dark=#6E96C8), or a brighter
+/// yellow-green for the last move (light=#F5F682, dark=#B9CA43), or orange
+/// under the selection cursor (light=#F6B26B, dark=#D68442). The markers
+/// look the same in every palette.
+pub fn marked_background(shade: SquareShade, marker: SquareMarker, palette: Palette, mode: ColorMode) -> &'static str {
+    match (marker, shade, mode) {
+        (SquareMarker::None, shade, mode) => square_background(shade, palette, mode),
+        (SquareMarker::Hint, SquareShade::Light, ColorMode::TrueColor) => "\x1b[48;2;170;200;235m",
+        (SquareMarker::Hint, SquareShade::Dark, ColorMode::TrueColor) => "\x1b[48;2;110;150;200m",
+        (SquareMarker::Hint, SquareShade::Light, ColorMode::Color256) => "\x1b[48;5;153m",
+        (SquareMarker::Hint, SquareShade::Dark, ColorMode::Color256) => "\x1b[48;5;68m",
+        (SquareMarker::LastMove, SquareShade::Light, ColorMode::TrueColor) => "\x1b[48;2;245;246;130m",
+        (SquareMarker::LastMove, SquareShade::Dark, ColorMode::TrueColor) => "\x1b[48;2;185;202;67m",
+        (SquareMarker::LastMove, SquareShade::Light, ColorMode::Color256) => "\x1b[48;5;228m",
+        (SquareMarker::LastMove, SquareShade::Dark, ColorMode::Color256) => "\x1b[48;5;149m",
+        (SquareMarker::Cursor, SquareShade::Light, ColorMode::TrueColor) => "\x1b[48;2;246;178;107m",
+        (SquareMarker::Cursor, SquareShade::Dark, ColorMode::TrueColor) => "\x1b[48;2;214;132;66m",
+        (SquareMarker::Cursor, SquareShade::Light, ColorMode::Color256) => "\x1b[48;5;215m",
+        (SquareMarker::Cursor, SquareShade::Dark, ColorMode::Color256) => "\x1b[48;5;173m",
     }
 }
 
@@ -39,11 +87,11 @@ mod tests {
     #[test]
     fn piece_foreground_truecolor() {
         assert_eq!(
-            piece_foreground(Color::White, ColorMode::TrueColor),
+            piece_foreground(Color::White, Palette::Green, ColorMode::TrueColor),
             "\x1b[38;2;255;255;255m"
         );
         assert_eq!(
-            piece_foreground(Color::Black, ColorMode::TrueColor),
+            piece_foreground(Color::Black, Palette::Green, ColorMode::TrueColor),
             "\x1b[38;2;0;0;0m"
         );
     }
@@ -51,28 +99,68 @@ mod tests {
     #[test]
     fn piece_foreground_256() {
         assert_eq!(
-            piece_foreground(Color::White, ColorMode::Color256),
+            piece_foreground(Color::White, Palette::Green, ColorMode::Color256),
             "\x1b[38;5;231m"
         );
         assert_eq!(
-            piece_foreground(Color::Black, ColorMode::Color256),
+            piece_foreground(Color::Black, Palette::Green, ColorMode::Color256),
             "\x1b[38;5;16m"
         );
     }
 
     #[test]
     fn square_background_truecolor() {
-        let light = square_background(SquareShade::Light, ColorMode::TrueColor);
+        let light = square_background(SquareShade::Light, Palette::Green, ColorMode::TrueColor);
         assert_eq!(light, "\x1b[48;2;235;236;208m");
-        let dark = square_background(SquareShade::Dark, ColorMode::TrueColor);
+        let dark = square_background(SquareShade::Dark, Palette::Green, ColorMode::TrueColor);
         assert_eq!(dark, "\x1b[48;2;119;149;86m");
     }
 
+    #[test]
+    fn marked_background_tints_hints_the_last_move_and_the_cursor() {
+        for mode in [ColorMode::TrueColor, ColorMode::Color256] {
+            for shade in [SquareShade::Light, SquareShade::Dark] {
+                let plain = square_background(shade, Palette::Green, mode);
+                let hint = marked_background(shade, SquareMarker::Hint, Palette::Green, mode);
+                let last_move = marked_background(shade, SquareMarker::LastMove, Palette::Green, mode);
+                let cursor = marked_background(shade, SquareMarker::Cursor, Palette::Green, mode);
+                assert_eq!(marked_background(shade, SquareMarker::None, Palette::Green, mode), plain);
+                assert_ne!(hint, plain);
+                assert_ne!(last_move, plain);
+                assert_ne!(last_move, hint);
+                assert!(![plain, hint, last_move].contains(&cursor));
+            }
+        }
+    }
+
     #[test]
     fn square_background_256() {
-        let light = square_background(SquareShade::Light, ColorMode::Color256);
+        let light = square_background(SquareShade::Light, Palette::Green, ColorMode::Color256);
         assert_eq!(light, "\x1b[48;5;187m");
-        let dark = square_background(SquareShade::Dark, ColorMode::Color256);
+        let dark = square_background(SquareShade::Dark, Palette::Green, ColorMode::Color256);
         assert_eq!(dark, "\x1b[48;5;65m");
     }
+
+    #[test]
+    fn palettes_shade_the_board_apart() {
+        let palettes = [Palette::Green, Palette::Blue, Palette::Brown, Palette::HighContrast];
+        for mode in [ColorMode::TrueColor, ColorMode::Color256] {
+            for (idx, palette) in palettes.iter().enumerate() {
+                let light = square_background(SquareShade::Light, *palette, mode);
+                assert_ne!(light, square_background(SquareShade::Dark, *palette, mode));
+                for other in &palettes[idx + 1..] {
+                    assert_ne!(light, square_background(SquareShade::Light, *other, mode));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn high_contrast_pieces_are_not_white_and_black() {
+        for mode in [ColorMode::TrueColor, ColorMode::Color256] {
+            let white = piece_foreground(Color::White, Palette::HighContrast, mode);
+            assert_ne!(white, piece_foreground(Color::White, Palette::Green, mode));
+            assert_ne!(white, piece_foreground(Color::Black, Palette::HighContrast, mode));
+        }
+    }
 }
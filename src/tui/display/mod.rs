@@ -8,7 +8,7 @@
 //!
 //! | Strategy | Rendering | Colors |
 //! |----------|-----------|--------|
-//! | [`SpriteDisplay`] | Half-block pixel art (7×3 per square) | ANSI |
+//! | [`SpriteDisplay`] | Half-block pixel art (7×3, 9×4 or 5×2 per square) | ANSI |
 //! | [`UnicodeDisplay`] | Chess symbols ♔♕♖♗♘♙ (3×1 per square) | ANSI |
 //! | [`AsciiDisplay`] | Letters K Q R B N P (3×1 per square) | None |
 //!
@@ -17,7 +17,8 @@
 //! [`ColorMode`] selects between truecolor (24-bit) and 256-color ANSI
 //! output. It is detected from the `COLORTERM` environment variable via
 //! [`detect_color_mode`]. Both [`SpriteDisplay`] and [`UnicodeDisplay`]
-//! accept a `ColorMode`; [`AsciiDisplay`] ignores colors entirely.
+//! accept a `ColorMode` and a [`Palette`] of square and piece colors;
+//! [`AsciiDisplay`] ignores colors entirely.
 
 mod ascii;
 mod colors;
@@ -25,16 +26,18 @@ mod sprite;
 mod unicode;
 
 pub use ascii::AsciiDisplay;
-pub use sprite::SpriteDisplay;
+pub use sprite::{SpriteDisplay, SpriteSet};
 pub use unicode::UnicodeDisplay;
 
 use std::io::{self, Write};
 
 use crate::engine::board::{Board, Color};
-use crate::engine::chess::Piece;
+use crate::engine::chess::{Piece, Square};
 
 const BOARD_SIZE: u8 = 8;
 const FILE_LABELS: [char; 8] = ['a', 'b', 'c', 'd', 'e', 'f', 'g', 'h'];
+/// Columns of the rank labels left of the board, in every strategy.
+const RANK_LABEL_WIDTH: usize = 3;
 
 /// ANSI color depth for terminal output.
 ///
@@ -47,6 +50,42 @@ pub enum ColorMode {
     Color256,
 }
 
+/// Square and piece colors of the colored displays, set with the REPL's
+/// `theme`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Palette {
+    /// The chess.com greens
+    #[default]
+    Green,
+    Blue,
+    Brown,
+    /// White and black squares, orange and blue pieces
+    HighContrast,
+}
+
+impl Palette {
+    pub const NAMES: [&'static str; 4] = ["green", "blue", "brown", "high-contrast"];
+
+    pub fn from_name(name: &str) -> Option<Palette> {
+        match name {
+            "green" => Some(Palette::Green),
+            "blue" => Some(Palette::Blue),
+            "brown" => Some(Palette::Brown),
+            "high-contrast" => Some(Palette::HighContrast),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Palette::Green => "green",
+            Palette::Blue => "blue",
+            Palette::Brown => "brown",
+            Palette::HighContrast => "high-contrast",
+        }
+    }
+}
+
 /// Checkerboard square parity — determines the background shade.
 ///
 /// On a standard board, a1 (file=0, rank=0) is dark. Adjacent squares
@@ -57,6 +96,40 @@ pub enum SquareShade {
     Dark,
 }
 
+/// Emphasis drawn on a square over its shade.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SquareMarker {
+    None,
+    /// A legal destination listed by the REPL's `hint`
+    Hint,
+    /// Origin or destination of the most recent move
+    LastMove,
+    /// The square under the REPL's `select` cursor, or the piece it picked
+    Cursor,
+}
+
+/// What the sidebar lists beside the board.
+pub struct Sidebar<'a, S> {
+    /// Half-moves played, oldest first
+    pub moves: &'a [S],
+    /// Half-moves played before the first of `moves` (see [`format_move_list`])
+    pub first_ply: usize,
+    /// Pieces taken so far, oldest first
+    pub captured: &'a [(Piece, Color)],
+    /// Comments on moves, by the move's index in `moves`
+    pub comments: &'a [(usize, String)],
+    /// The note each of `moves` played, e.g. "A4 440Hz"; with them the
+    /// list takes a line per move (see [`sidebar_lines`])
+    pub notes: &'a [String],
+}
+
+impl<'a, S: AsRef<str>> Sidebar<'a, S> {
+    /// A sidebar with `moves` and no captures, comments or notes.
+    pub fn new(moves: &'a [S], first_ply: usize) -> Self {
+        Self { moves, first_ply, captured: &[], comments: &[], notes: &[] }
+    }
+}
+
 /// Rendering strategy for board display.
 ///
 /// Each strategy controls how individual squares, rank labels, and file
@@ -71,6 +144,7 @@ pub trait DisplayStrategy {
         writer: &mut dyn Write,
         square: Option<(Piece, Color)>,
         shade: SquareShade,
+        marker: SquareMarker,
         row: usize,
     ) -> io::Result<()>;
     fn render_rank_label(
@@ -79,35 +153,103 @@ pub trait DisplayStrategy {
         rank: u8,
         row: usize,
     ) -> io::Result<()>;
-    fn render_file_labels(&self, writer: &mut dyn Write) -> io::Result<()>;
+    /// The file letters in `labels`, left to right.
+    fn render_file_labels(&self, writer: &mut dyn Write, labels: &[char]) -> io::Result<()>;
+    /// One character for a piece, as used in the sidebar.
+    fn piece_symbol(&self, piece: Piece, color: Color) -> char;
+    /// Whether rank and file labels frame the board; without them the
+    /// layout loses its two label lines.
+    fn shows_coordinates(&self) -> bool {
+        true
+    }
+}
+
+/// Another strategy with the rank and file labels left out, for a clean
+/// frame (the REPL's `coords off`).
+pub struct WithoutCoordinates(pub Box<dyn DisplayStrategy>);
+
+impl DisplayStrategy for WithoutCoordinates {
+    fn square_height(&self) -> usize {
+        self.0.square_height()
+    }
+
+    fn square_width(&self) -> usize {
+        self.0.square_width()
+    }
+
+    fn render_square_row(
+        &self,
+        writer: &mut dyn Write,
+        square: Option<(Piece, Color)>,
+        shade: SquareShade,
+        marker: SquareMarker,
+        row: usize,
+    ) -> io::Result<()> {
+        self.0.render_square_row(writer, square, shade, marker, row)
+    }
+
+    fn render_rank_label(&self, _writer: &mut dyn Write, _rank: u8, _row: usize) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn render_file_labels(&self, _writer: &mut dyn Write, _labels: &[char]) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn piece_symbol(&self, piece: Piece, color: Color) -> char {
+        self.0.piece_symbol(piece, color)
+    }
+
+    fn shows_coordinates(&self) -> bool {
+        false
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum DisplayMode {
-    Sprite,
+    Sprite(SpriteSet),
     Unicode,
     Ascii,
 }
 
+/// The options `parse_display_mode` takes, for messages.
+pub const DISPLAY_MODE_NAMES: &str = "sprite, sprite:large, sprite:small, unicode, ascii";
+
+/// A display mode by name; `sprite:<set>` picks a [`SpriteSet`].
 pub fn parse_display_mode(value: &str) -> Option<DisplayMode> {
+    if let Some(set) = value.strip_prefix("sprite:") {
+        return SpriteSet::from_name(set).map(DisplayMode::Sprite);
+    }
     match value {
-        "sprite" => Some(DisplayMode::Sprite),
+        "sprite" => Some(DisplayMode::Sprite(SpriteSet::Classic)),
         "unicode" => Some(DisplayMode::Unicode),
         "ascii" => Some(DisplayMode::Ascii),
         _ => None,
     }
 }
 
+/// The name `parse_display_mode` reads back as `mode`.
+pub fn display_mode_name(mode: DisplayMode) -> String {
+    match mode {
+        DisplayMode::Sprite(SpriteSet::Classic) => "sprite".to_string(),
+        DisplayMode::Sprite(set) => format!("sprite:{}", set.name()),
+        DisplayMode::Unicode => "unicode".to_string(),
+        DisplayMode::Ascii => "ascii".to_string(),
+    }
+}
+
 /// Returns a heap-allocated strategy chosen at runtime.
 /// `dyn DisplayStrategy` enables dynamic dispatch — the concrete type
 /// (Sprite, Unicode, or Ascii) is resolved through a vtable at runtime,
 /// which lets the REPL swap strategies via the `display` command.
-pub fn create_strategy(mode: DisplayMode, color_mode: ColorMode) -> Box<dyn DisplayStrategy> {
-    match mode {
-        DisplayMode::Sprite => Box::new(SpriteDisplay::new(color_mode)),
-        DisplayMode::Unicode => Box::new(UnicodeDisplay::new(color_mode)),
+/// Without `coordinates` it is wrapped in [`WithoutCoordinates`].
+pub fn create_strategy(mode: DisplayMode, color_mode: ColorMode, palette: Palette, coordinates: bool) -> Box<dyn DisplayStrategy> {
+    let strategy: Box<dyn DisplayStrategy> = match mode {
+        DisplayMode::Sprite(set) => Box::new(SpriteDisplay::new(color_mode, palette, set)),
+        DisplayMode::Unicode => Box::new(UnicodeDisplay::new(color_mode, palette)),
         DisplayMode::Ascii => Box::new(AsciiDisplay),
-    }
+    };
+    if coordinates { strategy } else { Box::new(WithoutCoordinates(strategy)) }
 }
 
 pub fn color_mode_from_env(colorterm: &str) -> ColorMode {
@@ -117,6 +259,15 @@ pub fn color_mode_from_env(colorterm: &str) -> ColorMode {
     }
 }
 
+/// Parses a color mode given explicitly (config file or `--color-mode`).
+pub fn parse_color_mode(value: &str) -> Option<ColorMode> {
+    match value {
+        "truecolor" | "24bit" => Some(ColorMode::TrueColor),
+        "256" => Some(ColorMode::Color256),
+        _ => None,
+    }
+}
+
 pub fn detect_color_mode() -> ColorMode {
     let colorterm = std::env::var("COLORTERM").unwrap_or_default();
     color_mode_from_env(&colorterm)
@@ -124,13 +275,25 @@ pub fn detect_color_mode() -> ColorMode {
 
 const SIDEBAR_HEADER: &str = "Moves";
 const SIDEBAR_DIVIDER: &str = "─────────────";
-
-pub fn format_move_list<S: AsRef<str>>(half_moves: &[S]) -> Vec<String> {
-    half_moves
-        .chunks(2)
-        .enumerate()
-        .map(|(index, pair)| {
-            let move_number = index + 1;
+/// Comments longer than this, in characters, are cut short in the sidebar.
+const SIDEBAR_COMMENT_WIDTH: usize = 24;
+
+/// One line per full move, "1. e4    e5", numbered from `first_ply` (the
+/// half-moves played before the first of `half_moves`). When Black moves
+/// first, the list opens with "1... e5".
+pub fn format_move_list<S: AsRef<str>>(half_moves: &[S], first_ply: usize) -> Vec<String> {
+    let first_move = first_ply / 2 + 1;
+    let (black_opening, pairs) = match half_moves.split_first() {
+        Some((black_move, rest)) if !first_ply.is_multiple_of(2) => {
+            (Some(format!("{first_move}... {}", black_move.as_ref())), rest)
+        }
+        _ => (None, half_moves),
+    };
+    let pairs_from = first_move + usize::from(black_opening.is_some());
+    black_opening
+        .into_iter()
+        .chain(pairs.chunks(2).enumerate().map(|(index, pair)| {
+            let move_number = pairs_from + index;
             let white_move = pair[0].as_ref();
             match pair.get(1) {
                 Some(black_move) => {
@@ -138,7 +301,7 @@ pub fn format_move_list<S: AsRef<str>>(half_moves: &[S]) -> Vec<String> {
                 }
                 None => format!("{move_number}. {white_move}"),
             }
-        })
+        }))
         .collect()
 }
 
@@ -147,49 +310,151 @@ pub fn cursor_up_and_clear(writer: &mut impl Write, line_count: usize) -> io::Re
 }
 
 pub fn layout_height(strategy: &dyn DisplayStrategy) -> usize {
-    1 + BOARD_SIZE as usize * strategy.square_height() + 1
+    let label_lines = if strategy.shows_coordinates() { 2 } else { 0 };
+    label_lines + BOARD_SIZE as usize * strategy.square_height()
+}
+
+/// The square drawn at `column` of `line`, both counted from 0 at the
+/// top-left of the layout, or None off the board (labels, sidebar).
+pub fn square_at(strategy: &dyn DisplayStrategy, perspective: Color, column: usize, line: usize) -> Option<Square> {
+    let (label_width, label_lines) = if strategy.shows_coordinates() { (RANK_LABEL_WIDTH, 1) } else { (0, 0) };
+    let file_idx = column.checked_sub(label_width)? / strategy.square_width();
+    let rank_idx = line.checked_sub(label_lines)? / strategy.square_height();
+    let (ranks, files) = board_order(perspective);
+    Some(Square { file: *files.get(file_idx)?, rank: *ranks.get(rank_idx)? })
 }
 
-pub fn sidebar_lines<S: AsRef<str>>(half_moves: &[S], available_height: usize) -> Vec<String> {
+/// "   {text}", cut to [`SIDEBAR_COMMENT_WIDTH`] characters with "…".
+fn comment_line(text: &str) -> String {
+    let shown: String = if text.chars().count() > SIDEBAR_COMMENT_WIDTH {
+        text.chars().take(SIDEBAR_COMMENT_WIDTH - 1).chain(['…']).collect()
+    } else {
+        text.to_string()
+    };
+    format!("   {{{shown}}}")
+}
+
+/// One line per half-move with the note it played, "12. Nf3 → A4 440Hz"
+/// or "12... Nc6 → ..."; a move without a note in `notes` has no arrow.
+fn format_move_notes<S: AsRef<str>>(half_moves: &[S], first_ply: usize, notes: &[String]) -> Vec<String> {
+    half_moves
+        .iter()
+        .enumerate()
+        .map(|(idx, half_move)| {
+            let ply = first_ply + idx;
+            let dots = if ply.is_multiple_of(2) { "." } else { "..." };
+            let line = format!("{}{dots} {}", ply / 2 + 1, half_move.as_ref());
+            match notes.get(idx).filter(|note| !note.is_empty()) {
+                Some(note) => format!("{line} → {note}"),
+                None => line,
+            }
+        })
+        .collect()
+}
+
+/// The header, then the move list with each comment on a line under its
+/// move's, scrolled to the newest lines that fit `available_height`. With
+/// `notes` every move takes its own line, followed by the note it played.
+pub fn sidebar_lines<S: AsRef<str>>(
+    half_moves: &[S],
+    first_ply: usize,
+    comments: &[(usize, String)],
+    notes: &[String],
+    available_height: usize,
+) -> Vec<String> {
     let mut lines = vec![SIDEBAR_HEADER.to_string(), SIDEBAR_DIVIDER.to_string()];
-    let move_lines = format_move_list(half_moves);
+    let mut move_lines = Vec::new();
+    // A lone Black move opens the paired list when Black moved first
+    let (list, moves_per_line, offset) = if notes.is_empty() {
+        (format_move_list(half_moves, first_ply), 2, first_ply % 2)
+    } else {
+        (format_move_notes(half_moves, first_ply, notes), 1, 0)
+    };
+    for (line_idx, line) in list.into_iter().enumerate() {
+        move_lines.push(line);
+        let on_this_line = |idx: usize| idx < half_moves.len() && (idx + offset) / moves_per_line == line_idx;
+        move_lines.extend(comments.iter().filter(|(idx, _)| on_this_line(*idx)).map(|(_, text)| comment_line(text)));
+    }
     let max_move_lines = available_height.saturating_sub(2);
     let skip_count = move_lines.len().saturating_sub(max_move_lines);
     lines.extend(move_lines.into_iter().skip(skip_count));
     lines
 }
 
+/// "White: ♟♟♞  Black: ♙" - what each side has taken, cheapest first, in
+/// the strategy's symbols. A side without captures is left out; None when
+/// neither has any.
+pub fn captured_line(strategy: &dyn DisplayStrategy, captured: &[(Piece, Color)]) -> Option<String> {
+    let taken_by = |side: Color| {
+        let mut pieces: Vec<(Piece, Color)> = captured.iter().copied().filter(|(_, color)| *color != side).collect();
+        pieces.sort_by_key(|(piece, _)| piece.value());
+        pieces.iter().map(|(piece, color)| strategy.piece_symbol(*piece, *color)).collect::<String>()
+    };
+    let parts: Vec<String> = [("White", Color::White), ("Black", Color::Black)]
+        .iter()
+        .map(|(name, side)| (name, taken_by(*side)))
+        .filter(|(_, symbols)| !symbols.is_empty())
+        .map(|(name, symbols)| format!("{name}: {symbols}"))
+        .collect();
+    if parts.is_empty() { None } else { Some(parts.join("  ")) }
+}
+
 fn square_shade(file: u8, rank: u8) -> SquareShade {
-    if (file + rank) % 2 != 0 {
+    if !(file + rank).is_multiple_of(2) {
         SquareShade::Light
     } else {
         SquareShade::Dark
     }
 }
 
+/// Ranks top to bottom and files left to right as seen from `perspective`'s
+/// side of the board: 8→1 and a→h for White, 1→8 and h→a for Black.
+fn board_order(perspective: Color) -> (Vec<u8>, Vec<u8>) {
+    match perspective {
+        Color::White => ((0..BOARD_SIZE).rev().collect(), (0..BOARD_SIZE).collect()),
+        Color::Black => ((0..BOARD_SIZE).collect(), (0..BOARD_SIZE).rev().collect()),
+    }
+}
+
 /// `&dyn DisplayStrategy` accepts any strategy behind a trait object,
-/// matching the `Box<dyn DisplayStrategy>` the REPL holds.
+/// matching the `Box<dyn DisplayStrategy>` the REPL holds. The board is
+/// drawn with `perspective`'s pieces at the bottom. The sidebar
+/// lists the moves, with the captured pieces under them. Squares
+/// in `markers` are drawn with their marker, the first listed winning when
+/// a square appears twice; the rest plain.
 pub fn render<S: AsRef<str>>(
     board: &Board,
     writer: &mut impl Write,
     strategy: &dyn DisplayStrategy,
-    moves: &[S],
+    sidebar: &Sidebar<S>,
+    markers: &[(Square, SquareMarker)],
+    perspective: Color,
 ) -> io::Result<()> {
-    strategy.render_file_labels(writer)?;
+    let (ranks, files) = board_order(perspective);
+    let labels: Vec<char> = files.iter().map(|&file| FILE_LABELS[file as usize]).collect();
+    strategy.render_file_labels(writer, &labels)?;
     let board_height = BOARD_SIZE as usize * strategy.square_height();
-    let sidebar = if moves.is_empty() {
+    let captured = captured_line(strategy, sidebar.captured);
+    let sidebar = if sidebar.moves.is_empty() {
         vec![]
     } else {
-        sidebar_lines(moves, board_height)
+        let move_height = board_height - usize::from(captured.is_some());
+        let mut lines = sidebar_lines(sidebar.moves, sidebar.first_ply, sidebar.comments, sidebar.notes, move_height);
+        lines.extend(captured);
+        lines
     };
     let mut board_line_index = 0;
-    for rank in (0..BOARD_SIZE).rev() {
+    for &rank in &ranks {
         for row in 0..strategy.square_height() {
             strategy.render_rank_label(writer, rank, row)?;
-            for file in 0..BOARD_SIZE {
+            for &file in &files {
                 let shade = square_shade(file, rank);
                 let square = board.get(file, rank);
-                strategy.render_square_row(writer, square, shade, row)?;
+                let marker = markers
+                    .iter()
+                    .find(|(marked, _)| *marked == Square { file, rank })
+                    .map_or(SquareMarker::None, |(_, marker)| *marker);
+                strategy.render_square_row(writer, square, shade, marker, row)?;
             }
             if let Some(sidebar_text) = sidebar.get(board_line_index) {
                 write!(writer, "   {sidebar_text}")?;
@@ -198,7 +463,7 @@ pub fn render<S: AsRef<str>>(
             writeln!(writer)?;
         }
     }
-    strategy.render_file_labels(writer)
+    strategy.render_file_labels(writer, &labels)
 }
 
 #[cfg(test)]
@@ -209,21 +474,21 @@ mod tests {
 
     #[test]
     fn format_move_list_empty_input() {
-        let result = format_move_list(NO_MOVES);
+        let result = format_move_list(NO_MOVES, 0);
         assert!(result.is_empty());
     }
 
     #[test]
     fn format_move_list_single_move() {
         let moves = vec!["e4".to_string()];
-        let result = format_move_list(&moves);
+        let result = format_move_list(&moves, 0);
         assert_eq!(result, vec!["1. e4"]);
     }
 
     #[test]
     fn format_move_list_complete_pair() {
         let moves = vec!["e4".to_string(), "e5".to_string()];
-        let result = format_move_list(&moves);
+        let result = format_move_list(&moves, 0);
         assert_eq!(result, vec!["1. e4    e5"]);
     }
 
@@ -235,7 +500,7 @@ mod tests {
             "Nf3".to_string(),
             "Nc6".to_string(),
         ];
-        let result = format_move_list(&moves);
+        let result = format_move_list(&moves, 0);
         assert_eq!(result, vec!["1. e4    e5", "2. Nf3   Nc6"]);
     }
 
@@ -246,20 +511,28 @@ mod tests {
             "e5".to_string(),
             "Nf3".to_string(),
         ];
-        let result = format_move_list(&moves);
+        let result = format_move_list(&moves, 0);
         assert_eq!(result, vec!["1. e4    e5", "2. Nf3"]);
     }
 
+    #[test]
+    fn format_move_list_numbers_from_the_start_position() {
+        let moves = vec!["Nf6".to_string(), "Nc3".to_string(), "e5".to_string()];
+        assert_eq!(format_move_list(&moves, 1), vec!["1... Nf6", "2. Nc3   e5"]);
+        assert_eq!(format_move_list(&moves, 78), vec!["40. Nf6   Nc3", "41. e5"]);
+        assert_eq!(format_move_list(&moves, 79), vec!["40... Nf6", "41. Nc3   e5"]);
+    }
+
     #[test]
     fn sidebar_lines_empty_moves() {
-        let result = sidebar_lines(NO_MOVES, 8);
+        let result = sidebar_lines(NO_MOVES, 0, &[], &[], 8);
         assert_eq!(result, vec!["Moves", "─────────────"]);
     }
 
     #[test]
     fn sidebar_lines_with_moves() {
         let moves = vec!["e4".to_string(), "e5".to_string()];
-        let result = sidebar_lines(&moves, 8);
+        let result = sidebar_lines(&moves, 0, &[], &[], 8);
         assert_eq!(result, vec!["Moves", "─────────────", "1. e4    e5"]);
     }
 
@@ -268,7 +541,7 @@ mod tests {
         let moves: Vec<String> = (0..20)
             .map(|i| format!("m{i}"))
             .collect();
-        let result = sidebar_lines(&moves, 8);
+        let result = sidebar_lines(&moves, 0, &[], &[], 8);
         assert_eq!(result.len(), 8);
         assert_eq!(result[0], "Moves");
         assert_eq!(result[1], "─────────────");
@@ -282,7 +555,7 @@ mod tests {
             "Nf3".to_string(), "Nc6".to_string(),
             "Bb5".to_string(), "a6".to_string(),
         ];
-        let result = sidebar_lines(&moves, 5);
+        let result = sidebar_lines(&moves, 0, &[], &[], 5);
         assert_eq!(result.len(), 5);
         assert_eq!(result[0], "Moves");
         assert_eq!(result[1], "─────────────");
@@ -291,11 +564,35 @@ mod tests {
         assert_eq!(result[4], "3. Bb5   a6");
     }
 
+    #[test]
+    fn sidebar_lines_put_comments_under_their_moves() {
+        let moves = vec!["e5".to_string(), "Nf3".to_string(), "Nc6".to_string()];
+        let comments = [(0, "the only reply".to_string()), (2, "developing the knight toward the centre".to_string())];
+        assert_eq!(
+            sidebar_lines(&moves, 1, &comments, &[], 8)[2..],
+            ["1... e5", "   {the only reply}", "2. Nf3   Nc6", "   {developing the knight t…}"]
+        );
+        // Comments on moves not in the list (taken back) are left out
+        assert_eq!(sidebar_lines(&moves[..1], 1, &comments, &[], 8).len(), 4);
+    }
+
+    #[test]
+    fn sidebar_lines_with_notes_take_a_line_per_move() {
+        let moves = vec!["e4".to_string(), "e5".to_string(), "O-O".to_string()];
+        let notes = ["G4 392Hz".to_string(), "G5 784Hz".to_string(), String::new()];
+        let comments = [(1, "symmetry".to_string())];
+        assert_eq!(
+            sidebar_lines(&moves, 0, &comments, &notes, 8)[2..],
+            ["1. e4 → G4 392Hz", "1... e5 → G5 784Hz", "   {symmetry}", "2. O-O"]
+        );
+        assert_eq!(sidebar_lines(&moves[1..], 1, &[], &notes[1..], 8)[2], "1... e5 → G5 784Hz");
+    }
+
     #[test]
     fn render_with_empty_moves_has_no_sidebar() {
         let board = Board::new();
         let mut buf = Vec::new();
-        render(&board, &mut buf, &AsciiDisplay, NO_MOVES).unwrap();
+        render(&board, &mut buf, &AsciiDisplay, &Sidebar::new(NO_MOVES, 0), &[], Color::White).unwrap();
         let output = String::from_utf8(buf).unwrap();
         assert!(!output.contains("Moves"));
     }
@@ -305,7 +602,7 @@ mod tests {
         let board = Board::new();
         let moves = vec!["e4".to_string(), "e5".to_string()];
         let mut buf = Vec::new();
-        render(&board, &mut buf, &AsciiDisplay, &moves).unwrap();
+        render(&board, &mut buf, &AsciiDisplay, &Sidebar::new(&moves, 0), &[], Color::White).unwrap();
         let output = String::from_utf8(buf).unwrap();
         assert!(output.contains("Moves"), "should contain sidebar header");
         assert!(output.contains("─────────────"), "should contain sidebar divider");
@@ -317,7 +614,7 @@ mod tests {
         let board = Board::new();
         let moves = vec!["e4".to_string(), "e5".to_string()];
         let mut buf = Vec::new();
-        render(&board, &mut buf, &AsciiDisplay, &moves).unwrap();
+        render(&board, &mut buf, &AsciiDisplay, &Sidebar::new(&moves, 0), &[], Color::White).unwrap();
         let output = String::from_utf8(buf).unwrap();
         let lines: Vec<&str> = output.lines().collect();
         let first_line = lines[0];
@@ -332,13 +629,77 @@ mod tests {
         let moves = vec!["e4".to_string(), "e5".to_string()];
         let mut buf_no_moves = Vec::new();
         let mut buf_with_moves = Vec::new();
-        render(&board, &mut buf_no_moves, &AsciiDisplay, NO_MOVES).unwrap();
-        render(&board, &mut buf_with_moves, &AsciiDisplay, &moves).unwrap();
+        render(&board, &mut buf_no_moves, &AsciiDisplay, &Sidebar::new(NO_MOVES, 0), &[], Color::White).unwrap();
+        render(&board, &mut buf_with_moves, &AsciiDisplay, &Sidebar::new(&moves, 0), &[], Color::White).unwrap();
         let lines_no_moves = String::from_utf8(buf_no_moves).unwrap().lines().count();
         let lines_with_moves = String::from_utf8(buf_with_moves).unwrap().lines().count();
         assert_eq!(lines_no_moves, lines_with_moves, "sidebar should not add extra lines");
     }
 
+    #[test]
+    fn render_draws_markers_on_their_squares() {
+        let board = Board::new();
+        let markers = [(Square::from_name("e3").unwrap(), SquareMarker::Hint)];
+        let mut buf = Vec::new();
+        render(&board, &mut buf, &AsciiDisplay, &Sidebar::new(NO_MOVES, 0), &markers, Color::White).unwrap();
+        let output = String::from_utf8(buf).unwrap();
+        let rank_3 = output.lines().find(|line| line.starts_with(" 3 ")).unwrap();
+        assert_eq!(rank_3, " 3  .  .  .  .  *  .  .  . ");
+        assert_eq!(output.matches('*').count(), 1);
+    }
+
+    #[test]
+    fn render_prefers_the_first_marker_of_a_square() {
+        let board = Board::new();
+        let e4 = Square::from_name("e4").unwrap();
+        let markers = [(e4, SquareMarker::Hint), (e4, SquareMarker::LastMove)];
+        let mut buf = Vec::new();
+        render(&board, &mut buf, &AsciiDisplay, &Sidebar::new(NO_MOVES, 0), &markers, Color::White).unwrap();
+        let output = String::from_utf8(buf).unwrap();
+        assert!(output.contains(" * "));
+        assert!(!output.contains('['));
+    }
+
+    #[test]
+    fn captured_line_lists_each_side_cheapest_first() {
+        let captured = [
+            (Piece::Knight, Color::Black),
+            (Piece::Pawn, Color::White),
+            (Piece::Pawn, Color::Black),
+            (Piece::Pawn, Color::Black),
+        ];
+        let unicode = UnicodeDisplay::new(ColorMode::TrueColor, Palette::Green);
+        assert_eq!(captured_line(&unicode, &captured), Some("White: ♟♟♞  Black: ♙".to_string()));
+        assert_eq!(captured_line(&AsciiDisplay, &captured[..1]), Some("White: n".to_string()));
+        assert_eq!(captured_line(&AsciiDisplay, &[]), None);
+    }
+
+    #[test]
+    fn render_shows_captures_under_the_moves() {
+        let board = Board::new();
+        let moves = vec!["e4".to_string(), "d5".to_string(), "exd5".to_string()];
+        let sidebar = Sidebar { moves: &moves, first_ply: 0, captured: &[(Piece::Pawn, Color::Black)], comments: &[], notes: &[] };
+        let mut buf = Vec::new();
+        render(&board, &mut buf, &AsciiDisplay, &sidebar, &[], Color::White).unwrap();
+        let output = String::from_utf8(buf).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert!(lines[4].ends_with("2. exd5"));
+        assert!(lines[5].ends_with("White: p"));
+    }
+
+    #[test]
+    fn render_from_blacks_side_flips_ranks_and_files() {
+        let board = Board::new();
+        let mut buf = Vec::new();
+        render(&board, &mut buf, &AsciiDisplay, &Sidebar::new(NO_MOVES, 0), &[], Color::Black).unwrap();
+        let output = String::from_utf8(buf).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines[0], "    h  g  f  e  d  c  b  a ");
+        assert_eq!(lines[1], " 1  R  N  B  K  Q  B  N  R ");
+        assert_eq!(lines[8], " 8  r  n  b  k  q  b  n  r ");
+        assert_eq!(lines[9], lines[0]);
+    }
+
     #[test]
     fn cursor_up_and_clear_ten_lines() {
         let mut buf = Vec::new();
@@ -361,15 +722,41 @@ mod tests {
         assert_eq!(layout_height(&strategy), 10);
     }
 
+    #[test]
+    fn without_coordinates_drops_the_labels() {
+        let strategy = WithoutCoordinates(Box::new(AsciiDisplay));
+        assert_eq!(layout_height(&strategy), 8);
+        let mut buf = Vec::new();
+        render(&Board::new(), &mut buf, &strategy, &Sidebar::new(NO_MOVES, 0), &[], Color::White).unwrap();
+        let output = String::from_utf8(buf).unwrap();
+        assert_eq!(output.lines().count(), 8);
+        assert_eq!(output.lines().next(), Some(" r  n  b  q  k  b  n  r "));
+    }
+
+    #[test]
+    fn square_at_accounts_for_labels_and_square_size() {
+        let sprite = SpriteDisplay::new(ColorMode::TrueColor, Palette::Green, SpriteSet::Classic);
+        // Sprite squares are 7 columns by 3 lines, after a 3-column rank label
+        assert_eq!(square_at(&sprite, Color::White, 3, 1), Square::from_name("a8"));
+        assert_eq!(square_at(&sprite, Color::White, 3 + 7 * 4, 1 + 3 * 4 + 2), Square::from_name("e4"));
+        assert_eq!(square_at(&sprite, Color::Black, 3, 1), Square::from_name("h1"));
+        assert_eq!(square_at(&sprite, Color::White, 1, 5), None);
+        assert_eq!(square_at(&sprite, Color::White, 3 + 7 * 8, 5), None);
+        assert_eq!(square_at(&sprite, Color::White, 10, 0), None);
+        let bare = WithoutCoordinates(Box::new(AsciiDisplay));
+        assert_eq!(square_at(&bare, Color::White, 0, 0), Square::from_name("a8"));
+        assert_eq!(square_at(&bare, Color::White, 23, 7), Square::from_name("h1"));
+    }
+
     #[test]
     fn layout_height_sprite() {
-        let strategy = SpriteDisplay::new(ColorMode::TrueColor);
+        let strategy = SpriteDisplay::new(ColorMode::TrueColor, Palette::Green, SpriteSet::Classic);
         assert_eq!(layout_height(&strategy), 26);
     }
 
     #[test]
     fn layout_height_unicode() {
-        let strategy = UnicodeDisplay::new(ColorMode::TrueColor);
+        let strategy = UnicodeDisplay::new(ColorMode::TrueColor, Palette::Green);
         assert_eq!(layout_height(&strategy), 10);
     }
 
@@ -393,34 +780,62 @@ mod tests {
         assert_eq!(color_mode_from_env(""), ColorMode::Color256);
     }
 
+    #[test]
+    fn parse_color_mode_values() {
+        assert_eq!(parse_color_mode("truecolor"), Some(ColorMode::TrueColor));
+        assert_eq!(parse_color_mode("24bit"), Some(ColorMode::TrueColor));
+        assert_eq!(parse_color_mode("256"), Some(ColorMode::Color256));
+        assert_eq!(parse_color_mode("auto"), None);
+    }
+
+    #[test]
+    fn palette_names_round_trip() {
+        for name in Palette::NAMES {
+            assert_eq!(Palette::from_name(name).map(Palette::name), Some(name));
+        }
+        assert_eq!(Palette::from_name("purple"), None);
+        assert_eq!(Palette::default(), Palette::Green);
+    }
+
     #[test]
     fn parse_display_mode_valid_values() {
-        assert_eq!(parse_display_mode("sprite"), Some(DisplayMode::Sprite));
+        assert_eq!(parse_display_mode("sprite"), Some(DisplayMode::Sprite(SpriteSet::Classic)));
+        assert_eq!(parse_display_mode("sprite:large"), Some(DisplayMode::Sprite(SpriteSet::Large)));
+        assert_eq!(parse_display_mode("sprite:small"), Some(DisplayMode::Sprite(SpriteSet::Small)));
+        assert_eq!(parse_display_mode("sprite:huge"), None);
         assert_eq!(parse_display_mode("unicode"), Some(DisplayMode::Unicode));
         assert_eq!(parse_display_mode("ascii"), Some(DisplayMode::Ascii));
     }
 
     #[test]
     fn create_strategy_sprite_dimensions() {
-        let strategy = create_strategy(DisplayMode::Sprite, ColorMode::TrueColor);
+        let strategy = create_strategy(DisplayMode::Sprite(SpriteSet::Classic), ColorMode::TrueColor, Palette::Green, true);
         assert_eq!(strategy.square_height(), 3);
         assert_eq!(strategy.square_width(), 7);
     }
 
     #[test]
     fn create_strategy_unicode_dimensions() {
-        let strategy = create_strategy(DisplayMode::Unicode, ColorMode::TrueColor);
+        let strategy = create_strategy(DisplayMode::Unicode, ColorMode::TrueColor, Palette::Green, true);
         assert_eq!(strategy.square_height(), 1);
         assert_eq!(strategy.square_width(), 3);
     }
 
     #[test]
     fn create_strategy_ascii_dimensions() {
-        let strategy = create_strategy(DisplayMode::Ascii, ColorMode::TrueColor);
+        let strategy = create_strategy(DisplayMode::Ascii, ColorMode::TrueColor, Palette::Green, true);
         assert_eq!(strategy.square_height(), 1);
         assert_eq!(strategy.square_width(), 3);
     }
 
+    #[test]
+    fn display_mode_names_parse_back() {
+        for name in DISPLAY_MODE_NAMES.split(", ") {
+            let mode = parse_display_mode(name).unwrap();
+            assert_eq!(display_mode_name(mode), name);
+        }
+    }
+
     #[test]
     fn parse_display_mode_invalid_values() {
         assert_eq!(parse_display_mode("foo"), None);
@@ -432,7 +847,7 @@ mod tests {
     fn display_initial_position() {
         let board = Board::new();
         let mut buf = Vec::new();
-        render(&board, &mut buf, &AsciiDisplay, NO_MOVES).unwrap();
+        render(&board, &mut buf, &AsciiDisplay, &Sidebar::new(NO_MOVES, 0), &[], Color::White).unwrap();
         let output = String::from_utf8(buf).unwrap();
         assert!(output.contains(" r "), "should contain black rook");
         assert!(output.contains(" P "), "should contain white pawn");
@@ -442,9 +857,9 @@ mod tests {
     #[test]
     fn render_full_board_initial_position() {
         let board = Board::new();
-        let strategy = SpriteDisplay::new(ColorMode::TrueColor);
+        let strategy = SpriteDisplay::new(ColorMode::TrueColor, Palette::Green, SpriteSet::Classic);
         let mut buf = Vec::new();
-        render(&board, &mut buf, &strategy, NO_MOVES).unwrap();
+        render(&board, &mut buf, &strategy, &Sidebar::new(NO_MOVES, 0), &[], Color::White).unwrap();
         let output = String::from_utf8(buf).unwrap();
         for rank in 1..=8 {
             assert!(output.contains(&format!(" {rank} ")), "missing rank {rank}");
@@ -464,7 +879,7 @@ mod tests {
         let board = Board::new();
         let strategy = AsciiDisplay;
         let mut buf = Vec::new();
-        render(&board, &mut buf, &strategy, NO_MOVES).unwrap();
+        render(&board, &mut buf, &strategy, &Sidebar::new(NO_MOVES, 0), &[], Color::White).unwrap();
         let output = String::from_utf8(buf).unwrap();
         for rank in 1..=8 {
             assert!(output.contains(&format!(" {rank} ")), "missing rank {rank}");
@@ -482,9 +897,9 @@ mod tests {
     #[test]
     fn render_with_sprite_strategy() {
         let board = Board::new();
-        let strategy = SpriteDisplay::new(ColorMode::TrueColor);
+        let strategy = SpriteDisplay::new(ColorMode::TrueColor, Palette::Green, SpriteSet::Classic);
         let mut buf = Vec::new();
-        render(&board, &mut buf, &strategy, NO_MOVES).unwrap();
+        render(&board, &mut buf, &strategy, &Sidebar::new(NO_MOVES, 0), &[], Color::White).unwrap();
         let output = String::from_utf8(buf).unwrap();
         for rank in 1..=8 {
             assert!(
@@ -502,9 +917,9 @@ mod tests {
     #[test]
     fn render_with_unicode_strategy() {
         let board = Board::new();
-        let strategy = UnicodeDisplay::new(ColorMode::TrueColor);
+        let strategy = UnicodeDisplay::new(ColorMode::TrueColor, Palette::Green);
         let mut buf = Vec::new();
-        render(&board, &mut buf, &strategy, NO_MOVES).unwrap();
+        render(&board, &mut buf, &strategy, &Sidebar::new(NO_MOVES, 0), &[], Color::White).unwrap();
         let output = String::from_utf8(buf).unwrap();
         assert!(output.contains('♔'), "should contain white king");
         assert!(output.contains('♟'), "should contain black pawn");
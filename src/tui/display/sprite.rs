@@ -3,60 +3,142 @@ use std::io::{self, Write};
 use crate::engine::board::Color;
 use crate::engine::chess::Piece;
 
-use super::colors::{label_foreground, piece_foreground, square_background, RESET};
-use super::{ColorMode, DisplayStrategy, SquareShade, FILE_LABELS};
+use super::unicode::unicode_symbol;
+use super::colors::{label_foreground, marked_background, piece_foreground, RESET};
+use super::{ColorMode, DisplayStrategy, Palette, SquareMarker, SquareShade};
 
-/// A sprite is 3 rows of 7-character strings using half-block characters
+/// A sprite is rows of equally wide strings using half-block characters
 /// (▄ ▀ █). Each character cell is 1 wide × 2 tall in the terminal, so
 /// 7 columns × 3 rows = 7×6 effective pixel resolution per square.
-type Sprite = [&'static str; 3];
+type Sprite = &'static [&'static str];
 
-const SPRITE_HEIGHT: usize = 3;
-pub(super) const SPRITE_SQUARE_WIDTH: usize = 7;
+/// One drawing of every piece, all sprites the same size.
+struct SpriteSheet {
+    /// Rows per square
+    height: usize,
+    /// Character cells per row
+    width: usize,
+    king: Sprite,
+    queen: Sprite,
+    rook: Sprite,
+    bishop: Sprite,
+    knight: Sprite,
+    pawn: Sprite,
+}
+
+impl SpriteSheet {
+    fn sprite_for(&self, piece: Piece) -> Sprite {
+        match piece {
+            Piece::King => self.king,
+            Piece::Queen => self.queen,
+            Piece::Rook => self.rook,
+            Piece::Bishop => self.bishop,
+            Piece::Knight => self.knight,
+            Piece::Pawn => self.pawn,
+        }
+    }
+}
+
+/// 7×3 cells, the default.
+const CLASSIC_SHEET: SpriteSheet = SpriteSheet {
+    height: 3,
+    width: 7,
+    king: &["   █   ", "  ▀█▀  ", "  ▀▀▀  "],
+    queen: &["  ▄ ▄  ", "  ▀█▀  ", "  ▀▀▀  "],
+    rook: &[" ▄ ▄ ▄ ", "  ███  ", "  ▀▀▀  "],
+    bishop: &["   ▄   ", "  ▄█▄  ", "  ▀▀▀  "],
+    knight: &["  ▄▄▄  ", "  ██   ", "  ▀    "],
+    pawn: &["       ", "  ▄█▄  ", "  ▀▀▀  "],
+};
 
-const KING_SPRITE: Sprite = ["   █   ", "  ▀█▀  ", "  ▀▀▀  "];
-const QUEEN_SPRITE: Sprite = ["  ▄ ▄  ", "  ▀█▀  ", "  ▀▀▀  "];
-const ROOK_SPRITE: Sprite = [" ▄ ▄ ▄ ", "  ███  ", "  ▀▀▀  "];
-const BISHOP_SPRITE: Sprite = ["   ▄   ", "  ▄█▄  ", "  ▀▀▀  "];
-const KNIGHT_SPRITE: Sprite = ["  ▄▄▄  ", "  ██   ", "  ▀    "];
-const PAWN_SPRITE: Sprite = ["       ", "  ▄█▄  ", "  ▀▀▀  "];
+/// 9×4 cells, with room for detail: crowns, the bishop's slit, a mane.
+const LARGE_SHEET: SpriteSheet = SpriteSheet {
+    height: 4,
+    width: 9,
+    king: &["   ▄█▄   ", "    █    ", "  ▄███▄  ", "  ▀▀▀▀▀  "],
+    queen: &[" ▄  ▄  ▄ ", "  ▀█▄█▀  ", "   ███   ", "  ▀▀▀▀▀  "],
+    rook: &["  ▄ ▄ ▄  ", "  █████  ", "   ███   ", "  ▀▀▀▀▀  "],
+    bishop: &["    ▄    ", "   █▀█   ", "   ▀█▀   ", "  ▀▀▀▀▀  "],
+    knight: &["   ▄▄▄   ", "  ██▀██  ", "    ███  ", "  ▀▀▀▀▀  "],
+    pawn: &["         ", "    ▄    ", "   ███   ", "  ▀▀▀▀▀  "],
+};
+
+/// 5×2 cells, for small terminals.
+const SMALL_SHEET: SpriteSheet = SpriteSheet {
+    height: 2,
+    width: 5,
+    king: &[" ▀█▀ ", " ▀▀▀ "],
+    queen: &["▄ ▄ ▄", " ▀▀▀ "],
+    rook: &[" █▄█ ", " ▀▀▀ "],
+    bishop: &["  █  ", " ▀▀▀ "],
+    knight: &[" ▄▄▄ ", " █▀  "],
+    pawn: &["  ▄  ", " ▀▀▀ "],
+};
+
+/// Which sprite sheet a [`SpriteDisplay`] draws with: `display sprite`,
+/// `sprite:large` or `sprite:small`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum SpriteSet {
+    #[default]
+    Classic,
+    Large,
+    Small,
+}
+
+impl SpriteSet {
+    pub const NAMES: [&'static str; 3] = ["classic", "large", "small"];
+
+    pub fn from_name(name: &str) -> Option<SpriteSet> {
+        match name {
+            "classic" => Some(SpriteSet::Classic),
+            "large" => Some(SpriteSet::Large),
+            "small" => Some(SpriteSet::Small),
+            _ => None,
+        }
+    }
 
-const SPRITE_EMPTY: &str = "       ";
+    pub fn name(self) -> &'static str {
+        match self {
+            SpriteSet::Classic => "classic",
+            SpriteSet::Large => "large",
+            SpriteSet::Small => "small",
+        }
+    }
 
-fn sprite_for(piece: Piece) -> Sprite {
-    match piece {
-        Piece::King => KING_SPRITE,
-        Piece::Queen => QUEEN_SPRITE,
-        Piece::Rook => ROOK_SPRITE,
-        Piece::Bishop => BISHOP_SPRITE,
-        Piece::Knight => KNIGHT_SPRITE,
-        Piece::Pawn => PAWN_SPRITE,
+    fn sheet(self) -> &'static SpriteSheet {
+        match self {
+            SpriteSet::Classic => &CLASSIC_SHEET,
+            SpriteSet::Large => &LARGE_SHEET,
+            SpriteSet::Small => &SMALL_SHEET,
+        }
     }
 }
 
 /// Half-block pixel art display with ANSI colored backgrounds.
 ///
-/// Each square is 7 characters wide and 3 rows tall, using Unicode
-/// half-block characters (▄ ▀ █) to create 7x6 effective pixel
-/// resolution per square. Piece foreground and square background
-/// colors are rendered via ANSI escape sequences.
+/// Each square is as wide and tall as the sprites of its [`SpriteSet`]
+/// (7×3 for the classic set), using Unicode half-block characters
+/// (▄ ▀ █) for twice the rows in pixels. Piece foreground and square
+/// background colors are rendered via ANSI escape sequences.
 pub struct SpriteDisplay {
     color_mode: ColorMode,
+    palette: Palette,
+    sheet: &'static SpriteSheet,
 }
 
 impl SpriteDisplay {
-    pub fn new(color_mode: ColorMode) -> Self {
-        Self { color_mode }
+    pub fn new(color_mode: ColorMode, palette: Palette, set: SpriteSet) -> Self {
+        Self { color_mode, palette, sheet: set.sheet() }
     }
 }
 
 impl DisplayStrategy for SpriteDisplay {
     fn square_height(&self) -> usize {
-        SPRITE_HEIGHT
+        self.sheet.height
     }
 
     fn square_width(&self) -> usize {
-        SPRITE_SQUARE_WIDTH
+        self.sheet.width
     }
 
     fn render_square_row(
@@ -64,14 +146,15 @@ impl DisplayStrategy for SpriteDisplay {
         writer: &mut dyn Write,
         square: Option<(Piece, Color)>,
         shade: SquareShade,
+        marker: SquareMarker,
         row: usize,
     ) -> io::Result<()> {
-        let bg = square_background(shade, self.color_mode);
+        let bg = marked_background(shade, marker, self.palette, self.color_mode);
         match square {
-            None => write!(writer, "{bg}{SPRITE_EMPTY}{RESET}"),
+            None => write!(writer, "{bg}{:width$}{RESET}", "", width = self.sheet.width),
             Some((piece, color)) => {
-                let fg = piece_foreground(color, self.color_mode);
-                let sprite_row = sprite_for(piece)[row];
+                let fg = piece_foreground(color, self.palette, self.color_mode);
+                let sprite_row = self.sheet.sprite_for(piece)[row];
                 write!(writer, "{bg}{fg}{sprite_row}{RESET}")
             }
         }
@@ -84,21 +167,28 @@ impl DisplayStrategy for SpriteDisplay {
         row: usize,
     ) -> io::Result<()> {
         let label_fg = label_foreground(self.color_mode);
-        if row == 1 {
+        // On the middle row, or the upper of the two middle ones
+        if row == (self.sheet.height - 1) / 2 {
             write!(writer, "{label_fg} {} {RESET}", rank + 1)
         } else {
             write!(writer, "   ")
         }
     }
 
-    fn render_file_labels(&self, writer: &mut dyn Write) -> io::Result<()> {
+    fn render_file_labels(&self, writer: &mut dyn Write, labels: &[char]) -> io::Result<()> {
         let label_fg = label_foreground(self.color_mode);
         write!(writer, "   ")?;
-        for label in FILE_LABELS {
-            write!(writer, "{label_fg}   {label}   {RESET}")?;
+        let before = (self.sheet.width - 1) / 2;
+        let after = self.sheet.width - 1 - before;
+        for label in labels {
+            write!(writer, "{label_fg}{:before$}{label}{:after$}{RESET}", "", "")?;
         }
         writeln!(writer)
     }
+
+    fn piece_symbol(&self, piece: Piece, color: Color) -> char {
+        unicode_symbol(piece, color)
+    }
 }
 
 #[cfg(test)]
@@ -107,17 +197,17 @@ mod tests {
 
     #[test]
     fn dimensions() {
-        let strategy = SpriteDisplay::new(ColorMode::TrueColor);
+        let strategy = SpriteDisplay::new(ColorMode::TrueColor, Palette::Green, SpriteSet::Classic);
         assert_eq!(strategy.square_height(), 3);
         assert_eq!(strategy.square_width(), 7);
     }
 
     #[test]
     fn renders_empty_square() {
-        let strategy = SpriteDisplay::new(ColorMode::TrueColor);
+        let strategy = SpriteDisplay::new(ColorMode::TrueColor, Palette::Green, SpriteSet::Classic);
         let mut buf = Vec::new();
         strategy
-            .render_square_row(&mut buf, None, SquareShade::Light, 0)
+            .render_square_row(&mut buf, None, SquareShade::Light, SquareMarker::None, 0)
             .unwrap();
         let output = String::from_utf8(buf).unwrap();
         assert_eq!(
@@ -128,13 +218,14 @@ mod tests {
 
     #[test]
     fn renders_occupied_square() {
-        let strategy = SpriteDisplay::new(ColorMode::TrueColor);
+        let strategy = SpriteDisplay::new(ColorMode::TrueColor, Palette::Green, SpriteSet::Classic);
         let mut buf = Vec::new();
         strategy
             .render_square_row(
                 &mut buf,
                 Some((Piece::Rook, Color::White)),
                 SquareShade::Dark,
+                SquareMarker::None,
                 1,
             )
             .unwrap();
@@ -143,45 +234,59 @@ mod tests {
         assert!(output.ends_with(RESET), "should end with reset");
     }
 
+    const PIECES: [Piece; 6] = [
+        Piece::King,
+        Piece::Queen,
+        Piece::Rook,
+        Piece::Bishop,
+        Piece::Knight,
+        Piece::Pawn,
+    ];
+
     #[test]
-    fn sprite_for_returns_three_rows_of_seven_cells() {
-        for piece in [
-            Piece::King,
-            Piece::Queen,
-            Piece::Rook,
-            Piece::Bishop,
-            Piece::Knight,
-            Piece::Pawn,
-        ] {
-            let sprite = sprite_for(piece);
-            assert_eq!(sprite.len(), 3, "sprite for {piece:?} should have 3 rows");
-            for (row_idx, row) in sprite.iter().enumerate() {
-                let cell_count = row.chars().count();
-                assert_eq!(
-                    cell_count, SPRITE_SQUARE_WIDTH,
-                    "sprite for {piece:?} row {row_idx} should have {SPRITE_SQUARE_WIDTH} cells, got {cell_count}"
-                );
+    fn sprites_fill_their_sheets_squares() {
+        for name in SpriteSet::NAMES {
+            let sheet = SpriteSet::from_name(name).unwrap().sheet();
+            for piece in PIECES {
+                let sprite = sheet.sprite_for(piece);
+                assert_eq!(sprite.len(), sheet.height, "{name} sprite for {piece:?} should have {} rows", sheet.height);
+                for (row_idx, row) in sprite.iter().enumerate() {
+                    let cell_count = row.chars().count();
+                    assert_eq!(
+                        cell_count, sheet.width,
+                        "{name} sprite for {piece:?} row {row_idx} should have {} cells, got {cell_count}",
+                        sheet.width
+                    );
+                }
             }
         }
     }
 
     #[test]
     fn sprites_are_distinct() {
-        let all_sprites = [
-            sprite_for(Piece::King),
-            sprite_for(Piece::Queen),
-            sprite_for(Piece::Rook),
-            sprite_for(Piece::Bishop),
-            sprite_for(Piece::Knight),
-            sprite_for(Piece::Pawn),
-        ];
-        for i in 0..all_sprites.len() {
-            for j in (i + 1)..all_sprites.len() {
-                assert_ne!(
-                    all_sprites[i], all_sprites[j],
-                    "sprites {i} and {j} should differ"
-                );
+        for name in SpriteSet::NAMES {
+            let sheet = SpriteSet::from_name(name).unwrap().sheet();
+            let all_sprites = PIECES.map(|piece| sheet.sprite_for(piece));
+            for i in 0..all_sprites.len() {
+                for j in (i + 1)..all_sprites.len() {
+                    assert_ne!(
+                        all_sprites[i], all_sprites[j],
+                        "{name} sprites {i} and {j} should differ"
+                    );
+                }
             }
         }
     }
+
+    #[test]
+    fn large_and_small_sets_change_the_square_size() {
+        let large = SpriteDisplay::new(ColorMode::TrueColor, Palette::Green, SpriteSet::Large);
+        assert_eq!((large.square_width(), large.square_height()), (9, 4));
+        let small = SpriteDisplay::new(ColorMode::TrueColor, Palette::Green, SpriteSet::Small);
+        assert_eq!((small.square_width(), small.square_height()), (5, 2));
+        let mut buf = Vec::new();
+        small.render_file_labels(&mut buf, &['a']).unwrap();
+        let output = String::from_utf8(buf).unwrap();
+        assert!(output.contains("  a  "), "{output:?}");
+    }
 }
@@ -3,12 +3,12 @@ use std::io::{self, Write};
 use crate::engine::board::Color;
 use crate::engine::chess::Piece;
 
-use super::colors::{label_foreground, piece_foreground, square_background, RESET};
-use super::{ColorMode, DisplayStrategy, SquareShade, FILE_LABELS};
+use super::colors::{label_foreground, marked_background, piece_foreground, RESET};
+use super::{ColorMode, DisplayStrategy, Palette, SquareMarker, SquareShade};
 
 const UNICODE_EMPTY: &str = "   ";
 
-fn unicode_symbol(piece: Piece, color: Color) -> char {
+pub fn unicode_symbol(piece: Piece, color: Color) -> char {
     match (piece, color) {
         (Piece::King, Color::White) => '♔',
         (Piece::Queen, Color::White) => '♕',
@@ -33,11 +33,12 @@ fn unicode_symbol(piece: Piece, color: Color) -> char {
 /// a compact colored view.
 pub struct UnicodeDisplay {
     color_mode: ColorMode,
+    palette: Palette,
 }
 
 impl UnicodeDisplay {
-    pub fn new(color_mode: ColorMode) -> Self {
-        Self { color_mode }
+    pub fn new(color_mode: ColorMode, palette: Palette) -> Self {
+        Self { color_mode, palette }
     }
 }
 
@@ -55,13 +56,14 @@ impl DisplayStrategy for UnicodeDisplay {
         writer: &mut dyn Write,
         square: Option<(Piece, Color)>,
         shade: SquareShade,
+        marker: SquareMarker,
         _row: usize,
     ) -> io::Result<()> {
-        let bg = square_background(shade, self.color_mode);
+        let bg = marked_background(shade, marker, self.palette, self.color_mode);
         match square {
             None => write!(writer, "{bg}{UNICODE_EMPTY}{RESET}"),
             Some((piece, color)) => {
-                let fg = piece_foreground(color, self.color_mode);
+                let fg = piece_foreground(color, self.palette, self.color_mode);
                 let symbol = unicode_symbol(piece, color);
                 write!(writer, "{bg}{fg} {symbol} {RESET}")
             }
@@ -78,14 +80,18 @@ impl DisplayStrategy for UnicodeDisplay {
         write!(writer, "{label_fg} {} {RESET}", rank + 1)
     }
 
-    fn render_file_labels(&self, writer: &mut dyn Write) -> io::Result<()> {
+    fn render_file_labels(&self, writer: &mut dyn Write, labels: &[char]) -> io::Result<()> {
         let label_fg = label_foreground(self.color_mode);
         write!(writer, "   ")?;
-        for label in FILE_LABELS {
+        for label in labels {
             write!(writer, "{label_fg} {label} {RESET}")?;
         }
         writeln!(writer)
     }
+
+    fn piece_symbol(&self, piece: Piece, color: Color) -> char {
+        unicode_symbol(piece, color)
+    }
 }
 
 #[cfg(test)]
@@ -94,17 +100,17 @@ mod tests {
 
     #[test]
     fn dimensions() {
-        let strategy = UnicodeDisplay::new(ColorMode::TrueColor);
+        let strategy = UnicodeDisplay::new(ColorMode::TrueColor, Palette::Green);
         assert_eq!(strategy.square_height(), 1);
         assert_eq!(strategy.square_width(), 3);
     }
 
     #[test]
     fn renders_empty_square() {
-        let strategy = UnicodeDisplay::new(ColorMode::TrueColor);
+        let strategy = UnicodeDisplay::new(ColorMode::TrueColor, Palette::Green);
         let mut buf = Vec::new();
         strategy
-            .render_square_row(&mut buf, None, SquareShade::Light, 0)
+            .render_square_row(&mut buf, None, SquareShade::Light, SquareMarker::None, 0)
             .unwrap();
         let output = String::from_utf8(buf).unwrap();
         assert!(output.starts_with("\x1b[48;2;235;236;208m"));
@@ -114,13 +120,14 @@ mod tests {
 
     #[test]
     fn renders_white_king() {
-        let strategy = UnicodeDisplay::new(ColorMode::TrueColor);
+        let strategy = UnicodeDisplay::new(ColorMode::TrueColor, Palette::Green);
         let mut buf = Vec::new();
         strategy
             .render_square_row(
                 &mut buf,
                 Some((Piece::King, Color::White)),
                 SquareShade::Dark,
+                SquareMarker::None,
                 0,
             )
             .unwrap();
@@ -130,13 +137,14 @@ mod tests {
 
     #[test]
     fn renders_black_pawn() {
-        let strategy = UnicodeDisplay::new(ColorMode::TrueColor);
+        let strategy = UnicodeDisplay::new(ColorMode::TrueColor, Palette::Green);
         let mut buf = Vec::new();
         strategy
             .render_square_row(
                 &mut buf,
                 Some((Piece::Pawn, Color::Black)),
                 SquareShade::Light,
+                SquareMarker::None,
                 0,
             )
             .unwrap();
@@ -0,0 +1,122 @@
+//! The REPL's `help`: every command, the keys at the prompt and in
+//! `select`, and what each piece sounds like in the session's sound theme.
+//!
+//! ```text
+//! Commands      one line each, usage then what it does
+//! Keys          the prompt's, then select's
+//! Sounds        each piece's waveform, then pitch, threats and captures
+//! ```
+//!
+//! The REPL shows it a page at a time under the board and clears it after.
+
+use crate::audio::theme::Theme;
+use crate::engine::chess::{Piece, Threat};
+
+/// Usage and what it does, in the order the banner lists them.
+const COMMANDS: [(&str, &str); 30] = [
+    ("<move>", "play a move in algebraic notation, e.g. e4 or Nf3"),
+    ("display <mode>", "sprite, sprite:large, sprite:small, unicode or ascii"),
+    ("theme [name]", "board colors: green, blue, brown, high-contrast"),
+    ("coords on|off", "show or hide the rank and file labels"),
+    ("announce on|off", "say each move in words instead of drawing the board"),
+    ("select", "pick a move with the arrow keys or the mouse"),
+    ("sound <square>", "hear a square, e.g. sound e4 or sound Nf3"),
+    ("volume [0-100]", "set or show the volume"),
+    ("mute", "silence the moves; again to bring them back"),
+    ("soundtheme [name]", "classic, 8-bit, ambient or a TOML file"),
+    ("hint <square>", "mark where the piece there can go"),
+    ("flip [auto]", "turn the board, or face the side to move"),
+    ("play engine [white|black] [depth]", "play against the engine"),
+    ("play off", "stop the engine"),
+    ("undo", "take back the last move"),
+    ("takeback", "take back a full move: the reply and yours"),
+    ("redo", "play the move taken back again"),
+    ("replay [0.5x|1x|2x]", "play the game again from its first move"),
+    ("note <text>", "comment on the last move"),
+    ("resign", "the side to move gives up the game"),
+    ("draw", "offer a draw; the other side accepts or declines"),
+    ("puzzle <file|fen moves>", "solve a position; the defense answers"),
+    ("fen [position]", "print the position as FEN, or set one up"),
+    ("import <file>", "load a PGN game; Enter steps through it"),
+    ("export <file>", "save the game as PGN"),
+    ("export audio <file>", "render the game to a WAV file with the sound theme"),
+    ("reset", "back to the starting position"),
+    ("new", "a new game, once one is over; the same as reset"),
+    ("help", "this overview"),
+    ("quit", "leave"),
+];
+
+/// Keys and what they do, at the prompt and then in `select`.
+const KEYS: [(&str, &str); 9] = [
+    ("↑ / ↓", "earlier commands at the prompt"),
+    ("← / →", "move within the line"),
+    ("Tab", "complete a move or a command; again for the next"),
+    ("Ctrl-C", "quit, as does Ctrl-D on an empty line"),
+    ("Enter", "on an empty line, the next move of an imported game"),
+    ("select: arrows", "move the cursor"),
+    ("select: Enter", "pick up a piece, then play it to the cursor"),
+    ("select: click", "the same as moving there and pressing Enter"),
+    ("select: q / Esc", "back to typing"),
+];
+
+/// Pieces in the order the legend lists them.
+const PIECES: [(Piece, &str); 6] = [
+    (Piece::Pawn, "Pawn"),
+    (Piece::Knight, "Knight"),
+    (Piece::Bishop, "Bishop"),
+    (Piece::Rook, "Rook"),
+    (Piece::Queen, "Queen"),
+    (Piece::King, "King"),
+];
+
+/// Width of the left column, enough for the longest usage.
+const COLUMN_WIDTH: usize = 35;
+
+fn row(left: &str, right: &str) -> String {
+    format!("{left:<COLUMN_WIDTH$}{right}")
+}
+
+/// The whole overview, one screen line each, with the legend of `theme`
+/// (named `theme_name`).
+pub fn lines(theme: &Theme, theme_name: &str) -> Vec<String> {
+    let mut lines = vec!["Commands".to_string()];
+    lines.extend(COMMANDS.iter().map(|(usage, meaning)| row(usage, meaning)));
+    lines.push(String::new());
+    lines.push("Keys".to_string());
+    lines.extend(KEYS.iter().map(|(key, meaning)| row(key, meaning)));
+    lines.push(String::new());
+    lines.push(format!("Sounds ({theme_name})"));
+    lines.extend(PIECES.iter().map(|(piece, name)| row(name, theme.sounds.get(*piece, Threat::None).timbre.name())));
+    lines.push(row("Square", "the pitch: files a-h climb the scale, ranks the octave"));
+    lines.push(row("Check, checkmate", "the moving piece's sound changes"));
+    lines.push(row("Capture", "a short noise hit under the note"));
+    lines
+}
+
+/// `lines` cut into pages of at most `page_height` lines.
+pub fn pages(lines: &[String], page_height: usize) -> Vec<&[String]> {
+    lines.chunks(page_height.max(1)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn legend_follows_the_sound_theme() {
+        let classic = lines(&Theme::default(), "classic");
+        assert!(classic.contains(&row("Pawn", "sine")));
+        let eight_bit = lines(&Theme::from_name("8-bit").unwrap(), "8-bit");
+        assert!(eight_bit.contains(&"Sounds (8-bit)".to_string()));
+        assert!(eight_bit.contains(&row("Pawn", "square")));
+    }
+
+    #[test]
+    fn pages_hold_every_line_in_order() {
+        let all = lines(&Theme::default(), "classic");
+        let pages = pages(&all, 10);
+        assert!(pages.iter().all(|page| page.len() <= 10));
+        assert_eq!(pages.concat(), all);
+        assert_eq!(super::pages(&all, 0).len(), all.len());
+    }
+}
@@ -1,2 +1,10 @@
+pub mod animate;
+pub mod autoplay;
 pub mod display;
+pub mod help;
+pub mod prompt;
+pub mod puzzle;
 pub mod repl;
+pub mod select;
+pub mod terminal;
+pub mod watch;
@@ -0,0 +1,273 @@
+//! The REPL's prompt in a terminal: a line editor with the moves and
+//! commands entered so far one arrow key away.
+//!
+//! ```text
+//! ↑ / ↓       older / newer entries; past the newest, the line being typed
+//! ← / →       move within the line
+//! Tab         complete a legal move or a command; again for the next match
+//! Backspace   delete before the cursor
+//! Enter       submit
+//! Ctrl-C      quit, as does Ctrl-D on an empty line
+//! ```
+//!
+//! Keys come from [`super::terminal`]; without a terminal the REPL reads
+//! plain lines instead.
+
+use std::io::{self, BufRead, Write};
+
+use super::terminal::{self, Key};
+
+/// Lines entered this session, oldest first.
+#[derive(Debug, Default)]
+pub struct History {
+    entries: Vec<String>,
+}
+
+impl History {
+    /// Adds `line` unless it is empty or repeats the newest entry.
+    pub fn push(&mut self, line: &str) {
+        if !line.is_empty() && self.entries.last().is_none_or(|newest| newest != line) {
+            self.entries.push(line.to_string());
+        }
+    }
+}
+
+/// What a key press did to the line.
+#[derive(Debug, PartialEq)]
+pub enum Edit {
+    Pending,
+    Submit(String),
+    Quit,
+}
+
+/// The line being typed, the cursor in it, and where in the history and
+/// the completions it is.
+pub struct LineEditor<'a> {
+    history: &'a History,
+    /// What Tab may complete to, in the order it offers them
+    completions: &'a [String],
+    chars: Vec<char>,
+    cursor: usize,
+    /// The entry shown, None for the line being typed
+    browsing: Option<usize>,
+    /// The line being typed, kept while browsing
+    draft: Vec<char>,
+    /// The text Tab is completing and the match shown, while Tab is pressed
+    completing: Option<(String, usize)>,
+}
+
+impl<'a> LineEditor<'a> {
+    pub fn new(history: &'a History, completions: &'a [String]) -> Self {
+        Self { history, completions, chars: Vec::new(), cursor: 0, browsing: None, draft: Vec::new(), completing: None }
+    }
+
+    pub fn press(&mut self, key: Key) -> Edit {
+        if key == Key::Tab {
+            self.complete();
+            return Edit::Pending;
+        }
+        self.completing = None;
+        match key {
+            Key::Char(typed) => {
+                self.chars.insert(self.cursor, typed);
+                self.cursor += 1;
+            }
+            Key::Backspace if self.cursor > 0 => {
+                self.cursor -= 1;
+                self.chars.remove(self.cursor);
+            }
+            Key::Left => self.cursor = self.cursor.saturating_sub(1),
+            Key::Right => self.cursor = (self.cursor + 1).min(self.chars.len()),
+            Key::Up => self.browse_older(),
+            Key::Down => self.browse_newer(),
+            Key::Enter => return Edit::Submit(self.chars.iter().collect()),
+            Key::CtrlC => return Edit::Quit,
+            Key::CtrlD if self.chars.is_empty() => return Edit::Quit,
+            Key::Tab | Key::Backspace | Key::CtrlD | Key::Escape | Key::Click { .. } | Key::Other => {}
+        }
+        Edit::Pending
+    }
+
+    /// Replaces the line with the first completion of what was typed, or
+    /// the next one when Tab is pressed again.
+    fn complete(&mut self) {
+        let typed = match &self.completing {
+            Some((typed, _)) => typed.clone(),
+            None => self.chars.iter().collect(),
+        };
+        let matches: Vec<&String> = self.completions.iter().filter(|candidate| candidate.starts_with(&typed)).collect();
+        if matches.is_empty() {
+            return;
+        }
+        let shown = self.completing.as_ref().map_or(0, |(_, shown)| (shown + 1) % matches.len());
+        self.chars = matches[shown].chars().collect();
+        self.cursor = self.chars.len();
+        self.completing = Some((typed, shown));
+    }
+
+    fn browse_older(&mut self) {
+        let Some(older) = self.browsing.unwrap_or(self.history.entries.len()).checked_sub(1) else {
+            return;
+        };
+        if self.browsing.is_none() {
+            self.draft = self.chars.clone();
+        }
+        self.show(Some(older));
+    }
+
+    fn browse_newer(&mut self) {
+        match self.browsing {
+            Some(shown) if shown + 1 < self.history.entries.len() => self.show(Some(shown + 1)),
+            Some(_) => self.show(None),
+            None => {}
+        }
+    }
+
+    fn show(&mut self, entry: Option<usize>) {
+        self.browsing = entry;
+        self.chars = match entry {
+            Some(idx) => self.history.entries[idx].chars().collect(),
+            None => self.draft.clone(),
+        };
+        self.cursor = self.chars.len();
+    }
+
+    /// Escapes that redraw the prompt line and put the cursor in place.
+    pub fn render(&self, prompt: &str) -> String {
+        let line: String = self.chars.iter().collect();
+        let mut output = format!("\r\x1b[K{prompt}{line}");
+        let after_cursor = self.chars.len() - self.cursor;
+        if after_cursor > 0 {
+            output.push_str(&format!("\x1b[{after_cursor}D"));
+        }
+        output
+    }
+}
+
+/// Edits a line after `prompt`, already on screen, until Enter; None when
+/// the user quits. Tab completes to `completions`. The terminal must be
+/// in [`terminal::RawMode`].
+pub fn read_line(
+    reader: &mut impl BufRead,
+    writer: &mut impl Write,
+    prompt: &str,
+    history: &History,
+    completions: &[String],
+) -> io::Result<Option<String>> {
+    let mut editor = LineEditor::new(history, completions);
+    loop {
+        match editor.press(terminal::read_key(reader)?) {
+            Edit::Pending => {
+                write!(writer, "{}", editor.render(prompt))?;
+                writer.flush()?;
+            }
+            Edit::Submit(line) => {
+                writeln!(writer)?;
+                writer.flush()?;
+                return Ok(Some(line));
+            }
+            Edit::Quit => {
+                writeln!(writer)?;
+                writer.flush()?;
+                return Ok(None);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history(lines: &[&str]) -> History {
+        let mut history = History::default();
+        for line in lines {
+            history.push(line);
+        }
+        history
+    }
+
+    fn type_keys(editor: &mut LineEditor, keys: &[Key]) -> Edit {
+        keys.iter().map(|key| editor.press(*key)).last().unwrap_or(Edit::Pending)
+    }
+
+    #[test]
+    fn history_skips_blanks_and_repeats() {
+        assert_eq!(history(&["e4", "", "e4", "Nf3", "e4"]).entries, ["e4", "Nf3", "e4"]);
+    }
+
+    #[test]
+    fn up_recalls_older_lines_and_down_comes_back_to_the_draft() {
+        let history = history(&["e4", "Nf3"]);
+        let mut editor = LineEditor::new(&history, &[]);
+        type_keys(&mut editor, &[Key::Char('d'), Key::Up]);
+        assert_eq!(editor.render("> "), "\r\x1b[K> Nf3");
+        type_keys(&mut editor, &[Key::Up, Key::Up]);
+        assert_eq!(editor.render("> "), "\r\x1b[K> e4");
+        type_keys(&mut editor, &[Key::Down, Key::Down]);
+        assert_eq!(editor.render("> "), "\r\x1b[K> d");
+    }
+
+    #[test]
+    fn recalled_lines_can_be_fixed_before_submitting() {
+        // Nf9 was a typo for Nf3
+        let history = history(&["Nf9"]);
+        let mut editor = LineEditor::new(&history, &[]);
+        let edit = type_keys(&mut editor, &[Key::Up, Key::Backspace, Key::Char('3'), Key::Enter]);
+        assert_eq!(edit, Edit::Submit("Nf3".to_string()));
+    }
+
+    #[test]
+    fn left_and_right_move_the_insertion_point() {
+        let history = History::default();
+        let mut editor = LineEditor::new(&history, &[]);
+        type_keys(&mut editor, &[Key::Char('e'), Key::Char('4'), Key::Left, Key::Left, Key::Char('N'), Key::Right]);
+        assert_eq!(editor.render(""), "\r\x1b[KNe4\x1b[1D");
+        assert_eq!(editor.press(Key::Enter), Edit::Submit("Ne4".to_string()));
+    }
+
+    #[test]
+    fn tab_cycles_through_the_completions() {
+        let history = History::default();
+        let completions: Vec<String> = ["Na3", "Nc3", "Nf3", "e4", "export"].iter().map(|name| name.to_string()).collect();
+        let mut editor = LineEditor::new(&history, &completions);
+        type_keys(&mut editor, &[Key::Char('N'), Key::Tab]);
+        assert_eq!(editor.render(""), "\r\x1b[KNa3");
+        type_keys(&mut editor, &[Key::Tab, Key::Tab]);
+        assert_eq!(editor.render(""), "\r\x1b[KNf3");
+        type_keys(&mut editor, &[Key::Tab]);
+        assert_eq!(editor.render(""), "\r\x1b[KNa3");
+        // Typing ends the cycle: the next Tab completes the new text
+        type_keys(&mut editor, &[Key::Backspace, Key::Backspace, Key::Char('f'), Key::Tab]);
+        assert_eq!(editor.render(""), "\r\x1b[KNf3");
+    }
+
+    #[test]
+    fn tab_without_a_match_leaves_the_line() {
+        let history = History::default();
+        let completions = ["export".to_string()];
+        let mut editor = LineEditor::new(&history, &completions);
+        type_keys(&mut editor, &[Key::Char('q'), Key::Tab]);
+        assert_eq!(editor.render(""), "\r\x1b[Kq");
+        assert_eq!(editor.press(Key::Char('e')), Edit::Pending);
+        assert_eq!(editor.press(Key::Enter), Edit::Submit("qe".to_string()));
+    }
+
+    #[test]
+    fn ctrl_c_quits_and_ctrl_d_only_on_an_empty_line() {
+        let history = History::default();
+        let mut editor = LineEditor::new(&history, &[]);
+        assert_eq!(type_keys(&mut editor, &[Key::Char('x'), Key::CtrlD]), Edit::Pending);
+        assert_eq!(editor.press(Key::CtrlC), Edit::Quit);
+        assert_eq!(LineEditor::new(&history, &[]).press(Key::CtrlD), Edit::Quit);
+    }
+
+    #[test]
+    fn read_line_echoes_and_submits() {
+        let history = history(&["e4"]);
+        let mut written = Vec::new();
+        let line = read_line(&mut &b"\x1b[A5\x7f\r"[..], &mut written, "> ", &history, &[]).unwrap();
+        assert_eq!(line, Some("e4".to_string()));
+        assert!(String::from_utf8(written).unwrap().ends_with("\r\x1b[K> e4\n"));
+    }
+}
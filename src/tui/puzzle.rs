@@ -0,0 +1,211 @@
+//! The REPL's `puzzle`: a position to solve and the moves that solve it.
+//!
+//! ```text
+//! puzzle mate.pgn                              first game: FEN tag, then moves
+//! puzzle 6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1 Ra8#   six-field FEN, then moves
+//! ```
+//!
+//! The side to move in the position is the solver's; the solution's moves
+//! alternate between the solver and the defense, which the REPL plays
+//! itself. A mate is always a solution, even one the file did not list.
+
+use std::fmt;
+use std::io;
+
+use crate::engine::board::{Board, Color, ParseFenError, Position};
+use crate::engine::chess::{NotationMove, ResolvedMove};
+use crate::engine::pgn;
+
+/// Fields of a full FEN record, which the solution follows.
+const FEN_FIELDS: usize = 6;
+
+#[derive(Debug)]
+pub enum LoadPuzzleError {
+    Io(io::Error),
+    /// The file holds no PGN game
+    NoGame,
+    Fen(ParseFenError),
+    /// A position without moves to find
+    NoSolution,
+    /// A solution move that cannot be played where it stands, as written
+    Unplayable(String),
+}
+
+impl fmt::Display for LoadPuzzleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LoadPuzzleError::Io(err) => write!(f, "cannot read puzzle: {err}"),
+            LoadPuzzleError::NoGame => write!(f, "no game found"),
+            LoadPuzzleError::Fen(err) => write!(f, "invalid FEN: {err}"),
+            LoadPuzzleError::NoSolution => write!(f, "no solution moves"),
+            LoadPuzzleError::Unplayable(notation) => write!(f, "solution move {notation} cannot be played"),
+        }
+    }
+}
+
+impl std::error::Error for LoadPuzzleError {}
+
+impl From<io::Error> for LoadPuzzleError {
+    fn from(err: io::Error) -> Self {
+        LoadPuzzleError::Io(err)
+    }
+}
+
+impl From<ParseFenError> for LoadPuzzleError {
+    fn from(err: ParseFenError) -> Self {
+        LoadPuzzleError::Fen(err)
+    }
+}
+
+/// How a move the solver tried measures up.
+#[derive(Debug, PartialEq)]
+pub enum Attempt {
+    /// Not the solution; it is not played
+    Wrong,
+    /// The solution's move, with more to find after the defense
+    Right,
+    /// The last move of the solution, or a mate
+    Solved,
+}
+
+pub struct Puzzle {
+    pub start: Position,
+    /// The solver's and the defense's moves in turn, with their notation
+    solution: Vec<(ResolvedMove, String)>,
+}
+
+impl Puzzle {
+    /// A PGN file's first game when `arg` is one word, else a FEN record
+    /// followed by the solution.
+    pub fn load(arg: &str) -> Result<Puzzle, LoadPuzzleError> {
+        let words: Vec<&str> = arg.split_whitespace().collect();
+        if words.len() > 1 {
+            let (fen, moves) = words.split_at(FEN_FIELDS.min(words.len()));
+            return Puzzle::new(Board::from_fen(&fen.join(" "))?, &moves.join(" "));
+        }
+        let contents = std::fs::read_to_string(arg)?;
+        let game = pgn::parse_games(&contents).into_iter().next().ok_or(LoadPuzzleError::NoGame)?;
+        Puzzle::new(game.start()?, &game.moves)
+    }
+
+    /// Resolves `moves` from `start`, each one legal and unambiguous. A
+    /// defense move after the solver's last is dropped: nothing follows it.
+    fn new(start: Position, moves: &str) -> Result<Puzzle, LoadPuzzleError> {
+        let mut board = start.board.clone();
+        let mut solution = Vec::new();
+        for (idx, notation) in moves.split_whitespace().enumerate() {
+            let move_index = start.ply + idx;
+            let resolved = NotationMove::parse(notation, move_index)
+                .and_then(|chess_move| board.resolve_legal_move(&chess_move, notation, side_to_move(move_index)).ok())
+                .filter(|resolved| board.apply_move(resolved).is_ok())
+                .ok_or_else(|| LoadPuzzleError::Unplayable(notation.to_string()))?;
+            solution.push((resolved, notation.to_string()));
+        }
+        if solution.len().is_multiple_of(2) {
+            solution.pop();
+        }
+        if solution.is_empty() {
+            return Err(LoadPuzzleError::NoSolution);
+        }
+        Ok(Puzzle { start, solution })
+    }
+
+    /// How many of the solution's moves are the solver's.
+    pub fn solver_moves(&self) -> usize {
+        self.solution.len().div_ceil(2)
+    }
+
+    /// The side that solves it.
+    pub fn solver(&self) -> Color {
+        side_to_move(self.start.ply)
+    }
+
+    /// Measures `resolved`, tried on `board` as the puzzle's move number
+    /// `played` (0 for the first).
+    pub fn attempt(&self, board: &Board, played: usize, resolved: &ResolvedMove) -> Attempt {
+        let Some((expected, _)) = self.solution.get(played) else {
+            return Attempt::Wrong;
+        };
+        if expected == resolved {
+            return if played + 1 == self.solution.len() { Attempt::Solved } else { Attempt::Right };
+        }
+        let color = side_to_move(self.start.ply + played);
+        let mut after = board.clone();
+        if after.apply_move(resolved).is_ok() && after.in_check(color.opponent()) && after.legal_moves(color.opponent()).is_empty() {
+            Attempt::Solved
+        } else {
+            Attempt::Wrong
+        }
+    }
+
+    /// The defense's reply once the puzzle's move number `played` is on the
+    /// board, None when the solution has no more.
+    pub fn defense(&self, played: usize) -> Option<(ResolvedMove, String)> {
+        self.solution.get(played).cloned()
+    }
+}
+
+fn side_to_move(move_index: usize) -> Color {
+    if move_index.is_multiple_of(2) { Color::White } else { Color::Black }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::chess::Square;
+
+    const BACK_RANK: &str = "6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1";
+
+    fn resolved(board: &Board, notation: &str, move_index: usize) -> ResolvedMove {
+        let chess_move = NotationMove::parse(notation, move_index).unwrap();
+        board.resolve_legal_move(&chess_move, notation, side_to_move(move_index)).unwrap()
+    }
+
+    #[test]
+    fn loads_a_fen_followed_by_the_solution() {
+        let puzzle = Puzzle::load(&format!("{BACK_RANK} Ra8#")).unwrap();
+        assert_eq!(puzzle.start.to_fen(), BACK_RANK);
+        assert_eq!(puzzle.solver_moves(), 1);
+        assert_eq!(puzzle.solution[0].1, "Ra8#");
+    }
+
+    #[test]
+    fn reports_what_cannot_be_a_puzzle() {
+        assert!(matches!(Puzzle::load(BACK_RANK), Err(LoadPuzzleError::NoSolution)));
+        assert!(matches!(Puzzle::load(&format!("{BACK_RANK} Ra9")), Err(LoadPuzzleError::Unplayable(_))));
+        assert!(matches!(Puzzle::load("8/8 w - - 0 1 e4"), Err(LoadPuzzleError::Fen(_))));
+        assert!(matches!(Puzzle::load("no-such-puzzle.pgn"), Err(LoadPuzzleError::Io(_))));
+    }
+
+    #[test]
+    fn right_moves_go_on_and_the_last_one_solves() {
+        // The queen decoys the rook off the back rank
+        let puzzle = Puzzle::load("5r1k/6pp/8/8/8/8/4Q3/4R1K1 w - - 0 1 Qe8 Rxe8 Rxe8#").unwrap();
+        assert_eq!((puzzle.solver(), puzzle.solver_moves()), (Color::White, 2));
+        let mut board = puzzle.start.board.clone();
+        assert_eq!(puzzle.attempt(&board, 0, &resolved(&board, "Qe7", 0)), Attempt::Wrong);
+        let decoy = resolved(&board, "Qe8", 0);
+        assert_eq!(puzzle.attempt(&board, 0, &decoy), Attempt::Right);
+        board.apply_move(&decoy).unwrap();
+        let (reply, notation) = puzzle.defense(1).unwrap();
+        assert_eq!((reply.dest, notation.as_str()), (Square::from_name("e8").unwrap(), "Rxe8"));
+        board.apply_move(&reply).unwrap();
+        assert_eq!(puzzle.attempt(&board, 2, &resolved(&board, "Rxe8#", 2)), Attempt::Solved);
+    }
+
+    #[test]
+    fn a_last_defense_move_is_dropped() {
+        let puzzle = Puzzle::load("5r1k/6pp/8/8/8/8/4Q3/4R1K1 w - - 0 1 Qe8 Rxe8").unwrap();
+        assert!(puzzle.defense(1).is_none());
+    }
+
+    #[test]
+    fn any_mate_solves() {
+        // Rb8# is not the listed Ra8#, but mates all the same
+        let puzzle = Puzzle::load("6k1/5ppp/8/8/8/8/8/RR4K1 w - - 0 1 Ra8#").unwrap();
+        let board = puzzle.start.board.clone();
+        assert_eq!(puzzle.attempt(&board, 0, &resolved(&board, "Rb8#", 0)), Attempt::Solved);
+        assert_eq!(puzzle.attempt(&board, 0, &resolved(&board, "Ra8#", 0)), Attempt::Solved);
+        assert_eq!(puzzle.attempt(&board, 0, &resolved(&board, "Rb7", 0)), Attempt::Wrong);
+    }
+}
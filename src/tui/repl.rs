@@ -1,190 +1,1835 @@
-use std::io::{self, BufRead, BufWriter, Write};
+use std::io::{self, BufRead, BufWriter, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
 
-use crate::audio;
-use crate::engine::board::{Board, Color};
-use crate::engine::chess::NotationMove;
-use super::display;
+use crate::{audio, config, toml};
+use crate::engine::board::{Board, Color, Position, ResolveMoveError};
+use crate::engine::chess::{NotationMove, Piece, ResolvedMove, Square};
+use crate::engine::{pgn, search};
+use crate::game::{Game, GameResult, PlayedMove};
+use super::{animate, display, help, prompt, puzzle, select, terminal};
 
 fn is_white_turn(move_index: usize) -> bool {
-    move_index % 2 == 0
+    move_index.is_multiple_of(2)
 }
 
 fn full_move_number(move_index: usize) -> usize {
     move_index / 2 + 1
 }
 
+/// Parses the argument of `sound`: a square, optionally prefixed by a piece
+/// letter ("e4" for a pawn, "Nf3" for a knight).
+fn parse_sound_target(target: &str) -> Option<(Square, Piece)> {
+    let mut chars = target.chars();
+    match chars.next().and_then(Piece::from_char) {
+        Some(piece) => Some((Square::from_name(chars.as_str())?, piece)),
+        None => Some((Square::from_name(target)?, Piece::Pawn)),
+    }
+}
+
+/// Parses the argument of `volume`: a percentage from 0 to 100.
+fn parse_volume(arg: &str) -> Option<u8> {
+    arg.parse::<u8>().ok().filter(|&percent| percent <= 100)
+}
+
+/// Milliseconds between the moves of a `replay` at normal speed.
+const REPLAY_MOVE_MS: u64 = 1000;
+
+/// How often a replay's position on the status bar moves on.
+const REPLAY_TICK_MS: u64 = 100;
+
+/// The pause before a puzzle's defense answers, so the solver's move is heard.
+const PUZZLE_REPLY_MS: u64 = 500;
+
+/// Parses the argument of `replay`: its speed, 0.5x, 1x (the default) or
+/// 2x, as the pause between moves.
+fn parse_replay_delay(arg: &str) -> Option<Duration> {
+    let delay_ms = match arg {
+        "" | "1x" => REPLAY_MOVE_MS,
+        "0.5x" => REPLAY_MOVE_MS * 2,
+        "2x" => REPLAY_MOVE_MS / 2,
+        _ => return None,
+    };
+    Some(Duration::from_millis(delay_ms))
+}
+
+/// Parses the arguments of `play engine`: the engine's side (by default
+/// the one not to move, so the user moves next) and its search depth.
+fn parse_engine_args(args: &str, side_to_move: Color) -> Option<(Color, u8)> {
+    let mut color = side_to_move.opponent();
+    let mut depth = search::DEFAULT_DEPTH;
+    for arg in args.split_whitespace() {
+        match arg {
+            "white" => color = Color::White,
+            "black" => color = Color::Black,
+            _ => depth = arg.parse().ok().filter(|depth| (1..=search::MAX_DEPTH).contains(depth))?,
+        }
+    }
+    Some((color, depth))
+}
+
+/// Whether the engine, playing `engine_color`, takes a draw: only when it
+/// has not taken more material than it has lost.
+fn engine_accepts_draw(played: &[PlayedMove], engine_color: Color) -> bool {
+    let (mut lost, mut taken) = (0, 0);
+    for (piece, color) in captured_pieces(played) {
+        if color == engine_color {
+            lost += piece.value();
+        } else {
+            taken += piece.value();
+        }
+    }
+    taken <= lost
+}
+
+/// Whether `input` may be entered once the game has a result: nothing
+/// that plays or takes back a move, only `new` and what leaves the game be.
+fn allowed_after_the_game(input: &str) -> bool {
+    const ALLOWED: [&str; 17] = [
+        "new", "reset", "quit", "help", "display", "theme", "coords", "announce", "sound", "volume", "mute", "soundtheme", "flip",
+        "replay", "fen", "import", "export",
+    ];
+    input.split_whitespace().next().is_some_and(|command| ALLOWED.contains(&command))
+}
+
+/// The session as a PGN game: the Seven Tag Roster with today's date and
+/// the game's result, plus SetUp/FEN when it did not start from the
+/// initial position, with the comments on moves still played.
+fn session_game(game: &Game, comments: &[(usize, String)], unix_seconds: u64) -> pgn::Game {
+    let (date, _) = audio::wav::utc_date_time(unix_seconds);
+    let result = game.result().map_or("*", GameResult::tag);
+    let mut pairs: Vec<(String, String)> = [
+        ("Event", "ChessWAV game"),
+        ("Site", "?"),
+        ("Date", &date.replace('-', ".")),
+        ("Round", "?"),
+        ("White", "?"),
+        ("Black", "?"),
+        ("Result", result),
+    ]
+    .iter()
+    .map(|(name, value)| (name.to_string(), value.to_string()))
+    .collect();
+    if *game.start() != Position::default() {
+        pairs.push(("SetUp".to_string(), "1".to_string()));
+        pairs.push(("FEN".to_string(), game.start().to_fen()));
+    }
+    let comments = comments.iter().filter(|(idx, _)| *idx < game.history().len()).cloned().collect();
+    pgn::Game { tags: pgn::Tags::new(pairs), moves: game.moves().join(" "), comments }
+}
+
+/// What `hint` prints: "Ng1: f3, h3", or why there is nothing to list.
+fn describe_hint(board: &Board, origin: Square, destinations: &[Square]) -> String {
+    let Some((piece, _)) = board.get(origin.file, origin.rank) else {
+        return format!("No piece on {}", origin.name());
+    };
+    let label = match piece {
+        Piece::Pawn => origin.name(),
+        Piece::Knight | Piece::Bishop | Piece::Rook | Piece::Queen | Piece::King => {
+            format!("{}{}", piece.letter(), origin.name())
+        }
+    };
+    if destinations.is_empty() {
+        return format!("{label}: no legal moves");
+    }
+    let names: Vec<String> = destinations.iter().map(Square::name).collect();
+    format!("{label}: {}", names.join(", "))
+}
+
+/// What an ambiguous move asks: "Which knight? (b1/f3) ".
+fn ambiguity_question(piece: Piece, origins: &[Square]) -> String {
+    let name = piece_name(piece);
+    let names: Vec<String> = origins.iter().map(Square::name).collect();
+    format!("  Which {name}? ({}) ", names.join("/"))
+}
+
+fn piece_name(piece: Piece) -> &'static str {
+    match piece {
+        Piece::Pawn => "pawn",
+        Piece::Knight => "knight",
+        Piece::Bishop => "bishop",
+        Piece::Rook => "rook",
+        Piece::Queen => "queen",
+        Piece::King => "king",
+    }
+}
+
+fn color_name(color: Color) -> &'static str {
+    match color {
+        Color::White => "White",
+        Color::Black => "Black",
+    }
+}
+
+/// What `announce on` says of `resolved`, played by `color` on `board`:
+/// the move in words, then check, mate or stalemate.
+fn announcement(board: &Board, resolved: &ResolvedMove, color: Color) -> String {
+    let Some((piece, _)) = board.get(resolved.origin.file, resolved.origin.rank) else {
+        return format!("{} moves from {} to {}", color_name(color), resolved.origin.name(), resolved.dest.name());
+    };
+    let mut sentence = match resolved.castling_rook {
+        Some(_) if resolved.dest.file > resolved.origin.file => format!("{} castles kingside", color_name(color)),
+        Some(_) => format!("{} castles queenside", color_name(color)),
+        None => format!("{} {} from {} to {}", color_name(color), piece_name(piece), resolved.origin.name(), resolved.dest.name()),
+    };
+    // A pawn changing file onto an empty square takes en passant
+    let taken = match board.get(resolved.dest.file, resolved.dest.rank) {
+        Some((taken, _)) => Some(taken),
+        None if piece == Piece::Pawn && resolved.origin.file != resolved.dest.file => Some(Piece::Pawn),
+        None => None,
+    };
+    if let Some(taken) = taken {
+        sentence.push_str(&format!(", takes the {} {}", color_name(color.opponent()).to_lowercase(), piece_name(taken)));
+    }
+    if let Some(promotion) = resolved.promotion {
+        sentence.push_str(&format!(", becomes a {}", piece_name(promotion)));
+    }
+    let mut after = board.clone();
+    if after.apply_move(resolved).is_err() {
+        return sentence;
+    }
+    let other = color.opponent();
+    match (after.in_check(other), after.legal_moves(other).is_empty()) {
+        (true, true) => format!("{sentence}. Checkmate, {} wins", color_name(color)),
+        (true, false) => format!("{sentence}. {} is in check", color_name(other)),
+        (false, true) => format!("{sentence}. Stalemate"),
+        (false, false) => sentence,
+    }
+}
+
+/// The origin the answer to [`ambiguity_question`] names: a square, or a
+/// file or rank only one of `origins` is on.
+fn pick_origin(answer: &str, origins: &[Square]) -> Option<Square> {
+    let matching: Vec<Square> = origins
+        .iter()
+        .copied()
+        .filter(|origin| {
+            let name = origin.name();
+            answer == name || (answer.len() == 1 && name.contains(answer))
+        })
+        .collect();
+    match matching.as_slice() {
+        [origin] => Some(*origin),
+        _ => None,
+    }
+}
+
+/// Plays the moves of an imported game from `start`, in order, stopping
+/// at the first one that cannot be played; that one is returned as well.
+fn resolve_game(start: &Position, moves: &str) -> (Game, Option<String>) {
+    let mut game = Game::from_position(start.clone());
+    for notation in moves.split_whitespace() {
+        if game.push_san(notation).is_err() {
+            return (game, Some(notation.to_string()));
+        }
+    }
+    (game, None)
+}
+
+/// The REPL's commands, for Tab to complete.
+const COMMANDS: [&str; 27] = [
+    "display", "theme", "coords", "announce", "select", "sound", "volume", "mute", "soundtheme", "hint", "flip", "play",
+    "undo", "takeback", "redo", "replay", "note", "resign", "draw", "puzzle", "fen", "import", "export", "reset", "new",
+    "help", "quit",
+];
+
+/// What Tab completes to: the legal moves of `color`, in SAN and sorted,
+/// then the commands.
+fn completions(board: &Board, color: Color) -> Vec<String> {
+    let mut moves: Vec<String> = board.legal_moves(color).iter().map(|resolved| board.san(resolved)).collect();
+    moves.sort();
+    moves.extend(COMMANDS.iter().map(|command| command.to_string()));
+    moves
+}
+
+/// One line from the user, without its newline: edited with the history
+/// and `completions` at hand in a terminal unless `plain`, read as is
+/// otherwise. None at the end of input.
+fn read_input(
+    stdin: &io::Stdin,
+    stdout: &mut impl Write,
+    prompt_text: &str,
+    history: &prompt::History,
+    completions: &[String],
+    plain: bool,
+) -> Option<String> {
+    if !plain
+        && stdin.is_terminal()
+        && let Ok(raw_mode) = terminal::RawMode::enable()
+    {
+        let line = prompt::read_line(&mut stdin.lock(), stdout, prompt_text, history, completions);
+        drop(raw_mode);
+        return line.ok().flatten();
+    }
+    let mut line = String::new();
+    match stdin.lock().read_line(&mut line) {
+        Ok(0) | Err(_) => None,
+        Ok(_) => Some(line),
+    }
+}
+
+/// The status bar: the move and whose turn it is, check or the game's
+/// end (`result` when there is one), the time since the game began, the
+/// sound, then `message`.
+fn status_line(
+    board: &Board,
+    move_index: usize,
+    result: Option<GameResult>,
+    elapsed: Duration,
+    session_audio: &SessionAudio,
+    message: Option<&str>,
+) -> String {
+    let (color, side, other_side) = if is_white_turn(move_index) {
+        (Color::White, "White", "Black")
+    } else {
+        (Color::Black, "Black", "White")
+    };
+    let mut parts = Vec::new();
+    if let Some(result) = result {
+        parts.push(format!("Move {}", full_move_number(move_index)));
+        parts.push(format!("{} ({})", result.describe(), result.tag()));
+    } else {
+        parts.push(format!("Move {} - {side} to play", full_move_number(move_index)));
+        match (board.in_check(color), board.legal_moves(color).is_empty()) {
+            (true, true) => parts.push(format!("Checkmate - {other_side} wins")),
+            (true, false) => parts.push("Check".to_string()),
+            (false, true) => parts.push("Stalemate".to_string()),
+            (false, false) => {}
+        }
+    }
+    parts.push(clock_text(elapsed));
+    parts.push(session_audio.describe());
+    parts.extend(message.map(str::to_string));
+    parts.join(" | ")
+}
+
+/// `elapsed` as the status bar shows it: "01:05".
+fn clock_text(elapsed: Duration) -> String {
+    let seconds = elapsed.as_secs();
+    format!("{:02}:{:02}", seconds / 60, seconds % 60)
+}
+
+/// The note `square` plays under `pitch`, e.g. "A4 440Hz".
+fn note_label(square: &Square, pitch: &audio::PitchMap) -> String {
+    format!("{} {}Hz", pitch.note_name(square), pitch.freq(square))
+}
+
+/// The note each of `moves` plays under `pitch`, e.g. "A4 440Hz", for the
+/// sidebar; empty for a move that does not parse.
+fn move_notes<S: AsRef<str>>(moves: &[S], first_ply: usize, pitch: &audio::PitchMap) -> Vec<String> {
+    moves
+        .iter()
+        .enumerate()
+        .map(|(idx, notation)| match NotationMove::parse(notation.as_ref(), first_ply + idx) {
+            Some(chess_move) => note_label(&chess_move.dest, pitch),
+            None => String::new(),
+        })
+        .collect()
+}
+
+/// Pieces taken so far, oldest first, for the sidebar.
+fn captured_pieces(played: &[PlayedMove]) -> Vec<(Piece, Color)> {
+    played.iter().filter_map(|played| played.undo.captured()).collect()
+}
+
+/// The origin and destination of the most recent move, for the board to
+/// tint.
+fn last_move_markers(played: &[PlayedMove]) -> Vec<(Square, display::SquareMarker)> {
+    played
+        .last()
+        .map(|last| {
+            vec![(last.resolved.origin, display::SquareMarker::LastMove), (last.resolved.dest, display::SquareMarker::LastMove)]
+        })
+        .unwrap_or_default()
+}
+
+/// How a session starts, from the config file and flags. `display`,
+/// `theme`, `soundtheme`, `volume` and `coords` save their changes to
+/// `config_path`, so the next session starts where this one left off.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Preferences {
+    pub palette: display::Palette,
+    pub coordinates: bool,
+    /// Percent, 0 to 100
+    pub volume: u8,
+    /// A built-in name or a theme file, as `soundtheme` takes; None for
+    /// the classic sounds
+    pub sound_theme: Option<String>,
+    /// Whether a rejected move buzzes
+    pub error_sound: bool,
+    /// Note length and gap of each move in `export audio`
+    pub timing: audio::Timing,
+    /// None keeps changes to the session
+    pub config_path: Option<PathBuf>,
+}
+
+/// Writes `key` to the config file at `path`, if there is one; what the
+/// status bar says when that fails.
+fn save_preference(path: Option<&Path>, key: &str, value: toml::Value) -> Option<String> {
+    let path = path?;
+    config::save(path, key, &value).err().map(|err| format!("Cannot save {key} to {}: {err}", path.display()))
+}
+
+/// How `soundtheme` saves `name`: a built-in name as is, a theme file by
+/// its full path, so the next session finds it from any directory.
+fn theme_setting(name: &str) -> String {
+    if audio::theme::BUILTIN_NAMES.contains(&name) {
+        return name.to_string();
+    }
+    std::fs::canonicalize(name).map_or_else(|_| name.to_string(), |path| path.display().to_string())
+}
+
+/// The session's sound: `volume`, `mute` and `soundtheme` set it, and it
+/// outlives `reset`, `fen` and `import`.
+#[derive(Debug, Clone, PartialEq)]
+struct SessionAudio {
+    /// Percent, 0 to 100
+    volume: u8,
+    muted: bool,
+    theme: audio::theme::Theme,
+    /// As typed: a built-in name or a file path
+    theme_name: String,
+    /// Whether a rejected move buzzes; `error_sound` in the config
+    error_sound: bool,
+    /// Note length and gap of each move in `export audio`, from `--tempo`
+    /// or the config
+    timing: audio::Timing,
+}
+
+impl SessionAudio {
+    /// The gain of each move's sound; None while muted, when nothing plays.
+    fn move_gain(&self) -> Option<f64> {
+        if self.muted { None } else { Some(self.volume as f64 / 100.0) }
+    }
+
+    /// The gain of the buzz for a rejected move; None while muted or with
+    /// the buzz turned off.
+    fn error_gain(&self) -> Option<f64> {
+        self.move_gain().filter(|_| self.error_sound)
+    }
+
+    /// How `export audio` renders a game from `start`: the theme's sounds
+    /// and pitches at the session's timing, otherwise as plain as the CLI.
+    fn export_config(&self, start: &Position) -> audio::AudioConfig {
+        audio::AudioConfig {
+            sounds: self.theme.sounds.clone(),
+            pitch: self.theme.pitch,
+            timing: self.timing,
+            start: start.clone(),
+            ..audio::AudioConfig::default()
+        }
+    }
+
+    /// How `replay` renders a game from `start` as one sound: like `export
+    /// audio`, a move every `delay`, at the volume; None while muted.
+    fn replay_config(&self, start: &Position, delay: Duration) -> Option<audio::AudioConfig> {
+        let moves_per_minute = 60_000.0 / delay.as_millis().max(1) as f64;
+        let timing = audio::Timing::from_tempo(moves_per_minute);
+        self.move_gain().map(|gain| audio::AudioConfig { timing, gain: Some(gain), ..self.export_config(start) })
+    }
+
+    /// What `mute` and `volume` print.
+    fn describe(&self) -> String {
+        if self.muted {
+            format!("Muted (volume {})", self.volume)
+        } else {
+            format!("Volume: {}", self.volume)
+        }
+    }
+}
+
+impl Default for SessionAudio {
+    fn default() -> Self {
+        Self {
+            volume: 100,
+            muted: false,
+            theme: audio::theme::Theme::default(),
+            theme_name: "classic".to_string(),
+            error_sound: true,
+            timing: audio::Timing::default(),
+        }
+    }
+}
+
+/// Which side of the board is drawn at the bottom.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Orientation {
+    Fixed(Color),
+    /// Turns to face whoever is to move
+    SideToMove,
+}
+
+impl Orientation {
+    fn perspective(self, move_index: usize) -> Color {
+        match self {
+            Orientation::Fixed(color) => color,
+            Orientation::SideToMove if is_white_turn(move_index) => Color::White,
+            Orientation::SideToMove => Color::Black,
+        }
+    }
+}
+
+/// Lines under the board: the status bar, then the prompt.
+const UNDER_BOARD: usize = 2;
+
+#[derive(Clone, Copy)]
 enum RenderMode {
     Initial,
     Redraw(usize),
+    /// Nothing is drawn: `announce on` says each move instead
+    Hidden,
 }
 
 fn render_board<S: AsRef<str>>(
     board: &Board,
     writer: &mut impl Write,
     strategy: &dyn display::DisplayStrategy,
-    moves: &[S],
+    sidebar: &display::Sidebar<S>,
+    markers: &[(Square, display::SquareMarker)],
+    perspective: Color,
     mode: RenderMode,
 ) -> io::Result<()> {
-    if let RenderMode::Redraw(clear_height) = mode {
-        display::cursor_up_and_clear(writer, clear_height)?;
+    match mode {
+        RenderMode::Initial => {}
+        RenderMode::Redraw(clear_height) => display::cursor_up_and_clear(writer, clear_height)?,
+        RenderMode::Hidden => return Ok(()),
+    }
+    display::render(board, writer, strategy, sidebar, markers, perspective)?;
+    // Where the status bar and prompt go; the next prompt draws them
+    for _ in 0..UNDER_BOARD {
+        writeln!(writer)?;
     }
-    display::render(board, writer, strategy, moves)?;
     writer.flush()
 }
 
-pub fn run(initial_mode: display::DisplayMode) {
-    let mut board = Board::new();
-    let mut move_index: usize = 0;
-    let mut move_history: Vec<String> = Vec::new();
+/// Draws `frames` [`animate::FRAME_MS`] apart, each as `mode` over the
+/// last: the moving piece on its way, before the move itself is drawn.
+fn slide<S: AsRef<str>>(
+    frames: &[Board],
+    writer: &mut impl Write,
+    strategy: &dyn display::DisplayStrategy,
+    sidebar: &display::Sidebar<S>,
+    perspective: Color,
+    mode: RenderMode,
+) -> io::Result<()> {
+    for frame in frames {
+        render_board(frame, writer, strategy, sidebar, &[], perspective, mode)?;
+        thread::sleep(Duration::from_millis(animate::FRAME_MS));
+    }
+    Ok(())
+}
+
+/// Asks `question` on the prompt's line, with `answers` for Tab; the answer,
+/// None at the end of input.
+fn ask(stdin: &io::Stdin, out: &mut impl Write, question: &str, answers: &[String], plain: bool) -> Option<String> {
+    if !plain {
+        display::cursor_up_and_clear(out, 1).ok();
+    }
+    write!(out, "{question}").ok();
+    out.flush().ok();
+    read_input(stdin, out, question, &prompt::History::default(), answers, plain)
+}
+
+/// Everything the REPL keeps from one command to the next: the game on
+/// the board, who plays it, and how it is drawn and sounds.
+struct Session {
+    /// The game on the board; `reset` returns to its start. After `resign`
+    /// or `draw` it is over until `new`
+    game: Game,
+    /// Moves taken back, most recent last, for `redo`; a new move clears
+    /// them. `import` fills it with a whole game to step through
+    undone: Vec<ResolvedMove>,
+    /// `note` comments, by index in the game's moves. Those on moves taken
+    /// back stay for `redo` until a new move replaces them
+    comments: Vec<(usize, String)>,
+    /// When the game on the board began, for the status bar's clock
+    clock: Instant,
+    /// The last command's message, for the status bar
+    message: Option<String>,
+    /// The puzzle being solved from `start`
+    puzzle: Option<puzzle::Puzzle>,
+    /// Whether the puzzle's defense answers next
+    puzzle_reply_due: bool,
+    /// The engine's side and depth
+    engine: Option<(Color, u8)>,
+    /// Whether the engine should answer next
+    engine_to_move: bool,
+    display_mode: display::DisplayMode,
+    color_mode: display::ColorMode,
+    palette: display::Palette,
+    coordinates: bool,
+    strategy: Box<dyn display::DisplayStrategy>,
+    orientation: Orientation,
+    /// `announce on`: moves said in plain words, without the board or
+    /// escape codes
+    announce: bool,
+    audio: SessionAudio,
+    playback: audio::PlaybackQueue,
+    /// Where `display`, `theme`, `soundtheme`, `volume` and `coords` save
+    /// their changes; None keeps them to the session
+    config_path: Option<PathBuf>,
+}
+
+impl Session {
+    fn new(
+        start: Position,
+        display_mode: display::DisplayMode,
+        color_mode: display::ColorMode,
+        preferences: Preferences,
+    ) -> Self {
+        let mut audio = SessionAudio {
+            volume: preferences.volume,
+            error_sound: preferences.error_sound,
+            timing: preferences.timing,
+            ..SessionAudio::default()
+        };
+        let mut message = None;
+        if let Some(name) = preferences.sound_theme {
+            match audio::theme::Theme::from_name_or_file(&name) {
+                Ok(theme) => {
+                    audio.theme = theme;
+                    audio.theme_name = name;
+                }
+                Err(err) => message = Some(format!("{name}: {err}. Using the classic sounds")),
+            }
+        }
+        Self {
+            game: Game::from_position(start),
+            undone: Vec::new(),
+            comments: Vec::new(),
+            clock: Instant::now(),
+            message,
+            puzzle: None,
+            puzzle_reply_due: false,
+            engine: None,
+            engine_to_move: false,
+            display_mode,
+            color_mode,
+            palette: preferences.palette,
+            coordinates: preferences.coordinates,
+            strategy: display::create_strategy(display_mode, color_mode, preferences.palette, preferences.coordinates),
+            orientation: Orientation::Fixed(Color::White),
+            announce: false,
+            audio,
+            playback: audio::PlaybackQueue::new(),
+            config_path: preferences.config_path,
+        }
+    }
+
+    /// The result `resign` or `draw` settled, which closes the game until
+    /// `new`; a mate on the board leaves `undo` open.
+    fn agreed_result(&self) -> Option<GameResult> {
+        self.game.result().filter(|result| match result {
+            GameResult::Resigned(_) | GameResult::DrawAgreed => true,
+            GameResult::Checkmate { .. } | GameResult::Stalemate => false,
+        })
+    }
+
+    /// How a draw goes over the board already on screen.
+    fn render_mode(&self) -> RenderMode {
+        if self.announce {
+            RenderMode::Hidden
+        } else {
+            RenderMode::Redraw(display::layout_height(&*self.strategy) + UNDER_BOARD)
+        }
+    }
+
+    /// Whether moves slide into place: in the colored displays, while the
+    /// board is drawn.
+    fn sliding(&self) -> bool {
+        animate::slides_in(self.display_mode) && !self.announce
+    }
+
+    /// Hands `render` the sidebar for the first `shown` moves of the game:
+    /// the moves with their notes, the pieces they took and the comments.
+    fn with_sidebar<R>(&self, shown: usize, render: impl FnOnce(&display::Sidebar<&str>) -> R) -> R {
+        let moves = &self.game.moves()[..shown];
+        let first_ply = self.game.start().ply;
+        render(&display::Sidebar {
+            moves,
+            first_ply,
+            captured: &captured_pieces(&self.game.history()[..shown]),
+            comments: &self.comments,
+            notes: &move_notes(moves, first_ply, &self.audio.theme.pitch),
+        })
+    }
+
+    /// Draws `board`, the position after the first `shown` moves, as `mode`,
+    /// tinting `markers` and the last of those moves.
+    fn draw(
+        &self,
+        writer: &mut impl Write,
+        board: &Board,
+        shown: usize,
+        markers: &[(Square, display::SquareMarker)],
+        mode: RenderMode,
+    ) -> io::Result<()> {
+        let mut markers = markers.to_vec();
+        markers.extend(last_move_markers(&self.game.history()[..shown]));
+        let perspective = self.orientation.perspective(self.game.start().ply + shown);
+        self.with_sidebar(shown, |sidebar| render_board(board, writer, &*self.strategy, sidebar, &markers, perspective, mode))
+    }
+
+    /// Slides the piece of `resolved` across `board`, the position after
+    /// the first `shown` moves, before the move itself is drawn.
+    fn slide(&self, writer: &mut impl Write, board: &Board, resolved: &ResolvedMove, shown: usize) -> io::Result<()> {
+        let frames = animate::frames(board, resolved);
+        let perspective = self.orientation.perspective(self.game.start().ply + shown);
+        self.with_sidebar(shown, |sidebar| slide(&frames, writer, &*self.strategy, sidebar, perspective, self.render_mode()))
+    }
+
+    /// Draws the board as the game stands, tinting `markers` too.
+    fn redraw_marked(&self, writer: &mut impl Write, markers: &[(Square, display::SquareMarker)]) {
+        if let Err(err) = self.draw(writer, self.game.board(), self.game.history().len(), markers, self.render_mode()) {
+            eprintln!("  Display error: {err}");
+        }
+    }
+
+    /// Draws the board as the game stands over the last one.
+    fn redraw(&self, writer: &mut impl Write) {
+        self.redraw_marked(writer, &[]);
+    }
+
+    /// Queues `sound` at `gain`; nothing plays without a gain.
+    fn play_sound(&self, gain: Option<f64>, sound: impl FnOnce(f64) -> Vec<i16>) {
+        if let Some(gain) = gain {
+            self.playback.enqueue(audio::to_wav(&sound(gain)));
+        }
+    }
+
+    /// Turns down a move: `reason` on the status bar, and the buzz.
+    fn reject(&mut self, reason: String) {
+        self.message = Some(reason);
+        self.play_sound(self.audio.error_gain(), audio::buzz_with_gain);
+    }
+
+    /// Plays `resolved` for the side to move: its note as the piece sets
+    /// off, then the board drawn with it. The next move taken back keeps the
+    /// rest for `redo`; any other move forgets them, and their comments.
+    fn play_move(&mut self, out: &mut impl Write, resolved: ResolvedMove) -> Result<(), ResolveMoveError> {
+        let notation = self.game.board().san(&resolved);
+        let theme = &self.audio.theme;
+        if let Some(chess_move) = NotationMove::parse(&notation, self.game.ply()) {
+            self.play_sound(self.audio.move_gain(), |gain| audio::synthesize_move_themed(&chess_move, theme, gain));
+        }
+        if self.announce {
+            writeln!(out, "  {}", announcement(self.game.board(), &resolved, self.game.side_to_move())).ok();
+        }
+        if self.sliding()
+            && let Err(err) = self.slide(out, self.game.board(), &resolved, self.game.history().len())
+        {
+            eprintln!("  Display error: {err}");
+        }
+        let kept = self.game.history().len();
+        let redone = self.undone.last() == Some(&resolved);
+        if let Err(err) = self.game.push(resolved) {
+            self.message = Some(format!("Cannot play {notation}: {err}"));
+            return Err(err);
+        }
+        if redone {
+            self.undone.pop();
+        } else {
+            self.undone.clear();
+            self.comments.retain(|(idx, _)| *idx < kept);
+        }
+        self.redraw(out);
+        Ok(())
+    }
+
+    /// Plays the puzzle's defense after a right move, or the engine's move
+    /// on its turn; whether either did.
+    fn reply(&mut self, out: &mut impl Write) -> bool {
+        if std::mem::take(&mut self.puzzle_reply_due)
+            && let Some((resolved, _)) = self.puzzle.as_ref().and_then(|active| active.defense(self.game.history().len()))
+        {
+            thread::sleep(Duration::from_millis(PUZZLE_REPLY_MS));
+            self.play_move(out, resolved).ok();
+            return true;
+        }
+        let color = self.game.side_to_move();
+        let Some((_, depth)) = self.engine.filter(|&(engine_color, _)| self.engine_to_move && engine_color == color) else {
+            return false;
+        };
+        self.engine_to_move = false;
+        // Without a move the game is over, as the status bar shows
+        let Some(resolved) = search::best_move(self.game.board(), color, depth) else {
+            return false;
+        };
+        self.play_move(out, resolved).ok();
+        true
+    }
 
+    /// Plays `input` as typed. "Nd2" with knights on b1 and f3 asks which
+    /// one, then plays "Nbd2"; a puzzle takes only its solution, or a mate.
+    fn play_typed(&mut self, stdin: &io::Stdin, out: &mut impl Write, input: &str) {
+        let Some(chess_move) = NotationMove::parse(input, self.game.ply()) else {
+            return self.reject(format!("Invalid move: {input}"));
+        };
+        let (notation, resolved) = match self.game.board().resolve_legal_move(&chess_move, input, self.game.side_to_move()) {
+            Ok(resolved) => (input.to_string(), resolved),
+            Err(ResolveMoveError::Ambiguous(origins)) => {
+                let names: Vec<String> = origins.iter().map(Square::name).collect();
+                let answer = ask(stdin, out, &ambiguity_question(chess_move.piece, &origins), &names, self.announce);
+                let Some(origin) = answer.and_then(|answer| pick_origin(answer.trim(), &origins)) else {
+                    self.message = Some(format!("{input} not played: choose {}", names.join(" or ")));
+                    return;
+                };
+                let resolved = ResolvedMove { origin, dest: chess_move.dest, promotion: chess_move.promotion, castling_rook: None };
+                (self.game.board().san(&resolved), resolved)
+            }
+            // A pinned piece or an empty square leaves the board as it was
+            Err(reason @ (ResolveMoveError::Illegal | ResolveMoveError::EmptyOrigin(_))) => {
+                return self.reject(format!("{input}: {reason}"));
+            }
+        };
+        let attempt =
+            self.puzzle.as_ref().map(|active| active.attempt(self.game.board(), self.game.history().len(), &resolved));
+        if let Some(puzzle::Attempt::Wrong) = attempt {
+            return self.reject(format!("{notation} is not the solution, try again"));
+        }
+        if self.play_move(out, resolved).is_err() {
+            return self.play_sound(self.audio.error_gain(), audio::buzz_with_gain);
+        }
+        self.engine_to_move = true;
+        match attempt {
+            Some(puzzle::Attempt::Right) => self.puzzle_reply_due = true,
+            Some(puzzle::Attempt::Solved) => {
+                self.puzzle = None;
+                self.message = Some("Puzzle solved!".to_string());
+                self.play_sound(self.audio.move_gain(), audio::win_jingle_with_gain);
+            }
+            Some(puzzle::Attempt::Wrong) | None => {}
+        }
+    }
+
+    /// Picks a move with the arrow keys or the mouse; None when the user
+    /// leaves, or there is no terminal to pick in.
+    fn select_move(&mut self, stdin: &io::Stdin, out: &mut impl Write) -> Option<ResolvedMove> {
+        if self.announce {
+            self.message = Some("select draws on the board: announce off first".to_string());
+            return None;
+        }
+        let raw_mode = match terminal::RawMode::enable_with_mouse() {
+            Ok(raw_mode) => raw_mode,
+            Err(err) => {
+                self.message = Some(format!("select needs a terminal: {err}"));
+                return None;
+            }
+        };
+        let color = self.game.side_to_move();
+        let perspective = self.orientation.perspective(self.game.ply());
+        let mut selection = select::Selection::new(color);
+        let mut chosen = None;
+        // Screen line of the layout's top, for clicks; found after the first draw
+        let mut board_top: Option<usize> = None;
+        loop {
+            let shown = self.game.history().len();
+            if let Err(err) = self.draw(out, self.game.board(), shown, &selection.markers(), self.render_mode()) {
+                eprintln!("  Display error: {err}");
+                break;
+            }
+            // The help takes the status bar's place, and the prompt's stays empty
+            display::cursor_up_and_clear(out, UNDER_BOARD).ok();
+            writeln!(out, "  {}\n", select::HELP).ok();
+            out.flush().ok();
+            if board_top.is_none() {
+                // The cursor sits under the help line and the empty prompt line
+                board_top = terminal::cursor_line(&mut stdin.lock(), out)
+                    .ok()
+                    .and_then(|line| line.checked_sub(display::layout_height(&*self.strategy) + UNDER_BOARD));
+            }
+            let Ok(key) = terminal::read_key(&mut stdin.lock()) else {
+                break;
+            };
+            let step = match key {
+                terminal::Key::Click { column, line } => board_top
+                    .and_then(|top| display::square_at(&*self.strategy, perspective, column.checked_sub(1)?, line.checked_sub(top)?))
+                    .and_then(|square| selection.click(square, self.game.board(), color)),
+                key => selection.press(key, self.game.board(), color, perspective),
+            };
+            match step {
+                Some(select::Step::Play(resolved)) => {
+                    chosen = Some(resolved);
+                    break;
+                }
+                Some(select::Step::Leave) => break,
+                None => {}
+            }
+        }
+        drop(raw_mode);
+        if chosen.is_none() {
+            self.redraw(out);
+        }
+        chosen
+    }
+
+    /// The help: in a terminal a page at a time under the board, each
+    /// cleared for the next; otherwise all of it.
+    fn show_help(&self, stdin: &io::Stdin, out: &mut impl Write) {
+        let lines = help::lines(&self.audio.theme, &self.audio.theme_name);
+        let raw_mode = if stdin.is_terminal() && !self.announce { terminal::RawMode::enable().ok() } else { None };
+        if raw_mode.is_none() {
+            for line in &lines {
+                writeln!(out, "  {line}").ok();
+            }
+            // Room for the status bar and prompt to take over
+            if !self.announce {
+                writeln!(out, "\n").ok();
+            }
+            return;
+        }
+        let pages = help::pages(&lines, display::layout_height(&*self.strategy));
+        for (number, page) in pages.iter().enumerate() {
+            for line in *page {
+                writeln!(out, "  {line}").ok();
+            }
+            writeln!(out, "  Page {}/{} - Enter for the next, q to close", number + 1, pages.len()).ok();
+            out.flush().ok();
+            let key = terminal::read_key(&mut stdin.lock());
+            display::cursor_up_and_clear(out, page.len() + 1).ok();
+            if !matches!(key, Ok(terminal::Key::Enter | terminal::Key::Char(' '))) {
+                break;
+            }
+        }
+        drop(raw_mode);
+        self.redraw(out);
+    }
+
+    /// What the prompt says: the move and whose turn, or that the game is over.
+    fn prompt_text(&self) -> String {
+        let side = color_name(self.game.side_to_move());
+        let opponent = match (&self.puzzle, self.engine) {
+            (Some(_), _) => " puzzle",
+            (None, Some(_)) => " vs engine",
+            (None, None) => "",
+        };
+        match self.agreed_result() {
+            Some(result) => format!("  [Game over {}] new or quit > ", result.tag()),
+            None => format!("  [Move {} - {side}{opponent}] > ", full_move_number(self.game.ply())),
+        }
+    }
+
+    /// Shows the last command's message: on the status bar, or on a line of
+    /// its own for the screen reader with `announce on`.
+    fn show_status(&mut self, out: &mut impl Write) {
+        // The first playback failure shows once; the game goes on without sound
+        if let Some(err) = self.playback.take_error() {
+            let failure = format!("Sound unavailable: {err}");
+            self.message = Some(self.message.take().map_or(failure.clone(), |text| format!("{text} | {failure}")));
+        }
+        if self.announce {
+            // Each line stays where it was printed, for the screen reader
+            if let Some(text) = self.message.take() {
+                writeln!(out, "  {text}").ok();
+            }
+            return;
+        }
+        // The cursor is always under the lines kept for the status bar and
+        // prompt: left by the last command's prompt, or blank after a redraw
+        display::cursor_up_and_clear(out, UNDER_BOARD).ok();
+        let message = self.message.take();
+        let status =
+            status_line(self.game.board(), self.game.ply(), self.agreed_result(), self.clock.elapsed(), &self.audio, message.as_deref());
+        writeln!(out, "  {status}").ok();
+    }
+
+    /// Starts over from `start`: no moves, no result, the clock at zero.
+    fn restart(&mut self, start: Position) {
+        self.game = Game::from_position(start);
+        self.undone.clear();
+        self.comments.clear();
+        self.clock = Instant::now();
+    }
+
+    /// Takes back up to `plies` moves, keeping them for `redo`.
+    fn take_back(&mut self, plies: usize) {
+        for _ in 0..plies {
+            let Some(last) = self.game.history().last().cloned() else {
+                break;
+            };
+            self.game.undo();
+            if self.announce {
+                self.message = Some(format!("Took back {}", last.san));
+            }
+            self.undone.push(last.resolved);
+        }
+        self.play_sound(self.audio.move_gain(), audio::rewind_with_gain);
+    }
+
+    /// Plays the game again as one sound, a move every `delay`, stepping a
+    /// copy of the board as the sound reaches each move and showing how far
+    /// it has got. The board ends where the game stands.
+    fn replay(&mut self, out: &mut impl Write, delay: Duration) {
+        let mut replay_board = self.game.start().board.clone();
+        if let Err(err) = self.draw(out, &replay_board, 0, &[], self.render_mode()) {
+            eprintln!("  Display error: {err}");
+            return;
+        }
+        thread::sleep(delay);
+        let playback = self
+            .audio
+            .replay_config(self.game.start(), delay)
+            .map(|config| audio::Playback::start(audio::to_wav(&self.game.to_samples(&config))));
+        let moves = self.game.history().len();
+        let length = playback.as_ref().map_or(delay * moves as u32, audio::Playback::duration);
+        let started = Instant::now();
+        // The sound's position while it plays; the clock once it stops, or
+        // when muted or without a player
+        let position = || {
+            playback.as_ref().filter(|playback| playback.is_playing()).map_or(started.elapsed(), audio::Playback::position)
+        };
+        for shown in 1..=moves {
+            // Each move is drawn as its note begins
+            let due = delay * (shown as u32 - 1);
+            while position() < due {
+                self.show_replay_position(out, position(), length);
+                thread::sleep(due.saturating_sub(position()).min(Duration::from_millis(REPLAY_TICK_MS)));
+            }
+            let PlayedMove { san: notation, resolved, .. } = &self.game.history()[shown - 1];
+            let ply = self.game.start().ply + shown - 1;
+            if self.announce {
+                let mover = if is_white_turn(ply) { Color::White } else { Color::Black };
+                writeln!(out, "  {}", announcement(&replay_board, resolved, mover)).ok();
+                out.flush().ok();
+            }
+            if self.sliding()
+                && let Err(err) = self.slide(out, &replay_board, resolved, shown - 1)
+            {
+                eprintln!("  Display error: {err}");
+            }
+            if let Err(err) = replay_board.make_move(resolved) {
+                self.message = Some(format!("Replay stopped at {notation}: {err}"));
+                return;
+            }
+            if let Err(err) = self.draw(out, &replay_board, shown, &[], self.render_mode()) {
+                eprintln!("  Display error: {err}");
+                return;
+            }
+            self.show_replay_position(out, position(), length);
+        }
+        let Some(playback) = playback else {
+            return;
+        };
+        // The last note rings on after its move is drawn
+        let (done, finished) = mpsc::channel();
+        playback.on_complete(move || {
+            done.send(()).ok();
+        });
+        while finished.recv_timeout(Duration::from_millis(REPLAY_TICK_MS)).is_err() {
+            self.show_replay_position(out, playback.position(), length);
+        }
+        if let Err(err) = playback.wait() {
+            self.message = Some(format!("Sound unavailable: {err}"));
+        }
+    }
+
+    /// Shows a replay's `position` in the sound, `length` long, where the
+    /// status bar goes.
+    fn show_replay_position(&self, out: &mut impl Write, position: Duration, length: Duration) {
+        if self.announce {
+            return;
+        }
+        display::cursor_up_and_clear(out, UNDER_BOARD).ok();
+        writeln!(out, "  Replay {} / {}\n", clock_text(position), clock_text(length)).ok();
+        out.flush().ok();
+    }
+
+    /// Comments on the last move, replacing an earlier comment on it.
+    fn note(&mut self, out: &mut impl Write, text: &str) {
+        let Some(last) = self.game.history().len().checked_sub(1) else {
+            self.message = Some("No move to comment on".to_string());
+            return;
+        };
+        self.comments.retain(|(idx, _)| *idx != last);
+        self.comments.push((last, text.to_string()));
+        self.play_sound(self.audio.move_gain(), audio::chime_with_gain);
+        self.redraw(out);
+    }
+
+    /// Closes the game `resign` or `draw` just settled, to its jingle.
+    fn end(&mut self) {
+        let Some(result) = self.game.result() else {
+            return;
+        };
+        self.engine_to_move = false;
+        if self.announce {
+            self.message = Some(result.describe());
+        }
+        let jingle = match result {
+            GameResult::Checkmate { .. } | GameResult::Resigned(_) => audio::win_jingle_with_gain,
+            GameResult::Stalemate | GameResult::DrawAgreed => audio::draw_jingle_with_gain,
+        };
+        self.play_sound(self.audio.move_gain(), jingle);
+    }
+
+    /// Offers a draw to the other side: the engine takes it when it is not
+    /// ahead, a player answers on the prompt's line.
+    fn offer_draw(&mut self, stdin: &io::Stdin, out: &mut impl Write) {
+        let color = self.game.side_to_move();
+        let other_side = color_name(color.opponent());
+        let accepted = match self.engine {
+            Some((engine_color, _)) if engine_color != color => engine_accepts_draw(self.game.history(), engine_color),
+            Some(_) | None => {
+                let question = format!("  {other_side}, accept a draw? (yes/no) ");
+                let answers = ["yes".to_string(), "no".to_string()];
+                let answer = ask(stdin, out, &question, &answers, self.announce);
+                answer.is_some_and(|answer| matches!(answer.trim(), "y" | "yes"))
+            }
+        };
+        if accepted {
+            self.game.agree_draw();
+            self.end();
+        } else {
+            self.message = Some(format!("{other_side} declines the draw"));
+        }
+    }
+
+    /// Sets up `loaded` to solve, the solver to move.
+    fn start_puzzle(&mut self, loaded: puzzle::Puzzle) {
+        self.restart(loaded.start.clone());
+        self.engine = None;
+        let side = if loaded.solver() == Color::White { "White" } else { "Black" };
+        self.message = Some(match loaded.solver_moves() {
+            1 => format!("Puzzle: {side} to play, one move to find"),
+            moves => format!("Puzzle: {side} to play, {moves} moves to find"),
+        });
+        self.puzzle = Some(loaded);
+    }
+
+    /// Loads the first game of the PGN file at `path` to step through with
+    /// `redo`, up to its first move no piece can make.
+    fn import(&mut self, out: &mut impl Write, path: &str) {
+        let contents = match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(err) => {
+                self.message = Some(format!("Cannot read {path}: {err}"));
+                return;
+            }
+        };
+        let Some(game) = pgn::parse_games(&contents).into_iter().next() else {
+            self.message = Some(format!("No game found in {path}"));
+            return;
+        };
+        let start = match game.start() {
+            Ok(position) => position,
+            Err(err) => {
+                self.message = Some(format!("Invalid FEN in {path}: {err}"));
+                return;
+            }
+        };
+        self.puzzle = None;
+        let (imported, stopped_at) = resolve_game(&start, &game.moves);
+        self.restart(start);
+        self.undone = imported.history().iter().rev().map(|played| played.resolved.clone()).collect();
+        self.redraw(out);
+        let loaded = format!("Loaded {} moves. Press Enter or n for the next move", self.undone.len());
+        self.message = Some(match stopped_at {
+            Some(notation) => format!("{loaded}. Stopped before {notation}: no piece can make it"),
+            None => loaded,
+        });
+    }
+
+    /// Renders the moves played to a WAV file at `path`; what the status bar says.
+    fn export_audio(&self, path: &str) -> String {
+        if self.game.history().is_empty() {
+            return "Nothing to export: no moves played".to_string();
+        }
+        let samples = self.game.to_samples(&self.audio.export_config(self.game.start()));
+        match std::fs::write(path, audio::to_wav(&samples)) {
+            Ok(()) => format!("Rendered {} moves to {path}", self.game.history().len()),
+            Err(err) => format!("Cannot write {path}: {err}"),
+        }
+    }
+
+    /// Saves the game as PGN at `path`; what the status bar says.
+    fn export_pgn(&self, path: &str) -> String {
+        let unix_seconds =
+            std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_secs());
+        let game = session_game(&self.game, &self.comments, unix_seconds);
+        match std::fs::write(path, game.to_pgn()) {
+            Ok(()) => format!("Saved {} moves to {path}", self.game.history().len()),
+            Err(err) => format!("Cannot write {path}: {err}"),
+        }
+    }
+
+    /// Plays the note of `target`, a square or a piece on one, even while
+    /// muted: a preview is asked for.
+    fn preview(&mut self, target: &str) {
+        let Some((square, piece)) = parse_sound_target(target) else {
+            self.message = Some("Usage: sound <square>, e.g. sound e4 or sound Nf3".to_string());
+            return;
+        };
+        let theme = &self.audio.theme;
+        self.play_sound(Some(self.audio.volume as f64 / 100.0), |gain| audio::preview_square_themed(square, piece, theme, gain));
+        self.message = Some(format!("{target}: {}", note_label(&square, &self.audio.theme.pitch)));
+    }
+
+    /// Switches to the sound theme `name`, a built-in one or a file.
+    fn set_sound_theme(&mut self, name: &str) {
+        match audio::theme::Theme::from_name_or_file(name) {
+            Ok(theme) => {
+                self.audio.theme = theme;
+                self.audio.theme_name = name.to_string();
+                self.message = save_preference(self.config_path.as_deref(), "sound_theme", toml::Value::String(theme_setting(name)))
+                    .or_else(|| Some(format!("Sound theme: {name}")));
+            }
+            Err(err) => {
+                self.message = Some(format!("{name}: {err}. Built-in: {}", audio::theme::BUILTIN_NAMES.join(", ")));
+            }
+        }
+    }
+
+    /// Draws the board anew after the display mode, palette or coordinates
+    /// changed, saving `key` as `value`.
+    fn restyle(&mut self, out: &mut impl Write, key: &str, value: toml::Value) {
+        self.strategy = display::create_strategy(self.display_mode, self.color_mode, self.palette, self.coordinates);
+        self.message = save_preference(self.config_path.as_deref(), key, value);
+        self.redraw(out);
+    }
+}
+
+/// Runs the REPL from `start`; `reset` returns to it.
+pub fn run(
+    initial_mode: display::DisplayMode,
+    color_mode: display::ColorMode,
+    start: Position,
+    preferences: Preferences,
+) {
     println!();
     println!("  ChessWAV Interactive Mode");
-    println!("  Type moves in algebraic notation. Commands: display, reset, quit");
+    println!("  Type moves in algebraic notation. Commands: display, theme <name>, coords on|off, announce on|off, select, sound <square>, volume <0-100>, mute, soundtheme <name>, hint <square>, flip [auto], play engine [white|black] [depth], play off, undo, takeback, redo, replay [0.5x|1x|2x], note <text>, resign, draw, puzzle <file|fen moves>, fen [position], import <file>, export <file>, export audio <file>, reset, new, help, quit");
     println!();
 
-    let color_mode = display::detect_color_mode();
-    let mut strategy: Box<dyn display::DisplayStrategy> =
-        display::create_strategy(initial_mode, color_mode);
+    let mut session = Session::new(start, initial_mode, color_mode, preferences);
     let stdin = io::stdin();
     let mut stdout = BufWriter::new(io::stdout());
+    // Lines entered, for the arrow keys at the prompt
+    let mut history = prompt::History::default();
 
-    if let Err(err) = render_board(&board, &mut stdout, &*strategy, &move_history, RenderMode::Initial) {
+    if let Err(err) = session.draw(&mut stdout, session.game.board(), 0, &[], RenderMode::Initial) {
         eprintln!("  Display error: {err}");
     }
 
     loop {
-        let side = if is_white_turn(move_index) {
-            "White"
-        } else {
-            "Black"
-        };
-        let move_num = full_move_number(move_index);
-        write!(stdout, "  [Move {move_num} - {side}] > ").ok();
+        // The board was just drawn, the lines under it still blank
+        if session.reply(&mut stdout) {
+            continue;
+        }
+        let prompt_text = session.prompt_text();
+        session.show_status(&mut stdout);
+        write!(stdout, "{prompt_text}").ok();
         stdout.flush().ok();
 
-        let mut line = String::new();
-        match stdin.lock().read_line(&mut line) {
-            Ok(0) => break,
-            Err(_) => break,
-            _ => {}
-        }
+        let completions = completions(session.game.board(), session.game.side_to_move());
+        let Some(line) = read_input(&stdin, &mut stdout, &prompt_text, &history, &completions, session.announce) else {
+            break;
+        };
+        history.push(line.trim());
 
-        let input = line.trim();
-        if input.is_empty() {
+        // Enter steps through an imported game
+        let input = match line.trim() {
+            "" if !session.undone.is_empty() => "n",
+            "" => continue,
+            input => input,
+        };
+
+        if session.agreed_result().is_some() && !allowed_after_the_game(input) {
+            session.message = Some("The game is over: new to play again, or quit".to_string());
             continue;
         }
 
-        let redraw_height = display::layout_height(&*strategy) + 1;
+        // `select` picks a move with the arrow keys and plays it as if typed
+        let selected_notation;
+        let input = if input == "select" {
+            let Some(resolved) = session.select_move(&stdin, &mut stdout) else {
+                continue;
+            };
+            selected_notation = session.game.board().san(&resolved);
+            selected_notation.as_str()
+        } else {
+            input
+        };
 
         match input {
             "quit" => break,
-            "reset" => {
-                board = Board::new();
-                move_index = 0;
-                move_history.clear();
-                if let Err(err) = render_board(
-                    &board,
-                    &mut stdout,
-                    &*strategy,
-                    &move_history,
-                    RenderMode::Redraw(redraw_height),
-                ) {
-                    eprintln!("  Display error: {err}");
+            "help" => session.show_help(&stdin, &mut stdout),
+            "reset" | "new" => {
+                session.restart(session.game.start().clone());
+                session.redraw(&mut stdout);
+            }
+            "flip" | "flip auto" => {
+                session.orientation = match (input, session.orientation.perspective(session.game.ply())) {
+                    ("flip auto", _) => Orientation::SideToMove,
+                    (_, Color::White) => Orientation::Fixed(Color::Black),
+                    (_, Color::Black) => Orientation::Fixed(Color::White),
+                };
+                session.redraw(&mut stdout);
+            }
+            "play" => session.message = Some("Usage: play engine [white|black] [depth], or play off".to_string()),
+            "play off" => session.engine = None,
+            _ if input == "play engine" || input.starts_with("play engine ") => {
+                match parse_engine_args(&input["play engine".len()..], session.game.side_to_move()) {
+                    Some(engine) => {
+                        session.engine = Some(engine);
+                        session.engine_to_move = true;
+                        session.puzzle = None;
+                        session.redraw(&mut stdout);
+                    }
+                    None => {
+                        session.message = Some(format!("Usage: play engine [white|black] [depth 1-{}]", search::MAX_DEPTH));
+                    }
                 }
-                continue;
             }
-            "display" => {
-                writeln!(stdout, "  Usage: display <mode>. Options: sprite, unicode, ascii")
-                    .ok();
-                stdout.flush().ok();
-                continue;
+            "hint" => session.message = Some("Usage: hint <square>, e.g. hint e2".to_string()),
+            _ if input.starts_with("hint ") => match Square::from_name(input["hint ".len()..].trim()) {
+                Some(origin) => {
+                    let destinations: Vec<Square> =
+                        session.game.board().legal_moves_from(origin).iter().map(|resolved| resolved.dest).collect();
+                    let markers: Vec<(Square, display::SquareMarker)> =
+                        destinations.iter().map(|dest| (*dest, display::SquareMarker::Hint)).collect();
+                    session.redraw_marked(&mut stdout, &markers);
+                    session.message = Some(describe_hint(session.game.board(), origin, &destinations));
+                }
+                None => session.message = Some("Usage: hint <square>, e.g. hint e2".to_string()),
+            },
+            "undo" | "takeback" if session.game.history().is_empty() => {
+                session.message = Some(if input == "undo" { "Nothing to undo" } else { "Nothing to take back" }.to_string());
+            }
+            "undo" | "takeback" => {
+                // `takeback` reverts a full move: the reply and the move before it
+                session.take_back(if input == "takeback" { 2 } else { 1 });
+                session.redraw(&mut stdout);
+            }
+            "redo" | "n" => match session.undone.last().cloned() {
+                Some(resolved) => {
+                    session.play_move(&mut stdout, resolved).ok();
+                }
+                None => session.message = Some("Nothing to redo".to_string()),
+            },
+            _ if input == "replay" || input.starts_with("replay ") => {
+                match parse_replay_delay(input["replay".len()..].trim()) {
+                    None => session.message = Some("Usage: replay [0.5x|1x|2x]".to_string()),
+                    Some(_) if session.game.history().is_empty() => session.message = Some("Nothing to replay".to_string()),
+                    Some(delay) => session.replay(&mut stdout, delay),
+                }
+            }
+            "note" => session.message = Some("Usage: note <text>, e.g. note a risky pawn grab".to_string()),
+            _ if input.starts_with("note ") => session.note(&mut stdout, input["note ".len()..].trim()),
+            "resign" => {
+                session.game.resign(session.game.side_to_move());
+                session.end();
+            }
+            "draw" => session.offer_draw(&stdin, &mut stdout),
+            "fen" => {
+                let position = Position { board: session.game.board().clone(), ply: session.game.ply() };
+                session.message = Some(position.to_fen());
+            }
+            _ if input.starts_with("fen ") => match Board::from_fen(input["fen ".len()..].trim()) {
+                Ok(position) => {
+                    session.puzzle = None;
+                    session.restart(position);
+                    session.redraw(&mut stdout);
+                }
+                Err(err) => session.message = Some(format!("Invalid FEN: {err}")),
+            },
+            "puzzle" => session.message = Some("Usage: puzzle <file>, or puzzle <fen> <moves>".to_string()),
+            _ if input.starts_with("puzzle ") => match puzzle::Puzzle::load(input["puzzle ".len()..].trim()) {
+                Ok(loaded) => {
+                    session.start_puzzle(loaded);
+                    session.redraw(&mut stdout);
+                }
+                Err(err) => session.message = Some(format!("Cannot load puzzle: {err}")),
+            },
+            "import" => session.message = Some("Usage: import <file>, e.g. import game.pgn".to_string()),
+            _ if input.starts_with("import ") => session.import(&mut stdout, input["import ".len()..].trim()),
+            "export" => {
+                session.message = Some("Usage: export <file>, e.g. export game.pgn, or export audio <file>".to_string());
+            }
+            "export audio" => {
+                session.message = Some("Usage: export audio <file>, e.g. export audio game.wav".to_string());
+            }
+            _ if input.starts_with("export audio ") => {
+                session.message = Some(session.export_audio(input["export audio ".len()..].trim()));
+            }
+            _ if input.starts_with("export ") => session.message = Some(session.export_pgn(input["export ".len()..].trim())),
+            "display" => session.message = Some(format!("Usage: display <mode>. Options: {}", display::DISPLAY_MODE_NAMES)),
+            "sound" => session.message = Some("Usage: sound <square>, e.g. sound e4 or sound Nf3".to_string()),
+            _ if input.starts_with("sound ") => session.preview(input["sound ".len()..].trim()),
+            "mute" => session.audio.muted = !session.audio.muted,
+            "volume" => session.message = Some(format!("{}. Usage: volume <0-100>", session.audio.describe())),
+            _ if input.starts_with("volume ") => match parse_volume(input["volume ".len()..].trim()) {
+                Some(percent) => {
+                    session.audio.volume = percent;
+                    session.message =
+                        save_preference(session.config_path.as_deref(), "volume", toml::Value::Integer(percent.into()));
+                }
+                None => session.message = Some("Usage: volume <0-100>, e.g. volume 50".to_string()),
+            },
+            "soundtheme" => {
+                session.message = Some(format!(
+                    "Sound theme: {}. Usage: soundtheme <name or file>. Built-in: {}",
+                    session.audio.theme_name,
+                    audio::theme::BUILTIN_NAMES.join(", ")
+                ));
+            }
+            _ if input.starts_with("soundtheme ") => session.set_sound_theme(input["soundtheme ".len()..].trim()),
+            "announce" => {
+                let state = if session.announce { "on" } else { "off" };
+                session.message = Some(format!("Announce: {state}. Usage: announce on|off"));
+            }
+            _ if input.starts_with("announce ") => match input["announce ".len()..].trim() {
+                "on" => {
+                    session.announce = true;
+                    session.message =
+                        Some("Announce on: each move is said in words. announce off brings the board back".to_string());
+                }
+                "off" if session.announce => {
+                    session.announce = false;
+                    let shown = session.game.history().len();
+                    if let Err(err) = session.draw(&mut stdout, session.game.board(), shown, &[], RenderMode::Initial) {
+                        eprintln!("  Display error: {err}");
+                    }
+                }
+                "off" => {}
+                _ => session.message = Some("Usage: announce on|off".to_string()),
+            },
+            "coords" => {
+                let state = if session.coordinates { "on" } else { "off" };
+                session.message = Some(format!("Coordinates: {state}. Usage: coords on|off"));
+            }
+            _ if input.starts_with("coords ") => {
+                let shown = match input["coords ".len()..].trim() {
+                    "on" => Some(true),
+                    "off" => Some(false),
+                    _ => None,
+                };
+                match shown {
+                    Some(shown) => {
+                        session.coordinates = shown;
+                        session.restyle(&mut stdout, "coordinates", toml::Value::Boolean(shown));
+                    }
+                    None => session.message = Some("Usage: coords on|off".to_string()),
+                }
+            }
+            "theme" => {
+                session.message = Some(format!(
+                    "Board theme: {}. Usage: theme <name>. Options: {}",
+                    session.palette.name(),
+                    display::Palette::NAMES.join(", ")
+                ));
+            }
+            _ if input.starts_with("theme ") => {
+                let name = input["theme ".len()..].trim();
+                match display::Palette::from_name(name) {
+                    Some(palette) => {
+                        session.palette = palette;
+                        session.restyle(&mut stdout, "board_theme", toml::Value::String(palette.name().to_string()));
+                    }
+                    None => {
+                        session.message =
+                            Some(format!("Unknown theme: {name}. Options: {}", display::Palette::NAMES.join(", ")));
+                    }
+                }
             }
             _ if input.starts_with("display ") => {
                 let mode_str = &input["display ".len()..];
                 match display::parse_display_mode(mode_str) {
                     Some(mode) => {
-                        strategy = display::create_strategy(mode, color_mode);
-                        if let Err(err) = render_board(
-                            &board,
-                            &mut stdout,
-                            &*strategy,
-                            &move_history,
-                            RenderMode::Redraw(redraw_height),
-                        ) {
-                            eprintln!("  Display error: {err}");
-                        }
+                        session.display_mode = mode;
+                        session.restyle(&mut stdout, "display", toml::Value::String(display::display_mode_name(mode)));
                     }
                     None => {
-                        writeln!(
-                            stdout,
-                            "  Unknown display mode: {mode_str}. Options: sprite, unicode, ascii"
-                        )
-                        .ok();
-                        stdout.flush().ok();
+                        session.message =
+                            Some(format!("Unknown display mode: {mode_str}. Options: {}", display::DISPLAY_MODE_NAMES));
                     }
                 }
-                continue;
             }
-            _ => {}
+            _ => session.play_typed(&stdin, &mut stdout, input),
         }
+    }
+}
 
-        let chess_move = match NotationMove::parse(input, move_index) {
-            Some(m) => m,
-            None => {
-                writeln!(stdout, "  Invalid move: {input}").ok();
-                stdout.flush().ok();
-                continue;
-            }
-        };
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tui::display::AsciiDisplay;
 
-        let color = if is_white_turn(move_index) {
-            Color::White
-        } else {
-            Color::Black
+    const NO_MOVES: &[&str] = &[];
+
+    #[test]
+    fn announcements_say_the_move_in_words() {
+        let say = |fen: &str, notation: &str| {
+            let position = Board::from_fen(fen).unwrap();
+            let color = if is_white_turn(position.ply) { Color::White } else { Color::Black };
+            let chess_move = NotationMove::parse(notation, position.ply).unwrap();
+            let resolved = position.board.resolve_legal_move(&chess_move, notation, color).unwrap();
+            announcement(&position.board, &resolved, color)
         };
+        let initial = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+        assert_eq!(say(initial, "Nf3"), "White knight from g1 to f3");
+        assert_eq!(
+            say("rnbqkbnr/ppp1pppp/8/3p4/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 0 2", "exd5"),
+            "White pawn from e4 to d5, takes the black pawn"
+        );
+        assert_eq!(
+            say("rnbqkbnr/pppp1ppp/8/4p3/4P3/5P2/PPPP2PP/RNBQKBNR b KQkq - 0 2", "Qh4+"),
+            "Black queen from d8 to h4. White is in check"
+        );
+        assert_eq!(say("4k3/8/8/8/8/8/8/4K2R w K - 0 1", "O-O"), "White castles kingside");
+        assert_eq!(say("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1", "Ra8#"), "White rook from a1 to a8. Checkmate, White wins");
+        assert_eq!(say("8/P6k/8/8/8/8/8/K7 w - - 0 1", "a8=Q"), "White pawn from a7 to a8, becomes a queen");
+        assert_eq!(say("7k/8/6Q1/8/8/8/8/K7 w - - 0 1", "Qf7"), "White queen from g6 to f7. Stalemate");
+        assert_eq!(
+            say("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 2", "exd6"),
+            "White pawn from e5 to d6, takes the black pawn"
+        );
+    }
 
-        let parsed = match board.resolve_move(&chess_move, input, color) {
-            Some(p) => p,
-            None => {
-                writeln!(stdout, "  No piece found for: {input}").ok();
-                stdout.flush().ok();
-                continue;
-            }
+    #[test]
+    fn move_notes_name_each_destinations_pitch() {
+        let moves = ["Nf3".to_string(), "e5".to_string(), "?".to_string()];
+        assert_eq!(move_notes(&moves, 0, &audio::PitchMap::default()), ["A3 220Hz", "G5 784Hz", ""]);
+    }
+
+    #[test]
+    fn ambiguity_question_lists_the_origins() {
+        let origins = [Square::from_name("b1").unwrap(), Square::from_name("f3").unwrap()];
+        assert_eq!(ambiguity_question(Piece::Knight, &origins), "  Which knight? (b1/f3) ");
+    }
+
+    #[test]
+    fn origin_is_picked_by_square_file_or_rank() {
+        let square = |name| Square::from_name(name).unwrap();
+        let knights = [square("b1"), square("f3")];
+        assert_eq!(pick_origin("f3", &knights), Some(square("f3")));
+        assert_eq!(pick_origin("b", &knights), Some(square("b1")));
+        assert_eq!(pick_origin("3", &knights), Some(square("f3")));
+        assert_eq!(pick_origin("d2", &knights), None);
+        assert_eq!(pick_origin("", &knights), None);
+        // Rooks on a1 and a5 share the file
+        assert_eq!(pick_origin("a", &[square("a1"), square("a5")]), None);
+    }
+
+    #[test]
+    fn sound_target_defaults_to_pawn() {
+        assert_eq!(parse_sound_target("e4"), Some((Square { file: 4, rank: 3 }, Piece::Pawn)));
+    }
+
+    #[test]
+    fn sound_target_takes_piece_letter() {
+        assert_eq!(parse_sound_target("Nf3"), Some((Square { file: 5, rank: 2 }, Piece::Knight)));
+    }
+
+    #[test]
+    fn audio_export_plays_the_session_theme_at_its_timing() {
+        let timing = audio::Timing { note_ms: 200, gap_ms: 20 };
+        let theme = audio::theme::Theme::from_name("8-bit").unwrap();
+        let session_audio = SessionAudio { theme: theme.clone(), timing, volume: 10, ..SessionAudio::default() };
+        let start = Board::from_fen("4k3/8/8/8/8/8/4P3/4K3 w - - 0 1").unwrap();
+        let config = session_audio.export_config(&start);
+        assert_eq!((config.sounds, config.pitch, config.timing), (theme.sounds, theme.pitch, timing));
+        assert_eq!(config.start, start);
+        // The volume is for the speakers, not the file
+        assert_eq!(config.gain, None);
+    }
+
+    #[test]
+    fn note_label_follows_the_theme() {
+        let f4 = Square::from_name("f4").unwrap();
+        assert_eq!(note_label(&f4, &audio::PitchMap::default()), "A4 440Hz");
+        let chromatic = audio::PitchMap { layout: audio::Layout::Chromatic, ..audio::PitchMap::default() };
+        assert_eq!(note_label(&Square::from_name("a1").unwrap(), &chromatic), "C2 65Hz");
+    }
+
+    #[test]
+    fn sound_target_rejects_non_squares() {
+        assert_eq!(parse_sound_target("Nz9"), None);
+        assert_eq!(parse_sound_target("hello"), None);
+    }
+
+    fn played(start: &Position, moves: &str) -> Game {
+        let (game, stopped_at) = resolve_game(start, moves);
+        assert_eq!(stopped_at, None);
+        game
+    }
+
+    #[test]
+    fn session_game_fills_the_seven_tag_roster() {
+        let game = session_game(&played(&Position::default(), "f3 e5 g4 Qh4#"), &[], 1_700_000_000);
+        assert_eq!(game.tags.get("Date"), Some("2023.11.14"));
+        assert_eq!(game.tags.get("Result"), Some("0-1"));
+        assert_eq!(game.tags.get("FEN"), None);
+        assert!(game.to_pgn().ends_with("\n\n1. f3 e5 2. g4 Qh4# 0-1\n"));
+
+        let start = Board::from_fen("4k3/8/8/8/8/8/4P3/4K3 b - - 0 40").unwrap();
+        let game = session_game(&played(&start, "Kd7"), &[], 0);
+        assert_eq!(game.tags.get("Result"), Some("*"));
+        assert_eq!(game.tags.get("FEN"), Some("4k3/8/8/8/8/8/4P3/4K3 b - - 0 40"));
+        assert!(game.to_pgn().ends_with("\n\n40... Kd7 *\n"));
+    }
+
+    #[test]
+    fn session_game_reads_the_result_off_the_board() {
+        let game = session_game(&played(&Position::default(), "f3 e5 g4 Qh4"), &[], 0);
+        assert_eq!(game.tags.get("Result"), Some("0-1"));
+
+        let start = Board::from_fen("k7/8/1Q6/8/8/8/8/K7 w - - 0 1").unwrap();
+        let game = session_game(&played(&start, "Qc7"), &[], 0);
+        assert_eq!(game.tags.get("Result"), Some("1/2-1/2"));
+        assert!(game.to_pgn().ends_with("\n\n1. Qc7 1/2-1/2\n"));
+    }
+
+    #[test]
+    fn session_game_records_a_resignation_or_draw() {
+        let mut game = played(&Position::default(), "e4");
+        game.resign(Color::Black);
+        assert_eq!(session_game(&game, &[], 0).tags.get("Result"), Some("1-0"));
+        game.agree_draw();
+        let game = session_game(&game, &[], 0);
+        assert!(game.to_pgn().ends_with("\n\n1. e4 1/2-1/2\n"));
+    }
+
+    #[test]
+    fn engine_takes_a_draw_only_when_not_ahead() {
+        let game = played(&Position::default(), "e4 d5 exd5");
+        assert!(engine_accepts_draw(game.history(), Color::Black));
+        assert!(!engine_accepts_draw(game.history(), Color::White));
+        assert!(engine_accepts_draw(&[], Color::White));
+    }
+
+    #[test]
+    fn only_new_and_what_leaves_the_game_be_follow_a_result() {
+        for input in ["new", "quit", "export game.pgn", "fen", "volume 50", "announce on"] {
+            assert!(allowed_after_the_game(input), "{input}");
+        }
+        for input in ["e4", "undo", "takeback", "redo", "n", "select", "play engine", "resign", "draw", ""] {
+            assert!(!allowed_after_the_game(input), "{input}");
+        }
+    }
+
+    #[test]
+    fn session_game_keeps_comments_on_moves_still_played() {
+        let comments = [(0, "king's pawn".to_string()), (1, "taken back".to_string())];
+        let game = session_game(&played(&Position::default(), "e4"), &comments, 0);
+        assert!(game.to_pgn().ends_with("\n\n1. e4 {king's pawn} *\n"));
+    }
+
+    #[test]
+    fn replay_speed_sets_the_pause_between_moves() {
+        assert_eq!(parse_replay_delay(""), Some(Duration::from_millis(REPLAY_MOVE_MS)));
+        assert_eq!(parse_replay_delay("2x"), Some(Duration::from_millis(500)));
+        assert_eq!(parse_replay_delay("0.5x"), Some(Duration::from_millis(2000)));
+        assert_eq!(parse_replay_delay("3x"), None);
+    }
+
+    #[test]
+    fn replays_render_a_move_every_delay_at_the_volume() {
+        let mut session_audio = SessionAudio { volume: 50, ..SessionAudio::default() };
+        let config = session_audio.replay_config(&Position::default(), Duration::from_millis(500)).unwrap();
+        assert_eq!(config.timing.slot_ms(), 500);
+        assert_eq!(config.gain, Some(0.5));
+        session_audio.muted = true;
+        assert_eq!(session_audio.replay_config(&Position::default(), Duration::from_millis(500)), None);
+        assert_eq!(clock_text(Duration::from_millis(65_900)), "01:05");
+    }
+
+    #[test]
+    fn engine_args_default_to_the_other_side() {
+        assert_eq!(parse_engine_args("", Color::White), Some((Color::Black, search::DEFAULT_DEPTH)));
+        assert_eq!(parse_engine_args(" white 2", Color::White), Some((Color::White, 2)));
+        assert_eq!(parse_engine_args(" 4", Color::Black), Some((Color::White, 4)));
+        assert_eq!(parse_engine_args(" 0", Color::White), None);
+        assert_eq!(parse_engine_args(" purple", Color::White), None);
+    }
+
+    #[test]
+    fn orientation_follows_the_side_to_move_when_asked() {
+        assert_eq!(Orientation::Fixed(Color::Black).perspective(0), Color::Black);
+        assert_eq!(Orientation::SideToMove.perspective(0), Color::White);
+        assert_eq!(Orientation::SideToMove.perspective(79), Color::Black);
+    }
+
+    #[test]
+    fn hint_lists_destinations() {
+        let board = Board::new();
+        let knight = Square::from_name("g1").unwrap();
+        let destinations: Vec<Square> = board.legal_moves_from(knight).iter().map(|resolved| resolved.dest).collect();
+        assert_eq!(describe_hint(&board, knight, &destinations), "Ng1: f3, h3");
+        assert_eq!(describe_hint(&board, Square::from_name("a1").unwrap(), &[]), "Ra1: no legal moves");
+        assert_eq!(describe_hint(&board, Square::from_name("e4").unwrap(), &[]), "No piece on e4");
+    }
+
+    #[test]
+    fn help_covers_every_command() {
+        let lines = help::lines(&audio::theme::Theme::default(), "classic");
+        for command in COMMANDS {
+            assert!(lines.iter().any(|line| line.starts_with(command)), "{command} is missing from help");
+        }
+    }
+
+    #[test]
+    fn completions_list_sorted_moves_then_commands() {
+        let names = completions(&Board::new(), Color::White);
+        assert_eq!(names.len(), 20 + COMMANDS.len());
+        assert_eq!(&names[..3], ["Na3", "Nc3", "Nf3"]);
+        assert!(names[..20].contains(&"e4".to_string()));
+        assert_eq!(names[20..], COMMANDS);
+    }
+
+    /// A silent session from `fen`, drawn in ASCII.
+    fn session_from(fen: &str) -> Session {
+        let preferences = Preferences {
+            palette: display::Palette::default(),
+            coordinates: true,
+            volume: 100,
+            sound_theme: None,
+            error_sound: true,
+            timing: audio::Timing::default(),
+            config_path: None,
         };
+        let start = Board::from_fen(fen).unwrap();
+        let mut session = Session::new(start, display::DisplayMode::Ascii, display::ColorMode::TrueColor, preferences);
+        session.audio.muted = true;
+        session
+    }
 
-        board.apply_move(&parsed);
-        move_history.push(input.to_string());
+    fn fen_of(session: &Session) -> String {
+        Position { board: session.game.board().clone(), ply: session.game.ply() }.to_fen()
+    }
 
-        let samples = audio::synthesize_move(&chess_move);
-        let wav = audio::to_wav(&samples);
-        audio::play(&wav);
+    #[test]
+    fn typed_moves_leave_pinned_pieces_alone() {
+        let mut out = Vec::new();
+        let mut session = session_from("4r2k/8/2N5/8/8/8/4N3/4K3 w - - 0 1");
+        session.play_typed(&io::stdin(), &mut out, "Nd4");
+        assert!(fen_of(&session).starts_with("4r2k/8/8/8/3N4/8/4N3/4K3 b"));
+        assert_eq!(session.game.moves(), ["Nd4"]);
+        assert!(session.engine_to_move);
 
-        if let Err(err) = render_board(
-            &board,
-            &mut stdout,
-            &*strategy,
-            &move_history,
-            RenderMode::Redraw(redraw_height),
-        ) {
-            eprintln!("  Display error: {err}");
+        let mut pinned = session_from("4r2k/8/8/8/8/8/4N3/4K3 w - - 0 1");
+        pinned.play_typed(&io::stdin(), &mut out, "Nd4");
+        assert_eq!(pinned.message.as_deref(), Some("Nd4: illegal move"));
+        assert!(pinned.game.history().is_empty());
+        assert!(fen_of(&pinned).starts_with("4r2k/8/8/8/8/8/4N3/4K3 w"));
+    }
+
+    #[test]
+    fn replaying_a_move_taken_back_keeps_the_rest() {
+        let mut out = Vec::new();
+        let mut session = session_from("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+        for notation in ["e4", "e5", "Nf3"] {
+            session.play_typed(&io::stdin(), &mut out, notation);
         }
-        move_index += 1;
+        session.comments.push((1, "solid".to_string()));
+        session.take_back(2);
+        assert_eq!(session.game.moves(), ["e4"]);
+
+        let resolved = session.undone.last().cloned().unwrap();
+        session.play_move(&mut out, resolved).unwrap();
+        assert_eq!(session.game.moves(), ["e4", "e5"]);
+        assert_eq!(session.undone.len(), 1);
+        assert_eq!(session.comments, [(1, "solid".to_string())]);
+
+        session.play_typed(&io::stdin(), &mut out, "Nc3");
+        assert_eq!(session.game.moves(), ["e4", "e5", "Nc3"]);
+        assert!(session.undone.is_empty());
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::tui::display::AsciiDisplay;
+    #[test]
+    fn last_move_markers_follow_the_newest_move() {
+        assert!(last_move_markers(&[]).is_empty());
+        let game = played(&Position::default(), "e4 e5");
+        let squares: Vec<String> = last_move_markers(game.history()).iter().map(|(square, _)| square.name()).collect();
+        assert_eq!(squares, ["e7", "e5"]);
+    }
 
-    const NO_MOVES: &[&str] = &[];
+    #[test]
+    fn resolve_game_stops_at_an_unplayable_move() {
+        let (game, stopped_at) = resolve_game(&Position::default(), "e4 e5 Ng1f3");
+        assert_eq!(game.moves(), ["e4", "e5", "Nf3"]);
+        assert_eq!(game.history()[2].resolved.origin, Square::from_name("g1").unwrap());
+        assert_eq!(stopped_at, None);
+
+        let (game, stopped_at) = resolve_game(&Position::default(), "e4 e5 Bb4 Nc6");
+        assert_eq!(game.history().len(), 2);
+        assert_eq!(stopped_at, Some("Bb4".to_string()));
+    }
+
+    #[test]
+    fn resolve_game_plays_from_the_start_position() {
+        let start = Board::from_fen("4k3/8/8/8/8/8/4P3/4K3 b - - 0 40").unwrap();
+        let (game, stopped_at) = resolve_game(&start, "Kd7 e4");
+        assert_eq!(game.history().len(), 2);
+        assert_eq!(stopped_at, None);
+    }
+
+    #[test]
+    fn volume_is_a_percentage() {
+        assert_eq!(parse_volume("0"), Some(0));
+        assert_eq!(parse_volume("100"), Some(100));
+        assert_eq!(parse_volume("101"), None);
+        assert_eq!(parse_volume("-5"), None);
+        assert_eq!(parse_volume("loud"), None);
+    }
+
+    #[test]
+    fn mute_silences_moves_and_keeps_the_volume() {
+        let mut session_audio = SessionAudio { volume: 50, ..SessionAudio::default() };
+        assert_eq!(session_audio.move_gain(), Some(0.5));
+        assert_eq!(session_audio.describe(), "Volume: 50");
+        session_audio.muted = true;
+        assert_eq!(session_audio.move_gain(), None);
+        assert_eq!(session_audio.describe(), "Muted (volume 50)");
+        assert_eq!(SessionAudio::default().move_gain(), Some(1.0));
+    }
+
+    #[test]
+    fn error_buzz_follows_the_volume_and_the_switch() {
+        let session_audio = SessionAudio { volume: 40, ..SessionAudio::default() };
+        assert_eq!(session_audio.error_gain(), Some(0.4));
+        assert_eq!(SessionAudio { error_sound: false, ..session_audio.clone() }.error_gain(), None);
+        assert_eq!(SessionAudio { muted: true, ..session_audio }.error_gain(), None);
+    }
+
+    #[test]
+    fn status_line_shows_turn_state_clock_sound_and_message() {
+        let audio = SessionAudio::default();
+        let status = status_line(&Board::new(), 0, None, Duration::from_secs(75), &audio, None);
+        assert_eq!(status, "Move 1 - White to play | 01:15 | Volume: 100");
+        let muted = SessionAudio { muted: true, ..SessionAudio::default() };
+        let status = status_line(&Board::new(), 1, None, Duration::ZERO, &muted, Some("Nothing to undo"));
+        assert_eq!(status, "Move 1 - Black to play | 00:00 | Muted (volume 100) | Nothing to undo");
+    }
+
+    #[test]
+    fn status_line_names_check_and_the_result() {
+        let audio = SessionAudio::default();
+        let mated = Board::from_fen("R5k1/5ppp/8/8/8/8/8/6K1 b - - 0 1").unwrap();
+        let status = status_line(&mated.board, mated.ply, None, Duration::ZERO, &audio, None);
+        assert!(status.contains("| Checkmate - White wins |"), "{status}");
+        let stalemated = Board::from_fen("k7/2Q5/8/8/8/8/8/K7 b - - 0 1").unwrap();
+        assert!(status_line(&stalemated.board, stalemated.ply, None, Duration::ZERO, &audio, None).contains("| Stalemate |"));
+        let checked = Board::from_fen("4k3/8/8/8/8/8/4R3/4K3 b - - 0 1").unwrap();
+        assert!(status_line(&checked.board, checked.ply, None, Duration::ZERO, &audio, None).contains("| Check |"));
+    }
+
+    #[test]
+    fn status_line_shows_the_result_instead_of_the_turn() {
+        let audio = SessionAudio::default();
+        let status = status_line(&Board::new(), 2, Some(GameResult::Resigned(Color::White)), Duration::ZERO, &audio, None);
+        assert_eq!(status, "Move 2 | White resigns - Black wins (0-1) | 00:00 | Volume: 100");
+        let status = status_line(&Board::new(), 3, Some(GameResult::DrawAgreed), Duration::ZERO, &audio, None);
+        assert!(status.starts_with("Move 2 | Draw agreed (1/2-1/2) |"), "{status}");
+    }
 
     #[test]
     fn render_board_with_moves_writes_sidebar() {
         let board = Board::new();
         let moves = vec!["e4".to_string(), "e5".to_string()];
         let mut buf = Vec::new();
-        render_board(&board, &mut buf, &AsciiDisplay, &moves, RenderMode::Initial).unwrap();
+        render_board(&board, &mut buf, &AsciiDisplay, &display::Sidebar::new(&moves, 0), &[], Color::White, RenderMode::Initial).unwrap();
         let output = String::from_utf8(buf).unwrap();
         assert!(output.contains("Moves"));
         assert!(output.contains("1. e4    e5"));
@@ -194,7 +1839,7 @@ mod tests {
     fn render_board_redraw_emits_cursor_up() {
         let board = Board::new();
         let mut buf = Vec::new();
-        render_board(&board, &mut buf, &AsciiDisplay, NO_MOVES, RenderMode::Redraw(11)).unwrap();
+        render_board(&board, &mut buf, &AsciiDisplay, &display::Sidebar::new(NO_MOVES, 0), &[], Color::White, RenderMode::Redraw(11)).unwrap();
         let output = String::from_utf8(buf).unwrap();
         assert!(
             output.starts_with("\x1b["),
@@ -207,7 +1852,7 @@ mod tests {
     fn render_board_first_draw_no_cursor_up() {
         let board = Board::new();
         let mut buf = Vec::new();
-        render_board(&board, &mut buf, &AsciiDisplay, NO_MOVES, RenderMode::Initial).unwrap();
+        render_board(&board, &mut buf, &AsciiDisplay, &display::Sidebar::new(NO_MOVES, 0), &[], Color::White, RenderMode::Initial).unwrap();
         let output = String::from_utf8(buf).unwrap();
         assert!(
             !output.starts_with("\x1b["),
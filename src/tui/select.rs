@@ -0,0 +1,175 @@
+//! The REPL's `select`: choosing a move with the arrow keys instead of
+//! typing it.
+//!
+//! ```text
+//! ← ↑ ↓ →   move the cursor
+//! Enter     pick up the piece under it (its moves are marked), or play
+//!           the picked piece to the cursor
+//! click     the same as moving the cursor there and pressing Enter
+//! q / Esc   back to typing
+//! ```
+//!
+//! Keys and clicks come from [`super::terminal`]; the REPL asks the
+//! terminal where its cursor is to find the board on screen.
+
+use crate::engine::board::{Board, Color};
+use crate::engine::chess::{ResolvedMove, Square};
+use super::display::SquareMarker;
+use super::terminal::Key;
+
+/// The line shown under the board while selecting.
+pub const HELP: &str = "Arrows or clicks move, Enter picks a piece and plays it, q goes back to typing";
+
+/// What a key press ended the selection with.
+pub enum Step {
+    Play(ResolvedMove),
+    Leave,
+}
+
+/// The cursor and the piece picked up, if any, with its legal moves.
+pub struct Selection {
+    pub cursor: Square,
+    picked: Option<(Square, Vec<ResolvedMove>)>,
+}
+
+impl Selection {
+    /// The cursor starts on the e-file pawn square of `color`.
+    pub fn new(color: Color) -> Self {
+        let rank = match color {
+            Color::White => 1,
+            Color::Black => 6,
+        };
+        Self { cursor: Square { file: 4, rank }, picked: None }
+    }
+
+    /// Handles one key for `color` to move on `board`, drawn from
+    /// `perspective`'s side: arrows follow the screen, not the board.
+    pub fn press(&mut self, key: Key, board: &Board, color: Color, perspective: Color) -> Option<Step> {
+        // Up the screen is up the board for White and down it for Black
+        let (up, right) = match perspective {
+            Color::White => (1, 1),
+            Color::Black => (-1, -1),
+        };
+        match key {
+            Key::Up => self.shift(0, up),
+            Key::Down => self.shift(0, -up),
+            Key::Right => self.shift(right, 0),
+            Key::Left => self.shift(-right, 0),
+            Key::Enter => return self.choose(board, color),
+            Key::Char('q') | Key::Escape | Key::CtrlC | Key::CtrlD => return Some(Step::Leave),
+            // Clicks need the board's place on screen; see `click`
+            Key::Click { .. } | Key::Char(_) | Key::Tab | Key::Backspace | Key::Other => {}
+        }
+        None
+    }
+
+    /// A click on `square`: the cursor goes there and Enter is pressed.
+    pub fn click(&mut self, square: Square, board: &Board, color: Color) -> Option<Step> {
+        self.cursor = square;
+        self.choose(board, color)
+    }
+
+    fn shift(&mut self, files: i8, ranks: i8) {
+        let step = |coordinate: u8, delta: i8| coordinate.saturating_add_signed(delta).min(7);
+        self.cursor = Square { file: step(self.cursor.file, files), rank: step(self.cursor.rank, ranks) };
+    }
+
+    /// Plays the picked piece to the cursor if it can go there; otherwise
+    /// picks up the piece of `color` under the cursor, or drops the pick.
+    fn choose(&mut self, board: &Board, color: Color) -> Option<Step> {
+        if let Some((_, moves)) = self.picked.as_mut()
+            && let Some(idx) = moves.iter().position(|candidate| candidate.dest == self.cursor)
+        {
+            return Some(Step::Play(moves.swap_remove(idx)));
+        }
+        self.picked = match board.get(self.cursor.file, self.cursor.rank) {
+            Some((_, owner)) if owner == color => Some((self.cursor, board.legal_moves_from(self.cursor))),
+            Some(_) | None => None,
+        };
+        None
+    }
+
+    /// The cursor, the picked piece and its destinations, to draw.
+    pub fn markers(&self) -> Vec<(Square, SquareMarker)> {
+        let mut markers = vec![(self.cursor, SquareMarker::Cursor)];
+        if let Some((origin, moves)) = &self.picked {
+            markers.push((*origin, SquareMarker::Cursor));
+            markers.extend(moves.iter().map(|candidate| (candidate.dest, SquareMarker::Hint)));
+        }
+        markers
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn q_escape_and_ctrl_c_leave() {
+        let board = Board::new();
+        for key in [Key::Char('q'), Key::Escape, Key::CtrlC] {
+            let step = Selection::new(Color::White).press(key, &board, Color::White, Color::White);
+            assert!(matches!(step, Some(Step::Leave)));
+        }
+    }
+
+    #[test]
+    fn clicking_origin_then_destination_plays() {
+        let board = Board::new();
+        let mut selection = Selection::new(Color::White);
+        let square = |name| Square::from_name(name).unwrap();
+        assert!(selection.click(square("g1"), &board, Color::White).is_none());
+        let Some(Step::Play(resolved)) = selection.click(square("f3"), &board, Color::White) else {
+            panic!("expected a move");
+        };
+        assert_eq!(board.san(&resolved), "Nf3");
+    }
+
+    #[test]
+    fn arrows_follow_the_screen_and_stop_at_the_edge() {
+        let board = Board::new();
+        let mut selection = Selection::new(Color::White);
+        selection.press(Key::Up, &board, Color::White, Color::White);
+        assert_eq!(selection.cursor.name(), "e3");
+        selection.press(Key::Up, &board, Color::White, Color::Black);
+        assert_eq!(selection.cursor.name(), "e2");
+        for _ in 0..5 {
+            selection.press(Key::Right, &board, Color::White, Color::White);
+        }
+        assert_eq!(selection.cursor.name(), "h2");
+    }
+
+    #[test]
+    fn enter_picks_a_piece_then_plays_it() {
+        let board = Board::new();
+        let mut selection = Selection::new(Color::White);
+        assert!(selection.press(Key::Enter, &board, Color::White, Color::White).is_none());
+        assert!(selection.markers().contains(&(Square::from_name("e4").unwrap(), SquareMarker::Hint)));
+        selection.press(Key::Up, &board, Color::White, Color::White);
+        selection.press(Key::Up, &board, Color::White, Color::White);
+        let Some(Step::Play(resolved)) = selection.press(Key::Enter, &board, Color::White, Color::White) else {
+            panic!("expected a move");
+        };
+        assert_eq!(board.san(&resolved), "e4");
+    }
+
+    #[test]
+    fn enter_on_an_empty_square_drops_the_pick() {
+        let board = Board::new();
+        let mut selection = Selection::new(Color::White);
+        selection.press(Key::Enter, &board, Color::White, Color::White);
+        for _ in 0..3 {
+            selection.press(Key::Up, &board, Color::White, Color::White);
+        }
+        assert!(selection.press(Key::Enter, &board, Color::White, Color::White).is_none());
+        assert_eq!(selection.markers().len(), 1);
+    }
+
+    #[test]
+    fn black_pieces_cannot_be_picked_by_white() {
+        let board = Board::new();
+        let mut selection = Selection::new(Color::Black);
+        selection.press(Key::Enter, &board, Color::White, Color::White);
+        assert_eq!(selection.markers().len(), 1);
+    }
+}
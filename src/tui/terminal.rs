@@ -0,0 +1,248 @@
+//! Key-at-a-time terminal input for the REPL's prompt and `select`.
+//!
+//! ```text
+//! stty -icanon -echo -isig   keys arrive one by one, unechoed; Ctrl-C too
+//! ESC [ A … ESC [ D          arrows, also with modifiers: ESC [ 1 ; 5 A
+//! ESC [ 3 ~                  any other control sequence, read whole
+//! ESC [ < 0 ; col ; line M   left click (SGR mouse report)
+//! ESC [ 6 n → ESC [ l ; c R  where the cursor is
+//! ```
+//!
+//! An Esc with nothing after it in the same read is the Esc key: a
+//! terminal writes a whole escape sequence at once.
+//!
+//! The terminal is set and restored with `stty`, so this needs a Unix
+//! terminal on stdin.
+
+use std::io::{self, BufRead, Read, Write};
+use std::process::{Command, Stdio};
+
+/// Turns on click reports, in SGR form so columns past 223 still fit.
+const MOUSE_ON: &str = "\x1b[?1000h\x1b[?1006h";
+const MOUSE_OFF: &str = "\x1b[?1000l\x1b[?1006l";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Key {
+    Up,
+    Down,
+    Left,
+    Right,
+    Enter,
+    Tab,
+    Backspace,
+    Escape,
+    /// Ctrl-C, which no longer interrupts the program
+    CtrlC,
+    /// Ctrl-D, end of input
+    CtrlD,
+    Char(char),
+    /// A left click on a screen cell, both counted from 1 at the top-left
+    Click { column: usize, line: usize },
+    Other,
+}
+
+/// Reads one key press: a character, an arrow's escape sequence, Enter,
+/// Tab, Backspace, Esc, Ctrl-C, Ctrl-D or a mouse report.
+pub fn read_key(reader: &mut impl BufRead) -> io::Result<Key> {
+    let (lead, follower) = {
+        let chunk = reader.fill_buf()?;
+        let (&lead, rest) = chunk.split_first().ok_or_else(|| io::Error::from(io::ErrorKind::UnexpectedEof))?;
+        (lead, rest.first().copied())
+    };
+    reader.consume(1);
+    match lead {
+        b'\r' | b'\n' => Ok(Key::Enter),
+        b'\t' => Ok(Key::Tab),
+        0x7f | 0x08 => Ok(Key::Backspace),
+        0x03 => Ok(Key::CtrlC),
+        0x04 => Ok(Key::CtrlD),
+        // Arrows are `ESC [ A` normally, `ESC O A` in application mode
+        0x1b => match follower {
+            Some(b'[') => {
+                reader.consume(1);
+                read_control_sequence(reader)
+            }
+            Some(b'O') => {
+                reader.consume(1);
+                let mut byte = [0u8; 1];
+                reader.read_exact(&mut byte)?;
+                Ok(arrow(byte[0]))
+            }
+            _ => Ok(Key::Escape),
+        },
+        lead if lead.is_ascii_control() => Ok(Key::Other),
+        lead => read_char(reader, lead),
+    }
+}
+
+/// The rest of a control sequence after `ESC [`: parameters up to a final
+/// byte from 0x40 to 0x7E, all read so none of it reaches the prompt.
+fn read_control_sequence(reader: &mut impl Read) -> io::Result<Key> {
+    let (parameters, last) = read_until(reader, |byte| (0x40..=0x7e).contains(&byte))?;
+    Ok(match parameters.strip_prefix('<') {
+        Some(fields) => mouse_report(fields, last),
+        None => arrow(last),
+    })
+}
+
+/// The arrow a sequence's final byte names, whatever modifiers came before.
+fn arrow(last: u8) -> Key {
+    match last {
+        b'A' => Key::Up,
+        b'B' => Key::Down,
+        b'C' => Key::Right,
+        b'D' => Key::Left,
+        _ => Key::Other,
+    }
+}
+
+/// The character a UTF-8 sequence starting with `lead` encodes.
+fn read_char(reader: &mut impl Read, lead: u8) -> io::Result<Key> {
+    let len = match lead.leading_ones() {
+        0 => 1,
+        2..=4 => lead.leading_ones() as usize,
+        _ => return Ok(Key::Other),
+    };
+    let mut bytes = [lead, 0, 0, 0];
+    reader.read_exact(&mut bytes[1..len])?;
+    Ok(std::str::from_utf8(&bytes[..len])
+        .ok()
+        .and_then(|text| text.chars().next())
+        .map_or(Key::Other, Key::Char))
+}
+
+/// An SGR mouse report, `fields` after `ESC [ <` and its final byte: a
+/// press of the left button is a click, anything else (releases, other
+/// buttons, drags) is not.
+fn mouse_report(fields: &str, last: u8) -> Key {
+    let numbers: Vec<usize> = fields.split(';').filter_map(|field| field.parse().ok()).collect();
+    match (numbers.as_slice(), last) {
+        ([0, column, line], b'M') => Key::Click { column: *column, line: *line },
+        _ => Key::Other,
+    }
+}
+
+/// Bytes up to the first one `is_end` accepts, and that one.
+fn read_until(reader: &mut impl Read, is_end: impl Fn(u8) -> bool) -> io::Result<(String, u8)> {
+    let mut text = String::new();
+    let mut byte = [0u8; 1];
+    loop {
+        reader.read_exact(&mut byte)?;
+        if is_end(byte[0]) {
+            return Ok((text, byte[0]));
+        }
+        text.push(byte[0] as char);
+    }
+}
+
+/// The screen line of the terminal's cursor, counted from 1, asked for with
+/// a cursor position report (`ESC [ 6 n`, answered `ESC [ line ; column R`).
+pub fn cursor_line(reader: &mut impl Read, writer: &mut impl Write) -> io::Result<usize> {
+    write!(writer, "\x1b[6n")?;
+    writer.flush()?;
+    let (report, _) = read_until(reader, |byte| byte == b'R')?;
+    report
+        .trim_start_matches("\x1b[")
+        .split(';')
+        .next()
+        .and_then(|line| line.parse().ok())
+        .ok_or_else(|| io::Error::other(format!("unexpected cursor report {report:?}")))
+}
+
+/// Key-at-a-time input without echo while it lives, with click reports
+/// if asked for; the terminal's previous settings come back when it is
+/// dropped.
+pub struct RawMode {
+    saved: String,
+    mouse: bool,
+}
+
+impl RawMode {
+    /// Fails when stdin is not a terminal or `stty` is missing.
+    pub fn enable() -> io::Result<RawMode> {
+        let saved = stty(&["-g"])?;
+        stty(&["-icanon", "-echo", "-isig", "min", "1"])?;
+        Ok(RawMode { saved: saved.trim().to_string(), mouse: false })
+    }
+
+    /// [`RawMode::enable`], with mouse clicks reported as [`Key::Click`].
+    pub fn enable_with_mouse() -> io::Result<RawMode> {
+        let mut raw_mode = RawMode::enable()?;
+        let mut stdout = io::stdout();
+        write!(stdout, "{MOUSE_ON}")?;
+        stdout.flush()?;
+        raw_mode.mouse = true;
+        Ok(raw_mode)
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        if self.mouse {
+            let mut stdout = io::stdout();
+            write!(stdout, "{MOUSE_OFF}").ok();
+            stdout.flush().ok();
+        }
+        stty(&[&self.saved]).ok();
+    }
+}
+
+/// Runs `stty` on the terminal behind stdin and returns what it printed.
+fn stty(args: &[&str]) -> io::Result<String> {
+    let output = Command::new("stty").args(args).stdin(Stdio::inherit()).output()?;
+    if !output.status.success() {
+        return Err(io::Error::other(String::from_utf8_lossy(&output.stderr).trim().to_string()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(bytes: &[u8]) -> Vec<Key> {
+        let mut reader = bytes;
+        std::iter::from_fn(|| read_key(&mut reader).ok()).collect()
+    }
+
+    #[test]
+    fn reads_arrows_in_both_modes() {
+        assert_eq!(keys(b"\x1b[A\x1b[B\x1b[C\x1b[D"), [Key::Up, Key::Down, Key::Right, Key::Left]);
+        assert_eq!(keys(b"\x1bOA\x1bOB"), [Key::Up, Key::Down]);
+    }
+
+    #[test]
+    fn reads_characters_and_control_keys() {
+        assert_eq!(keys(b"Nf3\x7f\r\t"), [Key::Char('N'), Key::Char('f'), Key::Char('3'), Key::Backspace, Key::Enter, Key::Tab]);
+        assert_eq!(keys("é♞".as_bytes()), [Key::Char('é'), Key::Char('♞')]);
+        assert_eq!(keys(b"\x03\x04\x01"), [Key::CtrlC, Key::CtrlD, Key::Other]);
+    }
+
+    #[test]
+    fn a_lone_escape_leaves_the_next_key_alone() {
+        assert_eq!(keys(b"\x1b"), [Key::Escape]);
+        assert_eq!(keys(b"\x1bx"), [Key::Escape, Key::Char('x')]);
+    }
+
+    #[test]
+    fn reads_control_sequences_with_parameters_whole() {
+        // Delete, then Ctrl-Up
+        assert_eq!(keys(b"\x1b[3~e4"), [Key::Other, Key::Char('e'), Key::Char('4')]);
+        assert_eq!(keys(b"\x1b[1;5Ae4"), [Key::Up, Key::Char('e'), Key::Char('4')]);
+    }
+
+    #[test]
+    fn reads_left_clicks_only() {
+        assert_eq!(keys(b"\x1b[<0;12;5M"), [Key::Click { column: 12, line: 5 }]);
+        // The release of that click, then a right click
+        assert_eq!(keys(b"\x1b[<0;12;5m\x1b[<2;3;4M"), [Key::Other, Key::Other]);
+    }
+
+    #[test]
+    fn cursor_line_reads_the_position_report() {
+        let mut written = Vec::new();
+        assert_eq!(cursor_line(&mut &b"\x1b[31;1R"[..], &mut written).unwrap(), 31);
+        assert_eq!(written, b"\x1b[6n");
+        assert!(cursor_line(&mut &b"\x1b[;R"[..], &mut Vec::new()).is_err());
+    }
+}
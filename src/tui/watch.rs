@@ -0,0 +1,215 @@
+//! `chesswav watch <file>`: follows a game as another program writes it,
+//! drawing and playing each move as it arrives.
+//!
+//! ```text
+//! read  ← the file every POLL_MS, or stdin a line at a time
+//! parse → the last game, PGN or bare moves; a half-written last word waits
+//! play  → sound, board, move list, MOVE_GAP_MS apart when several arrive
+//! ```
+//!
+//! Moves already written when watching starts are drawn at once, without
+//! sound. A file that no longer begins with the moves shown holds a new
+//! game, and the board starts over.
+
+use std::io::{self, BufRead, BufWriter, Write};
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+
+use crate::audio;
+use crate::engine::board::{Color, Position};
+use crate::engine::chess::NotationMove;
+use crate::engine::pgn;
+use crate::game::Game;
+use super::display;
+
+/// How often the file is read again.
+const POLL_MS: u64 = 250;
+/// Pause between moves that arrive together, so each is heard.
+const MOVE_GAP_MS: u64 = 600;
+
+/// Where the moves come from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Source {
+    File(PathBuf),
+    Stdin,
+}
+
+impl Source {
+    fn name(&self) -> String {
+        match self {
+            Source::File(path) => path.display().to_string(),
+            Source::Stdin => "standard input".to_string(),
+        }
+    }
+}
+
+/// The last game in `text` and its moves, leaving out a last word that may
+/// still be being written. Without a FEN tag the game starts at `start`.
+fn written_game(text: &str, start: &Position) -> (Position, Vec<String>) {
+    let complete = text.trim_end_matches(|letter: char| !letter.is_whitespace());
+    let Some(game) = pgn::parse_games(complete).pop() else {
+        return (start.clone(), Vec::new());
+    };
+    let position = match game.tags.get("FEN") {
+        Some(_) => game.start().unwrap_or_else(|_| start.clone()),
+        None => start.clone(),
+    };
+    (position, game.moves.split_whitespace().map(str::to_string).collect())
+}
+
+/// The moves of `written` still to play on top of `shown`; None when
+/// `written` does not continue `shown`, so the game has to start over.
+fn moves_to_play<'a>(shown: &[String], written: &'a [String]) -> Option<&'a [String]> {
+    written.starts_with(shown).then(|| &written[shown.len()..])
+}
+
+/// Follows `source` from `start` until Ctrl-C, or the end of stdin.
+pub fn run(mode: display::DisplayMode, color_mode: display::ColorMode, start: Position, source: Source) {
+    let strategy = display::create_strategy(mode, color_mode, display::Palette::default(), true);
+    let mut stdout = BufWriter::new(io::stdout());
+    let playback = audio::PlaybackQueue::new();
+    let stdin = io::stdin();
+
+    println!();
+    println!("  ChessWAV Watch - following {}. Ctrl-C to stop", source.name());
+    println!();
+
+    let mut game = Game::from_position(start.clone());
+    // The moves shown, as written, to tell more moves from a new game
+    let mut shown: Vec<String> = Vec::new();
+    let mut text = String::new();
+    // Lines from stdin are all new; a file may hold a game already
+    let mut first_read = source != Source::Stdin;
+    let mut first_draw = true;
+    let mut shown_status = String::new();
+    // The first playback failure stays on the status line
+    let mut sound_error: Option<String> = None;
+    loop {
+        let mut status = match &sound_error {
+            Some(err) => format!("Watching {} | Sound unavailable: {err}", source.name()),
+            None => format!("Watching {}", source.name()),
+        };
+        match &source {
+            Source::File(path) => match std::fs::read_to_string(path) {
+                Ok(contents) => text = contents,
+                Err(err) => status = format!("Cannot read {}: {err}", source.name()),
+            },
+            Source::Stdin => {
+                let mut line = String::new();
+                match stdin.lock().read_line(&mut line) {
+                    Ok(0) | Err(_) => return,
+                    Ok(_) => text.push_str(&line),
+                }
+            }
+        }
+
+        let (position, written) = written_game(&text, &start);
+        // Set when the board changed since it was last drawn
+        let mut stale = first_draw;
+        if position != *game.start() || moves_to_play(&shown, &written).is_none() {
+            game = Game::from_position(position);
+            shown.clear();
+            stale = true;
+        }
+        let arrived = moves_to_play(&shown, &written).map(<[String]>::to_vec).unwrap_or_default();
+        for (idx, notation) in arrived.iter().enumerate() {
+            let move_index = game.ply();
+            if game.push_san(notation).is_err() {
+                status = format!("Cannot play {notation}; waiting for the game to change");
+                break;
+            }
+            shown.push(notation.clone());
+            // Moves written before watching began are drawn once, silently
+            if first_read {
+                stale = true;
+                continue;
+            }
+            if idx > 0 {
+                thread::sleep(Duration::from_millis(MOVE_GAP_MS));
+            }
+            if let Some(chess_move) = NotationMove::parse(notation, move_index) {
+                playback.enqueue(audio::to_wav(&audio::synthesize_move(&chess_move)));
+                if let Some(err) = playback.take_error() {
+                    status = format!("{status} | Sound unavailable: {err}");
+                    sound_error = Some(err.to_string());
+                }
+            }
+            draw(&mut stdout, &*strategy, &game, &status, &mut first_draw);
+            shown_status = status.clone();
+            stale = false;
+        }
+        if stale || status != shown_status {
+            draw(&mut stdout, &*strategy, &game, &status, &mut first_draw);
+            shown_status = status;
+        }
+        first_read = false;
+        if let Source::File(_) = source {
+            thread::sleep(Duration::from_millis(POLL_MS));
+        }
+    }
+}
+
+/// Draws `game` over the previous drawing, the status under the board.
+fn draw(writer: &mut impl Write, strategy: &dyn display::DisplayStrategy, game: &Game, status: &str, first_draw: &mut bool) {
+    if !*first_draw {
+        display::cursor_up_and_clear(writer, display::layout_height(strategy) + 1).ok();
+    }
+    *first_draw = false;
+    if let Err(err) = draw_game(writer, strategy, game, status) {
+        eprintln!("  Display error: {err}");
+    }
+}
+
+/// `game` with its last move tinted and the pieces taken beside it.
+fn draw_game(writer: &mut impl Write, strategy: &dyn display::DisplayStrategy, game: &Game, status: &str) -> io::Result<()> {
+    let markers: Vec<_> = game
+        .history()
+        .last()
+        .iter()
+        .flat_map(|last| {
+            [(last.resolved.origin, display::SquareMarker::LastMove), (last.resolved.dest, display::SquareMarker::LastMove)]
+        })
+        .collect();
+    let captured: Vec<_> = game.history().iter().filter_map(|played| played.undo.captured()).collect();
+    let sidebar = display::Sidebar { moves: &game.moves(), first_ply: game.start().ply, captured: &captured, comments: &[], notes: &[] };
+    display::render(game.board(), writer, strategy, &sidebar, &markers, Color::White)?;
+    writeln!(writer, "  {status}")?;
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::board::Board;
+
+    fn moves(text: &str) -> Vec<String> {
+        text.split_whitespace().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn a_half_written_last_word_waits() {
+        let start = Position::default();
+        assert_eq!(written_game("e4 e5 Nf", &start).1, moves("e4 e5"));
+        assert_eq!(written_game("e4 e5 Nf3\n", &start).1, moves("e4 e5 Nf3"));
+        assert_eq!(written_game("", &start).1, Vec::<String>::new());
+    }
+
+    #[test]
+    fn the_last_pgn_game_is_followed_from_its_fen() {
+        let fen = "4k3/8/8/8/8/8/4P3/4K3 b - - 0 40";
+        let text = format!("[Event \"Round 1\"]\n\n1. d4 d5 1-0\n\n[Event \"Round 2\"]\n[FEN \"{fen}\"]\n\n40... Kd7 41. e4 ");
+        let (position, written) = written_game(&text, &Position::default());
+        assert_eq!(position, Board::from_fen(fen).unwrap());
+        assert_eq!(written, moves("Kd7 e4"));
+    }
+
+    #[test]
+    fn moves_to_play_continue_the_shown_ones_or_start_over() {
+        let shown = moves("e4 e5");
+        assert_eq!(moves_to_play(&shown, &moves("e4 e5 Nf3 Nc6")), Some(&moves("Nf3 Nc6")[..]));
+        assert_eq!(moves_to_play(&shown, &moves("e4 e5")), Some(&[][..]));
+        assert_eq!(moves_to_play(&shown, &moves("d4 d5 c4")), None);
+        assert_eq!(moves_to_play(&shown, &moves("e4")), None);
+    }
+}
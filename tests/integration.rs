@@ -1,7 +1,10 @@
-use std::process::{Command, Stdio};
+use std::process::{Command, Output, Stdio};
 use std::io::Write;
 use std::sync::Once;
 
+use chesswav::audio;
+use chesswav::engine::chess::NotationMove;
+
 static BUILD: Once = Once::new();
 
 fn ensure_built() {
@@ -14,22 +17,40 @@ fn ensure_built() {
 }
 
 fn run_chesswav(input: &str) -> Vec<u8> {
+    run_chesswav_with_args(input, &[])
+}
+
+fn run_chesswav_with_args(input: &str, args: &[&str]) -> Vec<u8> {
+    // Keeps the developer's own config file out of the tests
+    let no_config = std::env::temp_dir().join("chesswav-no-config");
+    run_chesswav_with_config_home(input, args, &no_config)
+}
+
+fn run_chesswav_with_config_home(input: &str, args: &[&str], config_home: &std::path::Path) -> Vec<u8> {
+    chesswav_output(input, args, config_home).stdout
+}
+
+fn chesswav_output(input: &str, args: &[&str], config_home: &std::path::Path) -> Output {
     ensure_built();
 
     let mut child = Command::new("./target/debug/chesswav")
+        .args(args)
+        .env("XDG_CONFIG_HOME", config_home)
         .stdin(Stdio::piped())
         .stdout(Stdio::piped())
-        .stderr(Stdio::null())
+        .stderr(Stdio::piped())
         .spawn()
         .expect("Failed to spawn");
 
     {
         let stdin = child.stdin.as_mut().unwrap();
-        stdin.write_all(input.as_bytes()).unwrap();
+        // A flag error exits before stdin is read, closing the pipe
+        if let Err(err) = stdin.write_all(input.as_bytes()) {
+            assert_eq!(err.kind(), std::io::ErrorKind::BrokenPipe, "{err}");
+        }
     } // stdin dropped here, sends EOF
 
-    let output = child.wait_with_output().expect("Failed to read output");
-    output.stdout
+    child.wait_with_output().expect("Failed to read output")
 }
 
 #[test]
@@ -71,3 +92,311 @@ fn capture_move() {
     let output = run_chesswav("Bxc6");
     assert!(output.len() > 20000);
 }
+
+#[test]
+fn cli_matches_interactive_move_audio() {
+    let input = "e4 d5 exd5 Qxd5 Nc3 Qa5+";
+    let interactive: Vec<i16> = input
+        .split_whitespace()
+        .enumerate()
+        .filter_map(|(idx, notation)| NotationMove::parse(notation, idx))
+        .flat_map(|m| audio::synthesize_move(&m))
+        .collect();
+    assert_eq!(run_chesswav(input), audio::to_wav(&interactive));
+}
+
+#[test]
+fn output_round_trips_through_wav_reader() {
+    let input = "e4 e5 Nf3 Nc6";
+    let path = std::env::temp_dir().join(format!("chesswav-round-trip-{}.wav", std::process::id()));
+    std::fs::write(&path, run_chesswav(input)).unwrap();
+    let (spec, samples) = audio::wav::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(spec.sample_rate, audio::SAMPLE_RATE);
+    assert_eq!(spec.num_channels, 1);
+    assert_eq!(samples, audio::generate(input));
+}
+
+#[test]
+fn output_flag_writes_file() {
+    let path = std::env::temp_dir().join(format!("chesswav-output-{}.wav", std::process::id()));
+    let stdout = run_chesswav_with_args("e4 e5", &["-o", path.to_str().unwrap()]);
+    let written = std::fs::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert!(stdout.is_empty());
+    assert_eq!(written, run_chesswav("e4 e5"));
+}
+
+#[test]
+fn optional_flag_values_leave_the_next_flag_alone() {
+    let path = std::env::temp_dir().join(format!("chesswav-optional-{}.wav", std::process::id()));
+    let stdout = run_chesswav_with_args("e4 e5", &["--bwf", "-o", path.to_str().unwrap()]);
+    let written = std::fs::read(&path).unwrap();
+    assert!(stdout.is_empty());
+    assert!(written.windows(4).any(|id| id == b"bext"), "--bwf without a description should still add a bext chunk");
+    let stdout = run_chesswav_with_args("e4 e5", &["--lufs", "-o", path.to_str().unwrap()]);
+    std::fs::remove_file(&path).unwrap();
+    assert!(stdout.is_empty());
+    assert_eq!(run_chesswav_with_args("e4 e5", &["--lufs", "-23"]).len(), run_chesswav("e4 e5").len());
+}
+
+#[test]
+fn multichannel_follows_the_note_length_and_rejects_stereo() {
+    let path = std::env::temp_dir().join(format!("chesswav-multichannel-{}.wav", std::process::id()));
+    run_chesswav_with_args("e4 e5", &["--multichannel", "--cues", "--note-ms", "150", "--gap-ms", "50", "-o", path.to_str().unwrap()]);
+    let (spec, samples) = audio::wav::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(spec.num_channels, audio::PIECE_CHANNELS);
+    // Two moves of 200 ms each, as the cues mark them
+    assert_eq!(samples.len(), 2 * audio::SAMPLE_RATE as usize / 5 * audio::PIECE_CHANNELS as usize);
+
+    let no_config = std::env::temp_dir().join("chesswav-no-config");
+    let output = chesswav_output("e4 e5", &["--multichannel", "--stereo"], &no_config);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn output_extension_selects_format() {
+    let path = std::env::temp_dir().join(format!("chesswav-output-{}.mid", std::process::id()));
+    run_chesswav_with_args("e4 e5", &["-o", path.to_str().unwrap()]);
+    let written = std::fs::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(&written[0..4], b"MThd");
+}
+
+#[test]
+fn raw_format_is_the_wav_data_chunk() {
+    let wav = run_chesswav("e4 e5");
+    let raw = run_chesswav_with_args("e4 e5", &["--format", "raw"]);
+    assert!(wav.ends_with(&raw));
+    assert_eq!(wav.len() - raw.len(), 44);
+}
+
+#[test]
+fn pgn_flag_reads_the_chosen_game_from_a_file() {
+    let path = std::env::temp_dir().join(format!("chesswav-games-{}.pgn", std::process::id()));
+    let pgn = "[Event \"First\"]\n\n1. e4 {main line} e5 1-0\n\n[Event \"Second\"]\n\n1. d4 d5 2. c4 0-1\n";
+    std::fs::write(&path, pgn).unwrap();
+    let first = run_chesswav_with_args("", &["--pgn", path.to_str().unwrap()]);
+    let second = run_chesswav_with_args("", &["--pgn", path.to_str().unwrap(), "--game", "2"]);
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(first, run_chesswav("[Event \"First\"]\n\ne4 e5"));
+    assert_eq!(second, run_chesswav("[Event \"Second\"]\n\nd4 d5 c4"));
+}
+
+#[test]
+fn config_file_sets_defaults_that_flags_override() {
+    let config_home = std::env::temp_dir().join(format!("chesswav-config-{}", std::process::id()));
+    std::fs::create_dir_all(config_home.join("chesswav")).unwrap();
+    std::fs::write(config_home.join("chesswav/config.toml"), "format = \"raw\"\ntempo = 120\n").unwrap();
+    let configured = run_chesswav_with_config_home("e4 e5", &[], &config_home);
+    let overridden = run_chesswav_with_config_home("e4 e5", &["--format", "wav", "--tempo", "60"], &config_home);
+    std::fs::remove_dir_all(&config_home).unwrap();
+    assert_eq!(configured, run_chesswav_with_args("e4 e5", &["--format", "raw", "--tempo", "120"]));
+    assert_eq!(overridden, run_chesswav_with_args("e4 e5", &["--tempo", "60"]));
+}
+
+#[test]
+fn tui_never_moves_a_pinned_piece() {
+    // The e2 knight is pinned to the king by the e8 rook; only c6 reaches d4
+    let args = ["tui", "-d", "ascii", "--fen", "4r2k/8/2N5/8/8/8/4N3/4K3 w - - 0 1"];
+    let played = String::from_utf8_lossy(&run_chesswav_with_args("Nd4\nfen\nquit\n", &args)).into_owned();
+    assert!(played.contains("4r2k/8/8/8/3N4/8/4N3/4K3 b"), "the c6 knight should go to d4");
+    let pinned = ["tui", "-d", "ascii", "--fen", "4r2k/8/8/8/8/8/4N3/4K3 w - - 0 1"];
+    let refused = String::from_utf8_lossy(&run_chesswav_with_args("Nd4\nfen\nquit\n", &pinned)).into_owned();
+    assert!(refused.contains("Nd4: illegal move"));
+    assert!(refused.contains("4r2k/8/8/8/8/8/4N3/4K3 w"), "the board should stay as it was");
+}
+
+#[test]
+fn tui_preferences_carry_over_to_the_next_session() {
+    let config_home = std::env::temp_dir().join(format!("chesswav-preferences-{}", std::process::id()));
+    run_chesswav_with_config_home("volume 30\ntheme blue\ncoords off\nquit\n", &["tui", "-d", "ascii"], &config_home);
+    let saved = std::fs::read_to_string(config_home.join("chesswav/config.toml")).unwrap();
+    let next = run_chesswav_with_config_home("volume\ntheme\nquit\n", &["tui", "-d", "ascii"], &config_home);
+    std::fs::remove_dir_all(&config_home).unwrap();
+    assert_eq!(saved, "volume = 30\nboard_theme = \"blue\"\ncoordinates = false\n");
+    let next = String::from_utf8_lossy(&next);
+    assert!(next.contains("Volume: 30"), "volume should be restored");
+    assert!(next.contains("Board theme: blue"), "board theme should be restored");
+}
+
+#[test]
+fn sound_theme_files_are_saved_by_full_path() {
+    let config_home = std::env::temp_dir().join(format!("chesswav-theme-path-{}", std::process::id()));
+    run_chesswav_with_config_home("soundtheme themes/8-bit.toml\nquit\n", &["tui", "-d", "ascii"], &config_home);
+    let saved = std::fs::read_to_string(config_home.join("chesswav/config.toml")).unwrap();
+    std::fs::remove_dir_all(&config_home).unwrap();
+    let full_path = std::fs::canonicalize("themes/8-bit.toml").unwrap();
+    assert_eq!(saved, format!("sound_theme = \"{}\"\n", full_path.display()));
+}
+
+#[test]
+fn missing_theme_from_the_config_falls_back_to_the_default_sounds() {
+    let config_home = std::env::temp_dir().join(format!("chesswav-missing-theme-{}", std::process::id()));
+    std::fs::create_dir_all(config_home.join("chesswav")).unwrap();
+    std::fs::write(config_home.join("chesswav/config.toml"), "sound_theme = \"gone.toml\"\n").unwrap();
+    let output = chesswav_output("e4 e5", &[], &config_home);
+    std::fs::remove_dir_all(&config_home).unwrap();
+    assert!(output.status.success());
+    assert_eq!(output.stdout, run_chesswav("e4 e5"));
+    assert!(String::from_utf8_lossy(&output.stderr).starts_with("warning: sound_theme gone.toml: "));
+}
+
+#[test]
+fn validate_reports_the_first_bad_move_without_audio() {
+    let no_config = std::env::temp_dir().join("chesswav-no-config");
+    let legal = chesswav_output("1. e4 e5 2. Nf3 Nc6", &["--validate"], &no_config);
+    assert!(legal.status.success());
+    assert_eq!(String::from_utf8_lossy(&legal.stdout), "4 moves, all legal\n");
+
+    let illegal = chesswav_output("1. e4 e5 2. Ke3 Nc6", &["--validate"], &no_config);
+    assert_eq!(illegal.status.code(), Some(1));
+    assert!(illegal.stdout.is_empty());
+    assert_eq!(String::from_utf8_lossy(&illegal.stderr), "2. Ke3: illegal move\n");
+}
+
+#[test]
+fn stats_json_goes_to_stderr_next_to_the_audio() {
+    let no_config = std::env::temp_dir().join("chesswav-no-config");
+    let output = chesswav_output("e4 d5 exd5 Qxd5", &["--stats", "json"], &no_config);
+    assert_eq!(output.stdout, run_chesswav("e4 d5 exd5 Qxd5"));
+    let stats = String::from_utf8_lossy(&output.stderr);
+    assert!(stats.starts_with("{\"moves\":4,\"captures\":2,\"checks\":0,"), "{stats}");
+    assert!(stats.trim_end().ends_with("\"duration_ms\":1400}"), "{stats}");
+}
+
+#[test]
+fn outdir_writes_one_file_per_game_named_from_tags() {
+    let dir = std::env::temp_dir().join(format!("chesswav-batch-{}", std::process::id()));
+    let pgn = "[White \"Carlsen, Magnus\"]\n[Black \"Nepomniachtchi, Ian\"]\n\n1. e4 e5 1-0\n\n[Event \"Second\"]\n\n1. d4 d5 *\n";
+    run_chesswav_with_args(pgn, &["--outdir", dir.to_str().unwrap(), "--parallel", "2"]);
+    let first = std::fs::read(dir.join("001-carlsen-nepomniachtchi.wav")).unwrap();
+    let second = std::fs::read(dir.join("002-second.wav")).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(first, run_chesswav("[White \"Carlsen, Magnus\"]\n[Black \"Nepomniachtchi, Ian\"]\n\ne4 e5"));
+    assert_eq!(second, run_chesswav("[Event \"Second\"]\n\nd4 d5"));
+}
+
+#[test]
+fn verbose_traces_moves_and_quiet_keeps_stderr_clean() {
+    let no_config = std::env::temp_dir().join("chesswav-no-config");
+    let verbose = chesswav_output("e4 Nf6", &["--verbose"], &no_config);
+    assert_eq!(verbose.stdout, run_chesswav("e4 Nf6"));
+    let trace = String::from_utf8_lossy(&verbose.stderr);
+    let lines: Vec<&str> = trace.lines().collect();
+    assert_eq!(lines.len(), 2, "{trace}");
+    assert!(lines[0].starts_with("1. e4       e2-e4"), "{trace}");
+    assert!(lines[1].starts_with("1... Nf6    g8-f6"), "{trace}");
+
+    let path = std::env::temp_dir().join(format!("chesswav-quiet-{}.pgn", std::process::id()));
+    std::fs::write(&path, "[Event \"A\"]\n\n1. e4 *\n\n[Event \"B\"]\n\n1. d4 *\n").unwrap();
+    let noisy = chesswav_output("", &["--pgn", path.to_str().unwrap()], &no_config);
+    let quiet = chesswav_output("", &["--pgn", path.to_str().unwrap(), "--quiet"], &no_config);
+    std::fs::remove_file(&path).unwrap();
+    assert!(!noisy.stderr.is_empty());
+    assert!(quiet.stderr.is_empty());
+    assert_eq!(quiet.stdout, noisy.stdout);
+}
+
+#[test]
+fn loop_needs_play() {
+    let no_config = std::env::temp_dir().join("chesswav-no-config");
+    let output = chesswav_output("e4 e5", &["--loop", "3"], &no_config);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(output.stdout.is_empty());
+    assert_eq!(String::from_utf8_lossy(&output.stderr), "--loop repeats playback and needs --play\n");
+}
+
+#[test]
+fn sample_rate_resamples_the_output() {
+    let wav = run_chesswav_with_args("e4 e5", &["--sample-rate", "48000"]);
+    let decoded = audio::wav::decode(&wav).unwrap();
+    assert_eq!(decoded.spec.sample_rate, 48000);
+    // Two slots of 350 ms
+    assert_eq!(decoded.samples.len(), 2 * 350 * 48);
+
+    let no_config = std::env::temp_dir().join("chesswav-no-config");
+    let output = chesswav_output("e4", &["--sample-rate", "fast"], &no_config);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).starts_with("Invalid sample rate: fast"));
+}
+
+#[test]
+fn pgn_on_stdin_is_read_like_a_pgn_file() {
+    let pgn = "[Event \"Casual\"]\n[White \"Anderssen\"]\n\n1. e4 {best by test} e5 2. Nf3 1-0\n";
+    let wav = run_chesswav(pgn);
+    let decoded = audio::wav::decode(&wav).unwrap();
+    assert_eq!(decoded.samples, audio::generate("e4 e5 Nf3"));
+    assert!(wav.windows(9).any(|window| window == b"Anderssen"));
+
+    let two_games = format!("{}\n[Event \"Second\"]\n\n1. d4 d5 *\n", pgn);
+    let wav = run_chesswav_with_args(&two_games, &["--game", "2"]);
+    assert_eq!(audio::wav::decode(&wav).unwrap().samples, audio::generate("d4 d5"));
+}
+
+#[test]
+fn fen_sets_the_starting_position() {
+    let no_config = std::env::temp_dir().join("chesswav-no-config");
+    let after_e4 = "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1";
+    let output = chesswav_output("e5 Nf3 Nc6", &["--fen", after_e4, "--validate"], &no_config);
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "3 moves, all legal\n");
+
+    let output = chesswav_output("e5", &["--fen", "8/8/8"], &no_config);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(String::from_utf8_lossy(&output.stderr), "Invalid FEN: expected 8 ranks, found 3\n");
+}
+
+#[test]
+fn pgn_games_start_from_their_fen_tag() {
+    let no_config = std::env::temp_dir().join("chesswav-no-config");
+    let pgn = "[FEN \"4k3/8/8/8/8/8/8/R3K3 w - - 0 1\"]\n\n1. Ra8+ Kd7 *\n\n[Event \"Second\"]\n\n1. e4 e5 *\n";
+    let validated = chesswav_output(pgn, &["--validate"], &no_config);
+    assert_eq!(String::from_utf8_lossy(&validated.stderr), "");
+    assert_eq!(String::from_utf8_lossy(&validated.stdout), "2 of 2 games valid\n");
+    assert!(validated.status.success());
+
+    let rendered = chesswav_output(pgn, &["--stdout"], &no_config);
+    assert_eq!(rendered.status.code(), Some(0));
+    assert!(!String::from_utf8_lossy(&rendered.stderr).contains("skipped"));
+
+    let bad_tag = chesswav_output("[FEN \"8/8/8\"]\n\n1. e4 *\n", &["--validate"], &no_config);
+    assert_eq!(bad_tag.status.code(), Some(1));
+    assert_eq!(String::from_utf8_lossy(&bad_tag.stderr), "Invalid FEN tag: expected 8 ranks, found 3\n");
+}
+
+#[test]
+fn autoplay_rejects_a_depth_out_of_range() {
+    let no_config = std::env::temp_dir().join("chesswav-no-config");
+    let output = chesswav_output("", &["autoplay", "--depth", "9"], &no_config);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(String::from_utf8_lossy(&output.stderr), "Invalid depth: 9. Expected 1 to 5\n");
+}
+
+#[test]
+fn watch_follows_stdin_until_it_ends() {
+    let no_config = std::env::temp_dir().join("chesswav-no-config");
+    let output = chesswav_output("e4\ne5\nKe3\n", &["watch", "-d", "ascii"], &no_config);
+    assert!(output.status.success());
+    let screen = String::from_utf8_lossy(&output.stdout);
+    assert!(screen.contains("following standard input"), "{screen}");
+    assert!(screen.contains("1. e4    e5"), "{screen}");
+    assert!(screen.contains("Cannot play Ke3"), "{screen}");
+}
+
+#[test]
+fn skipped_tokens_warn_and_exit_with_status_2() {
+    let no_config = std::env::temp_dir().join("chesswav-no-config");
+    let output = chesswav_output("1. e4 e5 2. Nf9 Nc6", &[], &no_config);
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        "warning: skipped \"Nf9\" (not a move)\nwarning: 1 token skipped; the audio leaves them out\n"
+    );
+    assert_eq!(audio::wav::decode(&output.stdout).unwrap().samples, audio::generate("e4 e5 Nc6"));
+
+    let quiet = chesswav_output("e4 Nf9", &["--quiet"], &no_config);
+    assert_eq!(quiet.status.code(), Some(2));
+    assert!(quiet.stderr.is_empty());
+}